@@ -1,11 +1,17 @@
+use crate::models::ai::chat_models::ChatOptions;
 use crate::models::ai::{ChatResponse, Message, ProviderType};
 use crate::services::agent_orchestrator::AgentOrchestrator;
 use crate::services::ai_service::AIService;
+use crate::services::backup_service::BackupService;
+use crate::services::cancellation_service::CancellationService;
 use crate::services::project_service::ProjectService;
+use crate::services::proposed_changes_service::{ProposedChangesService, ProposedFileChange};
+use crate::services::providers::ollama::{OllamaProvider, OllamaRunningModel};
 use crate::services::settings_service::SettingsService;
+use crate::services::skill_service::SkillService;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 #[tauri::command]
 pub async fn send_message(
@@ -15,6 +21,7 @@ pub async fn send_message(
     project_id: Option<String>,
     skill_id: Option<String>,
     skill_params: Option<HashMap<String, String>>,
+    options: Option<ChatOptions>,
 ) -> Result<ChatResponse, String> {
     // 1. Context Construction (Hoisted from God Method)
     let system_prompt = build_system_prompt(&project_id);
@@ -27,11 +34,66 @@ pub async fn send_message(
             project_id,
             skill_id,
             skill_params,
+            options.unwrap_or_default(),
         )
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Stop the chat response streaming for `project_id` (or the scratch chat when omitted).
+/// Whatever was received so far is kept in the chat history.
+#[tauri::command]
+pub async fn cancel_chat(project_id: Option<String>) -> Result<(), String> {
+    CancellationService::global()
+        .cancel_process(&CancellationService::chat_key(project_id.as_deref()))
+        .await
+        .map_err(|e| format!("Failed to cancel chat: {}", e))
+}
+
+/// Answer a `tool-approval-request` emitted while the agent waits to run a tool
+#[tauri::command]
+pub async fn respond_tool_approval(request_id: String, approved: bool) -> Result<(), String> {
+    crate::services::tool_approval_service::ToolApprovalService::respond(&request_id, approved)
+        .map_err(|e| format!("Failed to answer tool approval: {}", e))
+}
+
+/// Throwaway chat outside any project: no project context, history, research log or
+/// file changes. Only the response is returned.
+#[tauri::command]
+pub async fn send_ephemeral_message(
+    orchestrator: State<'_, Arc<AgentOrchestrator>>,
+    messages: Vec<Message>,
+    system_prompt: Option<String>,
+    skill_id: Option<String>,
+) -> Result<ChatResponse, String> {
+    orchestrator
+        .run_agent_loop(messages, system_prompt, None, skill_id, None)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Return the exact system prompt `send_message` would use for this project, skill and
+/// parameters, without calling a provider. Fails if the parameters don't render the skill.
+#[tauri::command]
+pub async fn preview_assembled_prompt(
+    project_id: Option<String>,
+    skill_id: String,
+    skill_params: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let skill = SkillService::get_skill(&skill_id)
+        .map_err(|e| format!("Failed to load skill: {}", e))?;
+    let rendered = skill
+        .render_prompt(skill_params.unwrap_or_default())
+        .map_err(|e| format!("Failed to render skill prompt: {}", e))?;
+
+    let system_prompt = build_system_prompt(&project_id);
+    Ok(AgentOrchestrator::assemble_system_prompt(
+        project_id.as_deref(),
+        Some(&system_prompt),
+        Some((&skill, &rendered)),
+    ))
+}
+
 /// Helper to build the system prompt based on project context
 fn build_system_prompt(project_id: &Option<String>) -> String {
     let mut prompt = String::from("You are a helpful AI research assistant.
@@ -188,15 +250,108 @@ pub async fn load_chat_history(
         .await
         .map_err(|e| format!("Failed to load chat history: {}", e))?;
 
-    Ok(old_messages
-        .into_iter()
-        .map(|m| Message {
-            role: m.role,
-            content: m.content,
-            tool_calls: None,
-            tool_results: None,
-        })
-        .collect())
+    Ok(old_messages.into_iter().map(to_message).collect())
+}
+
+/// Edit a user message in a saved chat and drop everything after it, rewriting the file.
+/// Returns the remaining conversation.
+#[tauri::command]
+pub async fn edit_chat_message(
+    project_id: String,
+    chat_file: String,
+    message_index: usize,
+    content: String,
+) -> Result<Vec<Message>, String> {
+    use crate::services::chat_service::ChatService;
+    let messages = ChatService::edit_chat_message(&project_id, &chat_file, message_index, &content)
+        .await
+        .map_err(|e| format!("Failed to edit chat message: {}", e))?;
+
+    Ok(messages.into_iter().map(to_message).collect())
+}
+
+/// Re-run the agent from a user message, optionally edited first. Everything after that
+/// message is left out of the request. The saved chat is not touched: the new response is
+/// saved as a new chat like any other turn, so both branches are kept.
+#[tauri::command]
+pub async fn regenerate_chat_message(
+    orchestrator: State<'_, Arc<AgentOrchestrator>>,
+    project_id: String,
+    chat_file: String,
+    message_index: usize,
+    content: Option<String>,
+    skill_id: Option<String>,
+    skill_params: Option<HashMap<String, String>>,
+) -> Result<ChatResponse, String> {
+    use crate::services::chat_service::ChatService;
+    ChatService::validate_chat_name(&chat_file).map_err(|e| e.to_string())?;
+    let mut messages = ChatService::load_chat_from_file(&project_id, &chat_file)
+        .await
+        .map_err(|e| format!("Failed to load chat history: {}", e))?;
+    let content = match content {
+        Some(content) => content,
+        None => messages
+            .get(message_index)
+            .map(|m| m.content.clone())
+            .ok_or_else(|| format!("Chat has no message {}", message_index))?,
+    };
+    ChatService::truncate_at_edit(&mut messages, message_index, &content)
+        .map_err(|e| format!("Failed to edit chat message: {}", e))?;
+
+    let project_id = Some(project_id);
+    let system_prompt = build_system_prompt(&project_id);
+    orchestrator
+        .run_agent_loop(
+            messages.into_iter().map(to_message).collect(),
+            Some(system_prompt),
+            project_id,
+            skill_id,
+            skill_params,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Start a new chat from the first messages of an existing one, up to and including
+/// `up_to_message_index`. Returns the new chat's file name.
+#[tauri::command]
+pub async fn fork_chat(
+    project_id: String,
+    file_name: String,
+    up_to_message_index: usize,
+) -> Result<String, String> {
+    use crate::services::chat_service::ChatService;
+    ChatService::fork_chat(&project_id, &file_name, up_to_message_index)
+        .await
+        .map_err(|e| format!("Failed to fork chat: {}", e))
+}
+
+/// Rename a chat file; returns the new name, which always ends in `.md`
+#[tauri::command]
+pub async fn rename_chat(
+    project_id: String,
+    old_name: String,
+    new_name: String,
+) -> Result<String, String> {
+    use crate::services::chat_service::ChatService;
+    ChatService::rename_chat(&project_id, &old_name, &new_name)
+        .map_err(|e| format!("Failed to rename chat: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_chat(project_id: String, file_name: String) -> Result<(), String> {
+    use crate::services::chat_service::ChatService;
+    ChatService::delete_chat(&project_id, &file_name)
+        .map_err(|e| format!("Failed to delete chat: {}", e))
+}
+
+fn to_message(message: crate::models::chat::ChatMessage) -> Message {
+    Message {
+        role: message.role,
+        content: message.content,
+        tool_calls: None,
+        tool_results: None,
+    }
 }
 
 #[tauri::command]
@@ -207,6 +362,54 @@ pub async fn get_chat_files(project_id: String) -> Result<Vec<String>, String> {
         .map_err(|e| format!("Failed to get chat files: {}", e))
 }
 
+/// Report which chat files load cleanly and which are corrupt
+#[tauri::command]
+pub async fn scan_chat_history(
+    project_id: String,
+) -> Result<crate::services::chat_service::ChatScanReport, String> {
+    use crate::services::chat_service::ChatService;
+    ChatService::scan_chat_files(&project_id)
+        .await
+        .map_err(|e| format!("Failed to scan chat history: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_chat_summaries(
+    project_id: String,
+) -> Result<Vec<crate::services::chat_service::ChatSummary>, String> {
+    use crate::services::chat_service::ChatService;
+    ChatService::get_chat_summaries(&project_id)
+        .await
+        .map_err(|e| format!("Failed to get chat summaries: {}", e))
+}
+
+/// Case-insensitive full-text search across the project's chats, most matches first
+#[tauri::command]
+pub async fn search_chats(
+    project_id: String,
+    query: String,
+    whole_word: Option<bool>,
+) -> Result<Vec<crate::services::chat_service::ChatSearchResult>, String> {
+    use crate::services::chat_service::ChatService;
+    ChatService::search_chats(&project_id, &query, whole_word.unwrap_or(false))
+        .map_err(|e| format!("Failed to search chats: {}", e))
+}
+
+/// Write a chat as HTML, markdown or JSON to the project's `.metadata/exports/` and
+/// return the file path
+#[tauri::command]
+pub async fn export_chat(
+    project_id: String,
+    file_name: String,
+    format: crate::services::chat_service::ChatExportFormat,
+) -> Result<String, String> {
+    use crate::services::chat_service::ChatService;
+    ChatService::export_chat(&project_id, &file_name, format)
+        .await
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to export chat: {}", e))
+}
+
 #[tauri::command]
 pub async fn save_chat(
     project_id: String,
@@ -230,36 +433,54 @@ pub async fn save_chat(
 }
 
 #[tauri::command]
-pub async fn get_ollama_models() -> Result<Vec<String>, String> {
-    let client = reqwest::Client::new();
-    let res = client
-        .get("http://localhost:11434/api/tags")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to connect to Ollama: {}", e))?;
+pub async fn get_proposed_changes(project_id: String) -> Result<Vec<ProposedFileChange>, String> {
+    ProposedChangesService::list(&project_id)
+        .map_err(|e| format!("Failed to load proposed changes: {}", e))
+}
 
-    if !res.status().is_success() {
-        return Err(format!(
-            "Ollama API returned detailed error: {}",
-            res.status()
-        ));
+#[tauri::command]
+pub async fn apply_proposed_changes(
+    app_handle: AppHandle,
+    project_id: String,
+    ids: Vec<String>,
+) -> Result<Vec<String>, String> {
+    BackupService::backup_before_risky_op(&project_id, "applying proposed changes")
+        .map_err(|e| format!("Failed to back up project before applying changes: {}", e))?;
+    let applied = ProposedChangesService::apply(&project_id, &ids)
+        .map_err(|e| format!("Failed to apply proposed changes: {}", e))?;
+
+    for path in &applied {
+        let _ = app_handle.emit("file-changed", (project_id.clone(), path.clone()));
     }
 
-    let body = res
-        .text()
+    Ok(applied)
+}
+
+#[tauri::command]
+pub async fn discard_proposed_changes(project_id: String, ids: Vec<String>) -> Result<(), String> {
+    ProposedChangesService::discard(&project_id, &ids)
+        .map_err(|e| format!("Failed to discard proposed changes: {}", e))
+}
+
+/// Models pulled into the configured Ollama server, for the model dropdowns
+#[tauri::command]
+pub async fn get_ollama_models() -> Result<Vec<String>, String> {
+    let settings = SettingsService::load_global_settings()
+        .map_err(|e| format!("Failed to load global settings: {}", e))?;
+
+    crate::detector::ollama_detector::OllamaDetector::list_models(&settings.ollama.api_url)
         .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
-    let json: serde_json::Value =
-        serde_json::from_str(&body).map_err(|e| format!("Failed to parse JSON: {}", e))?;
-
-    let mut models = Vec::new();
-    if let Some(models_arr) = json.get("models").and_then(|v| v.as_array()) {
-        for model in models_arr {
-            if let Some(name) = model.get("name").and_then(|v| v.as_str()) {
-                models.push(name.to_string());
-            }
-        }
-    }
+        .map_err(|e| format!("Failed to connect to Ollama: {}", e))
+}
 
-    Ok(models)
+/// Models Ollama currently has loaded and how much RAM/VRAM each one uses
+#[tauri::command]
+pub async fn get_ollama_running_models() -> Result<Vec<OllamaRunningModel>, String> {
+    let settings = SettingsService::load_global_settings()
+        .map_err(|e| format!("Failed to load global settings: {}", e))?;
+
+    OllamaProvider::new(settings.ollama)
+        .running_models()
+        .await
+        .map_err(|e| format!("Failed to connect to Ollama: {}", e))
 }
@@ -1,11 +1,18 @@
 use crate::services::file_service::FileService;
+use crate::services::global_search_service::{
+    GlobalSearchKind, GlobalSearchResults, GlobalSearchService, DEFAULT_GLOBAL_SEARCH_LIMIT,
+};
 use crate::services::project_service::ProjectService;
+use crate::services::search_replace_service::{
+    ReplaceOptions, ReplacePreview, ReplaceResult, SearchReplaceService,
+};
 use crate::services::settings_service::SettingsService;
 use crate::services::ai_service::AIService;
 use std::sync::Arc;
 use crate::models::ai::Message;
 use serde::{Deserialize, Serialize};
 use std::fs;
+use tauri::Emitter;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchMatch {
@@ -198,6 +205,22 @@ pub async fn search_in_files(
     Ok(matches)
 }
 
+/// Search every project's markdown files, the skills and all chats at once.
+/// `kinds` narrows the search (all kinds when omitted) and `limit` caps the hits returned.
+#[tauri::command]
+pub async fn global_search(
+    query: String,
+    kinds: Option<Vec<GlobalSearchKind>>,
+    limit: Option<usize>,
+) -> Result<GlobalSearchResults, String> {
+    GlobalSearchService::search(
+        &query,
+        &kinds.unwrap_or_default(),
+        limit.unwrap_or(DEFAULT_GLOBAL_SEARCH_LIMIT),
+    )
+    .map_err(|e| format!("Failed to search: {}", e))
+}
+
 #[tauri::command]
 pub async fn replace_in_files(
     project_id: String,
@@ -253,6 +276,59 @@ pub async fn replace_in_files(
     Ok(total_replacements)
 }
 
+/// Preview a project-wide replace: affected files, match counts and sample lines
+#[tauri::command]
+pub async fn search_and_replace_in_project(
+    project_id: String,
+    find: String,
+    replace: String,
+    options: Option<ReplaceOptions>,
+) -> Result<ReplacePreview, String> {
+    SearchReplaceService::preview(&project_id, &find, &replace, &options.unwrap_or_default())
+        .map_err(|e| format!("Failed to preview replace: {}", e))
+}
+
+/// Apply a previewed replace. Limit it to `file_names` when given.
+#[tauri::command]
+pub async fn apply_search_and_replace(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+    find: String,
+    replace: String,
+    options: Option<ReplaceOptions>,
+    file_names: Option<Vec<String>>,
+) -> Result<ReplaceResult, String> {
+    let result = SearchReplaceService::apply(
+        &project_id,
+        &find,
+        &replace,
+        &options.unwrap_or_default(),
+        file_names.as_deref(),
+    )
+    .map_err(|e| format!("Failed to replace in files: {}", e))?;
+
+    for file_name in &result.files_changed {
+        let _ = app_handle.emit("file-changed", (project_id.clone(), file_name.clone()));
+    }
+    Ok(result)
+}
+
+/// Restore the files changed by `apply_search_and_replace` from its snapshot
+#[tauri::command]
+pub async fn undo_search_and_replace(
+    app_handle: tauri::AppHandle,
+    project_id: String,
+    snapshot_id: String,
+) -> Result<Vec<String>, String> {
+    let restored = SearchReplaceService::undo(&project_id, &snapshot_id)
+        .map_err(|e| format!("Failed to undo replace: {}", e))?;
+
+    for file_name in &restored {
+        let _ = app_handle.emit("file-changed", (project_id.clone(), file_name.clone()));
+    }
+    Ok(restored)
+}
+
 fn is_safe_path(path: &str) -> Result<(), String> {
     use std::path::Path;
     let p = Path::new(path);
@@ -0,0 +1,23 @@
+use crate::services::ai_service::AIService;
+use crate::services::file_watcher::FileWatcherService;
+use crate::services::health_service::{HealthReport, HealthService};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+/// Overall app health plus the status of each component
+#[tauri::command]
+pub async fn health_check(app: tauri::AppHandle) -> Result<HealthReport, String> {
+    let watcher_running = app
+        .try_state::<Mutex<FileWatcherService>>()
+        .map(|watcher| watcher.lock().unwrap_or_else(|p| p.into_inner()).is_watching())
+        .unwrap_or(false);
+
+    let provider_type = match app.try_state::<Arc<AIService>>() {
+        Some(ai_service) => ai_service.get_active_provider_type().await,
+        None => crate::services::settings_service::SettingsService::load_global_settings()
+            .map_err(|e| format!("Failed to load settings: {}", e))?
+            .active_provider,
+    };
+
+    Ok(HealthService::check(provider_type, watcher_running).await)
+}
@@ -1,5 +1,7 @@
+use crate::detector::cli_detector::CliToolInfo;
 use crate::detector::{self, ClaudeCodeInfo, GeminiInfo, OllamaInfo};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use crate::directory;
 use crate::installer::{
     InstallationConfig, InstallationManager, InstallationProgress, InstallationResult,
@@ -7,14 +9,24 @@ use crate::installer::{
 use anyhow::Result;
 use tauri::Emitter;
 
+/// Report incomplete first-run setup steps with ordered next actions
+#[tauri::command]
+pub async fn onboarding_status(
+) -> Result<crate::services::onboarding_service::OnboardingStatus, String> {
+    crate::services::onboarding_service::OnboardingService::status()
+        .await
+        .map_err(|e| format!("Failed to check onboarding status: {}", e))
+}
+
 /// Check the current installation status
 #[tauri::command]
 pub async fn check_installation_status() -> Result<InstallationConfig, String> {
     let app_data_path = crate::utils::paths::get_app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
-    let config = InstallationManager::load_installation_state(&app_data_path)
+    let mut config = InstallationManager::load_installation_state(&app_data_path)
         .map_err(|e| format!("Failed to load installation state: {}", e))?;
+    config.node_toolchain = detector::detect_node_toolchain().await;
 
     Ok(config)
 }
@@ -40,6 +52,7 @@ pub async fn detect_claude_code() -> Result<Option<ClaudeCodeInfo>, String> {
                 path: Some(std::path::PathBuf::from(path_str)),
                 in_path: false,
                 authenticated,
+                error: None,
             }));
         }
     }
@@ -66,6 +79,7 @@ pub async fn detect_ollama() -> Result<Option<OllamaInfo>, String> {
                 path: Some(std::path::PathBuf::from(path_str)),
                 running: true, // Assume running to avoid slow check
                 in_path: false,
+                models: detector::list_ollama_models(Some(&settings.ollama.api_url)).await,
             }));
         }
     }
@@ -132,39 +146,31 @@ pub struct OpenAiCliInfo {
 /// Detect OpenAI/Codex CLI installation
 #[tauri::command]
 pub async fn detect_openai_cli() -> Result<Option<OpenAiCliInfo>, String> {
-    let candidates = ["codex", "openai"];
+    let settings = crate::services::settings_service::SettingsService::load_global_settings()
+        .map_err(|e| e.to_string())?;
 
-    for cmd in candidates {
-        let in_path = crate::utils::env::command_exists(cmd);
-        if !in_path {
-            continue;
-        }
+    let info = detector::detect_codex_with_path(settings.openai_cli.detected_path)
+        .await
+        .map_err(|e| format!("Failed to detect Codex CLI: {}", e))?;
+
+    Ok(info.map(|info| OpenAiCliInfo {
+        installed: info.installed,
+        version: info.version,
+        path: info.path,
+        in_path: info.in_path,
+    }))
+}
 
-        let version = std::process::Command::new(cmd)
-            .arg("--version")
-            .output()
-            .ok()
-            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-            .filter(|s| !s.is_empty());
-
-        let path = std::process::Command::new("where")
-            .arg(cmd)
-            .output()
-            .ok()
-            .and_then(|o| {
-                let out = String::from_utf8_lossy(&o.stdout).to_string();
-                out.lines().next().map(|l| std::path::PathBuf::from(l.trim()))
-            });
-
-        return Ok(Some(OpenAiCliInfo {
-            installed: true,
-            version,
-            path,
-            in_path,
-        }));
-    }
+/// Get Node.js installation instructions (needed for npx-based MCP servers)
+#[tauri::command]
+pub fn get_node_install_instructions() -> String {
+    detector::get_node_installation_instructions()
+}
 
-    Ok(None)
+/// Get Codex CLI installation instructions
+#[tauri::command]
+pub fn get_codex_install_instructions() -> String {
+    detector::get_codex_installation_instructions()
 }
 
 /// Detect all CLI tools at once (more efficient)
@@ -263,13 +269,76 @@ pub async fn redetect_dependencies() -> Result<InstallationConfig, String> {
         .map_err(|e| format!("Failed to create installation manager: {}", e))?;
 
     manager
-        .redetect_dependencies()
+        .redetect_dependencies(false)
+        .await
+        .map_err(|e| format!("Failed to re-detect dependencies: {}", e))?;
+
+    Ok(manager.config().clone())
+}
+
+/// Re-detect dependencies without using cached results, for the "Re-scan now" button
+#[tauri::command]
+pub async fn redetect_dependencies_force() -> Result<InstallationConfig, String> {
+    let mut manager = InstallationManager::with_default_path()
+        .map_err(|e| format!("Failed to create installation manager: {}", e))?;
+
+    manager
+        .redetect_dependencies(true)
         .await
         .map_err(|e| format!("Failed to re-detect dependencies: {}", e))?;
 
     Ok(manager.config().clone())
 }
 
+/// Clear every detection cache, re-run all detectors (including custom CLIs) concurrently,
+/// persist the results and emit `dependencies-updated`. Custom CLIs are keyed `custom-<id>`.
+#[tauri::command]
+pub async fn redetect_all_dependencies(
+    app: tauri::AppHandle,
+) -> Result<HashMap<String, CliToolInfo>, String> {
+    let mut manager = InstallationManager::with_default_path()
+        .map_err(|e| format!("Failed to create installation manager: {}", e))?;
+
+    let mut results = manager
+        .redetect_all_dependencies()
+        .await
+        .map_err(|e| format!("Failed to re-detect dependencies: {}", e))?;
+
+    let mut settings = crate::services::settings_service::SettingsService::load_global_settings()
+        .map_err(|e| e.to_string())?;
+
+    let custom_results = futures::future::join_all(
+        settings
+            .custom_clis
+            .iter()
+            .map(|cli| detector::detect_custom_cli(&cli.name, &cli.command)),
+    )
+    .await;
+    for (cli, info) in settings.custom_clis.iter_mut().zip(custom_results) {
+        cli.detected_path = info.path.clone();
+        results.insert(format!("custom-{}", cli.id), info);
+    }
+
+    // Stale paths would short-circuit the single-tool detect commands, so clear them too
+    let installed_path = |name: &str| {
+        results
+            .get(name)
+            .filter(|info| info.installed)
+            .and_then(|info| info.path.clone())
+    };
+    settings.claude.detected_path = installed_path("claude-code");
+    settings.ollama.detected_path = installed_path("ollama");
+    settings.gemini_cli.detected_path = installed_path("gemini");
+    settings.openai_cli.detected_path = installed_path("codex");
+
+    crate::services::settings_service::SettingsService::save_global_settings(&settings)
+        .map_err(|e| format!("Failed to save detected paths: {}", e))?;
+
+    let _ = app.emit("dependencies-updated", &results);
+
+    Ok(results)
+}
+
 /// Create a backup of the current installation
 #[tauri::command]
 pub async fn backup_installation() -> Result<String, String> {
@@ -1,14 +1,51 @@
-use crate::services::markdown_service::{MarkdownService, TocEntry};
+use crate::services::markdown_service::{CodeTheme, MarkdownService, TocEntry};
+use crate::services::settings_service::SettingsService;
 
-/// Render markdown to HTML
+/// Markdown files of the project, when one is given, for resolving `[[Note]]` links
+fn project_notes(project_id: Option<&str>) -> Result<Option<Vec<String>>, String> {
+    project_id
+        .map(MarkdownService::project_notes)
+        .transpose()
+        .map_err(|e| format!("Failed to list project notes: {}", e))
+}
+
+/// Render markdown to HTML. Code is highlighted for `theme` ("light" or "dark"), or for the
+/// app's theme setting when it isn't given. With a project, `[[Note]]` links point to its
+/// files.
+#[tauri::command]
+pub async fn render_markdown_to_html(
+    markdown: String,
+    theme: Option<String>,
+    project_id: Option<String>,
+) -> Result<String, String> {
+    let theme = theme
+        .or_else(|| SettingsService::load_global_settings().ok().map(|s| s.theme))
+        .unwrap_or_default();
+    let notes = project_notes(project_id.as_deref())?;
+    Ok(MarkdownService::render_to_html_themed(
+        &markdown,
+        CodeTheme::from_setting(&theme),
+        notes.as_deref(),
+    ))
+}
+
+/// Links in the markdown. With a project, `[[Note]]` links are resolved to its files.
 #[tauri::command]
-pub async fn render_markdown_to_html(markdown: String) -> Result<String, String> {
-    Ok(MarkdownService::render_to_html(&markdown))
+pub async fn extract_markdown_links(
+    markdown: String,
+    project_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    Ok(match project_notes(project_id.as_deref())? {
+        Some(notes) => MarkdownService::extract_project_links(&markdown, &notes),
+        None => MarkdownService::extract_links(&markdown),
+    })
 }
 
+/// Project files that link to `file_name`
 #[tauri::command]
-pub async fn extract_markdown_links(markdown: String) -> Result<Vec<String>, String> {
-    Ok(MarkdownService::extract_links(&markdown))
+pub async fn get_backlinks(project_id: String, file_name: String) -> Result<Vec<String>, String> {
+    MarkdownService::get_backlinks(&project_id, &file_name)
+        .map_err(|e| format!("Failed to find backlinks: {}", e))
 }
 
 /// Generate table of contents from markdown
@@ -1,18 +1,29 @@
-use crate::models::mcp::{McpMarketSearchResponse, McpServerConfig, RegistryResponse};
+use crate::models::mcp::{
+    registry_package_launch, McpMarketSearchResponse, McpServerConfig, McpServerTestResult,
+    RegistryResponse, SERVER_TEST_TIMEOUT_SECS,
+};
+use crate::services::mcp_marketplace_cache::McpMarketplaceCache;
+use crate::services::mcp_service::{McpService, McpTool};
 use crate::services::settings_service::SettingsService;
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
 use std::collections::HashSet;
 
-fn is_trusted_mcp_command(command: &str) -> bool {
-    matches!(command, "npx" | "node" | "uvx" | "python" | "python3")
-}
-
 fn is_trusted_mcp_source(source: Option<&str>) -> bool {
     matches!(source, Some("registry") | Some("mcpmarket"))
 }
 
+/// What to install when a server's launcher is missing
+fn launcher_install_hint(command: &str) -> Option<&'static str> {
+    match command {
+        "npx" | "node" => Some("Install Node.js from https://nodejs.org and restart the app."),
+        "uvx" => Some("Install uv from https://docs.astral.sh/uv/ and restart the app."),
+        "docker" => Some("Install Docker from https://docs.docker.com/get-docker/ and make sure it is running."),
+        _ => None,
+    }
+}
+
 fn can_enable_mcp_server(config: &McpServerConfig) -> Result<(), String> {
-    if !is_trusted_mcp_command(&config.command) {
+    if !config.has_trusted_command() {
         return Err(format!(
             "Refusing to enable untrusted MCP command '{}'. Save it disabled first, then review and enable manually.",
             config.command
@@ -27,6 +38,24 @@ fn can_enable_mcp_server(config: &McpServerConfig) -> Result<(), String> {
         ));
     }
 
+    if let Some(install_hint) = launcher_install_hint(&config.command) {
+        if !crate::utils::env::command_exists(&config.command) {
+            return Err(format!(
+                "MCP server '{}' needs '{}', which was not found. {}",
+                config.name, config.command, install_hint
+            ));
+        }
+    }
+
+    let missing = McpService::missing_credentials(config);
+    if !missing.is_empty() {
+        return Err(format!(
+            "MCP server '{}' is missing required settings: {}. Add them in the server's configuration before enabling it.",
+            config.name,
+            missing.join(", ")
+        ));
+    }
+
     Ok(())
 }
 
@@ -63,6 +92,7 @@ pub async fn remove_mcp_server(id: String) -> Result<(), String> {
         .map_err(|e| format!("Failed to load global settings: {}", e))?;
 
     settings.mcp_servers.retain(|s| s.id != id);
+    McpService::invalidate_tool_cache(&id);
 
     SettingsService::save_global_settings(&settings)
         .map_err(|e| format!("Failed to save global settings: {}", e))
@@ -105,13 +135,97 @@ pub async fn update_mcp_server(config: McpServerConfig) -> Result<(), String> {
         .map_err(|e| format!("Failed to save global settings: {}", e))
 }
 
+/// Start a configured server (enabled or not) and return the tools it exposes.
+/// Results are cached per server until its launch config changes or `refresh` is set.
 #[tauri::command]
-pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServerConfig>, String> {
-    let client = reqwest::Client::new();
-    let mut all_servers = Vec::new();
+pub async fn list_mcp_server_tools(id: String, refresh: Option<bool>) -> Result<Vec<McpTool>, String> {
+    let settings = SettingsService::load_global_settings()
+        .map_err(|e| format!("Failed to load global settings: {}", e))?;
+
+    let config = settings
+        .mcp_servers
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("MCP server with ID '{}' not found", id))?;
+
+    // Listing tools launches the server, so apply the same checks as enabling it
+    can_enable_mcp_server(&config)?;
+
+    McpService::new()
+        .list_server_tools(&config, refresh.unwrap_or(false))
+        .await
+        .map_err(|e| format!("Failed to list tools for MCP server '{}': {}", id, e))
+}
+
+/// Check that a configured server starts, completes the MCP handshake and lists its
+/// tools. Launch failures and timeouts come back as `ok: false` with the reason.
+#[tauri::command]
+pub async fn test_mcp_server(id: String) -> Result<McpServerTestResult, String> {
+    let settings = SettingsService::load_global_settings()
+        .map_err(|e| format!("Failed to load global settings: {}", e))?;
+
+    let config = settings
+        .mcp_servers
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("MCP server with ID '{}' not found", id))?;
+
+    // Testing launches the server, so apply the same checks as enabling it
+    if let Err(e) = can_enable_mcp_server(&config) {
+        return Ok(McpServerTestResult::failed(e));
+    }
+
+    Ok(McpService::new()
+        .test_server(&config, std::time::Duration::from_secs(SERVER_TEST_TIMEOUT_SECS))
+        .await)
+}
+
+/// Marketplace servers matching `query`. While the cached listing is fresh it is filtered
+/// locally instead of searching remotely, unless `refresh` is set; any cached copy is used
+/// when offline.
+#[tauri::command]
+pub async fn fetch_mcp_marketplace(
+    query: Option<String>,
+    refresh: Option<bool>,
+) -> Result<Vec<McpServerConfig>, String> {
+    let query = query.filter(|q| !q.trim().is_empty());
+    let refresh = refresh.unwrap_or(false);
+
+    if !refresh {
+        if let Some(servers) = McpMarketplaceCache::load_fresh() {
+            return Ok(servers
+                .into_iter()
+                .filter(|s| matches_query(&s.name, s.description.as_deref(), &query))
+                .collect());
+        }
+    }
+
+    let (servers, reached_remote) = fetch_marketplace_from_network(query.clone()).await;
+    if reached_remote {
+        if query.is_none() {
+            if let Err(e) = McpMarketplaceCache::store(&servers) {
+                log::warn!("Failed to cache MCP marketplace: {}", e);
+            }
+        }
+        return Ok(servers);
+    }
+
+    // Offline: a stale listing beats the handful of built-in servers
+    match McpMarketplaceCache::load_any() {
+        Some(cached) => {
+            log::info!("MCP marketplace unreachable; serving cached listing");
+            Ok(cached
+                .into_iter()
+                .filter(|s| matches_query(&s.name, s.description.as_deref(), &query))
+                .collect())
+        }
+        None => Ok(servers),
+    }
+}
 
-    // Helper to check if a server matches the query
-    let matches_query = |name: &str, desc: Option<&str>, q: &Option<String>| match q {
+/// Whether a server's name or description contains the query (case-insensitive)
+fn matches_query(name: &str, desc: Option<&str>, q: &Option<String>) -> bool {
+    match q {
         None => true,
         Some(query_str) => {
             let low_q = query_str.to_lowercase();
@@ -120,7 +234,15 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
                     .map(|d| d.to_lowercase().contains(&low_q))
                     .unwrap_or(false)
         }
-    };
+    }
+}
+
+/// Build the marketplace listing from the built-in servers, mcpmarket.com and the
+/// official registry. The flag reports whether either remote source answered.
+async fn fetch_marketplace_from_network(query: Option<String>) -> (Vec<McpServerConfig>, bool) {
+    let client = reqwest::Client::new();
+    let mut all_servers = Vec::new();
+    let mut reached_remote = false;
 
     // 0. Manual injection of core and PM-focused tools to ensure they are "out of the box"
     let core_tools = vec![
@@ -138,6 +260,9 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
             source: Some("registry".to_string()),
             categories: Some(vec!["Product Management".to_string(), "Featured".to_string()]),
             icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
         },
         McpServerConfig {
             id: "jira-mcp".to_string(),
@@ -153,6 +278,9 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
             source: Some("registry".to_string()),
             categories: Some(vec!["Product Management".to_string(), "Featured".to_string()]),
             icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
         },
         McpServerConfig {
             id: "monday-mcp".to_string(),
@@ -168,6 +296,9 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
             source: Some("registry".to_string()),
             categories: Some(vec!["Productivity".to_string(), "Featured".to_string()]),
             icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
         },
         McpServerConfig {
             id: "productboard-mcp".to_string(),
@@ -183,6 +314,9 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
             source: Some("registry".to_string()),
             categories: Some(vec!["Product Management".to_string(), "Featured".to_string()]),
             icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
         },
         McpServerConfig {
             id: "mcp-github".to_string(),
@@ -198,6 +332,9 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
             source: Some("registry".to_string()),
             categories: Some(vec!["Core".to_string(), "Featured".to_string()]),
             icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
         },
         McpServerConfig {
             id: "mcp-filesystem".to_string(),
@@ -213,6 +350,9 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
             source: Some("registry".to_string()),
             categories: Some(vec!["Core".to_string(), "Featured".to_string()]),
             icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
         },
     ];
 
@@ -242,6 +382,7 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
         .await
     {
         if res.status().is_success() {
+            reached_remote = true;
             if let Ok(market_data) = res.json::<McpMarketSearchResponse>().await {
                 for tool in market_data.tools {
                     let id = tool
@@ -299,6 +440,9 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
                             .as_ref()
                             .map(|cats| cats.iter().map(|c| c.name.clone()).collect()),
                         icon_url: tool.owner.as_ref().and_then(|o| o.avatar.clone()),
+                        tool_timeout_secs: None,
+                        allowed_tools: None,
+                        blocked_tools: None,
                     };
                     all_servers.push(config);
                 }
@@ -355,25 +499,32 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
         if !res.status().is_success() {
             break;
         }
+        reached_remote = true;
 
         if let Ok(registry_data) = res.json::<RegistryResponse>().await {
             for item in registry_data.servers {
                 let server = item.server;
                 if let Some(packages) = &server.packages {
                     for pkg in packages {
-                        if pkg.registry_type.to_lowercase() == "npm" {
-                            let id = pkg.identifier.replace("/", "-").replace("@", "");
+                        if let Some((command, args)) =
+                            registry_package_launch(&pkg.registry_type, &pkg.identifier)
+                        {
+                            let id = pkg
+                                .identifier
+                                .replace("/", "-")
+                                .replace("@", "")
+                                .replace(":", "-");
 
                             // Check if we already have this
                             let mut exists = false;
                             for s in all_servers.iter_mut() {
                                 if s.name.to_lowercase() == server.name.to_lowercase()
                                     || s.id == id
-                                    || (s.args.len() >= 2 && s.args[1] == pkg.identifier)
+                                    || s.args.last() == Some(&pkg.identifier)
                                 {
                                     // Update with concrete install info if it was a placeholder
-                                    s.command = "npx".to_string();
-                                    s.args = vec!["-y".to_string(), pkg.identifier.clone()];
+                                    s.command = command.clone();
+                                    s.args = args.clone();
                                     s.source = Some("registry".to_string());
                                     exists = true;
                                     break;
@@ -413,8 +564,8 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
                                     id: id.clone(),
                                     name: display_name,
                                     description: server.description.clone(),
-                                    command: "npx".to_string(),
-                                    args: vec!["-y".to_string(), pkg.identifier.clone()],
+                                    command,
+                                    args,
                                     env: None,
                                     secrets_env: None,
                                     enabled: false,
@@ -423,6 +574,9 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
                                     source: Some("registry".to_string()),
                                     categories: None,
                                     icon_url: None,
+                                    tool_timeout_secs: None,
+                                    allowed_tools: None,
+                                    blocked_tools: None,
                                 };
 
                                 if query.is_none()
@@ -452,7 +606,7 @@ pub async fn fetch_mcp_marketplace(query: Option<String>) -> Result<Vec<McpServe
         }
     }
 
-    Ok(all_servers)
+    (all_servers, reached_remote)
 }
 
 #[tauri::command]
@@ -3,6 +3,7 @@ pub mod cancellation;
 pub mod chat_commands;
 pub mod config_commands;
 pub mod file_commands;
+pub mod health_commands;
 pub mod installation_commands;
 pub mod markdown_commands;
 pub mod mcp;
@@ -1,12 +1,45 @@
-use crate::models::project::Project;
+use crate::models::project::{FileSort, Project, ProjectDetails, ProjectFilePage};
+use crate::services::backup_service::BackupService;
+use crate::services::context_service::ContextService;
+use crate::services::project_archive_service::ProjectArchiveService;
+use crate::services::project_maintenance_service::{
+    CompactionReport, ProjectMaintenanceService, DEFAULT_KEEP_RECENT_CHATS, DEFAULT_RETENTION_DAYS,
+};
 use crate::services::project_service::ProjectService;
+use crate::services::project_validation_service::{
+    ProjectValidationReport, ProjectValidationService,
+};
+use crate::services::research_record_service::{ResearchRecordFormat, ResearchRecordService};
 use crate::services::settings_service::SettingsService;
+use tauri::{AppHandle, Emitter};
 
 #[tauri::command]
 pub async fn get_all_projects() -> Result<Vec<Project>, String> {
     ProjectService::discover_projects().map_err(|e| format!("Failed to load all projects: {}", e))
 }
 
+/// Emit `project-discovered` for each project as it is loaded, then
+/// `project-discovery-done` with the count (or the error)
+#[tauri::command]
+pub async fn discover_projects_stream(app: AppHandle) -> Result<usize, String> {
+    let emitter = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        ProjectService::discover_projects_each(|project| {
+            let _ = emitter.emit("project-discovered", project);
+        })
+    })
+    .await
+    .map_err(|e| format!("Project discovery task failed: {}", e))?
+    .map(|projects| projects.len())
+    .map_err(|e| format!("Failed to load all projects: {}", e));
+
+    let _ = app.emit(
+        "project-discovery-done",
+        serde_json::json!({ "count": result.as_ref().ok(), "error": result.as_ref().err() }),
+    );
+    result
+}
+
 #[tauri::command]
 pub async fn get_project(project_id: String) -> Result<Project, String> {
     let projects_path = SettingsService::get_projects_path()
@@ -37,24 +70,158 @@ pub async fn create_project(
     }
 }
 
+/// Check every project's metadata, layout, skills and workflows and report problems
+#[tauri::command]
+pub async fn validate_all_projects() -> Result<Vec<ProjectValidationReport>, String> {
+    ProjectValidationService::validate_all()
+        .map_err(|e| format!("Failed to validate projects: {}", e))
+}
+
+/// Archive older chats and purge workflow runs and trash past the retention window
+#[tauri::command]
+pub async fn compact_project(
+    project_id: String,
+    keep_recent_chats: Option<usize>,
+    retention_days: Option<u32>,
+) -> Result<CompactionReport, String> {
+    ProjectMaintenanceService::compact_project(
+        &project_id,
+        keep_recent_chats.unwrap_or(DEFAULT_KEEP_RECENT_CHATS),
+        retention_days.unwrap_or(DEFAULT_RETENTION_DAYS),
+    )
+    .map_err(|e| format!("Failed to compact project: {}", e))
+}
+
+/// Write the project's research log, token usage, chats and applied changes to
+/// `.metadata/exports/` and return the file path
+#[tauri::command]
+pub async fn export_research_record(
+    project_id: String,
+    format: ResearchRecordFormat,
+) -> Result<String, String> {
+    ResearchRecordService::export(&project_id, format)
+        .await
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to export research record: {}", e))
+}
+
+/// Zip the project, without secrets or derived data, into `.metadata/exports/{id}.zip`
+/// and return the zip path
+#[tauri::command]
+pub async fn export_project(project_id: String) -> Result<String, String> {
+    ProjectArchiveService::export(&project_id)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to export project: {}", e))
+}
+
+/// Import a zip made by `export_project`, renaming the project if its id is taken
+#[tauri::command]
+pub async fn import_project(app: AppHandle, zip_path: String) -> Result<Project, String> {
+    let project = ProjectArchiveService::import(std::path::Path::new(&zip_path))
+        .map_err(|e| format!("Failed to import project: {}", e))?;
+    let _ = app.emit("project-added", &project);
+    Ok(project)
+}
+
+/// Snapshot the project under `backups/projects/{id}/` and return the snapshot path
+#[tauri::command]
+pub async fn backup_project(project_id: String) -> Result<String, String> {
+    BackupService::backup_project(&project_id)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| format!("Failed to back up project: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_project_files(project_id: String) -> Result<Vec<String>, String> {
     ProjectService::list_project_files(&project_id)
         .map_err(|e| format!("Failed to list project files: {}", e))
 }
 
+/// A page of project files with size and modification time, for large projects
+#[tauri::command]
+pub async fn get_project_files_page(
+    project_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort: Option<FileSort>,
+) -> Result<ProjectFilePage, String> {
+    ProjectService::list_project_files_page(
+        &project_id,
+        offset.unwrap_or(0),
+        limit,
+        sort.unwrap_or_default(),
+    )
+    .map_err(|e| format!("Failed to list project files: {}", e))
+}
+
+/// Create a project from an existing one's settings, workflows and markdown files,
+/// leaving its chats behind
+#[tauri::command]
+pub async fn duplicate_project(project_id: String, new_name: String) -> Result<Project, String> {
+    ProjectService::duplicate_project(&project_id, &new_name)
+        .map_err(|e| format!("Failed to duplicate project: {}", e))
+}
+
+/// Snapshot the project with `BackupService`, then delete it. Returns the snapshot path so
+/// the project can be recovered; pre-delete snapshots are never pruned.
 #[tauri::command]
-pub async fn delete_project(project_id: String) -> Result<(), String> {
+pub async fn delete_project(app: AppHandle, project_id: String) -> Result<String, String> {
     log::info!("Deleting project: {}", project_id);
+    let snapshot = BackupService::backup_before_delete(&project_id)
+        .map_err(|e| format!("Failed to back up project before deleting: {}", e))?;
     ProjectService::delete_project(&project_id)
-        .map_err(|e| format!("Failed to delete project: {}", e))
+        .map_err(|e| format!("Failed to delete project: {}", e))?;
+    let _ = app.emit("project-removed", &project_id);
+    Ok(snapshot.to_string_lossy().to_string())
 }
 
+/// Rename a project. With `rename_directory` its folder and id follow the new name (after a
+/// backup when risky-op backups are on) and the UI sees the old id removed and the project
+/// added under the new one.
 #[tauri::command]
-pub async fn rename_project(project_id: String, new_name: String) -> Result<(), String> {
+pub async fn rename_project(
+    app: AppHandle,
+    project_id: String,
+    new_name: String,
+    rename_directory: Option<bool>,
+) -> Result<Project, String> {
     log::info!("Renaming project {} to {}", project_id, new_name);
-    ProjectService::update_project_metadata(&project_id, Some(new_name), None)
-        .map_err(|e| format!("Failed to rename project: {}", e))
+    let rename_directory = rename_directory.unwrap_or(false);
+    if rename_directory {
+        BackupService::backup_before_risky_op(&project_id, "renaming its folder")
+            .map_err(|e| format!("Failed to back up project before renaming: {}", e))?;
+    }
+
+    let project = ProjectService::rename_project(&project_id, &new_name, rename_directory)
+        .map_err(|e| format!("Failed to rename project: {}", e))?;
+    if project.id == project_id {
+        let _ = app.emit("project-modified", &project.id);
+    } else {
+        let _ = app.emit("project-removed", &project_id);
+        let _ = app.emit("project-added", &project);
+    }
+    Ok(project)
+}
+
+#[tauri::command]
+pub async fn update_project_tags(project_id: String, tags: Vec<String>) -> Result<Project, String> {
+    ProjectService::update_project_tags(&project_id, tags)
+        .map_err(|e| format!("Failed to update project tags: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_projects_by_tag(tag: String) -> Result<Vec<Project>, String> {
+    ProjectService::get_projects_by_tag(&tag)
+        .map_err(|e| format!("Failed to load projects by tag: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_project_details(
+    project_id: String,
+    details: ProjectDetails,
+) -> Result<Project, String> {
+    ProjectService::update_project_details(&project_id, details)
+        .map_err(|e| format!("Failed to update project details: {}", e))
 }
 
 #[tauri::command]
@@ -65,3 +232,15 @@ pub async fn get_project_cost(project_id: String) -> Result<f64, String> {
     let cost_log = crate::models::cost::CostLog::load(&cost_log_path).unwrap_or_default();
     Ok(cost_log.total_cost())
 }
+
+#[tauri::command]
+pub async fn pin_context_file(project_id: String, file_path: String) -> Result<Vec<String>, String> {
+    ContextService::pin_file(&project_id, &file_path)
+        .map_err(|e| format!("Failed to pin context file: {}", e))
+}
+
+#[tauri::command]
+pub async fn unpin_context_file(project_id: String, file_path: String) -> Result<Vec<String>, String> {
+    ContextService::unpin_file(&project_id, &file_path)
+        .map_err(|e| format!("Failed to unpin context file: {}", e))
+}
@@ -1,9 +1,41 @@
-use crate::services::encryption_service::EncryptionService;
-use crate::services::secrets_service::{Secrets, SecretsService};
+use crate::services::api_key_pool::{ApiKeyPool, ApiKeySummary};
+use crate::services::encryption_service::{EncryptionInfo, EncryptionService};
+use crate::services::secrets_service::{Secrets, SecretsChangedEvent, SecretsService};
+use crate::services::settings_service::SettingsService;
+use tauri::Emitter;
 
 #[tauri::command]
-pub async fn save_secrets(secrets: Secrets) -> Result<(), String> {
-    SecretsService::save_secrets(&secrets).map_err(|e| format!("Failed to save secrets: {}", e))
+pub async fn save_secrets(app: tauri::AppHandle, secrets: Secrets) -> Result<(), String> {
+    SecretsService::save_secrets(&secrets).map_err(|e| format!("Failed to save secrets: {}", e))?;
+
+    let event = SecretsChangedEvent {
+        changed_ids: secrets.provided_ids(),
+        generation: SecretsService::generation(),
+    };
+    let _ = app.emit("secrets-changed", &event);
+    Ok(())
+}
+
+/// Stored Anthropic keys used for rotation, redacted
+#[tauri::command]
+pub async fn list_api_keys() -> Result<Vec<ApiKeySummary>, String> {
+    let settings = SettingsService::load_global_settings()
+        .map_err(|e| format!("Failed to load settings: {}", e))?;
+    ApiKeyPool::list_keys(&settings.hosted.api_key_secret_id)
+        .map_err(|e| format!("Failed to list API keys: {}", e))
+}
+
+/// Store an additional Anthropic key as `claude_api_key_N` and return its id
+#[tauri::command]
+pub async fn add_api_key(app: tauri::AppHandle, api_key: String) -> Result<String, String> {
+    let id = ApiKeyPool::add_key(&api_key).map_err(|e| format!("Failed to add API key: {}", e))?;
+
+    let event = SecretsChangedEvent {
+        changed_ids: vec![id.clone()],
+        generation: SecretsService::generation(),
+    };
+    let _ = app.emit("secrets-changed", &event);
+    Ok(id)
 }
 
 #[tauri::command]
@@ -42,7 +74,45 @@ pub async fn test_encryption() -> Result<bool, String> {
     Ok(test_data == decrypted)
 }
 
+#[tauri::command]
+pub async fn get_encryption_info() -> Result<EncryptionInfo, String> {
+    EncryptionService::get_info().map_err(|e| format!("Failed to get encryption info: {}", e))
+}
+
 #[tauri::command]
 pub async fn reset_encryption_key() -> Result<(), String> {
     EncryptionService::delete_master_key().map_err(|e| e.to_string())
 }
+
+/// Re-encrypt all secrets under a new master key, keeping every secret. Returns when the
+/// rotation happened (RFC 3339).
+#[tauri::command]
+pub async fn rotate_encryption_key() -> Result<String, String> {
+    SecretsService::rotate_encryption_key()
+        .map_err(|e| format!("Failed to rotate encryption key: {}", e))
+}
+
+/// Export all secrets as a blob encrypted with `passphrase`, for moving to another machine
+#[tauri::command]
+pub async fn export_secrets(passphrase: String) -> Result<String, String> {
+    SecretsService::export_secrets(&passphrase)
+        .map_err(|e| format!("Failed to export secrets: {}", e))
+}
+
+/// Merge a blob from `export_secrets` into the local store. Returns the imported ids.
+#[tauri::command]
+pub async fn import_secrets(
+    app: tauri::AppHandle,
+    blob: String,
+    passphrase: String,
+) -> Result<Vec<String>, String> {
+    let ids = SecretsService::import_secrets(&blob, &passphrase)
+        .map_err(|e| format!("Failed to import secrets: {}", e))?;
+
+    let event = SecretsChangedEvent {
+        changed_ids: ids.clone(),
+        generation: SecretsService::generation(),
+    };
+    let _ = app.emit("secrets-changed", &event);
+    Ok(ids)
+}
@@ -1,12 +1,37 @@
-use crate::models::skill::{Skill, SkillCategory};
+use crate::models::skill::{MarketplaceSkill, Skill, SkillCategory, SkillPromptPreview};
+use crate::services::mcp_service::McpService;
+use crate::services::skill_marketplace_service::SkillMarketplaceService;
 use crate::services::skill_service::SkillService;
 use std::collections::HashMap;
+use tauri::{AppHandle, Emitter};
 
 #[tauri::command]
 pub async fn get_all_skills() -> Result<Vec<Skill>, String> {
     SkillService::get_all_skills().map_err(|e| e.to_string())
 }
 
+/// Emit `skill-discovered` for each skill as it is parsed, then `skill-discovery-done`
+/// with the count (or the error). Resolves once discovery has finished.
+#[tauri::command]
+pub async fn discover_skills_stream(app: AppHandle) -> Result<usize, String> {
+    let emitter = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        SkillService::discover_skills_each(|skill| {
+            let _ = emitter.emit("skill-discovered", skill);
+        })
+    })
+    .await
+    .map_err(|e| format!("Skill discovery task failed: {}", e))?
+    .map(|skills| skills.len())
+    .map_err(|e| e.to_string());
+
+    let _ = app.emit(
+        "skill-discovery-done",
+        serde_json::json!({ "count": result.as_ref().ok(), "error": result.as_ref().err() }),
+    );
+    result
+}
+
 #[tauri::command]
 pub async fn get_skill(skill_id: String) -> Result<Skill, String> {
     SkillService::get_skill(&skill_id).map_err(|e| e.to_string())
@@ -38,23 +63,20 @@ pub async fn create_skill_template(
         SkillCategory::Other => vec!["general".to_string()],
     };
 
-    let skill = SkillService::create_skill_template(skill_id, name, description, capabilities);
+    let mut skill = SkillService::create_skill_template(skill_id, name, description, capabilities);
+    skill.category = category.as_str().to_string();
 
     Ok(skill)
 }
 
 #[tauri::command]
 pub async fn get_skills_by_category(category: SkillCategory) -> Result<Vec<Skill>, String> {
-    // Convert SkillCategory to string for filtering
-    let category_str = match category {
-        SkillCategory::Research => "research",
-        SkillCategory::Development => "development",
-        SkillCategory::Writing => "writing",
-        SkillCategory::Analysis => "analysis",
-        SkillCategory::Other => "general",
-    };
+    SkillService::get_skills_by_category(category.as_str()).map_err(|e| e.to_string())
+}
 
-    SkillService::get_skills_by_category(category_str).map_err(|e| e.to_string())
+#[tauri::command]
+pub async fn get_skills_by_capability(capability: String) -> Result<Vec<Skill>, String> {
+    SkillService::get_skills_by_capability(&capability).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -67,12 +89,54 @@ pub async fn render_skill_prompt(
     skill.render_prompt(params).map_err(|e| e.to_string())
 }
 
+/// Render a skill's prompt with sample params for the editor. Placeholders without a value
+/// are left in place and listed instead of failing the render.
+#[tauri::command]
+pub async fn preview_skill_prompt(
+    skill_id: String,
+    params: HashMap<String, String>,
+) -> Result<SkillPromptPreview, String> {
+    let skill = SkillService::get_skill(&skill_id).map_err(|e| e.to_string())?;
+    Ok(skill.preview_prompt(&params))
+}
+
+/// Structural problems with the skill, plus any required tools that the enabled MCP
+/// servers don't currently provide
 #[tauri::command]
 pub async fn validate_skill(skill: Skill) -> Result<Vec<String>, String> {
-    match skill.validate() {
-        Ok(_) => Ok(Vec::new()),
-        Err(errors) => Ok(errors),
+    let mut issues = skill.validate().err().unwrap_or_default();
+
+    if !skill.required_tools.is_empty() {
+        let available: Vec<String> = McpService::new()
+            .get_tools()
+            .await
+            .map_err(|e| format!("Failed to list MCP tools: {}", e))?
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect();
+        for tool in skill.missing_required_tools(&available) {
+            issues.push(format!(
+                "required tool '{}' is not provided by any enabled MCP server",
+                tool
+            ));
+        }
     }
+
+    Ok(issues)
+}
+
+#[tauri::command]
+pub async fn fetch_skill_marketplace(query: Option<String>) -> Result<Vec<MarketplaceSkill>, String> {
+    SkillMarketplaceService::fetch(query)
+        .await
+        .map_err(|e| format!("Failed to fetch skill marketplace: {}", e))
+}
+
+#[tauri::command]
+pub async fn install_marketplace_skill(id: String) -> Result<Skill, String> {
+    SkillMarketplaceService::install(&id)
+        .await
+        .map_err(|e| format!("Failed to install skill: {}", e))
 }
 
 // ===== Backward Compatibility Commands =====
@@ -83,9 +147,11 @@ pub async fn create_skill(
     name: String,
     description: String,
     prompt_template: String,
+    category: Option<String>,
     capabilities: Vec<String>,
 ) -> Result<Skill, String> {
-    SkillService::create_skill(&name, &description, &prompt_template, capabilities)
+    let category = category.unwrap_or_default();
+    SkillService::create_skill(&name, &description, &prompt_template, &category, capabilities)
         .map_err(|e| e.to_string())
 }
 
@@ -94,6 +160,25 @@ pub async fn update_skill(skill: Skill) -> Result<(), String> {
     SkillService::update_skill(&skill).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn set_skill_enabled(skill_id: String, enabled: bool) -> Result<Skill, String> {
+    SkillService::set_skill_enabled(&skill_id, enabled)
+        .map_err(|e| format!("Failed to update skill: {}", e))
+}
+
+/// A skill as one markdown file, with its metadata as frontmatter, for sharing
+#[tauri::command]
+pub async fn export_skill(skill_id: String) -> Result<String, String> {
+    SkillService::export_skill(&skill_id).map_err(|e| format!("Failed to export skill: {}", e))
+}
+
+/// Import a skill shared with `export_skill` (or any skill markdown). A taken id gets a
+/// numeric suffix rather than overwriting the existing skill.
+#[tauri::command]
+pub async fn import_skill_content(content: String) -> Result<Skill, String> {
+    SkillService::import_skill(&content).map_err(|e| format!("Failed to import skill: {}", e))
+}
+
 #[tauri::command]
 pub async fn import_skill(skill_command: String) -> Result<Skill, String> {
     // Create a temporary directory using tempfile crate
@@ -242,7 +327,7 @@ pub async fn import_skill(skill_command: String) -> Result<Skill, String> {
     }
 
     // Create the skill using SkillService
-    let skill = SkillService::create_skill(&name, &description, &cleaned_content, capabilities)
+    let skill = SkillService::create_skill(&name, &description, &cleaned_content, "", capabilities)
         .map_err(|e| e.to_string())?;
 
     log::info!("Successfully imported skill: {} (ID: {})", name, skill.id);
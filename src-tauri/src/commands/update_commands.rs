@@ -1,4 +1,5 @@
-use crate::updater::{UpdateManager, UpdateResult};
+use crate::updater::manifest::BackupVerification;
+use crate::updater::{BackupFile, BackupInfo, UpdateManager, UpdateResult};
 use anyhow::Result;
 use std::path::PathBuf;
 
@@ -47,6 +48,38 @@ pub async fn backup_user_data() -> Result<String, String> {
     Ok(backup_path.to_string_lossy().to_string())
 }
 
+/// Create a backup of user data as a single zip archive
+#[tauri::command]
+pub async fn backup_user_data_compressed() -> Result<String, String> {
+    log::info!("Creating compressed backup of user data...");
+
+    let manager = UpdateManager::with_default_path()
+        .map_err(|e| format!("Failed to create update manager: {}", e))?;
+
+    let backup_path = manager
+        .backup_user_data_compressed()
+        .await
+        .map_err(|e| format!("Failed to create backup: {}", e))?;
+
+    Ok(backup_path.to_string_lossy().to_string())
+}
+
+/// Create a backup that copies only files changed since the newest backup
+#[tauri::command]
+pub async fn backup_user_data_incremental() -> Result<String, String> {
+    log::info!("Creating incremental backup of user data...");
+
+    let manager = UpdateManager::with_default_path()
+        .map_err(|e| format!("Failed to create update manager: {}", e))?;
+
+    let backup_path = manager
+        .backup_user_data_incremental()
+        .await
+        .map_err(|e| format!("Failed to create backup: {}", e))?;
+
+    Ok(backup_path.to_string_lossy().to_string())
+}
+
 /// Verify installation integrity
 #[tauri::command]
 pub async fn verify_installation_integrity() -> Result<bool, String> {
@@ -68,12 +101,63 @@ pub async fn restore_from_backup(backup_path: String) -> Result<(), String> {
 
     let manager = UpdateManager::with_default_path()
         .map_err(|e| format!("Failed to create update manager: {}", e))?;
+    let backup_path = resolve_backup_path(&backup_path)?;
+
+    manager
+        .restore_if_needed(backup_path)
+        .await
+        .map_err(|e| format!("Failed to restore backup: {}", e))
+}
 
+/// Restore only the named files or directories (relative to the app data directory) from
+/// a backup, e.g. `projects/my-project` or `skills/summarize.md`
+#[tauri::command]
+pub async fn restore_items(backup_path: String, items: Vec<String>) -> Result<Vec<String>, String> {
+    log::info!("Restoring {} item(s) from backup: {}", items.len(), backup_path);
+
+    let manager = UpdateManager::with_default_path()
+        .map_err(|e| format!("Failed to create update manager: {}", e))?;
+    let backup_path = resolve_backup_path(&backup_path)?;
+
+    manager
+        .restore_items(&backup_path, &items)
+        .await
+        .map_err(|e| format!("Failed to restore items: {}", e))
+}
+
+/// Files inside a backup, for choosing what to restore
+#[tauri::command]
+pub async fn list_backup_contents(backup_path: String) -> Result<Vec<BackupFile>, String> {
+    let manager = UpdateManager::with_default_path()
+        .map_err(|e| format!("Failed to create update manager: {}", e))?;
+    let backup_path = resolve_backup_path(&backup_path)?;
+
+    manager
+        .list_backup_contents(&backup_path)
+        .map_err(|e| format!("Failed to list backup contents: {}", e))
+}
+
+/// Recompute checksums of a backup's files and report any that are missing or corrupted
+#[tauri::command]
+pub async fn verify_backup(backup_path: String) -> Result<BackupVerification, String> {
+    log::info!("Verifying backup: {}", backup_path);
+
+    let manager = UpdateManager::with_default_path()
+        .map_err(|e| format!("Failed to create update manager: {}", e))?;
+    let backup_path = resolve_backup_path(&backup_path)?;
+
+    manager
+        .verify_backup(&backup_path)
+        .map_err(|e| format!("Failed to verify backup: {}", e))
+}
+
+/// Canonical path of a backup, rejecting anything outside the app backups directory
+fn resolve_backup_path(backup_path: &str) -> Result<PathBuf, String> {
     let app_data_path = crate::utils::paths::get_app_data_dir()
         .map_err(|e| format!("Failed to get app data directory: {}", e))?;
     let backups_root = app_data_path.join("backups");
 
-    let requested = PathBuf::from(&backup_path);
+    let requested = PathBuf::from(backup_path);
     let canonical_requested = requested
         .canonicalize()
         .map_err(|_| "Backup path does not exist or is not accessible".to_string())?;
@@ -90,48 +174,21 @@ pub async fn restore_from_backup(backup_path: String) -> Result<(), String> {
         .and_then(|n| n.to_str())
         .unwrap_or_default();
     if !(backup_name.starts_with("backup_") || backup_name.starts_with("update_backup_")) {
-        return Err("Invalid backup directory name".to_string());
+        return Err("Invalid backup name".to_string());
     }
 
-    manager
-        .restore_if_needed(canonical_requested)
-        .await
-        .map_err(|e| format!("Failed to restore backup: {}", e))
+    Ok(canonical_requested)
 }
 
-/// List available backups
+/// List available backups, newest first
 #[tauri::command]
-pub async fn list_backups() -> Result<Vec<String>, String> {
-    use std::fs;
-
-    let app_data_path = crate::utils::paths::get_app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    let backups_dir = app_data_path.join("backups");
-
-    if !backups_dir.exists() {
-        return Ok(Vec::new());
-    }
+pub async fn list_backups() -> Result<Vec<BackupInfo>, String> {
+    let manager = UpdateManager::with_default_path()
+        .map_err(|e| format!("Failed to create update manager: {}", e))?;
 
-    let mut backups: Vec<String> = fs::read_dir(&backups_dir)
-        .map_err(|e| format!("Failed to read backups directory: {}", e))?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| {
-            entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false)
-                && (entry.file_name().to_string_lossy().starts_with("backup_")
-                    || entry
-                        .file_name()
-                        .to_string_lossy()
-                        .starts_with("update_backup_"))
-        })
-        .map(|entry| entry.path().to_string_lossy().to_string())
-        .collect();
-
-    // Sort by name (which includes timestamp)
-    backups.sort();
-    backups.reverse(); // Most recent first
-
-    Ok(backups)
+    manager
+        .list_backups()
+        .map_err(|e| format!("Failed to read backups directory: {}", e))
 }
 
 #[cfg(test)]
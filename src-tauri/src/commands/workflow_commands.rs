@@ -1,6 +1,7 @@
 use crate::models::workflow::*;
 use crate::services::workflow_service::WorkflowService;
 use crate::services::background_workflow_service::BackgroundWorkflowService;
+use crate::services::cancellation_service::CancellationService;
 use chrono::Utc;
 use tauri::{Emitter, Window, Manager};
 
@@ -9,6 +10,13 @@ pub async fn get_project_workflows(project_id: String) -> Result<Vec<Workflow>,
     WorkflowService::load_project_workflows(&project_id).map_err(|e| e.to_string())
 }
 
+/// Load a project's workflows together with the files that failed to load
+#[tauri::command]
+pub async fn scan_project_workflows(project_id: String) -> Result<WorkflowLoadReport, String> {
+    WorkflowService::scan_project_workflows(&project_id)
+        .map_err(|e| format!("Failed to scan workflows: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_workflow(project_id: String, workflow_id: String) -> Result<Workflow, String> {
     WorkflowService::load_workflow(&project_id, &workflow_id).map_err(|e| e.to_string())
@@ -22,12 +30,7 @@ pub async fn create_workflow(
     window: Window,
 ) -> Result<Workflow, String> {
     // Generate workflow ID from name
-    let workflow_id = name
-        .to_lowercase()
-        .replace(' ', "-")
-        .chars()
-        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
-        .collect::<String>();
+    let workflow_id = crate::utils::slug::slugify(&name);
 
     let now = Utc::now().to_rfc3339();
 
@@ -54,6 +57,8 @@ pub async fn create_workflow(
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     // Save the new workflow
@@ -116,12 +121,23 @@ pub async fn execute_workflow(
     Ok(run_id)
 }
 
+/// Stop a running workflow. Steps that already finished keep their outputs; the run
+/// is recorded as `Cancelled`.
+#[tauri::command]
+pub async fn cancel_workflow(project_id: String, workflow_id: String) -> Result<(), String> {
+    CancellationService::global()
+        .cancel_process(&CancellationService::workflow_key(&project_id, &workflow_id))
+        .await
+        .map_err(|e| format!("Failed to cancel workflow: {}", e))
+}
+
+/// Saved runs of a workflow, newest first, with each step's status, output and duration
 #[tauri::command]
-pub async fn get_workflow_history(
+pub async fn get_workflow_runs(
     project_id: String,
     workflow_id: String,
 ) -> Result<Vec<WorkflowRunRecord>, String> {
-    Ok(BackgroundWorkflowService::get_workflow_history(&project_id, &workflow_id))
+    Ok(BackgroundWorkflowService::get_workflow_runs(&project_id, &workflow_id))
 }
 
 #[tauri::command]
@@ -174,6 +190,9 @@ pub async fn clear_workflow_schedule(
     Ok(workflow)
 }
 
+/// Returns the validation errors, if any. Besides structure and dependencies this
+/// checks that every `{{steps.<step_id>.output}}` reference in a step's config names
+/// a step it depends on, directly or transitively; see `Workflow::validate`.
 #[tauri::command]
 pub async fn validate_workflow(workflow: Workflow) -> Result<Vec<String>, String> {
     match workflow.validate() {
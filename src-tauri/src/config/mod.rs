@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::utils::fs::write_atomic;
 use crate::utils::paths::get_app_data_dir;
 
 /// Application configuration that persists installation state and settings
@@ -88,7 +89,7 @@ impl AppConfig {
             fs::create_dir_all(parent).context("Failed to create config directory")?;
         }
 
-        fs::write(path.as_ref(), content).context("Failed to write config file")?;
+        write_atomic(path.as_ref(), content).context("Failed to write config file")?;
 
         Ok(())
     }
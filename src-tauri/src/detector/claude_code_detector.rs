@@ -6,6 +6,9 @@ use std::process::Command;
 
 use super::cli_detector::{check_command_in_path, get_home_based_paths, CliDetector, CliToolInfo};
 
+/// Oldest Claude Code release the app supports
+pub const MINIMUM_CLAUDE_CODE_VERSION: &str = "1.0.0";
+
 /// Claude Code CLI detector implementation with enhanced verification
 pub struct ClaudeCodeDetector;
 
@@ -397,6 +400,11 @@ impl CliDetector for ClaudeCodeDetector {
         paths
     }
 
+    /// Older releases lack flags the agent integration relies on and fail silently
+    fn minimum_version(&self) -> Option<&str> {
+        Some(MINIMUM_CLAUDE_CODE_VERSION)
+    }
+
     fn get_installation_instructions(&self) -> String {
         #[cfg(target_os = "macos")]
         {
@@ -1,11 +1,14 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 
+use crate::updater::changelog::compare_versions;
+
 /// Common information structure for all CLI tools
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CliToolInfo {
@@ -24,6 +27,8 @@ pub struct CliToolInfo {
 struct CachedDetectionResult {
     info: CliToolInfo,
     timestamp: SystemTime,
+    /// Modification time of the detected binary, to notice reinstalls and upgrades
+    binary_modified: Option<SystemTime>,
 }
 
 /// Trait that all CLI detectors must implement
@@ -57,6 +62,12 @@ pub trait CliDetector: Send + Sync {
     /// Get installation instructions
     fn get_installation_instructions(&self) -> String;
 
+    /// Oldest version this app works with (optional). Older installs are still reported
+    /// as installed, with an upgrade message in `CliToolInfo.error`.
+    fn minimum_version(&self) -> Option<&str> {
+        None
+    }
+
     /// Verify if the tool exists at the given path
     async fn verify_path(&self, path: &std::path::Path) -> bool;
 }
@@ -94,12 +105,14 @@ impl CliDetectorRegistry {
         self.detectors.insert(name, detector);
     }
 
-    /// Detect a specific CLI tool by name
-    pub async fn detect(&self, tool_name: &str) -> Result<CliToolInfo> {
+    /// Detect a specific CLI tool by name. `force_refresh` bypasses the cache.
+    pub async fn detect(&self, tool_name: &str, force_refresh: bool) -> Result<CliToolInfo> {
         // Check cache first
-        if let Some(cached) = self.get_from_cache(tool_name) {
-            log::debug!("Using cached detection result for {}", tool_name);
-            return Ok(cached.info);
+        if !force_refresh {
+            if let Some(cached) = self.get_from_cache(tool_name) {
+                log::debug!("Using cached detection result for {}", tool_name);
+                return Ok(cached.info);
+            }
         }
 
         // Get detector
@@ -112,10 +125,7 @@ impl CliDetectorRegistry {
         log::info!("Detecting {} installation...", tool_name);
         let info = detector.detect().await?;
 
-        // Cache the result
-        self.cache_result(tool_name, info.clone());
-
-        Ok(info)
+        Ok(self.finish_detection(detector.as_ref(), info))
     }
 
     /// Detect with a preferred path first
@@ -136,38 +146,48 @@ impl CliDetectorRegistry {
         }
 
         // Fallback to normal detection (which uses cache)
-        self.detect(tool_name).await
+        self.detect(tool_name, false).await
     }
 
-    /// Detect all registered CLI tools
-    pub async fn detect_all(&self) -> HashMap<String, CliToolInfo> {
-        let mut results = HashMap::new();
-
-        for (name, detector) in &self.detectors {
-            match self.detect(name).await {
-                Ok(info) => {
-                    results.insert(name.clone(), info);
+    /// Detect all registered CLI tools concurrently. Uncached detections each run on
+    /// their own task, because several detectors make blocking process calls that
+    /// would otherwise serialize on a single task.
+    pub async fn detect_all(&self, force_refresh: bool) -> HashMap<String, CliToolInfo> {
+        let detections = self.detectors.iter().map(|(name, detector)| async move {
+            let cached = if force_refresh { None } else { self.get_from_cache(name) };
+            let result = match cached {
+                Some(cached) => Ok(cached.info),
+                None => {
+                    let task_detector = Arc::clone(detector);
+                    match tokio::spawn(async move { task_detector.detect().await }).await {
+                        Ok(result) => result.map(|info| self.finish_detection(detector.as_ref(), info)),
+                        Err(e) => Err(anyhow::anyhow!("Detection task failed: {}", e)),
+                    }
                 }
+            };
+            let info = match result {
+                Ok(info) => info,
                 Err(e) => {
                     log::error!("Failed to detect {}: {}", name, e);
-                    results.insert(
-                        name.clone(),
-                        CliToolInfo {
-                            name: detector.tool_name().to_string(),
-                            installed: false,
-                            version: None,
-                            path: None,
-                            in_path: false,
-                            running: None,
-                            authenticated: None,
-                            error: Some(e.to_string()),
-                        },
-                    );
+                    CliToolInfo {
+                        name: detector.tool_name().to_string(),
+                        installed: false,
+                        version: None,
+                        path: None,
+                        in_path: false,
+                        running: None,
+                        authenticated: None,
+                        error: Some(e.to_string()),
+                    }
                 }
-            }
-        }
+            };
+            (name.clone(), info)
+        });
 
-        results
+        futures::future::join_all(detections)
+            .await
+            .into_iter()
+            .collect()
     }
 
     /// Clear cache for a specific tool
@@ -186,11 +206,16 @@ impl CliDetectorRegistry {
         }
     }
 
-    /// Get installation instructions for a tool
+    /// Get installation instructions for a tool, including its minimum version
     pub fn get_installation_instructions(&self, tool_name: &str) -> Option<String> {
-        self.detectors
-            .get(tool_name)
-            .map(|d| d.get_installation_instructions())
+        self.detectors.get(tool_name).map(|d| match d.minimum_version() {
+            Some(minimum) => format!(
+                "{}\n\nVersion {} or newer is required.",
+                d.get_installation_instructions(),
+                minimum
+            ),
+            None => d.get_installation_instructions(),
+        })
     }
 
     /// Check if a tool is registered
@@ -205,12 +230,19 @@ impl CliDetectorRegistry {
 
     // Private helper methods
 
+    /// Apply the version floor and cache a fresh detection result
+    fn finish_detection(&self, detector: &dyn CliDetector, mut info: CliToolInfo) -> CliToolInfo {
+        check_minimum_version(detector, &mut info);
+        self.cache_result(detector.tool_name(), info.clone());
+        info
+    }
+
     fn get_from_cache(&self, tool_name: &str) -> Option<CachedDetectionResult> {
         if let Ok(cache) = self.cache.read() {
             if let Some(cached) = cache.get(tool_name) {
                 // Check if cache is still valid
                 if let Ok(elapsed) = cached.timestamp.elapsed() {
-                    if elapsed < self.cache_duration {
+                    if elapsed < self.cache_duration && !Self::binary_changed(cached) {
                         return Some(cached.clone());
                     }
                 }
@@ -219,13 +251,23 @@ impl CliDetectorRegistry {
         None
     }
 
+    /// Whether the cached binary was replaced or removed since it was detected
+    fn binary_changed(cached: &CachedDetectionResult) -> bool {
+        match &cached.info.path {
+            Some(path) => binary_modified(path) != cached.binary_modified,
+            None => false,
+        }
+    }
+
     fn cache_result(&self, tool_name: &str, info: CliToolInfo) {
         if let Ok(mut cache) = self.cache.write() {
+            let binary_modified = info.path.as_deref().and_then(binary_modified);
             cache.insert(
                 tool_name.to_string(),
                 CachedDetectionResult {
                     info,
                     timestamp: SystemTime::now(),
+                    binary_modified,
                 },
             );
         }
@@ -238,6 +280,28 @@ impl Default for CliDetectorRegistry {
     }
 }
 
+fn binary_modified(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Set an upgrade error on `info` when its version is below the detector's floor.
+/// Versions are compared numerically, so 1.0.10 is newer than 1.0.9.
+pub fn check_minimum_version(detector: &dyn CliDetector, info: &mut CliToolInfo) {
+    let (Some(minimum), Some(version)) = (detector.minimum_version(), info.version.as_deref()) else {
+        return;
+    };
+    if !info.installed || info.error.is_some() {
+        return;
+    }
+    if compare_versions(version, minimum) == Ordering::Less {
+        log::warn!("{} {} is older than the required {}", info.name, version, minimum);
+        info.error = Some(format!(
+            "{} {} is too old; version {} or newer is required. Please upgrade.",
+            info.name, version, minimum
+        ));
+    }
+}
+
 /// Helper function to check if a command exists in PATH
 pub async fn check_command_in_path(cmd: &str) -> Option<PathBuf> {
     #[cfg(target_os = "windows")]
@@ -334,6 +398,54 @@ mod tests {
         name: String,
     }
 
+    struct VersionedMock {
+        version: &'static str,
+    }
+
+    #[async_trait]
+    impl CliDetector for VersionedMock {
+        fn tool_name(&self) -> &str {
+            "versioned"
+        }
+
+        fn command_name(&self) -> &str {
+            "versioned"
+        }
+
+        async fn detect(&self) -> Result<CliToolInfo> {
+            Ok(CliToolInfo {
+                name: "versioned".to_string(),
+                installed: true,
+                version: Some(self.version.to_string()),
+                path: Some(PathBuf::from("/usr/bin/versioned")),
+                in_path: true,
+                running: None,
+                authenticated: None,
+                error: None,
+            })
+        }
+
+        async fn get_version(&self, _path: &std::path::Path) -> Option<String> {
+            Some(self.version.to_string())
+        }
+
+        fn get_common_paths(&self) -> Vec<PathBuf> {
+            Vec::new()
+        }
+
+        fn get_installation_instructions(&self) -> String {
+            "Install versioned tool".to_string()
+        }
+
+        fn minimum_version(&self) -> Option<&str> {
+            Some("1.0.10")
+        }
+
+        async fn verify_path(&self, _path: &std::path::Path) -> bool {
+            true
+        }
+    }
+
     #[async_trait]
     impl CliDetector for MockDetector {
         fn tool_name(&self) -> &str {
@@ -394,7 +506,7 @@ mod tests {
         });
 
         registry.register(detector);
-        let result = registry.detect("mock").await;
+        let result = registry.detect("mock", false).await;
         assert!(result.is_ok());
 
         let info = result.unwrap();
@@ -402,6 +514,22 @@ mod tests {
         assert!(info.installed);
     }
 
+    #[tokio::test]
+    async fn test_minimum_version_gate() {
+        for (version, too_old) in [("1.0.9", true), ("0.9.99", true), ("1.0.10", false), ("1.1.0", false)] {
+            let mut registry = CliDetectorRegistry::new();
+            registry.register(Arc::new(VersionedMock { version }));
+            let info = registry.detect("versioned", false).await.unwrap();
+            assert!(info.installed);
+            assert_eq!(info.error.is_some(), too_old, "version {}", version);
+        }
+
+        let mut registry = CliDetectorRegistry::new();
+        registry.register(Arc::new(VersionedMock { version: "2.0.0" }));
+        let instructions = registry.get_installation_instructions("versioned").unwrap();
+        assert!(instructions.ends_with("Version 1.0.10 or newer is required."));
+    }
+
     #[tokio::test]
     async fn test_cache_functionality() {
         let registry = CliDetectorRegistry::with_cache_duration(Duration::from_secs(1));
@@ -413,19 +541,67 @@ mod tests {
         reg.register(detector);
 
         // First detection
-        let _ = reg.detect("mock").await;
+        let _ = reg.detect("mock", false).await;
 
         // Second detection should use cache
-        let result = reg.detect("mock").await;
+        let result = reg.detect("mock", false).await;
         assert!(result.is_ok());
 
         // Clear cache
         reg.clear_cache("mock");
 
         // Should detect again
-        let result = reg.detect("mock").await;
+        let result = reg.detect("mock", false).await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_cache_invalidated_when_binary_changes() {
+        let registry = CliDetectorRegistry::new();
+        let binary = tempfile::NamedTempFile::new().unwrap();
+        registry.cache_result(
+            "tool",
+            CliToolInfo {
+                name: "tool".to_string(),
+                installed: true,
+                version: Some("1.0.0".to_string()),
+                path: Some(binary.path().to_path_buf()),
+                in_path: true,
+                running: None,
+                authenticated: None,
+                error: None,
+            },
+        );
+        assert!(registry.get_from_cache("tool").is_some());
+
+        // An upgrade rewrites the binary
+        binary
+            .as_file()
+            .set_modified(SystemTime::now() - Duration::from_secs(3600))
+            .unwrap();
+        assert!(registry.get_from_cache("tool").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_detect_all_covers_every_detector() {
+        let mut registry = CliDetectorRegistry::new();
+        for name in ["alpha", "beta", "gamma"] {
+            registry.register(Arc::new(MockDetector {
+                name: name.to_string(),
+            }));
+        }
+
+        registry.clear_all_cache();
+        let results = registry.detect_all(false).await;
+        assert_eq!(results.len(), 3);
+        assert!(results.values().all(|info| info.installed));
+        assert_eq!(results["beta"].name, "beta");
+
+        // Results from the spawned detections land in the shared cache
+        assert!(["alpha", "beta", "gamma"]
+            .iter()
+            .all(|name| registry.get_from_cache(name).is_some()));
+    }
 }
 
 // Made with Bob
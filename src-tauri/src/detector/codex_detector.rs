@@ -0,0 +1,290 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::PathBuf;
+
+use super::cli_detector::{check_command_in_path, get_home_based_paths, CliDetector, CliToolInfo};
+
+/// Matches the version in `codex --version` output such as "codex-cli 0.46.0"
+static VERSION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+\.\d+\.\d+(-[0-9A-Za-z.]+)?").unwrap());
+
+/// OpenAI Codex CLI detector implementation
+pub struct CodexDetector;
+
+impl CodexDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Internal detection implementation
+    async fn detect_impl(&self) -> Result<CliToolInfo> {
+        log::debug!("Detecting Codex CLI installation...");
+
+        let mut codex_path: Option<PathBuf> = None;
+        let mut in_path = false;
+
+        // Strategy 1: Check PATH environment variable
+        if let Some(path) = check_command_in_path(self.command_name()).await {
+            if self.verify_executable(&path).await {
+                codex_path = Some(path);
+                in_path = true;
+            }
+        }
+
+        // Strategy 2: Check common installation directories
+        if codex_path.is_none() {
+            for path in self.get_common_paths() {
+                if path.exists() && self.verify_executable(&path).await {
+                    codex_path = Some(path);
+                    break;
+                }
+            }
+        }
+
+        // Strategy 3: Shell probe (Mac/Linux only)
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        if codex_path.is_none() {
+            if let Some(path) = super::cli_detector::probe_shell_path(self.command_name()).await {
+                if self.verify_executable(&path).await {
+                    codex_path = Some(path);
+                    in_path = true;
+                }
+            }
+        }
+
+        let Some(path) = codex_path else {
+            log::debug!("Codex CLI not detected");
+            return Ok(CliToolInfo {
+                name: self.tool_name().to_string(),
+                installed: false,
+                version: None,
+                path: None,
+                in_path: false,
+                running: None,
+                authenticated: None,
+                error: None,
+            });
+        };
+
+        log::info!("Found Codex CLI at {:?}", path);
+        let version = self.get_version(&path).await;
+        Ok(CliToolInfo {
+            name: self.tool_name().to_string(),
+            installed: true,
+            version,
+            path: Some(path),
+            in_path,
+            running: None,
+            authenticated: Some(self.check_auth_status()),
+            error: None,
+        })
+    }
+
+    /// `codex login` stores credentials in `~/.codex/auth.json`; an API key in the
+    /// environment or in secrets works too
+    fn check_auth_status(&self) -> bool {
+        let codex_home = std::env::var("CODEX_HOME")
+            .map(PathBuf::from)
+            .ok()
+            .or_else(|| get_home_based_paths(&[".codex"]).into_iter().next());
+        if codex_home.is_some_and(|dir| dir.join("auth.json").is_file()) {
+            return true;
+        }
+        if std::env::var("OPENAI_API_KEY").is_ok_and(|key| !key.trim().is_empty()) {
+            return true;
+        }
+        crate::services::secrets_service::SecretsService::get_secret("OPENAI_API_KEY")
+            .ok()
+            .flatten()
+            .is_some_and(|key| !key.trim().is_empty())
+    }
+
+    /// Verify the executable is the Codex CLI
+    async fn verify_executable(&self, path: &std::path::Path) -> bool {
+        if !path.exists() {
+            return false;
+        }
+
+        if let Ok(output) = tokio::process::Command::new(path).arg("--version").output().await {
+            let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
+            if output.status.success() && (stdout.contains("codex") || VERSION_RE.is_match(&stdout)) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn parse_version(output: &str) -> Option<String> {
+        VERSION_RE.find(output).map(|m| m.as_str().to_string())
+    }
+}
+
+impl Default for CodexDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl CliDetector for CodexDetector {
+    fn tool_name(&self) -> &str {
+        "codex"
+    }
+
+    fn command_name(&self) -> &str {
+        "codex"
+    }
+
+    async fn detect(&self) -> Result<CliToolInfo> {
+        self.detect_impl().await
+    }
+
+    async fn get_version(&self, path: &std::path::Path) -> Option<String> {
+        let output = tokio::process::Command::new(path).arg("--version").output().await.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::parse_version(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    async fn check_authentication(&self) -> Option<bool> {
+        Some(self.check_auth_status())
+    }
+
+    fn get_common_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        {
+            paths.extend(get_home_based_paths(&[
+                ".local/bin/codex",
+                ".npm-global/bin/codex",
+                ".npm/bin/codex",
+                ".volta/bin/codex",
+                ".bun/bin/codex",
+                ".asdf/shims/codex",
+            ]));
+
+            // npm's default global prefix and Homebrew
+            paths.push(PathBuf::from("/usr/local/bin/codex"));
+            paths.push(PathBuf::from("/opt/homebrew/bin/codex"));
+            paths.push(PathBuf::from("/home/linuxbrew/.linuxbrew/bin/codex"));
+            paths.push(PathBuf::from("/usr/bin/codex"));
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            if let Ok(app_data) = std::env::var("APPDATA") {
+                paths.push(PathBuf::from(&app_data).join("npm\\codex.cmd"));
+            }
+            if let Ok(user_profile) = std::env::var("USERPROFILE") {
+                paths.push(PathBuf::from(&user_profile).join(".local\\bin\\codex.exe"));
+            }
+        }
+
+        paths
+    }
+
+    fn get_installation_instructions(&self) -> String {
+        #[cfg(target_os = "macos")]
+        {
+            r#"To install the Codex CLI, please follow these steps:
+
+1. Open your terminal
+2. Install using one of these methods:
+
+   Option A - Using npm (requires Node.js 18+):
+   npm install -g @openai/codex
+
+   Option B - Using Homebrew:
+   brew install codex
+
+3. Verify installation:
+   codex --version
+
+4. Sign in with your ChatGPT account or an API key:
+   codex login
+
+5. Restart this application"#
+                .to_string()
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            r#"To install the Codex CLI, please follow these steps:
+
+1. Open your terminal
+2. Install using npm (requires Node.js 18+):
+   npm install -g @openai/codex
+
+   If npm reports a permissions error, set a user prefix first:
+   npm config set prefix ~/.npm-global
+
+3. Verify installation:
+   codex --version
+
+4. Sign in with your ChatGPT account or an API key:
+   codex login
+
+5. Restart this application"#
+                .to_string()
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            r#"To install the Codex CLI, please follow these steps:
+
+1. Install Node.js 18+ from https://nodejs.org
+2. In PowerShell, run:
+   npm install -g @openai/codex
+
+   Codex works best under WSL; see https://github.com/openai/codex for details.
+
+3. Verify installation:
+   codex --version
+
+4. Sign in with your ChatGPT account or an API key:
+   codex login
+
+5. Restart this application"#
+                .to_string()
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+        {
+            "Please visit https://github.com/openai/codex for Codex CLI installation instructions.".to_string()
+        }
+    }
+
+    async fn verify_path(&self, path: &std::path::Path) -> bool {
+        self.verify_executable(path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codex_detector_metadata() {
+        let detector = CodexDetector::new();
+        assert_eq!(detector.tool_name(), "codex");
+        assert_eq!(detector.command_name(), "codex");
+        assert!(detector.get_installation_instructions().contains("Codex"));
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(CodexDetector::parse_version("codex-cli 0.46.0\n"), Some("0.46.0".to_string()));
+        assert_eq!(CodexDetector::parse_version("codex 1.2.3-alpha.4"), Some("1.2.3-alpha.4".to_string()));
+        assert_eq!(CodexDetector::parse_version("codex"), None);
+    }
+
+    #[tokio::test]
+    async fn test_detect_returns_result() {
+        let info = CodexDetector::new().detect().await.unwrap();
+        assert_eq!(info.name, "codex");
+    }
+}
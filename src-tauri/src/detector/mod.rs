@@ -1,18 +1,23 @@
 use anyhow::Result;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 // New plugin-based detection system
 pub mod claude_code_detector;
 pub mod cli_detector;
+pub mod codex_detector;
 pub mod gemini_detector;
+pub mod node_detector;
 pub mod ollama_detector;
 
 use claude_code_detector::ClaudeCodeDetector;
-use cli_detector::CliDetectorRegistry;
+use cli_detector::{CliDetectorRegistry, CliToolInfo};
+use codex_detector::CodexDetector;
 use gemini_detector::GeminiDetector;
+use node_detector::{NodeToolDetector, NODE_TOOLS};
 use ollama_detector::OllamaDetector;
 
 // Global registry instance
@@ -21,8 +26,12 @@ static DETECTOR_REGISTRY: Lazy<CliDetectorRegistry> = Lazy::new(|| {
 
     // Register all detectors
     registry.register(Arc::new(ClaudeCodeDetector::new()));
+    registry.register(Arc::new(CodexDetector::new()));
     registry.register(Arc::new(GeminiDetector::new()));
     registry.register(Arc::new(OllamaDetector::new()));
+    for tool in NODE_TOOLS {
+        registry.register(Arc::new(NodeToolDetector::new(tool)));
+    }
 
     log::info!(
         "CLI detector registry initialized with {} detectors",
@@ -49,6 +58,9 @@ pub struct ClaudeCodeInfo {
     pub path: Option<PathBuf>,
     pub in_path: bool,
     pub authenticated: Option<bool>,
+    /// Set when the installed version is below the supported minimum
+    #[serde(default)]
+    pub error: Option<String>,
 }
 
 /// Information about detected Ollama installation
@@ -59,6 +71,29 @@ pub struct OllamaInfo {
     pub path: Option<PathBuf>,
     pub running: bool,
     pub in_path: bool,
+    /// Models pulled into the local server; empty when it isn't running
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+/// Information about detected OpenAI Codex CLI installation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodexInfo {
+    pub installed: bool,
+    pub version: Option<String>,
+    pub path: Option<PathBuf>,
+    pub in_path: bool,
+    pub authenticated: Option<bool>,
+}
+
+/// Versions of the Node.js commands MCP servers need; `None` when not found
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeToolchainInfo {
+    pub node_version: Option<String>,
+    pub npm_version: Option<String>,
+    pub npx_version: Option<String>,
+    /// Commands that could not be found, e.g. `["npx"]`
+    pub missing: Vec<String>,
 }
 
 /// Result of installation instruction request
@@ -116,6 +151,33 @@ pub async fn detect_claude_code_with_path(
             path: info.path,
             in_path: info.in_path,
             authenticated: info.authenticated,
+            error: info.error,
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Detect Codex CLI installation using new plugin system
+pub async fn detect_codex() -> Result<Option<CodexInfo>> {
+    detect_codex_with_path(None).await
+}
+
+/// Detect Codex CLI installation with a preferred path
+pub async fn detect_codex_with_path(
+    preferred_path: Option<PathBuf>,
+) -> Result<Option<CodexInfo>> {
+    let info = DETECTOR_REGISTRY
+        .detect_with_path("codex", preferred_path)
+        .await?;
+
+    if info.installed {
+        Ok(Some(CodexInfo {
+            installed: info.installed,
+            version: info.version,
+            path: info.path,
+            in_path: info.in_path,
+            authenticated: info.authenticated,
         }))
     } else {
         Ok(None)
@@ -136,18 +198,56 @@ pub async fn detect_ollama_with_path(
         .await?;
 
     if info.installed {
+        let running = info.running.unwrap_or(false);
+        let models = if running { list_ollama_models(None).await } else { Vec::new() };
         Ok(Some(OllamaInfo {
             installed: info.installed,
             version: info.version,
             path: info.path,
-            running: info.running.unwrap_or(false),
+            running,
             in_path: info.in_path,
+            models,
         }))
     } else {
         Ok(None)
     }
 }
 
+/// Detect `node`, `npm` and `npx`
+pub async fn detect_node_toolchain() -> NodeToolchainInfo {
+    let (node, npm, npx) = tokio::join!(
+        DETECTOR_REGISTRY.detect("node", false),
+        DETECTOR_REGISTRY.detect("npm", false),
+        DETECTOR_REGISTRY.detect("npx", false),
+    );
+    // Outer `None`: not installed; inner `None`: installed but the version didn't parse
+    let version = |result: Result<CliToolInfo>| result.ok().filter(|r| r.installed).map(|r| r.version);
+    let (node, npm, npx) = (version(node), version(npm), version(npx));
+
+    let missing = NODE_TOOLS
+        .iter()
+        .zip([&node, &npm, &npx])
+        .filter(|(_, detected)| detected.is_none())
+        .map(|(tool, _)| tool.to_string())
+        .collect();
+    NodeToolchainInfo {
+        node_version: node.flatten(),
+        npm_version: npm.flatten(),
+        npx_version: npx.flatten(),
+        missing,
+    }
+}
+
+/// Models pulled into Ollama at `api_url` (the default local server when `None`).
+/// An unreachable server yields an empty list.
+pub async fn list_ollama_models(api_url: Option<&str>) -> Vec<String> {
+    let api_url = api_url.unwrap_or(ollama_detector::DEFAULT_OLLAMA_API_URL);
+    OllamaDetector::list_models(api_url).await.unwrap_or_else(|e| {
+        log::debug!("Could not list Ollama models at {}: {}", api_url, e);
+        Vec::new()
+    })
+}
+
 /// Detect all CLI tools at once
 pub async fn detect_all_cli_tools(
     claude_path: Option<PathBuf>,
@@ -158,15 +258,40 @@ pub async fn detect_all_cli_tools(
     Option<OllamaInfo>,
     Option<GeminiInfo>,
 )> {
-    // For now detect_all doesn't support preferred paths easily in a batch,
-    // so we call them individually or update detect_all.
-    // Let's call them individually for simplicity and to honor the paths.
+    // detect_all doesn't take preferred paths, so run the per-tool lookups side by side
+    tokio::try_join!(
+        detect_claude_code_with_path(claude_path),
+        detect_ollama_with_path(ollama_path),
+        detect_gemini_with_path(gemini_path),
+    )
+}
+
+/// Run every registered detector concurrently. Cached results are reused unless
+/// `force_refresh` is set.
+pub async fn detect_registered_cli_tools(force_refresh: bool) -> HashMap<String, CliToolInfo> {
+    DETECTOR_REGISTRY.detect_all(force_refresh).await
+}
 
-    let claude_info = detect_claude_code_with_path(claude_path).await?;
-    let ollama_info = detect_ollama_with_path(ollama_path).await?;
-    let gemini_info = detect_gemini_with_path(gemini_path).await?;
+/// Drop every cached result and re-run all registered detectors concurrently,
+/// keyed by tool name (`claude-code`, `codex`, `gemini`, `ollama`)
+pub async fn redetect_all_cli_tools() -> HashMap<String, CliToolInfo> {
+    DETECTOR_REGISTRY.clear_all_cache();
+    DETECTOR_REGISTRY.detect_all(true).await
+}
 
-    Ok((claude_info, ollama_info, gemini_info))
+/// Detect a user-configured CLI by looking its command up on PATH
+pub async fn detect_custom_cli(name: &str, command: &str) -> CliToolInfo {
+    let path = cli_detector::check_command_in_path(command).await;
+    CliToolInfo {
+        name: name.to_string(),
+        installed: path.is_some(),
+        version: None,
+        in_path: path.is_some(),
+        path,
+        running: None,
+        authenticated: None,
+        error: None,
+    }
 }
 
 /// Clear detection cache for a specific tool
@@ -264,6 +389,20 @@ pub fn get_gemini_installation_instructions() -> String {
         .unwrap_or_else(|| "Please visit https://ai.google.dev/gemini-api/docs/quickstart for installation instructions.".to_string())
 }
 
+/// Get installation instructions for the Codex CLI
+pub fn get_codex_installation_instructions() -> String {
+    DETECTOR_REGISTRY
+        .get_installation_instructions("codex")
+        .unwrap_or_else(|| "Please visit https://github.com/openai/codex for installation instructions.".to_string())
+}
+
+/// Get installation instructions for Node.js (node, npm and npx)
+pub fn get_node_installation_instructions() -> String {
+    DETECTOR_REGISTRY
+        .get_installation_instructions("npx")
+        .unwrap_or_else(|| "Please install Node.js from https://nodejs.org.".to_string())
+}
+
 /// Get installation instructions for Ollama
 pub fn get_ollama_installation_instructions() -> String {
     DETECTOR_REGISTRY
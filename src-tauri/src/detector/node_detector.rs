@@ -0,0 +1,149 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::PathBuf;
+
+use super::cli_detector::{check_command_in_path, CliDetector, CliToolInfo};
+
+/// The Node.js commands MCP servers are launched with
+pub const NODE_TOOLS: [&str; 3] = ["node", "npm", "npx"];
+
+static VERSION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+\.\d+\.\d+").unwrap());
+
+/// Lightweight detector for one Node.js command. Most MCP servers run via `npx`, so
+/// these only need to be found and versioned, not authenticated.
+pub struct NodeToolDetector {
+    command: &'static str,
+}
+
+impl NodeToolDetector {
+    pub fn new(command: &'static str) -> Self {
+        Self { command }
+    }
+
+    fn executable_name(&self) -> String {
+        if cfg!(target_os = "windows") && self.command != "node" {
+            // npm and npx ship as batch shims on Windows
+            format!("{}.cmd", self.command)
+        } else if cfg!(target_os = "windows") {
+            format!("{}.exe", self.command)
+        } else {
+            self.command.to_string()
+        }
+    }
+
+    fn parse_version(output: &str) -> Option<String> {
+        VERSION_RE.find(output).map(|m| m.as_str().to_string())
+    }
+}
+
+#[async_trait]
+impl CliDetector for NodeToolDetector {
+    fn tool_name(&self) -> &str {
+        self.command
+    }
+
+    fn command_name(&self) -> &str {
+        self.command
+    }
+
+    async fn detect(&self) -> Result<CliToolInfo> {
+        let mut path = check_command_in_path(self.command).await;
+        let in_path = path.is_some();
+        if path.is_none() {
+            path = self.get_common_paths().into_iter().find(|p| p.is_file());
+        }
+
+        let version = match &path {
+            Some(p) => self.get_version(p).await,
+            None => None,
+        };
+        Ok(CliToolInfo {
+            name: self.tool_name().to_string(),
+            installed: path.is_some(),
+            version,
+            path,
+            in_path,
+            running: None,
+            authenticated: None,
+            error: None,
+        })
+    }
+
+    async fn get_version(&self, path: &std::path::Path) -> Option<String> {
+        let output = tokio::process::Command::new(path).arg("--version").output().await.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::parse_version(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    fn get_common_paths(&self) -> Vec<PathBuf> {
+        let name = self.executable_name();
+        let mut paths = Vec::new();
+
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        {
+            let relative_paths = [
+                format!(".volta/bin/{}", name),
+                format!(".asdf/shims/{}", name),
+                format!(".local/bin/{}", name),
+            ];
+            paths.extend(super::cli_detector::get_home_based_paths(
+                &relative_paths.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
+            ));
+            paths.push(PathBuf::from(format!("/usr/local/bin/{}", name)));
+            paths.push(PathBuf::from(format!("/opt/homebrew/bin/{}", name)));
+            paths.push(PathBuf::from(format!("/usr/bin/{}", name)));
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            if let Ok(program_files) = std::env::var("ProgramFiles") {
+                paths.push(PathBuf::from(&program_files).join("nodejs").join(&name));
+            }
+            if let Ok(app_data) = std::env::var("APPDATA") {
+                paths.push(PathBuf::from(&app_data).join("npm").join(&name));
+            }
+        }
+
+        paths
+    }
+
+    fn get_installation_instructions(&self) -> String {
+        r#"Node.js is required to run most MCP servers (they are started with `npx`).
+
+1. Download the LTS installer from: https://nodejs.org
+   (or use a version manager such as nvm, fnm or Volta)
+2. Run the installer; it includes npm and npx
+3. Verify installation:
+   node --version
+   npx --version
+4. Restart this application"#
+            .to_string()
+    }
+
+    async fn verify_path(&self, path: &std::path::Path) -> bool {
+        self.get_version(path).await.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(NodeToolDetector::parse_version("v20.11.1\n"), Some("20.11.1".to_string()));
+        assert_eq!(NodeToolDetector::parse_version("10.2.4"), Some("10.2.4".to_string()));
+        assert_eq!(NodeToolDetector::parse_version("command not found"), None);
+    }
+
+    #[test]
+    fn test_instructions_point_to_nodejs() {
+        let detector = NodeToolDetector::new("npx");
+        assert_eq!(detector.tool_name(), "npx");
+        assert!(detector.get_installation_instructions().contains("https://nodejs.org"));
+    }
+}
@@ -5,6 +5,9 @@ use std::process::Command;
 
 use super::cli_detector::{check_command_in_path, get_home_based_paths, CliDetector, CliToolInfo};
 
+/// Where a default Ollama install serves its API
+pub const DEFAULT_OLLAMA_API_URL: &str = "http://localhost:11434";
+
 /// Ollama CLI detector implementation
 pub struct OllamaDetector;
 
@@ -13,12 +16,42 @@ impl OllamaDetector {
         Self
     }
 
+    /// Names of the models pulled into the Ollama server at `api_url`, from `/api/tags`
+    pub async fn list_models(api_url: &str) -> Result<Vec<String>> {
+        let url = format!("{}/api/tags", api_url.trim_end_matches('/'));
+        let response = reqwest::Client::new()
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(3))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            anyhow::bail!("Ollama API returned status {}", response.status());
+        }
+        let json: serde_json::Value = response.json().await?;
+        Ok(Self::parse_model_names(&json))
+    }
+
+    fn parse_model_names(tags: &serde_json::Value) -> Vec<String> {
+        let mut models: Vec<String> = tags
+            .get("models")
+            .and_then(|v| v.as_array())
+            .map(|list| {
+                list.iter()
+                    .filter_map(|m| m.get("name").and_then(|n| n.as_str()))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        models.sort();
+        models
+    }
+
     /// Check if Ollama service is running
     async fn check_service_running(&self) -> bool {
         // Try to connect to Ollama API
         let client = reqwest::Client::new();
         let result = client
-            .get("http://localhost:11434/api/tags")
+            .get(format!("{}/api/tags", DEFAULT_OLLAMA_API_URL))
             .timeout(std::time::Duration::from_secs(2))
             .send()
             .await;
@@ -308,6 +341,19 @@ mod tests {
         assert_eq!(detector.command_name(), "ollama");
     }
 
+    #[test]
+    fn test_parse_model_names() {
+        let tags = serde_json::json!({
+            "models": [
+                { "name": "qwen2.5:7b", "size": 4683087332u64 },
+                { "name": "llama3.1:8b" },
+                { "size": 1 }
+            ]
+        });
+        assert_eq!(OllamaDetector::parse_model_names(&tags), vec!["llama3.1:8b", "qwen2.5:7b"]);
+        assert!(OllamaDetector::parse_model_names(&serde_json::json!({})).is_empty());
+    }
+
     #[test]
     fn test_common_paths_not_empty() {
         let detector = OllamaDetector::new();
@@ -1,6 +1,35 @@
+use crate::models::settings::GlobalSettings;
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path};
+
+/// Subdirectories of the app data directory that are always created and verified
+pub const APP_DATA_SUBDIRS: &[&str] = &["projects", "skills", "templates", "backups", "logs"];
+
+/// The built-in subdirectories followed by the `extra_app_data_dirs` from the settings in
+/// `base_path`. Extra entries that aren't plain relative paths are ignored.
+pub fn managed_subdirs(base_path: &Path) -> Vec<String> {
+    let mut subdirs: Vec<String> = APP_DATA_SUBDIRS.iter().map(|s| s.to_string()).collect();
+    let extra = GlobalSettings::load(base_path.join("settings.json"))
+        .map(|settings| settings.extra_app_data_dirs)
+        .unwrap_or_default();
+
+    for dir in extra {
+        let dir = dir.trim().trim_end_matches('/').to_string();
+        let is_relative = !dir.is_empty()
+            && Path::new(&dir)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+        if !is_relative {
+            log::warn!("Ignoring extra app data directory {:?}: not a relative path", dir);
+            continue;
+        }
+        if !subdirs.contains(&dir) {
+            subdirs.push(dir);
+        }
+    }
+    subdirs
+}
 
 /// Create the complete directory structure for the application
 pub async fn create_directory_structure(base_path: &Path) -> Result<()> {
@@ -14,10 +43,8 @@ pub async fn create_directory_structure(base_path: &Path) -> Result<()> {
     }
 
     // Create subdirectories
-    let subdirs = vec!["projects", "skills", "templates", "backups", "logs"];
-
-    for subdir in subdirs {
-        let dir_path = base_path.join(subdir);
+    for subdir in managed_subdirs(base_path) {
+        let dir_path = base_path.join(&subdir);
         if !dir_path.exists() {
             fs::create_dir_all(&dir_path)
                 .context(format!("Failed to create directory: {:?}", dir_path))?;
@@ -40,10 +67,8 @@ pub async fn verify_directory_structure(base_path: &Path) -> Result<bool> {
     }
 
     // Check required subdirectories
-    let required_subdirs = vec!["projects", "skills"];
-
-    for subdir in required_subdirs {
-        let dir_path = base_path.join(subdir);
+    for subdir in managed_subdirs(base_path) {
+        let dir_path = base_path.join(&subdir);
         if !dir_path.exists() {
             log::warn!("Required directory missing: {:?}", dir_path);
             return Ok(false);
@@ -346,6 +371,28 @@ mod tests {
         assert!(base_path.join("backups").exists());
     }
 
+    #[tokio::test]
+    async fn test_extra_managed_subdirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::write(
+            base_path.join("settings.json"),
+            r#"{"extraAppDataDirs": ["exports", "datasets/raw/", "../outside", "/abs", "logs"]}"#,
+        )
+        .unwrap();
+
+        let subdirs = managed_subdirs(base_path);
+        assert_eq!(&subdirs[..APP_DATA_SUBDIRS.len()], APP_DATA_SUBDIRS);
+        assert_eq!(&subdirs[APP_DATA_SUBDIRS.len()..], ["exports", "datasets/raw"]);
+
+        create_directory_structure(base_path).await.unwrap();
+        assert!(base_path.join("datasets").join("raw").is_dir());
+        assert!(verify_directory_structure(base_path).await.unwrap());
+
+        fs::remove_dir(base_path.join("exports")).unwrap();
+        assert!(!verify_directory_structure(base_path).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_verify_directory_structure() {
         let temp_dir = TempDir::new().unwrap();
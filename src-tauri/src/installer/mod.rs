@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use crate::config::{AppConfig, ConfigManager};
-use crate::detector::{self, ClaudeCodeInfo, GeminiInfo, OllamaInfo};
+use crate::detector::cli_detector::CliToolInfo;
+use crate::detector::{self, ClaudeCodeInfo, GeminiInfo, NodeToolchainInfo, OllamaInfo};
 use crate::directory;
 
 /// Installation configuration state
@@ -14,6 +16,11 @@ pub struct InstallationConfig {
     pub claude_code_detected: bool,
     pub ollama_detected: bool,
     pub gemini_detected: bool,
+    #[serde(default)]
+    pub codex_detected: bool,
+    /// Node.js commands needed by MCP servers launched with `npx`
+    #[serde(default)]
+    pub node_toolchain: NodeToolchainInfo,
 }
 
 /// Installation progress state
@@ -68,6 +75,8 @@ impl InstallationManager {
                 claude_code_detected: false,
                 ollama_detected: false,
                 gemini_detected: false,
+                codex_detected: false,
+                node_toolchain: Default::default(),
             },
         }
     }
@@ -137,13 +146,26 @@ impl InstallationManager {
             progress_percentage: 40,
         });
 
+        detector::detect_registered_cli_tools(false).await;
         let claude_code_info = detector::detect_claude_code().await?;
         let ollama_info = detector::detect_ollama().await?;
         let gemini_info = detector::detect_gemini().await?;
+        let codex_info = detector::detect_codex().await?;
 
         self.config.claude_code_detected = claude_code_info.is_some();
         self.config.ollama_detected = ollama_info.is_some();
         self.config.gemini_detected = gemini_info.is_some();
+        self.config.codex_detected = codex_info.is_some();
+        self.config.node_toolchain = detector::detect_node_toolchain().await;
+
+        if let Some(message) = Self::node_warning(&self.config.node_toolchain) {
+            log::warn!("{}", message);
+            progress_callback(InstallationProgress {
+                stage: InstallationStage::DetectingDependencies,
+                message,
+                progress_percentage: 50,
+            });
+        }
 
         // Stage 4: Installing Claude Code (if needed)
         if !self.config.claude_code_detected && self.config.is_first_install {
@@ -153,6 +175,13 @@ impl InstallationManager {
                 progress_percentage: 60,
             });
             // Note: Actual installation will be handled by the frontend
+        } else if let Some(error) = claude_code_info.as_ref().and_then(|info| info.error.clone()) {
+            log::warn!("{}", error);
+            progress_callback(InstallationProgress {
+                stage: InstallationStage::InstallingClaudeCode,
+                message: error,
+                progress_percentage: 60,
+            });
         } else {
             progress_callback(InstallationProgress {
                 stage: InstallationStage::InstallingClaudeCode,
@@ -185,23 +214,7 @@ impl InstallationManager {
         }
 
         // Create and save persistent AppConfig
-        let openai_path = if crate::utils::env::command_exists("codex") {
-            std::process::Command::new("where")
-                .arg("codex")
-                .output()
-                .ok()
-                .and_then(|o| String::from_utf8(o.stdout).ok())
-                .and_then(|s| s.lines().next().map(|l| std::path::PathBuf::from(l.trim())))
-        } else if crate::utils::env::command_exists("openai") {
-            std::process::Command::new("where")
-                .arg("openai")
-                .output()
-                .ok()
-                .and_then(|o| String::from_utf8(o.stdout).ok())
-                .and_then(|s| s.lines().next().map(|l| std::path::PathBuf::from(l.trim())))
-        } else {
-            None
-        };
+        let openai_path = codex_info.as_ref().and_then(|info| info.path.clone());
 
         let app_config = AppConfig {
             app_data_directory: self.config.app_data_path.clone(),
@@ -262,6 +275,8 @@ impl InstallationManager {
                 claude_code_detected: false,
                 ollama_detected: false,
                 gemini_detected: false,
+                codex_detected: false,
+                node_toolchain: Default::default(),
             });
         }
 
@@ -274,15 +289,33 @@ impl InstallationManager {
         Ok(config)
     }
 
-    /// Re-detect dependencies (useful for updates)
-    pub async fn redetect_dependencies(&mut self) -> Result<()> {
+    /// Warning shown during installation when MCP servers won't be able to start
+    pub fn node_warning(toolchain: &NodeToolchainInfo) -> Option<String> {
+        if toolchain.missing.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Node.js not found ({} missing). Most MCP servers are started with npx; install Node.js from https://nodejs.org to use them.",
+            toolchain.missing.join(", ")
+        ))
+    }
+
+    /// Re-detect dependencies (useful for updates). `force_refresh` ignores cached
+    /// results, e.g. right after the user installed a tool.
+    pub async fn redetect_dependencies(&mut self, force_refresh: bool) -> Result<()> {
+        // Warm the detection cache in parallel; the per-tool lookups below then hit it
+        detector::detect_registered_cli_tools(force_refresh).await;
+
         let claude_code_info = detector::detect_claude_code().await?;
         let ollama_info = detector::detect_ollama().await?;
         let gemini_info = detector::detect_gemini().await?;
+        let codex_info = detector::detect_codex().await?;
 
         self.config.claude_code_detected = claude_code_info.is_some();
         self.config.ollama_detected = ollama_info.is_some();
         self.config.gemini_detected = gemini_info.is_some();
+        self.config.codex_detected = codex_info.is_some();
+        self.config.node_toolchain = detector::detect_node_toolchain().await;
 
         self.save_installation_state()?;
 
@@ -292,25 +325,56 @@ impl InstallationManager {
                 config.claude_code_enabled = claude_code_info.is_some();
                 config.ollama_enabled = ollama_info.is_some();
                 config.gemini_enabled = gemini_info.is_some();
-                config.openai_enabled = crate::utils::env::command_exists("codex") || crate::utils::env::command_exists("openai");
+                config.openai_enabled = codex_info.is_some();
                 config.claude_code_path =
                     claude_code_info.as_ref().and_then(|info| info.path.clone());
                 config.ollama_path = ollama_info.as_ref().and_then(|info| info.path.clone());
                 config.gemini_path = gemini_info.as_ref().and_then(|info| info.path.clone());
-                config.openai_path = if crate::utils::env::command_exists("codex") {
-                    std::process::Command::new("where").arg("codex").output().ok()
-                        .and_then(|o| String::from_utf8(o.stdout).ok())
-                        .and_then(|s| s.lines().next().map(|l| std::path::PathBuf::from(l.trim())))
-                } else if crate::utils::env::command_exists("openai") {
-                    std::process::Command::new("where").arg("openai").output().ok()
-                        .and_then(|o| String::from_utf8(o.stdout).ok())
-                        .and_then(|s| s.lines().next().map(|l| std::path::PathBuf::from(l.trim())))
-                } else { None };
+                config.openai_path = codex_info.as_ref().and_then(|info| info.path.clone());
             })?;
         }
 
         Ok(())
     }
+
+    /// Clear the detector cache, re-run every registered detector concurrently and
+    /// persist the fresh results. Returns the detection results keyed by tool name.
+    pub async fn redetect_all_dependencies(&mut self) -> Result<HashMap<String, CliToolInfo>> {
+        let results = detector::redetect_all_cli_tools().await;
+        let installed_path = |name: &str| {
+            results
+                .get(name)
+                .filter(|info| info.installed)
+                .map(|info| info.path.clone())
+        };
+        let claude_code = installed_path("claude-code");
+        let ollama = installed_path("ollama");
+        let gemini = installed_path("gemini");
+        let codex = installed_path("codex");
+
+        self.config.claude_code_detected = claude_code.is_some();
+        self.config.ollama_detected = ollama.is_some();
+        self.config.gemini_detected = gemini.is_some();
+        self.config.codex_detected = codex.is_some();
+        self.config.node_toolchain = detector::detect_node_toolchain().await;
+
+        self.save_installation_state()?;
+
+        if ConfigManager::config_exists()? {
+            ConfigManager::update_config(|config| {
+                config.claude_code_enabled = claude_code.is_some();
+                config.ollama_enabled = ollama.is_some();
+                config.gemini_enabled = gemini.is_some();
+                config.openai_enabled = codex.is_some();
+                config.claude_code_path = claude_code.flatten();
+                config.ollama_path = ollama.flatten();
+                config.gemini_path = gemini.flatten();
+                config.openai_path = codex.flatten();
+            })?;
+        }
+
+        Ok(results)
+    }
 }
 
 #[cfg(test)]
@@ -326,6 +390,8 @@ mod tests {
             claude_code_detected: false,
             ollama_detected: false,
             gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -340,6 +406,19 @@ mod tests {
         assert_eq!(config.gemini_detected, deserialized.gemini_detected);
     }
 
+    #[test]
+    fn test_node_warning_lists_missing_commands() {
+        assert!(InstallationManager::node_warning(&NodeToolchainInfo::default()).is_none());
+
+        let toolchain = NodeToolchainInfo {
+            missing: vec!["npm".to_string(), "npx".to_string()],
+            ..Default::default()
+        };
+        let warning = InstallationManager::node_warning(&toolchain).unwrap();
+        assert!(warning.contains("npm, npx"));
+        assert!(warning.contains("https://nodejs.org"));
+    }
+
     #[test]
     fn test_installation_manager_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -175,6 +175,7 @@ pub fn run() {
 
             // Set up file watcher
             let app_handle = app.handle().clone();
+            let watcher_handle = app.handle().clone();
             std::thread::spawn(move || {
                 // Initialize file watcher
                 let base_path =
@@ -199,10 +200,52 @@ pub fn run() {
                         services::file_watcher::WatchEvent::FileChanged(project_id, file_name) => {
                             let _ = app_handle.emit("file-changed", (project_id, file_name));
                         }
+                        _ => {}
                     }
                 }) {
                     log::error!("Failed to start file watcher: {}", e);
+                    return;
                 }
+                // Managed state keeps the watcher alive and lets the health check see it
+                watcher_handle.manage(std::sync::Mutex::new(watcher));
+            });
+
+            // Watch the skills directory so hand-edited skills show up without a restart
+            let skills_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                let skills_path =
+                    match services::settings_service::SettingsService::get_skills_path() {
+                        Ok(path) => path,
+                        Err(e) => {
+                            log::error!("Failed to get skills directory for file watcher: {}", e);
+                            return;
+                        }
+                    };
+                if let Err(e) = std::fs::create_dir_all(&skills_path) {
+                    log::error!("Failed to create skills directory for file watcher: {}", e);
+                    return;
+                }
+                let emit_handle = skills_handle.clone();
+                let mut watcher = services::file_watcher::FileWatcherService::for_skills();
+
+                if let Err(e) = watcher.start_watching(&skills_path, move |event| match event {
+                    services::file_watcher::WatchEvent::SkillAdded(id) => {
+                        let _ = emit_handle.emit("skill-added", id);
+                    }
+                    services::file_watcher::WatchEvent::SkillChanged(id) => {
+                        let _ = emit_handle.emit("skill-changed", id);
+                    }
+                    services::file_watcher::WatchEvent::SkillRemoved(id) => {
+                        let _ = emit_handle.emit("skill-removed", id);
+                    }
+                    _ => {}
+                }) {
+                    log::error!("Failed to start skills watcher: {}", e);
+                    return;
+                }
+                skills_handle.manage(services::file_watcher::SkillsWatcher(std::sync::Mutex::new(
+                    watcher,
+                )));
             });
 
             // Initialize AI Service
@@ -324,52 +367,104 @@ pub fn run() {
       commands::settings_commands::get_project_settings,
       commands::settings_commands::save_project_settings,
       commands::project_commands::get_all_projects,
+      commands::project_commands::discover_projects_stream,
+      commands::project_commands::validate_all_projects,
+      commands::project_commands::compact_project,
+      commands::project_commands::export_research_record,
+      commands::project_commands::backup_project,
+      commands::project_commands::export_project,
+      commands::project_commands::import_project,
       commands::project_commands::get_project,
       commands::project_commands::create_project,
       commands::project_commands::get_project_files,
+      commands::project_commands::get_project_files_page,
       commands::project_commands::delete_project,
+      commands::project_commands::duplicate_project,
       commands::project_commands::rename_project,
+      commands::project_commands::update_project_details,
+      commands::project_commands::update_project_tags,
+      commands::project_commands::get_projects_by_tag,
       commands::project_commands::get_project_cost,
+      commands::project_commands::pin_context_file,
+      commands::project_commands::unpin_context_file,
       commands::file_commands::read_markdown_file,
       commands::file_commands::write_markdown_file,
       commands::file_commands::delete_markdown_file,
       commands::file_commands::rename_markdown_file,
       commands::file_commands::search_in_files,
+      commands::file_commands::global_search,
       commands::file_commands::replace_in_files,
+      commands::file_commands::search_and_replace_in_project,
+      commands::file_commands::apply_search_and_replace,
+      commands::file_commands::undo_search_and_replace,
       commands::file_commands::import_document,
       commands::file_commands::import_transcript,
       commands::file_commands::export_document,
+      commands::health_commands::health_check,
       commands::chat_commands::send_message,
+      commands::chat_commands::send_ephemeral_message,
+      commands::chat_commands::cancel_chat,
+      commands::chat_commands::respond_tool_approval,
+      commands::chat_commands::preview_assembled_prompt,
       commands::chat_commands::switch_provider,
       commands::chat_commands::load_chat_history,
+      commands::chat_commands::edit_chat_message,
+      commands::chat_commands::regenerate_chat_message,
+      commands::chat_commands::fork_chat,
+      commands::chat_commands::rename_chat,
+      commands::chat_commands::delete_chat,
       commands::chat_commands::get_chat_files,
+      commands::chat_commands::get_chat_summaries,
+      commands::chat_commands::search_chats,
+      commands::chat_commands::export_chat,
+      commands::chat_commands::scan_chat_history,
       commands::chat_commands::save_chat,
       commands::chat_commands::get_ollama_models,
+      commands::chat_commands::get_ollama_running_models,
+      commands::chat_commands::get_proposed_changes,
+      commands::chat_commands::apply_proposed_changes,
+      commands::chat_commands::discard_proposed_changes,
       commands::secrets_commands::save_secrets,
+      commands::secrets_commands::list_api_keys,
+      commands::secrets_commands::add_api_key,
       commands::secrets_commands::has_claude_api_key,
       commands::secrets_commands::has_gemini_api_key,
       commands::secrets_commands::has_secret,
       commands::secrets_commands::list_saved_secret_ids,
       commands::secrets_commands::test_encryption,
       commands::secrets_commands::reset_encryption_key,
+      commands::secrets_commands::rotate_encryption_key,
+      commands::secrets_commands::export_secrets,
+      commands::secrets_commands::import_secrets,
+      commands::secrets_commands::get_encryption_info,
       commands::skill_commands::get_all_skills,
+      commands::skill_commands::discover_skills_stream,
       commands::skill_commands::get_skill,
       commands::skill_commands::save_skill,
       commands::skill_commands::delete_skill,
       commands::skill_commands::create_skill_template,
       commands::skill_commands::get_skills_by_category,
+      commands::skill_commands::get_skills_by_capability,
       commands::skill_commands::render_skill_prompt,
+      commands::skill_commands::preview_skill_prompt,
       commands::skill_commands::validate_skill,
       commands::skill_commands::create_skill,
       commands::skill_commands::update_skill,
+      commands::skill_commands::set_skill_enabled,
       commands::skill_commands::import_skill,
+      commands::skill_commands::export_skill,
+      commands::skill_commands::import_skill_content,
+      commands::skill_commands::fetch_skill_marketplace,
+      commands::skill_commands::install_marketplace_skill,
       commands::workflow_commands::get_project_workflows,
+      commands::workflow_commands::scan_project_workflows,
       commands::workflow_commands::get_workflow,
       commands::workflow_commands::create_workflow,
       commands::workflow_commands::save_workflow,
       commands::workflow_commands::delete_workflow,
       commands::workflow_commands::execute_workflow,
-      commands::workflow_commands::get_workflow_history,
+      commands::workflow_commands::cancel_workflow,
+      commands::workflow_commands::get_workflow_runs,
       commands::workflow_commands::get_active_runs,
       commands::workflow_commands::set_workflow_schedule,
       commands::workflow_commands::clear_workflow_schedule,
@@ -379,6 +474,7 @@ pub fn run() {
       commands::markdown_commands::render_markdown_to_html,
       commands::markdown_commands::extract_markdown_links,
       commands::markdown_commands::generate_markdown_toc,
+      commands::markdown_commands::get_backlinks,
       commands::installation_commands::check_installation_status,
       commands::installation_commands::detect_claude_code,
       commands::installation_commands::detect_ollama,
@@ -388,19 +484,29 @@ pub fn run() {
       commands::installation_commands::get_claude_code_install_instructions,
       commands::installation_commands::get_ollama_install_instructions,
       commands::installation_commands::get_gemini_install_instructions,
+      commands::installation_commands::get_codex_install_instructions,
+      commands::installation_commands::get_node_install_instructions,
       commands::installation_commands::clear_cli_detection_cache,
       commands::installation_commands::clear_all_cli_detection_caches,
       commands::installation_commands::run_installation,
       commands::installation_commands::verify_directory_structure,
       commands::installation_commands::redetect_dependencies,
+      commands::installation_commands::redetect_dependencies_force,
+      commands::installation_commands::redetect_all_dependencies,
       commands::installation_commands::backup_installation,
       commands::installation_commands::cleanup_old_backups,
       commands::installation_commands::is_first_install,
+      commands::installation_commands::onboarding_status,
       commands::update_commands::run_update_process,
       commands::update_commands::check_and_preserve_structure,
       commands::update_commands::backup_user_data,
+      commands::update_commands::backup_user_data_compressed,
+      commands::update_commands::backup_user_data_incremental,
       commands::update_commands::verify_installation_integrity,
       commands::update_commands::restore_from_backup,
+      commands::update_commands::restore_items,
+      commands::update_commands::list_backup_contents,
+      commands::update_commands::verify_backup,
       commands::update_commands::list_backups,
       commands::config_commands::get_app_config,
       commands::config_commands::save_app_config,
@@ -441,6 +547,8 @@ pub fn run() {
       commands::mcp::remove_mcp_server,
       commands::mcp::toggle_mcp_server,
       commands::mcp::update_mcp_server,
+      commands::mcp::list_mcp_server_tools,
+      commands::mcp::test_mcp_server,
       commands::mcp::fetch_mcp_marketplace,
       commands::mcp::sync_mcp_with_clis,
       commands::mcp::test_litellm_connection,
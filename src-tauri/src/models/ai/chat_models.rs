@@ -11,13 +11,32 @@ pub struct ChatRequest {
     pub options: ChatOptions,
 }
 
+/// Per-request generation settings. Unset values fall back to the provider's defaults.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct ChatOptions {
     pub temperature: Option<f32>,
     pub max_tokens: Option<u32>,
     pub top_p: Option<f32>,
+    /// Stop generating when any of these strings is produced
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    /// Force prompt caching of the system prompt on or off; by default it is cached
+    /// once it passes the provider's size threshold
+    pub prompt_caching: Option<bool>,
     pub stream: bool,
+    /// Restrictions on which MCP tools are sent with the request; all must permit a tool
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_filters: Vec<crate::models::mcp::McpToolFilter>,
+    /// Fired to stop a streaming response; providers stop reading and drop the connection
+    #[serde(skip)]
+    pub cancel: Option<tokio_util::sync::CancellationToken>,
+    /// Receives token usage from streaming responses
+    #[serde(skip)]
+    pub usage: Option<crate::models::ai::UsageSink>,
+    /// Receives tool calls from streaming responses
+    #[serde(skip)]
+    pub tool_calls: Option<crate::models::ai::ToolCallSink>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
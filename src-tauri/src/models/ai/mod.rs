@@ -10,7 +10,11 @@ pub enum ProviderType {
     GeminiCli,
     OpenAiCli,
     LiteLlm,
+    /// Any server speaking OpenAI's `/v1/chat/completions` (vLLM, LM Studio, llama.cpp, ...)
+    OpenAiCompatible,
     AutoRouter,
+    /// Offline echo provider for demos and tests (no network or API key)
+    Mock,
     #[serde(untagged)]
     Custom(String),
 }
@@ -23,6 +27,9 @@ pub struct OllamaConfig {
     pub api_url: String, // e.g. "http://localhost:11434"
     #[serde(default)]
     pub detected_path: Option<std::path::PathBuf>,
+    /// Download the configured model with `ollama pull` when a chat finds it missing
+    #[serde(default, alias = "auto_pull_models")]
+    pub auto_pull_models: bool,
 }
 
 fn default_ollama_url() -> String {
@@ -43,6 +50,47 @@ pub struct HostedConfig {
     pub provider: String, // e.g., "anthropic", "openai"
     pub model: String,
     pub api_key_secret_id: String,
+    /// Sent as the `anthropic-beta` header, comma-joined, e.g. `prompt-caching-2024-07-31`
+    #[serde(default, alias = "beta_flags", skip_serializing_if = "Vec::is_empty")]
+    pub beta_flags: Vec<String>,
+    /// Extra request headers, passed through verbatim
+    #[serde(default, alias = "extra_headers", skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub extra_headers: std::collections::HashMap<String, String>,
+    /// How requests are spread across `claude_api_key`, `claude_api_key_1`, ...
+    #[serde(default, alias = "key_rotation")]
+    pub key_rotation: KeyRotation,
+    /// Retries after a 429 or 529 response, with exponential backoff
+    #[serde(default = "default_max_retries", alias = "max_retries")]
+    pub max_retries: u32,
+    /// First backoff delay; doubled on each further retry unless `retry-after` is sent
+    #[serde(default = "default_retry_base_delay_ms", alias = "retry_base_delay_ms")]
+    pub retry_base_delay_ms: u64,
+    /// System prompts with at least this many characters are sent with prompt caching
+    #[serde(default = "default_prompt_cache_min_chars", alias = "prompt_cache_min_chars")]
+    pub prompt_cache_min_chars: usize,
+}
+
+fn default_prompt_cache_min_chars() -> usize {
+    crate::services::claude_service::DEFAULT_PROMPT_CACHE_MIN_CHARS
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_base_delay_ms() -> u64 {
+    1000
+}
+
+/// Strategy for picking among several stored Anthropic API keys
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyRotation {
+    /// Always start with the primary key and move on only when it is rate limited
+    #[default]
+    OnRateLimit,
+    /// Start each request with the next key in turn
+    RoundRobin,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -77,6 +125,16 @@ pub struct LiteLlmConfig {
     pub shadow_mode: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenAiCompatibleConfig {
+    /// Server root, e.g. `http://localhost:8000`; a trailing `/v1` is also accepted
+    pub base_url: String,
+    pub model: String,
+    /// Sent as a bearer token when the secret is set; local servers usually need none
+    pub api_key_secret_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct RoutingStrategy {
@@ -181,8 +239,46 @@ pub struct GenerationMetadata {
     pub tokens_cache_write: u64,
     #[serde(default)]
     pub tokens_reasoning: u64,
+    /// Tool round trips the agent made before this answer
+    #[serde(default)]
+    pub tool_iterations: u32,
 }
 
+/// Input and output tokens reported by the API for one or more calls
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    pub input: u32,
+    pub output: u32,
+}
+
+impl TokenUsage {
+    pub fn is_empty(&self) -> bool {
+        self.input == 0 && self.output == 0
+    }
+
+    pub fn add(&mut self, other: TokenUsage) {
+        self.input = self.input.saturating_add(other.input);
+        self.output = self.output.saturating_add(other.output);
+    }
+}
+
+impl From<&GenerationMetadata> for TokenUsage {
+    fn from(meta: &GenerationMetadata) -> Self {
+        Self {
+            input: u32::try_from(meta.tokens_in).unwrap_or(u32::MAX),
+            output: u32::try_from(meta.tokens_out).unwrap_or(u32::MAX),
+        }
+    }
+}
+
+/// Filled in by a streaming provider as usage events arrive, since the stream itself
+/// only carries text
+pub type UsageSink = std::sync::Arc<std::sync::Mutex<TokenUsage>>;
+
+/// Collects the tool calls of a streaming response once each `tool_use` block completes
+pub type ToolCallSink = std::sync::Arc<std::sync::Mutex<Vec<ToolCall>>>;
+
 /// Actions to take based on confidence + cost heuristics
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use thiserror::Error;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChatMessage {
@@ -15,3 +16,20 @@ pub struct ChatRequest {
     pub skill_id: Option<String>,
     pub skill_params: Option<HashMap<String, String>>,
 }
+
+/// A chat markdown file that could not be parsed, with the offending line (1-based)
+#[derive(Debug, Error, Clone, PartialEq, Serialize, Deserialize)]
+#[error("line {line}: {message}")]
+pub struct ChatParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ChatParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
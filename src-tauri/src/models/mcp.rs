@@ -1,5 +1,39 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// Seconds a `tools/call` may take before it is abandoned, unless the server overrides it
+pub const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 60;
+
+/// Launchers a server may be enabled with; anything else needs manual review
+pub const TRUSTED_MCP_COMMANDS: &[&str] = &["npx", "node", "uvx", "python", "python3", "docker"];
+
+/// How registry packages are launched: (registry type, command, args before the identifier)
+const REGISTRY_LAUNCHERS: &[(&str, &str, &[&str])] = &[
+    ("npm", "npx", &["-y"]),
+    ("pypi", "uvx", &[]),
+    ("oci", "docker", &["run", "--rm", "-i"]),
+];
+
+/// Command and args that run a registry package, or `None` for unsupported package types
+pub fn registry_package_launch(
+    registry_type: &str,
+    identifier: &str,
+) -> Option<(String, Vec<String>)> {
+    let registry_type = registry_type.to_lowercase();
+    REGISTRY_LAUNCHERS
+        .iter()
+        .find(|(kind, _, _)| *kind == registry_type)
+        .map(|(_, command, prefix)| {
+            let mut args: Vec<String> = prefix.iter().map(|a| a.to_string()).collect();
+            args.push(identifier.to_string());
+            (command.to_string(), args)
+        })
+}
+
+/// Seconds a connection test waits for a server to start, initialize and list its tools.
+/// Generous because `npx` may download the package on first run.
+pub const SERVER_TEST_TIMEOUT_SECS: u64 = 30;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -26,9 +60,68 @@ pub struct McpServerConfig {
     pub categories: Option<Vec<String>>,
     #[serde(alias = "iconUrl", skip_serializing_if = "Option::is_none")]
     pub icon_url: Option<String>,
+    /// Per-call timeout for this server's tools; `DEFAULT_TOOL_TIMEOUT_SECS` when unset
+    #[serde(default, alias = "tool_timeout_secs", skip_serializing_if = "Option::is_none")]
+    pub tool_timeout_secs: Option<u64>,
+    /// When set, only these of the server's tools are offered to the model, so an empty
+    /// list offers none. Names are the server's own (unprefixed); a trailing `*` matches
+    /// by prefix.
+    #[serde(default, alias = "allowed_tools", skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    /// Never offered or run, even when also allowed
+    #[serde(default, alias = "blocked_tools", skip_serializing_if = "Option::is_none")]
+    pub blocked_tools: Option<Vec<String>>,
 }
 
 impl McpServerConfig {
+    pub fn tool_timeout(&self) -> Duration {
+        Duration::from_secs(self.tool_timeout_secs.unwrap_or(DEFAULT_TOOL_TIMEOUT_SECS))
+    }
+
+    /// Whether the server's allow/block lists let the model use `tool_name` (unprefixed)
+    pub fn permits_tool(&self, tool_name: &str) -> bool {
+        // An empty `McpToolFilter` allow-list means "everything", not "nothing"
+        if self.allowed_tools.as_ref().is_some_and(|allowed| allowed.is_empty()) {
+            return false;
+        }
+        McpToolFilter {
+            allowed_tools: self.allowed_tools.clone().unwrap_or_default(),
+            denied_tools: self.blocked_tools.clone().unwrap_or_default(),
+        }
+        .permits(tool_name)
+    }
+
+    pub fn has_trusted_command(&self) -> bool {
+        TRUSTED_MCP_COMMANDS.contains(&self.command.as_str())
+    }
+
+    /// Args to launch the server with. A container doesn't inherit the host environment,
+    /// so `docker run` gets a `-e NAME` per configured variable; docker then copies each
+    /// value from its own environment, keeping secrets off the command line.
+    pub fn launch_args(&self) -> Vec<String> {
+        if self.command != "docker" || self.args.first().map(String::as_str) != Some("run") {
+            return self.args.clone();
+        }
+
+        let mut names: Vec<&String> = self
+            .env
+            .iter()
+            .flatten()
+            .chain(self.secrets_env.iter().flatten())
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut args = vec!["run".to_string()];
+        for name in names {
+            args.push("-e".to_string());
+            args.push(name.clone());
+        }
+        args.extend(self.args[1..].iter().cloned());
+        args
+    }
+
     /// Convert to a format suitable for CLI settings.json
     pub fn to_cli_mcp_config(&self) -> serde_json::Value {
         let env = self.env.clone().unwrap_or_default();
@@ -38,22 +131,69 @@ impl McpServerConfig {
         // `collect_mcp_secrets` right before execution to prevent
         // tokens from being saved dynamically in cleartext settings.json
 
-        let trusted_command = matches!(
-            self.command.as_str(),
-            "npx" | "node" | "uvx" | "python" | "python3"
-        );
         let trusted_source = matches!(self.source.as_deref(), Some("registry") | Some("mcpmarket"));
 
         serde_json::json!({
             "command": self.command,
-            "args": self.args,
+            "args": self.launch_args(),
             "env": env,
-            "timeout": 60000, // Default timeout
-            "trust": trusted_command && trusted_source,
+            "timeout": self.tool_timeout().as_millis() as u64,
+            "trust": self.has_trusted_command() && trusted_source,
         })
     }
 }
 
+/// Narrows the MCP tools offered to the model for a project, skill or workflow.
+/// Entries use the namespaced `server__tool` names; `server__*` matches every tool of a server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolFilter {
+    /// When non-empty, only matching tools are offered
+    #[serde(default, alias = "allowed_tools")]
+    pub allowed_tools: Vec<String>,
+    /// Never offered, even when also allowed
+    #[serde(default, alias = "denied_tools")]
+    pub denied_tools: Vec<String>,
+}
+
+impl McpToolFilter {
+    pub fn permits(&self, tool_name: &str) -> bool {
+        let matches = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => tool_name.starts_with(prefix),
+            None => pattern == tool_name,
+        };
+
+        if self.denied_tools.iter().any(matches) {
+            return false;
+        }
+        self.allowed_tools.is_empty() || self.allowed_tools.iter().any(matches)
+    }
+
+    /// A tool is available only if every filter in scope permits it
+    pub fn permits_all(filters: &[McpToolFilter], tool_name: &str) -> bool {
+        filters.iter().all(|f| f.permits(tool_name))
+    }
+}
+
+/// Outcome of starting a server and listing its tools from the settings screen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerTestResult {
+    pub ok: bool,
+    pub tools: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl McpServerTestResult {
+    pub fn failed(error: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            tools: Vec::new(),
+            error: Some(error.into()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegistryResponse {
     pub servers: Vec<RegistryItem>,
@@ -119,3 +259,138 @@ pub struct McpMarketPagination {
     #[serde(rename = "hasMore")]
     pub has_more: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_filter_allow_and_deny() {
+        let filter = McpToolFilter {
+            allowed_tools: vec!["github__*".to_string(), "search__web_search".to_string()],
+            denied_tools: vec!["github__delete_repo".to_string()],
+        };
+
+        assert!(filter.permits("github__create_issue"));
+        assert!(filter.permits("search__web_search"));
+        assert!(!filter.permits("github__delete_repo"));
+        assert!(!filter.permits("filesystem__write_file"));
+        assert!(McpToolFilter::default().permits("filesystem__write_file"));
+    }
+
+    #[test]
+    fn test_registry_packages_map_to_launchers() {
+        assert_eq!(
+            registry_package_launch("npm", "@modelcontextprotocol/server-github"),
+            Some((
+                "npx".to_string(),
+                vec!["-y".to_string(), "@modelcontextprotocol/server-github".to_string()]
+            ))
+        );
+        assert_eq!(
+            registry_package_launch("PyPI", "mcp-server-fetch"),
+            Some(("uvx".to_string(), vec!["mcp-server-fetch".to_string()]))
+        );
+        let (command, args) =
+            registry_package_launch("oci", "docker.io/mcp/github:latest").unwrap();
+        assert_eq!(command, "docker");
+        assert_eq!(args, vec!["run", "--rm", "-i", "docker.io/mcp/github:latest"]);
+        assert_eq!(registry_package_launch("nuget", "Some.Package"), None);
+    }
+
+    #[test]
+    fn test_docker_launch_args_forward_env_names() {
+        let config = McpServerConfig {
+            id: "github".to_string(),
+            name: "GitHub".to_string(),
+            description: None,
+            command: "docker".to_string(),
+            args: ["run", "--rm", "-i", "mcp/github"].map(String::from).to_vec(),
+            env: Some(HashMap::from([("GITHUB_HOST".to_string(), "github.com".to_string())])),
+            secrets_env: Some(HashMap::from([(
+                "GITHUB_TOKEN".to_string(),
+                "github_token".to_string(),
+            )])),
+            enabled: true,
+            stars: None,
+            author: None,
+            source: Some("registry".to_string()),
+            categories: None,
+            icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
+        };
+
+        assert_eq!(
+            config.launch_args(),
+            vec!["run", "-e", "GITHUB_HOST", "-e", "GITHUB_TOKEN", "--rm", "-i", "mcp/github"]
+        );
+        assert!(config.has_trusted_command());
+
+        let npx = McpServerConfig {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "pkg".to_string()],
+            ..config
+        };
+        assert_eq!(npx.launch_args(), vec!["-y", "pkg"]);
+    }
+
+    #[test]
+    fn test_server_tool_lists() {
+        let open = McpServerConfig {
+            id: "filesystem".to_string(),
+            name: "Filesystem".to_string(),
+            description: None,
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "pkg".to_string()],
+            env: None,
+            secrets_env: None,
+            enabled: true,
+            stars: None,
+            author: None,
+            source: None,
+            categories: None,
+            icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
+        };
+        assert!(open.permits_tool("write_file"));
+
+        let restricted = McpServerConfig {
+            allowed_tools: Some(vec!["read_*".to_string(), "list_directory".to_string()]),
+            blocked_tools: Some(vec!["read_secrets".to_string()]),
+            ..open.clone()
+        };
+        assert!(restricted.permits_tool("read_file"));
+        assert!(restricted.permits_tool("list_directory"));
+        assert!(!restricted.permits_tool("read_secrets"));
+        assert!(!restricted.permits_tool("write_file"));
+
+        // An emptied allow-list locks the server down instead of opening it up
+        let locked = McpServerConfig {
+            allowed_tools: Some(Vec::new()),
+            ..open
+        };
+        assert!(!locked.permits_tool("read_file"));
+        assert!(!locked.permits_tool("write_file"));
+    }
+
+    #[test]
+    fn test_tool_filters_combine_restrictively() {
+        let project = McpToolFilter {
+            denied_tools: vec!["filesystem__*".to_string()],
+            ..Default::default()
+        };
+        let skill = McpToolFilter {
+            allowed_tools: vec!["filesystem__read_file".to_string(), "search__*".to_string()],
+            ..Default::default()
+        };
+        let filters = vec![project, skill];
+
+        assert!(!McpToolFilter::permits_all(&filters, "filesystem__read_file"));
+        assert!(McpToolFilter::permits_all(&filters, "search__web_search"));
+        assert!(McpToolFilter::permits_all(&[], "anything__goes"));
+    }
+}
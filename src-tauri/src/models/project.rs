@@ -1,5 +1,6 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -17,6 +18,9 @@ pub enum ProjectError {
 
     #[error("Settings error: {0}")]
     SettingsError(String),
+
+    #[error("Project id {0}")]
+    IdCollision(#[from] crate::utils::slug::SlugCollision),
 }
 
 impl From<crate::models::settings::SettingsError> for ProjectError {
@@ -33,9 +37,96 @@ pub struct Project {
     pub name: String,
     pub goal: String,
     pub skills: Vec<String>,
+    /// Labels for organizing the project list; see `Project::normalize_tags`
+    #[serde(default)]
+    pub tags: Vec<String>,
     #[serde(rename = "created_at")]
     pub created: DateTime<Utc>,
     pub path: PathBuf,
+    #[serde(flatten)]
+    pub details: ProjectDetails,
+}
+
+/// Optional structured metadata. Every field defaults, so projects written before
+/// these existed still load unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDetails {
+    /// Free-form status such as "active", "paused" or "done"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collaborators: Vec<ProjectCollaborator>,
+    /// Named links, e.g. `repo: https://github.com/...`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub links: BTreeMap<String, String>,
+    #[serde(default, alias = "due_date", skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectCollaborator {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+/// Order of a project file listing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileSort {
+    /// Relative path, A to Z
+    #[default]
+    Name,
+    /// Most recently modified first
+    Modified,
+}
+
+/// A project file with the details the file list shows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFileInfo {
+    /// Path relative to the project root, with `/` separators
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// One page of a project's files
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFilePage {
+    pub files: Vec<ProjectFileInfo>,
+    /// Number of files in the whole listing, not just this page
+    pub total: usize,
+    pub offset: usize,
+}
+
+impl ProjectFilePage {
+    /// Sort `files` and cut out the page starting at `offset`. No `limit` means the rest.
+    pub fn paginate(
+        mut files: Vec<ProjectFileInfo>,
+        sort: FileSort,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Self {
+        match sort {
+            FileSort::Name => files.sort_by(|a, b| a.path.cmp(&b.path)),
+            FileSort::Modified => {
+                files.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.path.cmp(&b.path)))
+            }
+        }
+
+        let total = files.len();
+        let files = files
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+        Self { files, total, offset }
+    }
 }
 
 /// Frontmatter from .project.md matching the YAML frontmatter structure
@@ -45,10 +136,72 @@ pub struct ProjectMetadata {
     pub name: String,
     pub goal: String,
     pub skills: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     pub created: String,
+    #[serde(flatten)]
+    pub details: ProjectDetails,
+}
+
+impl ProjectMetadata {
+    fn into_project(self, path: PathBuf) -> Result<Project, ProjectError> {
+        let created = DateTime::parse_from_rfc3339(&self.created)
+            .map_err(|e| ProjectError::ParseError(format!("Invalid date format: {}", e)))?
+            .with_timezone(&Utc);
+
+        Ok(Project {
+            id: self.id,
+            name: self.name,
+            goal: self.goal,
+            skills: self.skills,
+            tags: Project::normalize_tags(self.tags),
+            created,
+            path,
+            details: self.details,
+        })
+    }
+}
+
+/// Split a `---` delimited YAML frontmatter block from the markdown after it.
+/// Tolerates CRLF line endings, a BOM, and a closing `---` on the last line.
+fn split_frontmatter(content: &str) -> Option<(&str, &str)> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut lines = content.split_inclusive('\n');
+    let opening = lines.next()?;
+    if opening.trim_end() != "---" {
+        return None;
+    }
+
+    let start = opening.len();
+    let mut offset = start;
+    for line in lines {
+        if line.trim_end() == "---" {
+            return Some((&content[start..offset], &content[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
 }
 
 impl Project {
+    /// Trim tags and drop empty ones and case-insensitive duplicates, keeping the first
+    /// spelling of each
+    pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                normalized.push(tag.to_string());
+            }
+        }
+        normalized
+    }
+
+    /// Whether the project has `tag`, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
     /// Load a project from its metadata file or legacy .project.md
     pub fn load<P: AsRef<Path>>(project_path: P) -> Result<Self, ProjectError> {
         let project_path = project_path.as_ref().to_path_buf();
@@ -61,19 +214,7 @@ impl Project {
                 ProjectError::ParseError(format!("Failed to parse project JSON: {}", e))
             })?;
 
-            // Parse created date
-            let created = DateTime::parse_from_rfc3339(&metadata.created)
-                .map_err(|e| ProjectError::ParseError(format!("Invalid date format: {}", e)))?
-                .with_timezone(&Utc);
-
-            return Ok(Project {
-                id: metadata.id,
-                name: metadata.name,
-                goal: metadata.goal,
-                skills: metadata.skills,
-                created,
-                path: project_path,
-            });
+            return metadata.into_project(project_path);
         }
 
         // Strategy 2: Load from .project.md (Legacy Format) and Migrate
@@ -86,30 +227,13 @@ impl Project {
 
             let content = fs::read_to_string(&legacy_path)?;
 
-            // Extract frontmatter using regex
-            let re = regex::Regex::new(r"(?s)^---\s*\n(.*?)\n---\s*\n").unwrap();
-
-            if let Some(captures) = re.captures(&content) {
-                let frontmatter = &captures[1];
-
+            if let Some((frontmatter, _)) = split_frontmatter(&content) {
                 // Parse YAML
                 let metadata: ProjectMetadata = serde_yaml::from_str(frontmatter).map_err(|e| {
                     ProjectError::ParseError(format!("Failed to parse legacy YAML: {}", e))
                 })?;
 
-                // Parse date
-                let created = DateTime::parse_from_rfc3339(&metadata.created)
-                    .map_err(|e| ProjectError::ParseError(format!("Invalid legacy date: {}", e)))?
-                    .with_timezone(&Utc);
-
-                let project = Project {
-                    id: metadata.id,
-                    name: metadata.name,
-                    goal: metadata.goal,
-                    skills: metadata.skills,
-                    created,
-                    path: project_path.clone(),
-                };
+                let project = metadata.into_project(project_path.clone())?;
 
                 // Perform Migration: Save to new format
                 if let Err(e) = project.save() {
@@ -151,7 +275,9 @@ impl Project {
             name: self.name.clone(),
             goal: self.goal.clone(),
             skills: self.skills.clone(),
+            tags: self.tags.clone(),
             created: self.created.to_rfc3339(),
+            details: self.details.clone(),
         };
 
         let content = serde_json::to_string_pretty(&metadata)
@@ -187,6 +313,130 @@ impl Project {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_flat_metadata_still_loads() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".metadata")).unwrap();
+        fs::write(
+            temp.path().join(".metadata").join("project.json"),
+            r#"{"id":"p","name":"P","goal":"G","skills":[],"created":"2025-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+
+        let project = Project::load(temp.path()).unwrap();
+        assert_eq!(project.details, ProjectDetails::default());
+        assert!(project.tags.is_empty());
+    }
 
-    // No legacy migration tests needed as YAML/MD frontmatter support is removed.
+    #[test]
+    fn test_tags_are_normalized_and_saved() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join(".project.md"),
+            "---\nid: p\nname: P\ngoal: G\nskills: []\ntags: [Survey, ' interviews ', survey, '']\ncreated: 2025-01-01T00:00:00Z\n---\n",
+        )
+        .unwrap();
+        let project = Project::load(temp.path()).unwrap();
+        assert_eq!(project.tags, vec!["Survey", "interviews"]);
+        assert!(project.has_tag("survey") && project.has_tag(" INTERVIEWS"));
+        assert!(!project.has_tag("pricing"));
+
+        // Migration wrote the tags to project.json
+        assert_eq!(Project::load(temp.path()).unwrap().tags, project.tags);
+    }
+
+
+    #[test]
+    fn test_structured_frontmatter_round_trips() {
+        let temp = TempDir::new().unwrap();
+        let frontmatter = r#"---
+id: p
+name: P
+goal: G
+skills: [research]
+created: 2025-01-01T00:00:00Z
+status: active
+collaborators:
+  - name: Ada
+    role: reviewer
+links:
+  repo: https://example.com/repo
+due_date: 2025-03-01
+---
+# Notes
+"#;
+        fs::write(temp.path().join(".project.md"), frontmatter).unwrap();
+
+        let project = Project::load(temp.path()).unwrap();
+        assert_eq!(project.details.status.as_deref(), Some("active"));
+        assert_eq!(project.details.collaborators[0].role.as_deref(), Some("reviewer"));
+        assert_eq!(project.details.links["repo"], "https://example.com/repo");
+        assert_eq!(project.details.due_date, NaiveDate::from_ymd_opt(2025, 3, 1));
+
+        // Migration writes project.json; reloading from it keeps the details
+        let reloaded = Project::load(temp.path()).unwrap();
+        assert_eq!(reloaded.details, project.details);
+    }
+
+    fn load_legacy(frontmatter: &str) -> Result<Project, ProjectError> {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join(".project.md"), frontmatter).unwrap();
+        Project::load(temp.path())
+    }
+
+    #[test]
+    fn test_frontmatter_values_with_colons_and_quotes() {
+        let project = load_legacy(
+            "---\r\nid: p\r\nname: 'It''s: quoted'\r\ngoal: \"Study X: the sequel\"\r\nskills: []\r\ncreated: 2025-01-01T00:00:00Z\r\n---",
+        )
+        .unwrap();
+        assert_eq!(project.goal, "Study X: the sequel");
+        assert_eq!(project.name, "It's: quoted");
+        assert!(project.skills.is_empty());
+    }
+
+    #[test]
+    fn test_frontmatter_multiline_goal() {
+        let project = load_legacy(
+            "---\nid: p\nname: P\ngoal: |\n  First line: setup\n  Second line\nskills:\n  - research\ncreated: 2025-01-01T00:00:00Z\n---\n# Notes\n",
+        )
+        .unwrap();
+        assert_eq!(project.goal, "First line: setup\nSecond line\n");
+        assert_eq!(project.skills, vec!["research".to_string()]);
+    }
+
+    #[test]
+    fn test_split_frontmatter_requires_both_delimiters() {
+        assert_eq!(split_frontmatter("---\na: 1\n---\nbody"), Some(("a: 1\n", "body")));
+        assert!(split_frontmatter("a: 1\n---\n").is_none());
+        assert!(split_frontmatter("---\na: 1\n").is_none());
+    }
+
+    #[test]
+    fn test_paginate_sorts_and_counts_all_files() {
+        let file = |path: &str, day: u32| ProjectFileInfo {
+            path: path.to_string(),
+            size: 1,
+            modified: NaiveDate::from_ymd_opt(2025, 1, day)
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc()),
+        };
+        let files = vec![file("b.md", 3), file("a.md", 1), file("c.md", 2)];
+
+        let page = ProjectFilePage::paginate(files.clone(), FileSort::Name, 1, Some(1));
+        assert_eq!(page.total, 3);
+        assert_eq!(page.offset, 1);
+        assert_eq!(page.files, vec![file("b.md", 3)]);
+
+        let page = ProjectFilePage::paginate(files.clone(), FileSort::Modified, 0, None);
+        let paths: Vec<&str> = page.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["b.md", "c.md", "a.md"]);
+
+        let page = ProjectFilePage::paginate(files, FileSort::Name, 10, Some(5));
+        assert!(page.files.is_empty());
+        assert_eq!(page.total, 3);
+    }
 }
@@ -1,9 +1,10 @@
 use crate::models::ai::{
-    ClaudeConfig, GeminiCliConfig, HostedConfig, LiteLlmConfig, OllamaConfig, OpenAiCliConfig, ProviderType,
-    RoutingStrategy,
+    ClaudeConfig, GeminiCliConfig, HostedConfig, LiteLlmConfig, OllamaConfig, OpenAiCliConfig,
+    OpenAiCompatibleConfig, ProviderType, RoutingStrategy,
 };
 use crate::models::cost::CostBudget;
 use crate::models::mcp::McpServerConfig;
+use crate::utils::fs::write_atomic;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -59,6 +60,9 @@ pub struct GlobalSettings {
     #[serde(default = "default_litellm_config")]
     pub litellm: LiteLlmConfig,
 
+    #[serde(default = "default_openai_compatible_config", alias = "openai_compatible")]
+    pub openai_compatible: OpenAiCompatibleConfig,
+
     #[serde(default)]
     pub custom_clis: Vec<crate::models::ai::CustomCliConfig>,
 
@@ -79,6 +83,73 @@ pub struct GlobalSettings {
 
     #[serde(default, alias = "selected_providers")]
     pub selected_providers: Vec<String>,
+
+    /// When false, file changes parsed from model output are proposed for review
+    /// (`files-proposed` event) instead of being written immediately
+    #[serde(default, alias = "auto_apply_file_changes")]
+    pub auto_apply_file_changes: bool,
+
+    /// Remote skill marketplace index (JSON). When unset only the bundled skills are listed.
+    #[serde(default, alias = "skill_marketplace_url")]
+    pub skill_marketplace_url: Option<String>,
+
+    /// Where the master key used to encrypt secrets is kept
+    #[serde(default, alias = "encryption_key_store")]
+    pub encryption_key_store: KeyStore,
+
+    /// When the master key was last rotated (RFC 3339), if ever
+    #[serde(default, alias = "encryption_key_rotated_at")]
+    pub encryption_key_rotated_at: Option<String>,
+
+    /// How much of each chat turn is written to disk (research log, chat history, cost log)
+    #[serde(default, alias = "logging_privacy_level")]
+    pub logging_privacy_level: LoggingPrivacyLevel,
+
+    /// Snapshot a project under `backups/projects/{id}/` before applying file changes
+    /// or other operations that rewrite it
+    #[serde(default = "default_auto_backup", alias = "auto_backup_before_risky_ops")]
+    pub auto_backup_before_risky_ops: bool,
+
+    /// Project snapshots kept per project; older ones are pruned
+    #[serde(default = "default_project_backups_to_keep", alias = "project_backups_to_keep")]
+    pub project_backups_to_keep: usize,
+
+    /// Model -> tool -> model round trips allowed in one agent turn
+    #[serde(default = "default_max_tool_iterations", alias = "max_tool_iterations")]
+    pub max_tool_iterations: usize,
+
+    /// Estimated tokens of project files and history injected into the system prompt
+    #[serde(default = "default_max_context_tokens", alias = "max_context_tokens")]
+    pub max_context_tokens: usize,
+
+    /// Extra folders under the app data directory to create and keep alongside the
+    /// built-in ones (relative paths)
+    #[serde(default, alias = "extra_app_data_dirs")]
+    pub extra_app_data_dirs: Vec<String>,
+}
+
+/// Storage backend for the master encryption key
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyStore {
+    /// macOS Keychain, Windows Credential Manager or the Secret Service on Linux
+    #[default]
+    OsKeychain,
+    /// `master.key` in the app data directory, readable only by the current user
+    File,
+}
+
+/// What gets persisted about each chat turn
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LoggingPrivacyLevel {
+    /// Prompts and responses are saved to chat history and the research log
+    #[default]
+    Full,
+    /// Only that a turn happened, with provider, model, tokens and cost
+    MetadataOnly,
+    /// Nothing is written to disk
+    Off,
 }
 
 fn default_theme() -> String {
@@ -93,6 +164,22 @@ fn default_notifications() -> bool {
     true
 }
 
+fn default_auto_backup() -> bool {
+    true
+}
+
+fn default_project_backups_to_keep() -> usize {
+    10
+}
+
+fn default_max_tool_iterations() -> usize {
+    crate::services::agent_orchestrator::DEFAULT_MAX_TOOL_ITERATIONS
+}
+
+fn default_max_context_tokens() -> usize {
+    crate::services::context_service::DEFAULT_CONTEXT_TOKEN_BUDGET
+}
+
 fn default_active_provider() -> ProviderType {
     ProviderType::GeminiCli
 }
@@ -102,6 +189,7 @@ fn default_ollama_config() -> OllamaConfig {
         model: "llama3".to_string(),
         api_url: "http://localhost:11434".to_string(),
         detected_path: None,
+        auto_pull_models: false,
     }
 }
 
@@ -117,6 +205,12 @@ fn default_hosted_config() -> HostedConfig {
         provider: "anthropic".to_string(),
         model: "claude-3-5-sonnet-20241022".to_string(),
         api_key_secret_id: "ANTHROPIC_API_KEY".to_string(),
+        beta_flags: Vec::new(),
+        extra_headers: std::collections::HashMap::new(),
+        key_rotation: Default::default(),
+        max_retries: 3,
+        retry_base_delay_ms: 1000,
+        prompt_cache_min_chars: crate::services::claude_service::DEFAULT_PROMPT_CACHE_MIN_CHARS,
     }
 }
 
@@ -155,6 +249,14 @@ fn default_litellm_config() -> LiteLlmConfig {
     }
 }
 
+fn default_openai_compatible_config() -> OpenAiCompatibleConfig {
+    OpenAiCompatibleConfig {
+        base_url: "http://localhost:8000".to_string(),
+        model: String::new(),
+        api_key_secret_id: "OPENAI_COMPATIBLE_API_KEY".to_string(),
+    }
+}
+
 fn default_auto_escalate_threshold() -> f64 {
     0.6
 }
@@ -177,6 +279,7 @@ impl Default for GlobalSettings {
             gemini_cli: default_gemini_cli_config(),
             openai_cli: default_openai_cli_config(),
             litellm: default_litellm_config(),
+            openai_compatible: default_openai_compatible_config(),
             custom_clis: Vec::new(),
             mcp_servers: Vec::new(),
             artifact_templates: HashMap::new(),
@@ -184,6 +287,16 @@ impl Default for GlobalSettings {
             auto_escalate_threshold: default_auto_escalate_threshold(),
             budget_warning_threshold: default_budget_warning_threshold(),
             selected_providers: Vec::new(),
+            auto_apply_file_changes: false,
+            skill_marketplace_url: None,
+            encryption_key_store: KeyStore::OsKeychain,
+            encryption_key_rotated_at: None,
+            logging_privacy_level: LoggingPrivacyLevel::Full,
+            auto_backup_before_risky_ops: default_auto_backup(),
+            project_backups_to_keep: default_project_backups_to_keep(),
+            max_tool_iterations: default_max_tool_iterations(),
+            max_context_tokens: default_max_context_tokens(),
+            extra_app_data_dirs: Vec::new(),
         }
     }
 }
@@ -209,7 +322,7 @@ impl GlobalSettings {
             SettingsError::WriteError(format!("Failed to serialize settings: {}", e))
         })?;
 
-        fs::write(path, content)
+        write_atomic(path.as_ref(), content)
             .map_err(|e| SettingsError::WriteError(format!("Failed to write settings: {}", e)))?;
 
         Ok(())
@@ -242,6 +355,28 @@ pub struct ProjectSettings {
 
     #[serde(default)]
     pub brand_settings: Option<String>,
+
+    /// Project-relative paths always included in the agent's context
+    #[serde(default)]
+    pub pinned_context_files: Vec<String>,
+
+    /// Globs of project files the automatic context may draw on (all files when empty).
+    /// Patterns without a `/` also match against the file name alone.
+    #[serde(default)]
+    pub context_include: Vec<String>,
+
+    /// Globs of project files kept out of the automatic context. Pinned files are
+    /// always included.
+    #[serde(default)]
+    pub context_exclude: Vec<String>,
+
+    /// Restricts which MCP tools the agent may use in this project
+    #[serde(default)]
+    pub tool_filter: Option<crate::models::mcp::McpToolFilter>,
+
+    /// Ask the user before each MCP tool call the agent makes in this project
+    #[serde(default)]
+    pub require_tool_approval: bool,
 }
 
 impl Default for ProjectSettings {
@@ -255,6 +390,11 @@ impl Default for ProjectSettings {
             encryption_enabled: Some(true),
             personalization_rules: None,
             brand_settings: None,
+            pinned_context_files: Vec::new(),
+            context_include: Vec::new(),
+            context_exclude: Vec::new(),
+            tool_filter: None,
+            require_tool_approval: false,
         }
     }
 }
@@ -280,7 +420,7 @@ impl ProjectSettings {
             SettingsError::WriteError(format!("Failed to serialize project settings: {}", e))
         })?;
 
-        fs::write(path, content).map_err(|e| {
+        write_atomic(path.as_ref(), content).map_err(|e| {
             SettingsError::WriteError(format!("Failed to write project settings: {}", e))
         })?;
 
@@ -301,5 +441,12 @@ mod tests {
         // Onboarding defaults for OpenAI CLI provider should be auto
         assert_eq!(settings.openai_cli.command, "codex");
         assert_eq!(settings.openai_cli.model_alias, "auto");
+
+        // File changes from the model require review unless explicitly enabled
+        assert!(!settings.auto_apply_file_changes);
+
+        // The master key lives in the OS keychain unless the user opts out
+        assert_eq!(settings.encryption_key_store, KeyStore::OsKeychain);
+        assert_eq!(settings.logging_privacy_level, LoggingPrivacyLevel::Full);
     }
 }
@@ -1,3 +1,4 @@
+use crate::models::mcp::McpToolFilter;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -23,6 +24,12 @@ pub enum SkillError {
 
     #[error("Template rendering error: {0}")]
     RenderError(String),
+
+    #[error("Invalid skill parameters: {}", .0.join("; "))]
+    InvalidParams(Vec<String>),
+
+    #[error("Skill id {0}")]
+    IdCollision(#[from] crate::utils::slug::SlugCollision),
 }
 
 /// Skill category enumeration
@@ -35,12 +42,51 @@ pub enum SkillCategory {
     Other,
 }
 
+impl SkillCategory {
+    /// The `category` value stored on skills in this category
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkillCategory::Research => "research",
+            SkillCategory::Development => "development",
+            SkillCategory::Writing => "writing",
+            SkillCategory::Analysis => "analysis",
+            SkillCategory::Other => DEFAULT_SKILL_CATEGORY,
+        }
+    }
+}
+
+/// Category of skills that don't declare one
+pub const DEFAULT_SKILL_CATEGORY: &str = "general";
+
+fn default_category() -> String {
+    DEFAULT_SKILL_CATEGORY.to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// The part of a skill's YAML frontmatter the loader reads; the rest lives in the sidecar
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SkillFrontmatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+}
+
 /// Represents a comprehensive skill/prompt template with full metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
     pub id: String,
     pub name: String,
     pub description: String,
+    /// Lowercase category such as "research"; see `SkillCategory::as_str`
+    #[serde(default = "default_category")]
+    pub category: String,
+    /// Disabled skills are still listed but can't be used in chats
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
     pub capabilities: Vec<String>,
     pub prompt_template: String,
     pub examples: Vec<SkillExample>,
@@ -49,6 +95,15 @@ pub struct Skill {
     pub created: String,
     pub updated: String,
     pub file_path: PathBuf,
+    /// MCP tools this skill may use; narrows the project's tool set when the skill is active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_filter: Option<McpToolFilter>,
+    /// MCP tools the skill can't do without, as `server__tool`, a bare tool name or `server__*`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_tools: Vec<String>,
+    /// Refuse to run, rather than only warn, when a required tool is unavailable
+    #[serde(default)]
+    pub block_without_required_tools: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,15 +123,85 @@ pub struct SkillParameter {
     pub default_value: Option<String>,
 }
 
+impl SkillParameter {
+    /// Why `value` isn't a valid `param_type`, if it isn't. Arrays may be a JSON array or a
+    /// comma-separated list; unknown types accept anything (`validate` reports those).
+    pub fn type_error(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        let valid = match self.param_type.as_str() {
+            "number" => value.parse::<f64>().is_ok_and(f64::is_finite),
+            "boolean" => value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false"),
+            "array" if value.starts_with('[') => {
+                serde_json::from_str::<Vec<serde_json::Value>>(value).is_ok()
+            }
+            _ => true,
+        };
+        (!valid).then(|| {
+            format!("'{}' must be a {} (got '{}')", self.name, self.param_type, value)
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillMetadata {
     pub skill_id: String,
     pub name: String,
     pub description: String,
+    /// Empty in sidecars written before categories were stored
+    #[serde(default)]
+    pub category: String,
     pub capabilities: Vec<String>,
     pub version: String,
     pub created: String,
     pub updated: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_filter: Option<McpToolFilter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_tools: Vec<String>,
+    #[serde(default)]
+    pub block_without_required_tools: bool,
+}
+
+/// A skill prompt rendered for previewing; see `Skill::preview_prompt`
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillPromptPreview {
+    /// The template with every parameter that has a value or default filled in
+    pub rendered: String,
+    /// Names of the `{{placeholders}}` left in `rendered`, in order of appearance
+    pub unresolved: Vec<String>,
+    /// Parameter problems that would stop the skill from running in a chat
+    pub problems: Vec<String>,
+}
+
+/// A skill listed in the skill marketplace (bundled or from a remote index)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketplaceSkill {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// URL of the skill markdown. Not present for bundled skills.
+    #[serde(default, alias = "download_url")]
+    pub download_url: Option<String>,
+    /// "bundled" or "remote"
+    #[serde(default)]
+    pub source: String,
+    /// Whether a skill with this id already exists in the skills directory
+    #[serde(default)]
+    pub installed: bool,
+}
+
+/// Remote skill marketplace index format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillMarketplaceIndex {
+    pub skills: Vec<MarketplaceSkill>,
 }
 
 impl Skill {
@@ -92,42 +217,7 @@ impl Skill {
         if !sidecar_path.exists() {
             // Read the markdown body to extract what we can
             let body = fs::read_to_string(path)?;
-            let (prompt_template, examples, parameters) = Self::parse_body(&body)?;
-
-            // Extract name from first heading or filename
-            let name = body.lines()
-                .find(|l| l.starts_with("# "))
-                .map(|l| l.trim_start_matches("# ").trim().to_string())
-                .unwrap_or_else(|| skill_id.replace('-', " ").replace('_', " "));
-
-            // Extract description from Overview section or first paragraph
-            let description = body.lines()
-                .skip_while(|l| !l.starts_with("## Overview"))
-                .skip(1)
-                .take_while(|l| !l.starts_with("## "))
-                .filter(|l| !l.trim().is_empty())
-                .collect::<Vec<_>>()
-                .join(" ");
-            let description = if description.is_empty() {
-                format!("Skill loaded from {}", file_name)
-            } else {
-                description
-            };
-
-            let now = chrono::Utc::now().to_rfc3339();
-            let skill = Skill {
-                id: skill_id.to_string(),
-                name,
-                description,
-                capabilities: vec![],
-                prompt_template,
-                examples,
-                parameters,
-                version: "1.0.0".to_string(),
-                created: now.clone(),
-                updated: now,
-                file_path: path.clone(),
-            };
+            let skill = Self::from_markdown_str(&body, skill_id, None, path.clone())?;
 
             // Auto-save the sidecar for future loads
             if let Err(e) = fs::create_dir_all(&sidecar_dir) {
@@ -149,29 +239,111 @@ impl Skill {
             .map_err(|e| SkillError::ParseError(format!("Failed to parse skill JSON: {}", e)))?;
 
         let body = fs::read_to_string(path)?;
+        Self::from_markdown_str(&body, skill_id, Some(metadata), path.clone())
+    }
+
+    /// Build a skill from markdown content. Metadata comes from `metadata` (the sidecar),
+    /// else from frontmatter holding the full metadata (as written by `to_shareable_markdown`),
+    /// else it is inferred from the headings, with `fallback_id` as the id.
+    pub fn from_markdown_str(
+        content: &str,
+        fallback_id: &str,
+        metadata: Option<SkillMetadata>,
+        file_path: PathBuf,
+    ) -> Result<Self, SkillError> {
+        // Parse markdown body for examples, parameters, and prompt template
+        let (prompt_template, examples, parameters) = Self::parse_body(content)?;
+        let metadata = metadata.or_else(|| {
+            Self::frontmatter(content).and_then(|yaml| serde_yaml::from_str(yaml).ok())
+        });
 
-        // 3. Parse markdown body for examples, parameters, and prompt template
-        let (prompt_template, examples, parameters) = Self::parse_body(&body)?;
+        if let Some(metadata) = metadata {
+            return Ok(Skill {
+                id: metadata.skill_id,
+                name: metadata.name,
+                description: metadata.description,
+                category: Self::parse_category(content, Some(metadata.category)),
+                enabled: Self::parse_enabled(content),
+                capabilities: metadata.capabilities,
+                prompt_template,
+                examples,
+                parameters,
+                version: metadata.version,
+                created: metadata.created,
+                updated: metadata.updated,
+                file_path,
+                tool_filter: metadata.tool_filter,
+                required_tools: metadata.required_tools,
+                block_without_required_tools: metadata.block_without_required_tools,
+            });
+        }
+
+        // Extract name from first heading or the id
+        let name = content
+            .lines()
+            .find(|l| l.starts_with("# "))
+            .map(|l| l.trim_start_matches("# ").trim().to_string())
+            .unwrap_or_else(|| fallback_id.replace('-', " ").replace('_', " "));
+
+        // Extract description from Overview section or first paragraph
+        let description = content
+            .lines()
+            .skip_while(|l| !l.starts_with("## Overview"))
+            .skip(1)
+            .take_while(|l| !l.starts_with("## "))
+            .filter(|l| !l.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let description = if description.is_empty() {
+            format!("Skill loaded from {}.md", fallback_id)
+        } else {
+            description
+        };
 
-        // 4. Return populated Skill struct
+        let now = chrono::Utc::now().to_rfc3339();
         Ok(Skill {
-            id: metadata.skill_id,
-            name: metadata.name,
-            description: metadata.description,
-            capabilities: metadata.capabilities,
+            id: fallback_id.to_string(),
+            name,
+            description,
+            category: Self::parse_category(content, None),
+            enabled: Self::parse_enabled(content),
+            capabilities: vec![],
             prompt_template,
             examples,
             parameters,
-            version: metadata.version,
-            created: metadata.created,
-            updated: metadata.updated,
-            file_path: path.clone(),
+            version: "1.0.0".to_string(),
+            created: now.clone(),
+            updated: now,
+            file_path,
+            tool_filter: None,
+            required_tools: Vec::new(),
+            block_without_required_tools: false,
         })
     }
 
     /// Convert skill to markdown format
-    /// Convert skill to pure markdown format (No frontmatter)
-    pub fn to_markdown(&self) -> String {
+    /// The frontmatter holds the category, and `enabled: false` for disabled skills, so
+    /// they're visible when editing the file; the rest of the metadata lives in the JSON sidecar
+    pub fn to_markdown(&self) -> Result<String, SkillError> {
+        let frontmatter = SkillFrontmatter {
+            category: Some(self.category.clone()),
+            enabled: (!self.enabled).then_some(false),
+        };
+        let yaml = serde_yaml::to_string(&frontmatter)
+            .map_err(|e| SkillError::ParseError(format!("Failed to serialize skill: {}", e)))?;
+        Ok(format!("---\n{}---\n\n{}", yaml, self.body_markdown()))
+    }
+
+    /// A single self-contained file for sharing: all metadata as YAML frontmatter, followed
+    /// by the same markdown body (prompt, parameters and examples) as the skill file
+    pub fn to_shareable_markdown(&self) -> Result<String, SkillError> {
+        let yaml = serde_yaml::to_string(&self.metadata())
+            .map_err(|e| SkillError::ParseError(format!("Failed to serialize skill: {}", e)))?;
+        Ok(format!("---\n{}---\n\n{}", yaml, self.body_markdown()))
+    }
+
+    /// Markdown content without frontmatter
+    fn body_markdown(&self) -> String {
         let mut markdown = String::new();
 
         // 1. Generate markdown header
@@ -247,14 +419,17 @@ impl Skill {
             errors.push("prompt_template cannot be empty".to_string());
         }
 
-        // Validate skill_id format (alphanumeric + hyphens + underscores)
-        let valid_id = self
-            .id
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
-        if !valid_id {
+        // Validate skill_id format (alphanumeric + hyphens + underscores, with `/` between
+        // folder names for skills in subfolders)
+        let valid_id = self.id.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        });
+        if !self.id.is_empty() && !valid_id {
             errors.push(format!(
-                "skill_id '{}' contains invalid characters (only alphanumeric, hyphens, and underscores allowed)",
+                "skill_id '{}' contains invalid characters (only alphanumeric, hyphens, and underscores allowed, with / between folders)",
                 self.id
             ));
         }
@@ -286,6 +461,12 @@ impl Skill {
             }
         }
 
+        for tool in &self.required_tools {
+            if tool.trim().is_empty() {
+                errors.push("required tool name cannot be empty".to_string());
+            }
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -293,20 +474,58 @@ impl Skill {
         }
     }
 
-    /// Apply skill parameters to prompt template
-    pub fn render_prompt(&self, params: HashMap<String, String>) -> Result<String, SkillError> {
-        let mut rendered = self.prompt_template.clone();
+    /// Required tools not offered by any of `available` (namespaced `server__tool` names).
+    /// A bare tool name matches that tool on any server.
+    pub fn missing_required_tools(&self, available: &[String]) -> Vec<String> {
+        let matches = |required: &str, tool: &str| match required.strip_suffix('*') {
+            Some(prefix) => tool.starts_with(prefix),
+            None if required.contains("__") => tool == required,
+            None => tool.split_once("__").map_or(tool, |(_, name)| name) == required,
+        };
+        self.required_tools
+            .iter()
+            .filter(|required| !available.iter().any(|tool| matches(required.as_str(), tool.as_str())))
+            .cloned()
+            .collect()
+    }
+
+    /// Check `params` against the declared parameters before rendering: every required
+    /// parameter needs a non-blank value, every other one a value or a default, and values
+    /// must match their declared type. All problems are reported together.
+    pub fn validate_params(&self, params: &HashMap<String, String>) -> Result<(), SkillError> {
+        let mut missing = Vec::new();
+        let mut invalid = Vec::new();
 
-        // Check that all required parameters are provided
         for param in &self.parameters {
-            if param.required && !params.contains_key(&param.name) {
-                return Err(SkillError::RenderError(format!(
-                    "Required parameter '{}' not provided",
-                    param.name
-                )));
+            let provided = params.get(&param.name).filter(|v| !v.trim().is_empty());
+            match provided {
+                Some(value) => invalid.extend(param.type_error(value)),
+                None if param.required => missing.push(param.name.clone()),
+                None if param.default_value.is_none() => {
+                    missing.push(format!("{} (no default)", param.name))
+                }
+                None => {}
             }
         }
 
+        let mut problems = Vec::new();
+        if !missing.is_empty() {
+            problems.push(format!("missing {}", missing.join(", ")));
+        }
+        problems.extend(invalid);
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(SkillError::InvalidParams(problems))
+        }
+    }
+
+    /// Apply skill parameters to prompt template
+    pub fn render_prompt(&self, params: HashMap<String, String>) -> Result<String, SkillError> {
+        let mut rendered = self.prompt_template.clone();
+
+        self.validate_params(&params)?;
+
         // Replace placeholders in prompt_template with actual values
         // Format: {{parameter_name}}
         for param in &self.parameters {
@@ -338,6 +557,74 @@ impl Skill {
         Ok(rendered)
     }
 
+    /// Render the prompt as far as the params allow, for previewing. Unlike `render_prompt`
+    /// this doesn't fail: unresolved placeholders are left in place and listed.
+    pub fn preview_prompt(&self, params: &HashMap<String, String>) -> SkillPromptPreview {
+        let mut rendered = self.prompt_template.clone();
+        for param in &self.parameters {
+            if let Some(value) = params.get(&param.name).or(param.default_value.as_ref()) {
+                rendered = rendered.replace(&format!("{{{{{}}}}}", param.name), value);
+            }
+        }
+
+        let problems = match self.validate_params(params) {
+            Err(SkillError::InvalidParams(problems)) => problems,
+            _ => Vec::new(),
+        };
+        SkillPromptPreview {
+            unresolved: Self::placeholders(&rendered),
+            rendered,
+            problems,
+        }
+    }
+
+    /// Distinct names of the `{{placeholders}}` in `text`
+    fn placeholders(text: &str) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                break;
+            };
+            let name = after[..end].trim().to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+            rest = &after[end + 2..];
+        }
+        names
+    }
+
+    /// The YAML between a leading `---` and the next `---` line
+    fn frontmatter(content: &str) -> Option<&str> {
+        let rest = content.strip_prefix("---")?;
+        rest.find("\n---").map(|end| &rest[..end])
+    }
+
+    /// Category from the file's frontmatter, else the sidecar's, else the default.
+    /// Normalized to lowercase so categories compare exactly.
+    fn parse_category(body: &str, sidecar_category: Option<String>) -> String {
+        Self::parse_frontmatter(body)
+            .category
+            .into_iter()
+            .chain(sidecar_category)
+            .map(|category| category.trim().to_lowercase())
+            .find(|category| !category.is_empty())
+            .unwrap_or_else(default_category)
+    }
+
+    /// Whether the file's frontmatter leaves the skill enabled (the default)
+    fn parse_enabled(body: &str) -> bool {
+        Self::parse_frontmatter(body).enabled.unwrap_or_else(default_enabled)
+    }
+
+    fn parse_frontmatter(body: &str) -> SkillFrontmatter {
+        Self::frontmatter(body)
+            .and_then(|yaml| serde_yaml::from_str::<SkillFrontmatter>(yaml).ok())
+            .unwrap_or_default()
+    }
+
     /// Parse markdown body for prompt template, examples, and parameters
     fn parse_body(
         body: &str,
@@ -556,10 +843,14 @@ impl Skill {
             skill_id: self.id.clone(),
             name: self.name.clone(),
             description: self.description.clone(),
+            category: self.category.clone(),
             capabilities: self.capabilities.clone(),
             version: self.version.clone(),
             created: self.created.clone(),
             updated: self.updated.clone(),
+            tool_filter: self.tool_filter.clone(),
+            required_tools: self.required_tools.clone(),
+            block_without_required_tools: self.block_without_required_tools,
         }
     }
 
@@ -569,7 +860,7 @@ impl Skill {
         let path = path.as_ref();
 
         // 1. Save pure Markdown content
-        let content = self.to_markdown();
+        let content = self.to_markdown()?;
         fs::write(path, content)?;
 
         // 2. Save JSON metadata sidecar
@@ -578,7 +869,12 @@ impl Skill {
             fs::create_dir_all(&sidecar_dir)?;
         }
 
-        let sidecar_path = sidecar_dir.join(format!("{}.json", self.id));
+        // Named by the file, since ids of skills in subfolders include the folder
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.id.clone());
+        let sidecar_path = sidecar_dir.join(format!("{}.json", stem));
         let metadata = self.metadata();
         let meta_content = serde_json::to_string_pretty(&metadata).map_err(|e| {
             SkillError::ParseError(format!("Failed to serialize skill JSON: {}", e))
@@ -600,6 +896,7 @@ mod tests {
             id: "test-skill".to_string(),
             name: "Test Skill".to_string(),
             description: "A test skill".to_string(),
+            category: "research".to_string(),
             capabilities: vec!["testing".to_string()],
             prompt_template: "Test prompt".to_string(),
             examples: vec![],
@@ -608,6 +905,10 @@ mod tests {
             created: "2024-11-13".to_string(),
             updated: "2024-11-13".to_string(),
             file_path: PathBuf::from("test.md"),
+            tool_filter: None,
+            required_tools: Vec::new(),
+            block_without_required_tools: false,
+            enabled: true,
         };
 
         assert!(skill.validate().is_ok());
@@ -619,6 +920,7 @@ mod tests {
             id: "test skill!".to_string(),
             name: "Test Skill".to_string(),
             description: "A test skill".to_string(),
+            category: "research".to_string(),
             capabilities: vec![],
             prompt_template: "Test prompt".to_string(),
             examples: vec![],
@@ -627,17 +929,52 @@ mod tests {
             created: "".to_string(),
             updated: "".to_string(),
             file_path: PathBuf::from("test.md"),
+            tool_filter: None,
+            required_tools: Vec::new(),
+            block_without_required_tools: false,
+            enabled: true,
         };
 
         assert!(skill.validate().is_err());
     }
 
+    #[test]
+    fn test_missing_required_tools() {
+        let skill = Skill {
+            id: "web-research".to_string(),
+            name: "Web Research".to_string(),
+            description: "Researches the web".to_string(),
+            category: "research".to_string(),
+            capabilities: vec![],
+            prompt_template: "Research {{topic}}".to_string(),
+            examples: vec![],
+            parameters: vec![],
+            version: "1.0.0".to_string(),
+            created: "".to_string(),
+            updated: "".to_string(),
+            file_path: PathBuf::from("web-research.md"),
+            tool_filter: None,
+            required_tools: vec![
+                "web_search".to_string(),
+                "brave__fetch".to_string(),
+                "github__*".to_string(),
+            ],
+            block_without_required_tools: false,
+            enabled: true,
+        };
+
+        let available = vec!["brave__web_search".to_string(), "brave__fetch".to_string()];
+        assert_eq!(skill.missing_required_tools(&available), vec!["github__*"]);
+        assert_eq!(skill.missing_required_tools(&[]).len(), 3);
+    }
+
     #[test]
     fn test_render_prompt() {
         let skill = Skill {
             id: "test".to_string(),
             name: "Test".to_string(),
             description: "Test".to_string(),
+            category: "research".to_string(),
             capabilities: vec![],
             prompt_template: "Hello {{name}}, you are {{age}} years old.".to_string(),
             examples: vec![],
@@ -661,13 +998,87 @@ mod tests {
             created: "".to_string(),
             updated: "".to_string(),
             file_path: PathBuf::from("test.md"),
+            tool_filter: None,
+            required_tools: Vec::new(),
+            block_without_required_tools: false,
+            enabled: true,
         };
 
         let mut params = HashMap::new();
         params.insert("name".to_string(), "Alice".to_string());
 
+        let preview = skill.preview_prompt(&params);
+        assert_eq!(preview.rendered, "Hello Alice, you are 25 years old.");
+        assert!(preview.unresolved.is_empty() && preview.problems.is_empty());
+
         let result = skill.render_prompt(params).unwrap();
         assert_eq!(result, "Hello Alice, you are 25 years old.");
+
+        // Previews fill in what they can and list the rest
+        let preview = skill.preview_prompt(&HashMap::new());
+        assert_eq!(preview.rendered, "Hello {{name}}, you are 25 years old.");
+        assert_eq!(preview.unresolved, vec!["name"]);
+        assert_eq!(preview.problems, vec!["missing name"]);
+        assert_eq!(Skill::placeholders("{{ a }} {{b}} {{a}} {{unclosed"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_validate_params() {
+        let param = |name: &str, param_type: &str, required: bool, default: Option<&str>| {
+            SkillParameter {
+                name: name.to_string(),
+                param_type: param_type.to_string(),
+                description: String::new(),
+                required,
+                default_value: default.map(str::to_string),
+            }
+        };
+        let skill = Skill {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            description: "Test".to_string(),
+            category: "research".to_string(),
+            capabilities: vec![],
+            prompt_template: "{{topic}} {{depth}} {{cite}} {{sources}} {{tone}}".to_string(),
+            examples: vec![],
+            parameters: vec![
+                param("topic", "string", true, None),
+                param("depth", "number", true, None),
+                param("cite", "boolean", false, Some("true")),
+                param("sources", "array", false, Some("[]")),
+                param("tone", "string", false, None),
+            ],
+            version: "1.0.0".to_string(),
+            created: "".to_string(),
+            updated: "".to_string(),
+            file_path: PathBuf::from("test.md"),
+            tool_filter: None,
+            required_tools: Vec::new(),
+            block_without_required_tools: false,
+            enabled: true,
+        };
+        let params = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+
+        let ok = params(&[("topic", "LLMs"), ("depth", "2"), ("tone", "formal")]);
+        assert!(skill.validate_params(&ok).is_ok());
+        assert_eq!(skill.render_prompt(ok).unwrap(), "LLMs 2 true [] formal");
+
+        let err = skill
+            .validate_params(&params(&[("topic", " "), ("depth", "deep"), ("cite", "yes")]))
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "Invalid skill parameters: missing topic, tone (no default); \
+             'depth' must be a number (got 'deep'); 'cite' must be a boolean (got 'yes')"
+        );
+
+        let bad_array = params(&[("topic", "x"), ("depth", "1"), ("tone", "x"), ("sources", "[a")]);
+        assert!(skill.validate_params(&bad_array).is_err());
+        let list = params(&[("topic", "x"), ("depth", "1"), ("tone", "x"), ("sources", "a, b")]);
+        assert!(skill.validate_params(&list).is_ok());
     }
 
     #[test]
@@ -766,12 +1177,78 @@ A comprehensive report on ML trends.
         assert!(rendered.contains("Compare PyTorch vs TensorFlow"));
 
         // Serialize back to markdown (Should be pure content)
-        let serialized = skill.to_markdown();
+        let serialized = skill.to_markdown().unwrap();
         assert!(!serialized.contains("skill_id: test-researcher")); // No YAML in MD anymore
         assert!(serialized.contains("Test Research Assistant"));
         assert!(!serialized.contains("web_search")); // Capabilities are in JSON now
+        assert_eq!(skill.category, DEFAULT_SKILL_CATEGORY);
 
         // Cleanup
         fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[test]
+    fn test_category_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("reviewer.md");
+        fs::write(
+            &path,
+            "---\nname: Reviewer\ncategory: Research\ntags: []\n---\n\n# Reviewer\n\n\
+             ## Prompt Template\nReview {{paper}}\n",
+        )
+        .unwrap();
+
+        // First load reads the template frontmatter, normalized
+        let mut skill = Skill::from_markdown_file(&path).unwrap();
+        assert_eq!(skill.category, "research");
+        assert_eq!(skill.prompt_template, "Review {{paper}}");
+
+        skill.category = "analysis".to_string();
+        skill.save(&path).unwrap();
+        let markdown = fs::read_to_string(&path).unwrap();
+        assert!(markdown.starts_with("---\ncategory: analysis\n---\n"));
+        let reloaded = Skill::from_markdown_file(&path).unwrap();
+        assert_eq!(reloaded.category, "analysis");
+        assert!(reloaded.prompt_template.starts_with("Review {{paper}}"));
+
+        // Categories that look like YAML syntax are quoted instead of breaking the frontmatter
+        for category in ["data: raw", "a # b"] {
+            skill.category = category.to_string();
+            skill.enabled = false;
+            skill.save(&path).unwrap();
+            let reloaded = Skill::from_markdown_file(&path).unwrap();
+            assert_eq!(reloaded.category, category);
+            assert!(!reloaded.enabled);
+        }
+
+        // Sidecar category is the fallback when the file has no frontmatter
+        assert_eq!(Skill::parse_category("# Skill", Some("Writing".to_string())), "writing");
+        assert_eq!(Skill::parse_category("# Skill", Some(String::new())), DEFAULT_SKILL_CATEGORY);
+    }
+
+    #[test]
+    fn test_enabled_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("summarize.md");
+        fs::write(&path, "# Summarize
+
+## Prompt Template
+Summarize {{text}}
+").unwrap();
+
+        // Skills without the flag are enabled
+        let mut skill = Skill::from_markdown_file(&path).unwrap();
+        assert!(skill.enabled);
+
+        skill.enabled = false;
+        skill.save(&path).unwrap();
+        let markdown = fs::read_to_string(&path).unwrap();
+        assert!(markdown.starts_with("---\ncategory: general\nenabled: false\n---\n"));
+        assert!(!Skill::from_markdown_file(&path).unwrap().enabled);
+
+        skill.enabled = true;
+        skill.save(&path).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("enabled:"));
+        assert!(Skill::from_markdown_file(&path).unwrap().enabled);
+    }
 }
@@ -1,4 +1,6 @@
+use crate::models::ai::GenerationMetadata;
 use crate::models::artifact::ArtifactType;
+use crate::models::cost::CostLog;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
@@ -25,8 +27,21 @@ pub enum WorkflowError {
 
     #[error("Dependency cycle detected")]
     DependencyCycle,
+
+    #[error("Workflow was cancelled")]
+    Cancelled,
 }
 
+/// Opening of a step output reference, e.g. `{{steps.search.output.count}}`
+pub const STEP_REFERENCE_PREFIX: &str = "{{steps.";
+
+/// Items a loop step may run when it sets no `max_iterations`
+pub const DEFAULT_MAX_LOOP_ITERATIONS: u32 = 100;
+
+/// Schema version written by this app version. Bump it and add a step to
+/// `Workflow::migrate` whenever the on-disk format changes incompatibly.
+pub const WORKFLOW_SCHEMA_VERSION: u32 = 2;
+
 /// Represents a workflow with steps and configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workflow {
@@ -44,6 +59,30 @@ pub struct Workflow {
     pub active_execution_id: Option<String>,
     #[serde(default)]
     pub schedule: Option<WorkflowSchedule>,
+    /// MCP tools available to this workflow's steps, on top of project and skill filters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_filter: Option<crate::models::mcp::McpToolFilter>,
+    /// Files written before schema versioning existed are version 1
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+}
+
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+/// A workflow file that was skipped while loading a project's workflows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowLoadError {
+    pub file_name: String,
+    pub error: String,
+}
+
+/// Result of loading a project's `.workflows` directory
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowLoadReport {
+    pub workflows: Vec<Workflow>,
+    pub errors: Vec<WorkflowLoadError>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +111,24 @@ fn default_step_type() -> StepType {
     StepType::Agent
 }
 
+impl WorkflowStep {
+    /// Ids of the steps whose output this step's config references with
+    /// `{{steps.<step_id>.output...}}`
+    pub fn referenced_step_ids(&self) -> Vec<String> {
+        let config = serde_json::to_string(&self.config).unwrap_or_default();
+        let mut ids = Vec::new();
+        let mut rest = config.as_str();
+        while let Some(start) = rest.find(STEP_REFERENCE_PREFIX) {
+            rest = &rest[start + STEP_REFERENCE_PREFIX.len()..];
+            let id = rest.split(['.', '}']).next().unwrap_or_default();
+            if !id.is_empty() && !ids.iter().any(|existing| existing == id) {
+                ids.push(id.to_string());
+            }
+        }
+        ids
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum StepType {
@@ -80,6 +137,8 @@ pub enum StepType {
     Iteration,
     Synthesis,
     Conditional,
+    /// Runs `config.child` once per entry of `config.items`
+    Loop,
     #[serde(alias = "SubAgent", alias = "subagent")]
     SubAgent,
     // Legacy types for backward compatibility
@@ -113,10 +172,21 @@ pub struct StepConfig {
     pub output_pattern: Option<String>,
 
     // Conditional step fields
+    /// `file_exists:<path>`, or `{{steps.<id>.output...}} contains|equals|matches <text>`
     pub condition: Option<String>,
+    /// Dependent step that runs only when the condition holds (as do unnamed dependents)
     pub then_step: Option<String>,
+    /// Dependent step that runs only when the condition is false
     pub else_step: Option<String>,
 
+    // Loop step fields
+    /// A JSON array, or a `{{steps.<id>.output...}}` reference to a list (a text output
+    /// is split into lines)
+    pub items: Option<serde_json::Value>,
+    /// Step run for each item, with `{{item}}` and `{{index}}` substituted in its config
+    pub child: Option<Box<WorkflowStep>>,
+    /// Most items a loop may run; defaults to `DEFAULT_MAX_LOOP_ITERATIONS`
+    pub max_iterations: Option<u32>,
 
     // Sub-Agent / Parallel fields
     pub context: Option<String>,
@@ -127,7 +197,90 @@ pub struct StepConfig {
 }
 
 impl Workflow {
-    /// Validate workflow structure
+    /// Parse a workflow file, upgrading older schema versions first.
+    /// Returns the workflow and whether it was migrated (and so should be re-saved).
+    pub fn from_json(content: &str, project_id: &str) -> Result<(Self, bool), WorkflowError> {
+        let mut value: serde_json::Value = serde_json::from_str(content)
+            .map_err(|e| WorkflowError::ParseError(format!("Invalid JSON: {}", e)))?;
+        let migrated = Self::migrate(&mut value, project_id)?;
+        let workflow = serde_json::from_value(value)
+            .map_err(|e| WorkflowError::ParseError(format!("Failed to parse workflow: {}", e)))?;
+        Ok((workflow, migrated))
+    }
+
+    /// Upgrade a raw workflow document to `WORKFLOW_SCHEMA_VERSION` in place
+    fn migrate(value: &mut serde_json::Value, project_id: &str) -> Result<bool, WorkflowError> {
+        let Some(doc) = value.as_object_mut() else {
+            return Err(WorkflowError::ParseError(
+                "workflow file must contain a JSON object".to_string(),
+            ));
+        };
+
+        let version = doc
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(legacy_schema_version() as u64);
+        if version > WORKFLOW_SCHEMA_VERSION as u64 {
+            return Err(WorkflowError::ParseError(format!(
+                "workflow schema version {} is newer than this app supports ({})",
+                version, WORKFLOW_SCHEMA_VERSION
+            )));
+        }
+        if version == WORKFLOW_SCHEMA_VERSION as u64 {
+            return Ok(false);
+        }
+
+        if version < 2 {
+            Self::migrate_v1(doc, project_id);
+        }
+
+        doc.insert("schema_version".to_string(), WORKFLOW_SCHEMA_VERSION.into());
+        Ok(true)
+    }
+
+    /// Version 1 files could omit metadata and used `type` / `dependencies` on steps
+    fn migrate_v1(doc: &mut serde_json::Map<String, serde_json::Value>, project_id: &str) {
+        use serde_json::{json, Value};
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let id = doc.get("id").cloned().unwrap_or(Value::Null);
+        doc.entry("project_id").or_insert_with(|| json!(project_id));
+        doc.entry("name").or_insert(id);
+        doc.entry("description").or_insert_with(|| json!(""));
+        doc.entry("version").or_insert_with(|| json!("1.0.0"));
+        doc.entry("created").or_insert_with(|| json!(now));
+        let created = doc["created"].clone();
+        doc.entry("updated").or_insert(created);
+        doc.entry("steps").or_insert_with(|| json!([]));
+
+        let Some(steps) = doc.get_mut("steps").and_then(Value::as_array_mut) else {
+            return;
+        };
+        for step in steps.iter_mut().filter_map(Value::as_object_mut) {
+            if !step.contains_key("step_type") {
+                if let Some(Value::String(kind)) = step.remove("type") {
+                    step.insert("step_type".to_string(), json!(kind.to_lowercase()));
+                }
+            }
+            if !step.contains_key("depends_on") {
+                let deps = step.remove("dependencies").unwrap_or_else(|| json!([]));
+                step.insert("depends_on".to_string(), deps);
+            }
+            let step_id = step.get("id").cloned().unwrap_or(Value::Null);
+            step.entry("name").or_insert(step_id);
+            let config = step.entry("config").or_insert_with(|| json!({}));
+            if let Some(config) = config.as_object_mut() {
+                config.entry("parameters").or_insert_with(|| json!({}));
+            }
+        }
+    }
+
+    /// Validate workflow structure.
+    ///
+    /// Step configs may use another step's output with `{{steps.<step_id>.output}}`, or
+    /// `{{steps.<step_id>.output.<key>...}}` to pick a field or index from JSON output.
+    /// References are resolved just before the step runs, so every referenced id must
+    /// name a step the referencing step depends on, directly or through other steps.
     pub fn validate(&self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
@@ -183,6 +336,22 @@ impl Workflow {
                     ));
                 }
             }
+            Self::validate_step_config(step, &step.id, &step_ids, &mut errors);
+            for referenced in step.referenced_step_ids() {
+                if referenced == step.id {
+                    errors.push(format!("step '{}' references its own output", step.id));
+                } else if !step_ids.contains(&referenced) {
+                    errors.push(format!(
+                        "step '{}' references output of non-existent step '{}'",
+                        step.id, referenced
+                    ));
+                } else if !self.upstream_steps(&step.id).contains(referenced.as_str()) {
+                    errors.push(format!(
+                        "step '{}' references output of step '{}' but does not depend on it",
+                        step.id, referenced
+                    ));
+                }
+            }
         }
 
         // Check for circular dependencies using depth-first search
@@ -197,6 +366,69 @@ impl Workflow {
         }
     }
 
+    /// Checks that depend on the step type, applied to a step and, for loops, to the child
+    /// step they run. `label` names the step in errors; a child is `<loop_id>.<child_id>`.
+    fn validate_step_config(
+        step: &WorkflowStep,
+        label: &str,
+        step_ids: &std::collections::HashSet<&String>,
+        errors: &mut Vec<String>,
+    ) {
+        if step.step_type == StepType::Conditional {
+            if step.config.condition.as_deref().map_or(true, |c| c.trim().is_empty()) {
+                errors.push(format!("conditional step '{}' has no condition", label));
+            }
+            let branches = [&step.config.then_step, &step.config.else_step];
+            for branch in branches.into_iter().flatten() {
+                if !step_ids.contains(branch) {
+                    errors.push(format!(
+                        "conditional step '{}' branches to non-existent step '{}'",
+                        label, branch
+                    ));
+                }
+            }
+        }
+        if step.step_type == StepType::Loop {
+            if step.config.items.is_none() {
+                errors.push(format!("loop step '{}' has no items", label));
+            }
+            if step.config.max_iterations == Some(0) {
+                errors.push(format!("loop step '{}' must allow at least one iteration", label));
+            }
+            match &step.config.child {
+                None => errors.push(format!("loop step '{}' has no child step", label)),
+                Some(child) => {
+                    let child_label = format!("{}.{}", label, child.id);
+                    if child.id.trim().is_empty() {
+                        errors.push(format!("loop step '{}' has a child step without an id", label));
+                    }
+                    // The child's step references are checked with the loop step's own
+                    if child.referenced_step_ids().contains(&child.id) {
+                        errors.push(format!("step '{}' references its own output", child_label));
+                    }
+                    Self::validate_step_config(child, &child_label, step_ids, errors);
+                }
+            }
+        }
+    }
+
+    /// Ids of the steps that finish before `step_id` runs: its `depends_on`, theirs, and so on
+    fn upstream_steps(&self, step_id: &str) -> std::collections::HashSet<&str> {
+        let mut upstream = std::collections::HashSet::new();
+        let mut pending = vec![step_id];
+        while let Some(id) = pending.pop() {
+            let Some(step) = self.steps.iter().find(|s| s.id == id) else {
+                continue;
+            };
+            for dep in &step.depends_on {
+                if upstream.insert(dep.as_str()) {
+                    pending.push(dep);
+                }
+            }
+        }
+        upstream
+    }
+
     /// Detect circular dependencies in workflow steps using DFS
     fn detect_cycles(&self) -> Result<(), String> {
         use std::collections::{HashMap, HashSet};
@@ -231,14 +463,13 @@ impl Workflow {
                     if !visited.contains(neighbor) {
                         dfs(neighbor, graph, visited, rec_stack, path)?;
                     } else if rec_stack.contains(neighbor) {
-                        // Found a cycle - build error message with cycle path
+                        // Found a cycle - name it from its first step back around to that step
                         let cycle_start = path.iter().position(|&n| n == neighbor).unwrap();
-                        let cycle_path: Vec<&str> = path[cycle_start..].to_vec();
+                        let mut cycle_path: Vec<&str> = path[cycle_start..].to_vec();
+                        cycle_path.push(neighbor);
                         return Err(format!(
-                            "circular dependency detected: {} → {} → {}",
-                            cycle_path.join(" → "),
-                            neighbor,
-                            cycle_path[0]
+                            "circular dependency detected: {}",
+                            cycle_path.join(" → ")
                         ));
                     }
                 }
@@ -289,6 +520,8 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         };
 
         assert!(workflow.validate().is_ok());
@@ -309,6 +542,8 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         };
 
         let result = workflow.validate();
@@ -332,6 +567,8 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         };
 
         let result = workflow.validate();
@@ -377,6 +614,8 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         };
 
         // Now validate() detects cycles
@@ -433,13 +672,47 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         };
 
         // This test verifies that cycle detection works correctly
         let result = workflow.validate();
         assert!(result.is_err()); // Should detect the circular dependency
         let errors = result.unwrap_err();
-        assert!(errors.iter().any(|e| e.contains("circular dependency")));
+        assert!(errors
+            .iter()
+            .any(|e| e == "circular dependency detected: step-a → step-c → step-b → step-a"));
+    }
+
+    #[test]
+    fn test_validate_self_dependency_names_the_step() {
+        let workflow = Workflow {
+            id: "self-loop".to_string(),
+            project_id: "test-project".to_string(),
+            name: "Self Loop".to_string(),
+            description: String::new(),
+            steps: vec![WorkflowStep {
+                id: "step-a".to_string(),
+                name: "Step A".to_string(),
+                step_type: StepType::Synthesis,
+                config: StepConfig::default(),
+                depends_on: vec!["step-a".to_string(), "missing".to_string()],
+            }],
+            version: "1.0.0".to_string(),
+            created: "2024-11-13".to_string(),
+            updated: "2024-11-13".to_string(),
+            status: None,
+            last_run: None,
+            active_execution_id: None,
+            schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
+        };
+
+        let errors = workflow.validate().unwrap_err();
+        assert!(errors.contains(&"circular dependency detected: step-a → step-a".to_string()));
+        assert!(errors.contains(&"step 'step-a' depends on non-existent step 'missing'".to_string()));
     }
 
     #[test]
@@ -491,6 +764,8 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         };
 
         let result = workflow.validate();
@@ -522,6 +797,8 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         };
 
         let result = workflow.validate();
@@ -530,6 +807,114 @@ mod tests {
         assert!(errors.iter().any(|e| e.contains("non-existent step")));
     }
 
+    #[test]
+    fn test_validate_step_output_references() {
+        let step = |id: &str, parameters: serde_json::Value| WorkflowStep {
+            id: id.to_string(),
+            name: id.to_string(),
+            step_type: StepType::Agent,
+            config: StepConfig {
+                skill_id: Some("skill-1".to_string()),
+                parameters,
+                ..Default::default()
+            },
+            depends_on: vec![],
+        };
+        let mut workflow = Workflow {
+            id: "test-references".to_string(),
+            project_id: "test-project".to_string(),
+            name: "Test References".to_string(),
+            description: "Summarize, critique, rewrite".to_string(),
+            steps: vec![
+                step("summarize", serde_json::json!({})),
+                step("critique", serde_json::json!({"draft": "{{steps.summarize.output}}"})),
+                step(
+                    "rewrite",
+                    serde_json::json!({"notes": "Fix: {{steps.critique.output}} in {{steps.summarize.output}}"}),
+                ),
+            ],
+            version: "1.0.0".to_string(),
+            created: "2024-11-13".to_string(),
+            updated: "2024-11-13".to_string(),
+            status: None,
+            last_run: None,
+            active_execution_id: None,
+            schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
+        };
+        workflow.steps[1].depends_on = vec!["summarize".to_string()];
+        workflow.steps[2].depends_on = vec!["critique".to_string()];
+
+        assert_eq!(workflow.steps[2].referenced_step_ids(), vec!["critique", "summarize"]);
+        assert!(workflow.validate().is_ok());
+
+        workflow.steps[2].config.parameters = serde_json::json!({"notes": "{{steps.review.output.items.0}}"});
+        let errors = workflow.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("non-existent step 'review'")), "{:?}", errors);
+
+        // A sibling that may run before or after `rewrite` can't be referenced
+        workflow.steps[2].config.parameters = serde_json::json!({"notes": "{{steps.summarize.output}}"});
+        workflow.steps[2].depends_on = vec![];
+        let errors = workflow.validate().unwrap_err();
+        assert!(
+            errors.iter().any(|e| e.contains("step 'rewrite' references output of step 'summarize' but does not depend on it")),
+            "{:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_validate_loop_child_step() {
+        let mut workflow = Workflow {
+            id: "test-loop".to_string(),
+            project_id: "test-project".to_string(),
+            name: "Test Loop".to_string(),
+            description: "Summarize each paper".to_string(),
+            steps: vec![WorkflowStep {
+                id: "summaries".to_string(),
+                name: "Summaries".to_string(),
+                step_type: StepType::Loop,
+                config: StepConfig {
+                    items: Some(serde_json::json!(["alpha", "beta"])),
+                    child: Some(Box::new(WorkflowStep {
+                        id: "check".to_string(),
+                        name: "Check".to_string(),
+                        step_type: StepType::Conditional,
+                        config: StepConfig {
+                            condition: Some("file_exists:{{item}}.md".to_string()),
+                            ..Default::default()
+                        },
+                        depends_on: vec![],
+                    })),
+                    ..Default::default()
+                },
+                depends_on: vec![],
+            }],
+            version: "1.0.0".to_string(),
+            created: "2024-11-13".to_string(),
+            updated: "2024-11-13".to_string(),
+            status: None,
+            last_run: None,
+            active_execution_id: None,
+            schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
+        };
+        assert!(workflow.validate().is_ok());
+
+        let child = workflow.steps[0].config.child.as_mut().unwrap();
+        child.config.condition = None;
+        child.config.else_step = Some("missing".to_string());
+        let errors = workflow.validate().unwrap_err();
+        assert!(errors.contains(&"conditional step 'summaries.check' has no condition".to_string()), "{:?}", errors);
+        assert!(
+            errors.iter().any(|e| e.contains("'summaries.check' branches to non-existent step 'missing'")),
+            "{:?}",
+            errors
+        );
+    }
+
     #[test]
     fn test_workflow_step_deserialization() {
         use serde_json::json;
@@ -580,6 +965,38 @@ mod tests {
         let step: WorkflowStep = serde_json::from_value(json_data).unwrap();
         matches!(step.step_type, StepType::SubAgent);
     }
+
+    #[test]
+    fn test_step_usage_totals_and_summary() {
+        let metadata = GenerationMetadata {
+            confidence: 1.0,
+            cost_usd: 0.0,
+            model_used: "claude-sonnet-4".to_string(),
+            tokens_in: 2000,
+            tokens_out: 1200,
+            tokens_cache_read: 0,
+            tokens_cache_write: 0,
+            tokens_reasoning: 0,
+            tool_iterations: 0,
+        };
+        let step = StepUsage::from_metadata(&metadata);
+        assert!(step.cost_usd > 0.0, "cost is estimated when the provider reports none");
+
+        let total = StepUsage::total([&step, &step]);
+        assert_eq!(total.tokens_in, 4000);
+        assert_eq!(total.ai_calls, 2);
+        assert_eq!(step.summary(), format!("3.2k tokens / ${:.2}", step.cost_usd));
+    }
+
+    #[test]
+    fn test_from_json_rejects_newer_schema() {
+        let json = format!(
+            r#"{{"id": "w", "schema_version": {}, "steps": []}}"#,
+            WORKFLOW_SCHEMA_VERSION + 1
+        );
+        let err = Workflow::from_json(&json, "p").unwrap_err();
+        assert!(err.to_string().contains("newer than this app supports"));
+    }
 }
 
 // ===== Execution Structures =====
@@ -593,6 +1010,13 @@ pub struct WorkflowExecution {
     pub status: ExecutionStatus,
     pub error: Option<String>,
     pub step_results: HashMap<String, StepResult>,
+    /// Output of each completed step, keyed by step id, for `{{steps.<id>.output}}`
+    /// references in later steps. JSON output is parsed; anything else is a string.
+    #[serde(default)]
+    pub step_outputs: HashMap<String, serde_json::Value>,
+    /// Sum of `usage` over all steps
+    #[serde(default)]
+    pub total_usage: StepUsage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -601,6 +1025,8 @@ pub enum ExecutionStatus {
     Completed,
     Failed,
     PartialSuccess,
+    /// Stopped by the user; steps that finished keep their results
+    Cancelled,
 }
 
 /// Result of executing a single step
@@ -615,6 +1041,104 @@ pub struct StepResult {
     pub detailed_error: Option<String>,
     pub logs: Vec<String>,
     pub next_step_id: Option<String>, // For conditional steps
+    /// Outcome of a conditional step's condition; `None` for other step types
+    #[serde(default)]
+    pub condition_met: Option<bool>,
+    /// How long the step ran; `None` for steps that were skipped or never finished
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Output of steps that produce a value rather than a single file, such as a loop's array
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+    #[serde(default)]
+    pub usage: StepUsage,
+}
+
+impl StepResult {
+    /// Whether `dependent` may run after this step: a false condition skips every
+    /// dependent except the step's `else_step`, a true one skips only the `else_step`
+    pub fn allows_dependent(&self, step: &WorkflowStep, dependent: &str) -> bool {
+        if !matches!(self.status, StepStatus::Completed) {
+            return false;
+        }
+        let Some(met) = self.condition_met else {
+            return true;
+        };
+        if step.config.else_step.as_deref() == Some(dependent) {
+            !met
+        } else {
+            met
+        }
+    }
+}
+
+/// Tokens and estimated cost of the AI calls made by a step, or by a whole run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StepUsage {
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub tokens_cache_read: u64,
+    pub tokens_cache_write: u64,
+    pub cost_usd: f64,
+    pub ai_calls: u32,
+}
+
+impl StepUsage {
+    /// Usage of one AI call; the cost is estimated from the model when the provider
+    /// didn't report one
+    pub fn from_metadata(metadata: &GenerationMetadata) -> Self {
+        let cost_usd = if metadata.cost_usd > 0.0 {
+            metadata.cost_usd
+        } else {
+            CostLog::compute_cost_usd(
+                &metadata.model_used,
+                metadata.tokens_in,
+                metadata.tokens_out,
+                metadata.tokens_cache_read,
+                metadata.tokens_cache_write,
+            )
+        };
+        Self {
+            tokens_in: metadata.tokens_in,
+            tokens_out: metadata.tokens_out,
+            tokens_cache_read: metadata.tokens_cache_read,
+            tokens_cache_write: metadata.tokens_cache_write,
+            cost_usd,
+            ai_calls: 1,
+        }
+    }
+
+    pub fn add(&mut self, other: &StepUsage) {
+        self.tokens_in += other.tokens_in;
+        self.tokens_out += other.tokens_out;
+        self.tokens_cache_read += other.tokens_cache_read;
+        self.tokens_cache_write += other.tokens_cache_write;
+        self.cost_usd += other.cost_usd;
+        self.ai_calls += other.ai_calls;
+    }
+
+    pub fn total<'a>(usages: impl IntoIterator<Item = &'a StepUsage>) -> Self {
+        let mut total = Self::default();
+        for usage in usages {
+            total.add(usage);
+        }
+        total
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.tokens_in + self.tokens_out + self.tokens_cache_read + self.tokens_cache_write
+    }
+
+    /// Short form for logs and the run view, e.g. `3.2k tokens / $0.04`
+    pub fn summary(&self) -> String {
+        let tokens = self.total_tokens();
+        let tokens = if tokens >= 1000 {
+            format!("{:.1}k", tokens as f64 / 1000.0)
+        } else {
+            tokens.to_string()
+        };
+        format!("{} tokens / ${:.2}", tokens, self.cost_usd)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -624,6 +1148,7 @@ pub enum StepStatus {
     Completed,
     Failed,
     Skipped,
+    Cancelled,
 }
 
 /// Progress information for workflow execution
@@ -633,9 +1158,46 @@ pub struct WorkflowProgress {
     pub step_name: String,
     pub status: String,
     pub progress_percent: u32,
+    /// Why a step was skipped or failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
-/// A record of a workflow run for history persistence
+/// Payload of `workflow-step-started`, `workflow-step-completed` and `workflow-step-failed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStepEvent {
+    pub workflow_id: String,
+    pub step_id: String,
+    pub step_name: String,
+    /// Time the step ran for; 0 when it has just started
+    pub elapsed_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl WorkflowStepEvent {
+    pub fn new(workflow_id: &str, step: &WorkflowStep, elapsed_ms: u64) -> Self {
+        Self {
+            workflow_id: workflow_id.to_string(),
+            step_id: step.id.clone(),
+            step_name: step.name.clone(),
+            elapsed_ms,
+            error: None,
+        }
+    }
+}
+
+/// Payload of `workflow-completed`, emitted once a run ends whatever its outcome
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowCompletedEvent {
+    pub workflow_id: String,
+    pub status: ExecutionStatus,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A record of a workflow run, saved to `.workflows/runs/{timestamp}.json` in the project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowRunRecord {
     pub id: String,
@@ -648,4 +1210,22 @@ pub struct WorkflowRunRecord {
     pub error: Option<String>,
     pub trigger: String, // "manual", "schedule"
     pub step_results: HashMap<String, StepResult>,
+    /// Parsed output of each completed step, as referenced by `{{steps.<id>.output}}`
+    #[serde(default)]
+    pub step_outputs: HashMap<String, serde_json::Value>,
+    /// Wall-clock time of the whole run
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub total_usage: StepUsage,
+}
+
+impl WorkflowRunRecord {
+    /// File name under `.workflows/runs`: the start time, made safe for every filesystem
+    pub fn file_name(&self) -> String {
+        let stamp = chrono::DateTime::parse_from_rfc3339(&self.started)
+            .map(|started| started.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now());
+        format!("{}.json", stamp.format("%Y%m%dT%H%M%S%.3fZ"))
+    }
 }
@@ -1,10 +1,28 @@
-use crate::models::ai::{ChatResponse, Message};
+use crate::models::ai::chat_models::ChatOptions;
+use crate::models::ai::{
+    ChatResponse, GenerationMetadata, Message, ProviderType, TokenUsage, ToolCall, ToolCallSink, ToolResult,
+    UsageSink,
+};
 use crate::models::chat::ChatMessage;
+use crate::models::mcp::McpToolFilter;
+use crate::models::settings::LoggingPrivacyLevel;
+use crate::models::skill::Skill;
 use crate::services::ai_service::AIService;
-use crate::services::chat_service::ChatService;
-use crate::services::output_parser_service::OutputParserService;
+use crate::services::ai_provider::AIProvider;
+use crate::services::api_key_pool::ApiKeyPool;
+use crate::services::backup_service::BackupService;
+use crate::services::cancellation_service::CancellationService;
+use crate::services::chat_service::{ChatService, ChatSource};
+use crate::services::context_service::ContextReport;
+use crate::services::mcp_service::McpService;
+use crate::services::output_parser_service::{FileChange, OutputParserService};
+use crate::services::proposed_changes_service::ProposedChangesService;
 use crate::services::research_log_service::ResearchLogService;
 use crate::services::prompt_service::{PromptService, PromptMode};
+use crate::services::providers::ollama::{OllamaModelMissing, OllamaProvider};
+use crate::services::settings_service::SettingsService;
+use crate::services::skill_service::SkillService;
+use crate::services::tool_approval_service::{ToolApprovalService, APPROVAL_TIMEOUT};
 use anyhow::{anyhow, Context, Result};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -12,80 +30,125 @@ use tokio::sync::Mutex;
 
 use tauri::{AppHandle, Emitter};
 
+/// Default upper bound on model -> tool -> model round trips within a single turn;
+/// configurable as `maxToolIterations` in global settings
+pub const DEFAULT_MAX_TOOL_ITERATIONS: usize = 10;
+
+/// Receives orchestrator events (`trace-log`, `chat-delta`, `file-changed`, ...).
+/// In the app this forwards to the Tauri frontend; tests can capture events instead.
+pub type EventSink = Arc<dyn Fn(&str, serde_json::Value) + Send + Sync>;
+
 pub struct AgentOrchestrator {
     ai_service: Arc<AIService>,
-    app_handle: AppHandle,
+    event_sink: EventSink,
     execution_lock: Mutex<()>,
 }
 
 impl AgentOrchestrator {
     pub fn new(ai_service: Arc<AIService>, app_handle: AppHandle) -> Self {
+        let sink: EventSink = Arc::new(move |event: &str, payload: serde_json::Value| {
+            let _ = app_handle.emit(event, payload);
+        });
+        Self::with_event_sink(ai_service, sink)
+    }
+
+    /// Create an orchestrator that is not bound to a running Tauri app
+    pub fn with_event_sink(ai_service: Arc<AIService>, event_sink: EventSink) -> Self {
         Self {
             ai_service,
-            app_handle,
+            event_sink,
             execution_lock: Mutex::new(()),
         }
     }
 
-    /// Primary entry point for sending a message and handling all side effects
+    /// Note which hosted API key (redacted) answered the request
+    fn trace_served_key(&self) {
+        if let Some(key) = ApiKeyPool::take_last_served() {
+            self.emit("trace-log", format!("Request served by API key {}", key));
+        }
+    }
+
+    fn emit<S: serde::Serialize>(&self, event: &str, payload: S) {
+        if let Ok(value) = serde_json::to_value(payload) {
+            (self.event_sink)(event, value);
+        }
+    }
+
+    /// Primary entry point for sending a message and handling all side effects.
+    /// Without a `project_id` this is a scratch chat: no project context is injected and
+    /// nothing is logged, saved to history or written to disk.
     pub async fn run_agent_loop(
         &self,
         messages: Vec<Message>,
         system_prompt: Option<String>,
         project_id: Option<String>,
-        _skill_id: Option<String>,
-        _skill_params: Option<HashMap<String, String>>,
+        skill_id: Option<String>,
+        skill_params: Option<HashMap<String, String>>,
     ) -> Result<ChatResponse> {
         let _lock = self.execution_lock.lock().await;
 
-        let _ = self.app_handle.emit("trace-log", "Initializing agent session...");
+        self.emit("trace-log", "Initializing agent session...");
 
         // 1. Authentication & Health Guard
         let provider_type = self.ai_service.get_active_provider_type().await;
         let settings = crate::services::settings_service::SettingsService::load_global_settings()?;
+        let privacy = settings.logging_privacy_level;
         let active_provider: Arc<dyn crate::services::ai_provider::AIProvider> = match AIService::create_provider(&provider_type, &settings) {
             Ok(p) => Arc::from(p),
             Err(e) => return Err(anyhow!("Failed to initialize current provider: {}", e)),
         };
 
-        let _ = self.app_handle.emit("trace-log", format!("Checking authentication for {:?}...", provider_type));
+        self.emit("trace-log", format!("Checking authentication for {:?}...", provider_type));
         if !active_provider.check_authentication().await.unwrap_or(false) {
             let msg = format!("Provider {:?} may not be authenticated yet. Proceeding and letting provider return actionable auth guidance if needed.", provider_type);
-            let _ = self.app_handle.emit("trace-log", format!("WARN: {}", msg));
+            self.emit("trace-log", format!("WARN: {}", msg));
         }
 
         // 2. Build Unified System Prompt
-        let _ = self.app_handle.emit("trace-log", "Building unified system prompt...");
-        let mut final_system_prompt = PromptService::build_system_prompt(
+        self.emit("trace-log", "Building unified system prompt...");
+        let active_skill = self.active_skill(skill_id.as_deref(), skill_params)?;
+        let (final_system_prompt, context_report) = Self::assemble_system_prompt_with_report(
             project_id.as_deref(),
-            PromptMode::General, // Default to general, can be refined based on skill_id
+            system_prompt.as_deref(),
+            active_skill.as_ref().map(|(skill, rendered)| (skill, rendered.as_str())),
         );
-
-        if let Some(custom) = system_prompt {
-            final_system_prompt.push_str("\n\n--- ADDITIONAL INSTRUCTIONS ---\n");
-            final_system_prompt.push_str(&custom);
+        if let Some(report) = &context_report {
+            self.emit("trace-log", report.summary());
         }
 
-        // 3. Execute Chat
-        let _ = self.app_handle.emit("trace-log", format!("Executing request via {:?}...", provider_type));
+        // 3. Execute Chat (looping while the model requests tools)
+        self.emit("trace-log", format!("Executing request via {:?}...", provider_type));
+        let tool_filters = Self::tool_filters(project_id.as_deref(), active_skill.as_ref().map(|(skill, _)| skill));
+        if let Some((skill, _)) = &active_skill {
+            self.check_required_tools(skill, &tool_filters).await?;
+        }
+        let token = Self::register_chat_token(project_id.as_deref()).await;
         let chat_result = self
-            .ai_service
-            .chat(
+            .run_tool_loop(
                 messages.clone(),
-                Some(final_system_prompt),
+                final_system_prompt,
                 project_id.clone(),
+                tool_filters,
+                settings.max_tool_iterations.max(1),
+                active_provider.as_ref(),
+                &token,
             )
             .await;
+        Self::release_chat_token(project_id.as_deref()).await;
+        self.trace_served_key();
+        if let Some(metadata) = chat_result.as_ref().ok().and_then(|r| r.metadata.as_ref()) {
+            self.emit_usage(project_id.as_deref(), TokenUsage::from(metadata));
+        }
 
         // 4. Handle results & side effects
         if let Some(ref pid) = project_id {
             match &chat_result {
                 Ok(response) => {
                     let provider_name = format!("{:?}", provider_type);
-                    let _ = ResearchLogService::log_event(pid, &provider_name, None, &response.content);
+                    let _ = ResearchLogService::log_turn(pid, &provider_name, privacy, &response.content, response.metadata.as_ref());
 
                     // Track Cost
-                    if let Some(metadata) = &response.metadata {
+                    if let Some(metadata) = response.metadata.as_ref().filter(|_| privacy != LoggingPrivacyLevel::Off) {
                         if let Ok(project) = crate::services::project_service::ProjectService::load_project_by_id(pid) {
                             let cost_log_path = project.path.join(".metadata").join("cost_log.json");
                             let mut cost_log = crate::models::cost::CostLog::load(&cost_log_path).unwrap_or_default();
@@ -137,22 +200,29 @@ impl AgentOrchestrator {
                     }
 
                     // Save history
-                    self.save_history(pid, messages, &response.content).await?;
+                    if privacy == LoggingPrivacyLevel::Full {
+                        let fallback_model = Self::fallback_model(&provider_type, active_provider.as_ref(), &settings).await;
+                        let source = Self::chat_source(&provider_type, fallback_model, response.metadata.as_ref());
+                        self.save_history(pid, messages, &response.content, &source).await?;
+                    }
 
-                    // Apply file changes
+                    // Apply (or propose) file changes
                     let changes = OutputParserService::parse_file_changes(&response.content);
                     if !changes.is_empty() {
-                        let _ = self.app_handle.emit("trace-log", format!("Applying {} detected file changes...", changes.len()));
-                        OutputParserService::apply_changes(pid, &changes)?;
-                        let _ = self.app_handle.emit("file-changed", (pid.to_string(), "unknown".to_string()));
+                        self.handle_file_changes(pid, &changes, settings.auto_apply_file_changes)?;
                     }
-                    let _ = self.app_handle.emit("trace-log", "Agent session completed successfully.");
+                    self.emit("trace-log", "Agent session completed successfully.");
                 }
                 Err(e) => {
-                    let _ = self.app_handle.emit("trace-log", format!("ERROR: {}", e));
-                    let _ = ResearchLogService::log_event(pid, &format!("{:?}", provider_type), None, &format!("ERROR: {}", e));
+                    self.emit("trace-log", format!("ERROR: {}", Self::redact_details(privacy, &e.to_string())));
+                    let _ = ResearchLogService::log_failure(pid, &format!("{:?}", provider_type), privacy, &e.to_string());
                 }
             }
+        } else {
+            match &chat_result {
+                Ok(_) => self.emit("trace-log", "Scratch session completed; nothing was saved."),
+                Err(e) => self.emit("trace-log", format!("ERROR: {}", Self::redact_details(privacy, &e.to_string()))),
+            }
         }
 
         chat_result.context("Failed to get response from AI agent")
@@ -163,16 +233,18 @@ impl AgentOrchestrator {
         messages: Vec<Message>,
         system_prompt: Option<String>,
         project_id: Option<String>,
-        _skill_id: Option<String>,
-        _skill_params: Option<HashMap<String, String>>,
+        skill_id: Option<String>,
+        skill_params: Option<HashMap<String, String>>,
+        request_options: ChatOptions,
     ) -> Result<ChatResponse> {
         let _lock = self.execution_lock.lock().await;
 
-        let _ = self.app_handle.emit("trace-log", "Initializing streaming agent session...");
+        self.emit("trace-log", "Initializing streaming agent session...");
 
         // 1. Authentication Guard
         let provider_type = self.ai_service.get_active_provider_type().await;
         let settings = crate::services::settings_service::SettingsService::load_global_settings()?;
+        let privacy = settings.logging_privacy_level;
         let active_provider: Arc<dyn crate::services::ai_provider::AIProvider> = match AIService::create_provider(&provider_type, &settings) {
             Ok(p) => Arc::from(p),
             Err(e) => return Err(anyhow!("Failed to initialize current provider: {}", e)),
@@ -180,80 +252,135 @@ impl AgentOrchestrator {
 
         if !active_provider.check_authentication().await.unwrap_or(false) {
             let msg = format!("Provider {:?} may not be authenticated. Continuing in advisory mode.", provider_type);
-            let _ = self.app_handle.emit("trace-log", format!("WARN: {}", msg));
+            self.emit("trace-log", format!("WARN: {}", msg));
         }
 
         // 2. Build Prompt
-        let mut final_system_prompt = PromptService::build_system_prompt(
+        let active_skill = self.active_skill(skill_id.as_deref(), skill_params)?;
+        let (final_system_prompt, context_report) = Self::assemble_system_prompt_with_report(
             project_id.as_deref(),
-            PromptMode::General,
+            system_prompt.as_deref(),
+            active_skill.as_ref().map(|(skill, rendered)| (skill, rendered.as_str())),
         );
-        if let Some(custom) = system_prompt {
-            final_system_prompt.push_str("\n\n--- ADDITIONAL INSTRUCTIONS ---\n");
-            final_system_prompt.push_str(&custom);
+        if let Some(report) = &context_report {
+            self.emit("trace-log", report.summary());
         }
 
         // 3. Execute Stream
-        let stream_result = self
+        let token = Self::register_chat_token(project_id.as_deref()).await;
+        let usage_sink = UsageSink::default();
+        let options = ChatOptions {
+            tool_filters: Self::tool_filters(project_id.as_deref(), active_skill.as_ref().map(|(skill, _)| skill)),
+            cancel: Some(token.clone()),
+            usage: Some(usage_sink.clone()),
+            ..request_options
+        };
+        if let Some((skill, _)) = &active_skill {
+            if let Err(e) = self.check_required_tools(skill, &options.tool_filters).await {
+                Self::release_chat_token(project_id.as_deref()).await;
+                return Err(e);
+            }
+        }
+        let stream_result = match self
             .ai_service
-            .chat_stream(
+            .chat_stream_with_options(
                 messages.clone(),
-                Some(final_system_prompt),
+                Some(final_system_prompt.clone()),
                 project_id.clone(),
+                options.clone(),
             )
-            .await;
-
-        let mut stream = stream_result.map_err(|e| {
-            let _ = self.app_handle.emit("trace-log", format!("ERROR: {}", e));
-            e
-        })?;
+            .await
+        {
+            Err(e) if self.auto_pull_missing_model(&e).await => {
+                self.ai_service
+                    .chat_stream_with_options(
+                        messages.clone(),
+                        Some(final_system_prompt),
+                        project_id.clone(),
+                        options,
+                    )
+                    .await
+            }
+            result => result,
+        };
 
-        let token = tokio_util::sync::CancellationToken::new();
-        crate::services::cancellation_service::CANCELLATION_MANAGER
-            .register_token("chat".to_string(), token.clone())
-            .await;
+        self.trace_served_key();
+        let mut stream = match stream_result {
+            Ok(stream) => stream,
+            Err(e) => {
+                Self::release_chat_token(project_id.as_deref()).await;
+                self.emit("trace-log", format!("ERROR: {}", Self::redact_details(privacy, &e.to_string())));
+                return Err(e);
+            }
+        };
 
         let mut full_content = String::new();
         let mut stream_error: Option<String> = None;
         use futures_util::StreamExt;
 
-        while let Some(chunk) = stream.next().await {
-            if token.is_cancelled() {
-                let _ = self.app_handle.emit("trace-log", "Stream cancelled by user.");
-                break;
-            }
+        loop {
+            let chunk = tokio::select! {
+                biased;
+                _ = token.cancelled() => None,
+                chunk = stream.next() => chunk,
+            };
+            let Some(chunk) = chunk else { break };
             match chunk {
                 Ok(text) => {
                     full_content.push_str(&text);
-                    let _ = self.app_handle.emit("chat-delta", text);
+                    self.emit("chat-delta", text);
                 }
                 Err(e) => {
                     let err_msg = format!("Stream error: {}", e);
-                    let _ = self.app_handle.emit("trace-log", format!("ERROR: {}", err_msg));
+                    self.emit("trace-log", format!("ERROR: {}", Self::redact_details(privacy, &err_msg)));
                     stream_error = Some(err_msg);
                     break;
                 }
             }
         }
 
-        // Cleanup
-        {
-            let mut tokens = crate::services::cancellation_service::CANCELLATION_MANAGER.active_tokens.lock().await;
-            tokens.remove("chat");
+        // Cleanup: dropping the stream closes the connection
+        drop(stream);
+        Self::release_chat_token(project_id.as_deref()).await;
+        let cancelled = token.is_cancelled();
+        if cancelled {
+            self.emit("trace-log", "Stream cancelled by user; keeping the partial response.");
+        }
+        let usage = *usage_sink.lock().unwrap_or_else(|p| p.into_inner());
+        if !usage.is_empty() {
+            self.emit_usage(project_id.as_deref(), usage);
         }
 
         // 4. Finalize
         if let Some(ref pid) = project_id {
             let provider_name = format!("{:?}", provider_type);
             if let Some(ref err_msg) = stream_error {
-                let _ = ResearchLogService::log_event(pid, &provider_name, None, &format!("ERROR: {}", err_msg));
+                let _ = ResearchLogService::log_failure(pid, &provider_name, privacy, err_msg);
             } else if !full_content.is_empty() {
-                let _ = ResearchLogService::log_event(pid, &provider_name, None, &full_content);
-                let _ = self.save_history(pid, messages, &full_content).await;
+                let metadata = if usage.is_empty() {
+                    OutputParserService::parse_generation_metadata(&full_content)
+                } else {
+                    Some(GenerationMetadata {
+                        confidence: 1.0,
+                        cost_usd: 0.0,
+                        model_used: active_provider.resolve_model().await,
+                        tokens_in: u64::from(usage.input),
+                        tokens_out: u64::from(usage.output),
+                        tokens_cache_read: 0,
+                        tokens_cache_write: 0,
+                        tokens_reasoning: 0,
+                        tool_iterations: 0,
+                    })
+                };
+                let _ = ResearchLogService::log_turn(pid, &provider_name, privacy, &full_content, metadata.as_ref());
+                if privacy == LoggingPrivacyLevel::Full {
+                    let fallback_model = Self::fallback_model(&provider_type, active_provider.as_ref(), &settings).await;
+                    let source = Self::chat_source(&provider_type, fallback_model, metadata.as_ref());
+                    let _ = self.save_history(pid, messages, &full_content, &source).await;
+                }
 
                 // Track Cost for Stream
-                let metadata = crate::services::output_parser_service::OutputParserService::parse_generation_metadata(&full_content);
-                if let Some(meta) = metadata {
+                if let Some(meta) = metadata.filter(|_| privacy != LoggingPrivacyLevel::Off) {
                     if let Ok(project) = crate::services::project_service::ProjectService::load_project_by_id(pid) {
                         let cost_log_path = project.path.join(".metadata").join("cost_log.json");
                         let mut cost_log = crate::models::cost::CostLog::load(&cost_log_path).unwrap_or_default();
@@ -295,15 +422,17 @@ impl AgentOrchestrator {
                     }
                 }
 
+                // A cut-off response may end mid-file, so its changes aren't applied
                 let changes = OutputParserService::parse_file_changes(&full_content);
-                if !changes.is_empty() {
-                    let _ = OutputParserService::apply_changes(pid, &changes);
-                    let _ = self.app_handle.emit("file-changed", (pid.to_string(), "unknown".to_string()));
+                if !changes.is_empty() && !cancelled {
+                    if let Err(e) = self.handle_file_changes(pid, &changes, settings.auto_apply_file_changes) {
+                        self.emit("trace-log", format!("ERROR: Failed to handle file changes: {}", e));
+                    }
                 }
             }
         }
 
-        let _ = self.app_handle.emit("trace-log", "Streaming session completed.");
+        self.emit("trace-log", "Streaming session completed.");
 
         Ok(ChatResponse {
             content: full_content,
@@ -312,11 +441,552 @@ impl AgentOrchestrator {
         })
     }
 
+    /// Tell the frontend how many tokens the turn used (`chat-usage`)
+    fn emit_usage(&self, project_id: Option<&str>, usage: TokenUsage) {
+        self.emit("chat-usage", serde_json::json!({ "projectId": project_id, "usage": usage }));
+    }
+
+    /// Register a fresh token under both the legacy `chat` key used by
+    /// `stop_agent_execution` and the per-project key used by `cancel_chat`
+    async fn register_chat_token(project_id: Option<&str>) -> tokio_util::sync::CancellationToken {
+        let token = tokio_util::sync::CancellationToken::new();
+        let manager = CancellationService::global();
+        manager.register_token("chat".to_string(), token.clone()).await;
+        manager.register_token(CancellationService::chat_key(project_id), token.clone()).await;
+        token
+    }
+
+    async fn release_chat_token(project_id: Option<&str>) {
+        let manager = CancellationService::global();
+        manager.release_token("chat").await;
+        manager.release_token(&CancellationService::chat_key(project_id)).await;
+    }
+
+    /// The system prompt for a turn: base rules and project context, the caller's
+    /// instructions, then the active skill's rendered prompt.
+    /// Also used to preview prompts, so keep it free of side effects.
+    pub fn assemble_system_prompt(
+        project_id: Option<&str>,
+        system_prompt: Option<&str>,
+        skill: Option<(&Skill, &str)>,
+    ) -> String {
+        Self::assemble_system_prompt_with_report(project_id, system_prompt, skill).0
+    }
+
+    /// `assemble_system_prompt`, plus what the project context included and dropped
+    fn assemble_system_prompt_with_report(
+        project_id: Option<&str>,
+        system_prompt: Option<&str>,
+        skill: Option<(&Skill, &str)>,
+    ) -> (String, Option<ContextReport>) {
+        let (mut prompt, context_report) = PromptService::build_system_prompt_with_report(
+            project_id,
+            PromptMode::General, // Default to general, can be refined based on skill_id
+        );
+
+        if let Some(custom) = system_prompt {
+            prompt.push_str("\n\n--- ADDITIONAL INSTRUCTIONS ---\n");
+            prompt.push_str(custom);
+        }
+
+        if let Some((skill, rendered)) = skill {
+            prompt.push_str(&format!("\n\n--- ACTIVE SKILL: {} ---\n", skill.name));
+            prompt.push_str(rendered);
+        }
+
+        (prompt, context_report)
+    }
+
+    /// Load the selected skill and render its prompt. Missing or mistyped parameters fail
+    /// with an error naming them, so the model never sees unfilled placeholders.
+    fn active_skill(
+        &self,
+        skill_id: Option<&str>,
+        skill_params: Option<HashMap<String, String>>,
+    ) -> Result<Option<(Skill, String)>> {
+        let Some(skill_id) = skill_id else {
+            return Ok(None);
+        };
+        let skill = match SkillService::load_skill(skill_id) {
+            Ok(skill) => skill,
+            Err(e) => {
+                self.emit("trace-log", format!("WARN: Failed to load skill {}: {}", skill_id, e));
+                return Ok(None);
+            }
+        };
+        if !skill.enabled {
+            return Err(anyhow!("Skill '{}' is disabled", skill.name));
+        }
+
+        // Parameter problems fail the request rather than sending `{{placeholders}}` to the model
+        let params = skill_params.unwrap_or_default();
+        skill
+            .validate_params(&params)
+            .with_context(|| format!("Cannot run skill '{}'", skill.name))?;
+
+        let rendered = match skill.render_prompt(params) {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                self.emit("trace-log", format!("WARN: Using unrendered prompt for skill {}: {}", skill_id, e));
+                skill.prompt_template.clone()
+            }
+        };
+        Ok(Some((skill, rendered)))
+    }
+
+    /// Warn (via `trace-log` and `skill-tools-missing`) when tools the skill declares as
+    /// required aren't offered by the enabled MCP servers; fail if the skill asks to be blocked
+    async fn check_required_tools(&self, skill: &Skill, tool_filters: &[McpToolFilter]) -> Result<()> {
+        if skill.required_tools.is_empty() {
+            return Ok(());
+        }
+
+        let available: Vec<String> = self
+            .ai_service
+            .get_mcp_tools()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tool| tool.name)
+            .filter(|name| McpToolFilter::permits_all(tool_filters, name))
+            .collect();
+        let missing = skill.missing_required_tools(&available);
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let message = format!(
+            "Skill '{}' requires tools that no enabled MCP server provides: {}",
+            skill.name,
+            missing.join(", ")
+        );
+        self.emit(
+            "skill-tools-missing",
+            serde_json::json!({ "skillId": skill.id, "missingTools": missing }),
+        );
+        if skill.block_without_required_tools {
+            self.emit("trace-log", format!("ERROR: {}", message));
+            return Err(anyhow!(message));
+        }
+        self.emit("trace-log", format!("WARN: {}", message));
+        Ok(())
+    }
+
+    /// Write detected file changes immediately, or hold them for review and
+    /// notify the frontend with a `files-proposed` event
+    fn handle_file_changes(&self, project_id: &str, changes: &[FileChange], auto_apply: bool) -> Result<()> {
+        if auto_apply {
+            if let Some(snapshot) = BackupService::backup_before_risky_op(project_id, "applying file changes")? {
+                self.emit("trace-log", format!("Backed up project to {}", snapshot.display()));
+            }
+            self.emit("trace-log", format!("Applying {} detected file changes...", changes.len()));
+            OutputParserService::apply_changes(project_id, changes)?;
+            let paths: Vec<String> = changes.iter().map(|c| c.path.clone()).collect();
+            if let Err(e) = ProposedChangesService::record_applied(project_id, &paths, "auto") {
+                log::warn!("Failed to record applied changes: {}", e);
+            }
+            self.emit("file-changed", (project_id.to_string(), "unknown".to_string()));
+        } else {
+            let proposed = ProposedChangesService::propose(project_id, changes)?;
+            self.emit("trace-log", format!("{} file change(s) proposed for review.", proposed.len()));
+            self.emit(
+                "files-proposed",
+                serde_json::json!({ "projectId": project_id, "changes": proposed }),
+            );
+        }
+        Ok(())
+    }
+
+    /// Send the conversation and keep executing requested tool calls, feeding their
+    /// results back, until the model answers without tools or `max_iterations` is hit.
+    /// Assistant text is emitted as `chat-token` events: chunk by chunk when the provider
+    /// streams tool calls, otherwise once per model turn. The number of round trips is
+    /// reported as `tool_iterations` in the response metadata.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_tool_loop(
+        &self,
+        messages: Vec<Message>,
+        system_prompt: String,
+        project_id: Option<String>,
+        tool_filters: Vec<McpToolFilter>,
+        max_iterations: usize,
+        provider: &dyn AIProvider,
+        cancel: &tokio_util::sync::CancellationToken,
+    ) -> Result<ChatResponse> {
+        let mut conversation = messages;
+        let mut iterations = 0;
+        let streaming = provider.streams_tool_calls();
+        let require_approval = Self::requires_tool_approval(project_id.as_deref());
+
+        loop {
+            let options = ChatOptions {
+                tool_filters: tool_filters.clone(),
+                cancel: Some(cancel.clone()),
+                ..Default::default()
+            };
+            let response = if streaming {
+                self.stream_turn(conversation.clone(), system_prompt.clone(), project_id.clone(), options, provider)
+                    .await?
+            } else {
+                let response = match self
+                    .ai_service
+                    .chat_with_options(conversation.clone(), Some(system_prompt.clone()), project_id.clone(), options.clone())
+                    .await
+                {
+                    Ok(response) => response,
+                    Err(e) if self.auto_pull_missing_model(&e).await => {
+                        self.ai_service
+                            .chat_with_options(conversation.clone(), Some(system_prompt.clone()), project_id.clone(), options)
+                            .await?
+                    }
+                    Err(e) => return Err(e),
+                };
+                if !response.content.is_empty() {
+                    self.emit("chat-token", &response.content);
+                }
+                response
+            };
+
+            let tool_calls = match &response.tool_calls {
+                Some(calls) if !calls.is_empty() => calls.clone(),
+                _ => return Ok(Self::with_tool_iterations(response, iterations, provider).await),
+            };
+
+            if iterations >= max_iterations {
+                self.emit(
+                    "trace-log",
+                    format!(
+                        "WARN: Tool loop limit reached after {} round trip(s); {} requested tool call(s) were not run.",
+                        iterations,
+                        tool_calls.len()
+                    ),
+                );
+                let note = format!(
+                    "\n\n> Stopped after {} tool round trips, the configured limit. Ask me to continue, \
+                     or raise \"Max tool iterations\" in Settings for longer tasks.",
+                    iterations
+                );
+                self.emit("chat-token", &note);
+                let mut response = Self::with_tool_iterations(response, iterations, provider).await;
+                response.content.push_str(&note);
+                return Ok(response);
+            }
+            iterations += 1;
+
+            self.emit("trace-log", format!("Executing {} tool call(s)...", tool_calls.len()));
+            let mut results = Vec::with_capacity(tool_calls.len());
+            for call in &tool_calls {
+                results.push(
+                    self.execute_tool_call(call, &tool_filters, require_approval, project_id.as_deref(), cancel)
+                        .await,
+                );
+            }
+            // A timed-out tool is reported back to the model; a cancelled turn ends here
+            if cancel.is_cancelled() {
+                self.emit("trace-log", "Agent execution cancelled by user.");
+                return Err(anyhow!("Agent execution cancelled by user"));
+            }
+
+            conversation.push(Message {
+                role: "assistant".to_string(),
+                content: response.content.clone(),
+                tool_calls: Some(tool_calls),
+                tool_results: None,
+            });
+            conversation.push(Message {
+                role: "user".to_string(),
+                content: String::new(),
+                tool_calls: None,
+                tool_results: Some(results),
+            });
+        }
+    }
+
+    /// Record the round-trip count on the final response, adding metadata if the
+    /// provider reported none
+    async fn with_tool_iterations(mut response: ChatResponse, iterations: usize, provider: &dyn AIProvider) -> ChatResponse {
+        if iterations == 0 {
+            return response;
+        }
+        let iterations = u32::try_from(iterations).unwrap_or(u32::MAX);
+        match response.metadata.as_mut() {
+            Some(metadata) => metadata.tool_iterations = iterations,
+            None => {
+                response.metadata = Some(GenerationMetadata {
+                    confidence: 1.0,
+                    cost_usd: 0.0,
+                    model_used: provider.resolve_model().await,
+                    tokens_in: 0,
+                    tokens_out: 0,
+                    tokens_cache_read: 0,
+                    tokens_cache_write: 0,
+                    tokens_reasoning: 0,
+                    tool_iterations: iterations,
+                })
+            }
+        }
+        response
+    }
+
+    /// One model turn over the provider's stream, emitting each chunk as `chat-token`.
+    /// Tool calls and usage arrive through sinks and are folded into the response.
+    async fn stream_turn(
+        &self,
+        messages: Vec<Message>,
+        system_prompt: String,
+        project_id: Option<String>,
+        options: ChatOptions,
+        provider: &dyn AIProvider,
+    ) -> Result<ChatResponse> {
+        let cancel = options.cancel.clone().unwrap_or_default();
+        let usage_sink = UsageSink::default();
+        let tool_call_sink = ToolCallSink::default();
+        let options = ChatOptions {
+            usage: Some(usage_sink.clone()),
+            tool_calls: Some(tool_call_sink.clone()),
+            ..options
+        };
+        let mut stream = self
+            .ai_service
+            .chat_stream_with_options(messages, Some(system_prompt), project_id, options)
+            .await?;
+
+        use futures_util::StreamExt;
+        let mut content = String::new();
+        loop {
+            let chunk = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => None,
+                chunk = stream.next() => chunk,
+            };
+            let Some(chunk) = chunk else { break };
+            let text = chunk?;
+            self.emit("chat-token", &text);
+            content.push_str(&text);
+        }
+        drop(stream);
+        if cancel.is_cancelled() {
+            self.emit("trace-log", "Agent execution cancelled by user.");
+            return Err(anyhow!("Agent execution cancelled by user"));
+        }
+
+        let usage = *usage_sink.lock().unwrap_or_else(|p| p.into_inner());
+        let tool_calls = std::mem::take(&mut *tool_call_sink.lock().unwrap_or_else(|p| p.into_inner()));
+        let metadata = if usage.is_empty() {
+            None
+        } else {
+            Some(GenerationMetadata {
+                confidence: 1.0,
+                cost_usd: 0.0,
+                model_used: provider.resolve_model().await,
+                tokens_in: u64::from(usage.input),
+                tokens_out: u64::from(usage.output),
+                tokens_cache_read: 0,
+                tokens_cache_write: 0,
+                tokens_reasoning: 0,
+                tool_iterations: 0,
+            })
+        };
+
+        Ok(ChatResponse {
+            content,
+            tool_calls: if tool_calls.is_empty() { None } else { Some(tool_calls) },
+            metadata,
+        })
+    }
+
+    /// If `error` is a missing Ollama model and automatic pulls are enabled, pull the model,
+    /// reporting progress via `trace-log`. Returns true when the request should be retried.
+    async fn auto_pull_missing_model(&self, error: &anyhow::Error) -> bool {
+        let Some(missing) = error.downcast_ref::<OllamaModelMissing>() else {
+            return false;
+        };
+        let settings = match SettingsService::load_global_settings() {
+            Ok(settings) if settings.ollama.auto_pull_models => settings,
+            _ => return false,
+        };
+
+        self.emit("trace-log", format!("Model {} is not installed, pulling it with Ollama...", missing.model));
+        let provider = OllamaProvider::new(settings.ollama);
+        let result = provider
+            .pull_model(&missing.model, |progress| {
+                let message = match progress.percent {
+                    Some(percent) => format!("Pulling {}: {} ({}%)", progress.model, progress.status, percent),
+                    None => format!("Pulling {}: {}", progress.model, progress.status),
+                };
+                self.emit("trace-log", message);
+                self.emit("ollama-pull-progress", progress);
+            })
+            .await;
+
+        match result {
+            Ok(()) => {
+                self.emit("trace-log", format!("Pulled {}, retrying request.", missing.model));
+                true
+            }
+            Err(e) => {
+                self.emit("trace-log", format!("ERROR: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Below `Full` privacy, keep only the first line of an error: provider errors often
+    /// quote the request or response after it
+    fn redact_details(privacy: LoggingPrivacyLevel, message: &str) -> String {
+        if privacy == LoggingPrivacyLevel::Full {
+            return message.to_string();
+        }
+        let mut lines = message.lines().filter(|l| !l.trim().is_empty());
+        let first = lines.next().unwrap_or_default().to_string();
+        if lines.next().is_some() {
+            format!("{} [details hidden by logging privacy level]", first)
+        } else {
+            first
+        }
+    }
+
+    /// Tool filters in scope for a turn: the project's settings plus the active skill's
+    fn tool_filters(project_id: Option<&str>, skill: Option<&Skill>) -> Vec<McpToolFilter> {
+        let project_filter = project_id.and_then(McpService::project_tool_filter);
+        let skill_filter = skill.and_then(|skill| skill.tool_filter.clone());
+        project_filter.into_iter().chain(skill_filter).collect()
+    }
+
+    /// Whether the project's settings ask for approval before each tool call
+    fn requires_tool_approval(project_id: Option<&str>) -> bool {
+        project_id
+            .and_then(|id| crate::services::project_service::ProjectService::resolve_project_path(id).ok())
+            .and_then(|path| SettingsService::load_project_settings(&path).ok().flatten())
+            .is_some_and(|settings| settings.require_tool_approval)
+    }
+
+    /// Emit `tool-approval-request` and wait for the user's answer; silence means no
+    async fn request_tool_approval(
+        &self,
+        call: &ToolCall,
+        arguments: &serde_json::Value,
+        project_id: Option<&str>,
+        cancel: &tokio_util::sync::CancellationToken,
+    ) -> bool {
+        let request_id = format!("approval-{}", uuid::Uuid::new_v4());
+        let receiver = ToolApprovalService::register(&request_id);
+        self.emit(
+            "tool-approval-request",
+            serde_json::json!({
+                "requestId": request_id,
+                "projectId": project_id,
+                "toolName": call.function.name,
+                "arguments": arguments,
+                "timeoutSecs": APPROVAL_TIMEOUT.as_secs(),
+            }),
+        );
+        self.emit("trace-log", format!("Waiting for approval to run {}...", call.function.name));
+        ToolApprovalService::wait(&request_id, receiver, APPROVAL_TIMEOUT, cancel).await
+    }
+
+    async fn execute_tool_call(
+        &self,
+        call: &ToolCall,
+        tool_filters: &[McpToolFilter],
+        require_approval: bool,
+        project_id: Option<&str>,
+        cancel: &tokio_util::sync::CancellationToken,
+    ) -> ToolResult {
+        // The model only sees permitted tools, but don't trust it not to name others
+        if !McpToolFilter::permits_all(tool_filters, &call.function.name) {
+            self.emit("trace-log", format!("WARN: Blocked call to disabled tool {}", call.function.name));
+            return ToolResult {
+                tool_use_id: call.id.clone(),
+                content: format!("Tool '{}' is not enabled for this task", call.function.name),
+                is_error: true,
+            };
+        }
+
+        let arguments = serde_json::from_str(&call.function.arguments)
+            .unwrap_or_else(|_| serde_json::json!({}));
+
+        if require_approval && !self.request_tool_approval(call, &arguments, project_id, cancel).await {
+            self.emit("trace-log", format!("Tool call {} was not approved", call.function.name));
+            return ToolResult {
+                tool_use_id: call.id.clone(),
+                content: format!("The user declined to run tool '{}'.", call.function.name),
+                is_error: true,
+            };
+        }
+
+        match self.ai_service.call_mcp_tool(&call.function.name, arguments, cancel).await {
+            Ok(value) => ToolResult {
+                tool_use_id: call.id.clone(),
+                content: Self::tool_output_text(&value),
+                is_error: value.get("isError").and_then(|v| v.as_bool()).unwrap_or(false),
+            },
+            Err(e) => {
+                self.emit("trace-log", format!("WARN: Tool {} failed: {}", call.function.name, e));
+                ToolResult {
+                    tool_use_id: call.id.clone(),
+                    content: e.to_string(),
+                    is_error: true,
+                }
+            }
+        }
+    }
+
+    /// Flatten an MCP `tools/call` result into plain text for the model
+    fn tool_output_text(value: &serde_json::Value) -> String {
+        let texts: Vec<&str> = value
+            .get("content")
+            .and_then(|c| c.as_array())
+            .map(|blocks| blocks.iter().filter_map(|b| b.get("text").and_then(|t| t.as_str())).collect())
+            .unwrap_or_default();
+
+        if texts.is_empty() {
+            value.to_string()
+        } else {
+            texts.join("\n")
+        }
+    }
+
+    /// Model that ran when usage does not name one: the provider's resolved model, else the configured one
+    async fn fallback_model(
+        provider_type: &ProviderType,
+        provider: &dyn AIProvider,
+        settings: &crate::models::settings::GlobalSettings,
+    ) -> String {
+        let resolved = provider.resolve_model().await;
+        if resolved.is_empty() {
+            AIService::configured_model(provider_type, settings)
+        } else {
+            resolved
+        }
+    }
+
+    /// Describe the provider/model that produced a response for the chat metadata
+    fn chat_source(
+        provider_type: &ProviderType,
+        fallback_model: String,
+        usage: Option<&GenerationMetadata>,
+    ) -> ChatSource {
+        // CLI output parsing only yields a placeholder model name
+        let model = usage
+            .map(|m| m.model_used.clone())
+            .filter(|m| !m.is_empty() && m != "cli-extracted")
+            .unwrap_or(fallback_model);
+
+        ChatSource {
+            provider: Some(format!("{:?}", provider_type)),
+            model,
+            tokens_in: usage.map(|m| m.tokens_in),
+            tokens_out: usage.map(|m| m.tokens_out),
+        }
+    }
+
     async fn save_history(
         &self,
         project_id: &str,
         user_messages: Vec<Message>,
         assistant_content: &str,
+        source: &ChatSource,
     ) -> Result<()> {
         let mut all_messages = user_messages;
         all_messages.push(Message {
@@ -334,7 +1004,7 @@ impl AgentOrchestrator {
             })
             .collect();
 
-        ChatService::save_chat_to_file(project_id, chat_messages, "UnifiedAI").await?;
+        ChatService::save_chat_with_source(project_id, chat_messages, source).await?;
         Ok(())
     }
 }
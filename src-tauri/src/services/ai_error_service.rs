@@ -18,7 +18,9 @@ impl AIErrorService {
             ProviderType::GeminiCli => "Gemini",
             ProviderType::OpenAiCli => "OpenAI",
             ProviderType::LiteLlm => "LiteLLM",
+            ProviderType::OpenAiCompatible => "OpenAI-compatible server",
             ProviderType::AutoRouter => "Auto-Router",
+            ProviderType::Mock => "Mock",
             ProviderType::Custom(id) => id,
         };
 
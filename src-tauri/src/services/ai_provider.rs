@@ -26,6 +26,12 @@ pub trait AIProvider: Send + Sync {
         false
     }
 
+    /// Whether `chat_stream` reports tool calls through `ChatOptions::tool_calls`, so an
+    /// agent turn can be streamed without losing them
+    fn streams_tool_calls(&self) -> bool {
+        false
+    }
+
     fn provider_type(&self) -> ProviderType;
 
     /// Pluggable detection for CLI availability
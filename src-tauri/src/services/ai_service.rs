@@ -1,9 +1,12 @@
 use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::models::ai::{ChatResponse, ProviderType};
 use crate::services::ai_provider::AIProvider;
+use crate::services::model_registry::ModelRegistry;
+use crate::services::secrets_service::SecretsService;
 use crate::services::settings_service::SettingsService;
 
 // Import our new decoupled providers
@@ -12,12 +15,18 @@ use crate::services::providers::custom_cli::CustomCliProvider;
 use crate::services::providers::gemini_cli::GeminiCliProvider;
 use crate::services::providers::hosted::HostedAPIProvider;
 use crate::services::providers::litellm::LiteLlmProvider;
+use crate::services::providers::mock::MockProvider;
 use crate::services::providers::ollama::OllamaProvider;
 use crate::services::providers::openai_cli::OpenAiCliProvider;
+use crate::services::providers::openai_compatible::OpenAiCompatibleProvider;
 
 pub struct AIService {
     active_provider: RwLock<Arc<dyn AIProvider>>,
     mcp_service: crate::services::mcp_service::McpService,
+    /// Secrets generation the active provider was built against
+    secrets_generation: AtomicU64,
+    /// Injected providers (tests) are kept as-is rather than rebuilt from settings
+    reload_on_secrets_change: bool,
 }
 
 impl AIService {
@@ -37,9 +46,47 @@ impl AIService {
         Ok(Self {
             active_provider: RwLock::new(Arc::from(provider)),
             mcp_service: crate::services::mcp_service::McpService::new(),
+            secrets_generation: AtomicU64::new(SecretsService::generation()),
+            reload_on_secrets_change: true,
         })
     }
 
+    /// Build a service around an already constructed provider, bypassing settings.
+    /// Used by tests to drive the agent loop with a scripted `MockProvider`.
+    pub fn with_provider(provider: Box<dyn AIProvider>) -> Self {
+        Self {
+            active_provider: RwLock::new(Arc::from(provider)),
+            mcp_service: crate::services::mcp_service::McpService::new(),
+            secrets_generation: AtomicU64::new(SecretsService::generation()),
+            reload_on_secrets_change: false,
+        }
+    }
+
+    /// The active provider, rebuilt from settings first if secrets were saved since it
+    /// was created, so updated API keys apply to the next call without a restart
+    async fn current_provider(&self) -> Arc<dyn AIProvider> {
+        let generation = SecretsService::generation();
+        let stale = self.secrets_generation.swap(generation, Ordering::SeqCst) != generation;
+        if stale && self.reload_on_secrets_change {
+            if let Err(e) = self.reload_provider().await {
+                log::warn!("Failed to reload provider after secrets changed: {}", e);
+            }
+        }
+        self.active_provider.read().await.clone()
+    }
+
+    /// Rebuild the active provider from the current settings and secrets
+    pub async fn reload_provider(&self) -> Result<()> {
+        let settings = SettingsService::load_global_settings()
+            .map_err(|e| anyhow!("Failed to load settings: {}", e))?;
+
+        let mut active = self.active_provider.write().await;
+        let provider_type = active.provider_type();
+        *active = Arc::from(Self::create_provider(&provider_type, &settings)?);
+        log::info!("Reloaded AI provider {:?}", provider_type);
+        Ok(())
+    }
+
     pub async fn supports_mcp(&self) -> bool {
         self.active_provider.read().await.supports_mcp()
     }
@@ -53,6 +100,7 @@ impl AIService {
         settings: &crate::models::settings::GlobalSettings,
     ) -> Result<Box<dyn AIProvider>> {
         log::debug!("Creating provider for type: {:?}", provider_type);
+        let settings = &Self::with_normalized_models(settings);
         let provider: Box<dyn AIProvider> = match provider_type {
             ProviderType::Ollama => {
                 log::info!(
@@ -97,6 +145,14 @@ impl AIService {
                 );
                 Box::new(LiteLlmProvider::new(settings.litellm.clone()))
             }
+            ProviderType::OpenAiCompatible => {
+                log::info!(
+                    "Initializing OpenAI-compatible provider at {} with model: {}",
+                    settings.openai_compatible.base_url,
+                    settings.openai_compatible.model
+                );
+                Box::new(OpenAiCompatibleProvider::new(settings.openai_compatible.clone()))
+            }
             ProviderType::Custom(id) => {
                 let id_to_find = if let Some(stripped) = id.strip_prefix("custom-") {
                     stripped
@@ -121,6 +177,10 @@ impl AIService {
                     Box::new(HostedAPIProvider::new(settings.hosted.clone()))
                 }
             }
+            ProviderType::Mock => {
+                log::info!("Initializing offline Mock provider");
+                Box::new(MockProvider::new())
+            }
             ProviderType::AutoRouter => {
                 log::info!("Initializing Auto-Router provider (Falling back to HostedAPI baseline for direct invocation)");
                 Box::new(HostedAPIProvider::new(settings.hosted.clone()))
@@ -129,6 +189,36 @@ impl AIService {
         Ok(provider)
     }
 
+    /// Settings with configured model names mapped to canonical ids
+    fn with_normalized_models(
+        settings: &crate::models::settings::GlobalSettings,
+    ) -> crate::models::settings::GlobalSettings {
+        let mut settings = settings.clone();
+        settings.hosted.model = ModelRegistry::normalize(&ProviderType::HostedApi, &settings.hosted.model);
+        settings.gemini_cli.model_alias =
+            ModelRegistry::normalize(&ProviderType::GeminiCli, &settings.gemini_cli.model_alias);
+        settings.openai_cli.model_alias =
+            ModelRegistry::normalize(&ProviderType::OpenAiCli, &settings.openai_cli.model_alias);
+        settings
+    }
+
+    /// Model the settings select for a provider; empty when the provider has no model setting
+    pub fn configured_model(
+        provider_type: &ProviderType,
+        settings: &crate::models::settings::GlobalSettings,
+    ) -> String {
+        let settings = Self::with_normalized_models(settings);
+        match provider_type {
+            ProviderType::Ollama => settings.ollama.model,
+            ProviderType::ClaudeCode => settings.claude.model,
+            ProviderType::HostedApi | ProviderType::AutoRouter => settings.hosted.model,
+            ProviderType::GeminiCli => settings.gemini_cli.model_alias,
+            ProviderType::OpenAiCli => settings.openai_cli.model_alias,
+            ProviderType::OpenAiCompatible => settings.openai_compatible.model,
+            ProviderType::LiteLlm | ProviderType::Custom(_) | ProviderType::Mock => String::new(),
+        }
+    }
+
     pub async fn chat(
         &self,
         messages: Vec<crate::models::ai::Message>,
@@ -145,7 +235,8 @@ impl AIService {
         project_id: Option<String>,
         options: crate::models::ai::chat_models::ChatOptions,
     ) -> Result<ChatResponse> {
-        let provider = self.active_provider.read().await.clone();
+        let provider = self.current_provider().await;
+        ModelRegistry::check_provider(provider.as_ref()).await?;
 
         let project_path = if let Some(pid) = project_id {
             crate::services::project_service::ProjectService::load_project_by_id(&pid)
@@ -156,15 +247,7 @@ impl AIService {
         };
 
         let tools = if provider.supports_mcp() {
-            match self.mcp_service.get_tools().await {
-                Ok(t) if !t.is_empty() => Some(t.into_iter().map(|mt| crate::models::ai::Tool {
-                    name: mt.name,
-                    description: mt.description,
-                    input_schema: mt.input_schema,
-                    tool_type: "function".to_string(),
-                }).collect()),
-                _ => None,
-            }
+            self.mcp_tools(&options.tool_filters).await
         } else {
             None
         };
@@ -196,7 +279,8 @@ impl AIService {
         project_id: Option<String>,
         options: crate::models::ai::chat_models::ChatOptions,
     ) -> Result<std::pin::Pin<Box<dyn futures_util::Stream<Item = Result<String>> + Send>>> {
-        let provider = self.active_provider.read().await.clone();
+        let provider = self.current_provider().await;
+        ModelRegistry::check_provider(provider.as_ref()).await?;
 
         let project_path = if let Some(pid) = project_id {
             crate::services::project_service::ProjectService::load_project_by_id(&pid)
@@ -207,15 +291,7 @@ impl AIService {
         };
 
         let tools = if provider.supports_mcp() {
-            match self.mcp_service.get_tools().await {
-                Ok(t) if !t.is_empty() => Some(t.into_iter().map(|mt| crate::models::ai::Tool {
-                    name: mt.name,
-                    description: mt.description,
-                    input_schema: mt.input_schema,
-                    tool_type: "function".to_string(),
-                }).collect()),
-                _ => None,
-            }
+            self.mcp_tools(&options.tool_filters).await
         } else {
             None
         };
@@ -231,12 +307,40 @@ impl AIService {
         provider.chat_stream(request).await
     }
 
+    /// MCP tools to offer the model, after applying the request's tool filters
+    async fn mcp_tools(
+        &self,
+        filters: &[crate::models::mcp::McpToolFilter],
+    ) -> Option<Vec<crate::models::ai::Tool>> {
+        let tools: Vec<crate::models::ai::Tool> = self
+            .mcp_service
+            .get_tools()
+            .await
+            .ok()?
+            .into_iter()
+            .filter(|mt| crate::models::mcp::McpToolFilter::permits_all(filters, &mt.name))
+            .map(|mt| crate::models::ai::Tool {
+                name: mt.name,
+                description: mt.description,
+                input_schema: mt.input_schema,
+                tool_type: "function".to_string(),
+            })
+            .collect();
+
+        if tools.is_empty() {
+            None
+        } else {
+            Some(tools)
+        }
+    }
+
     pub async fn call_mcp_tool(
         &self,
         tool_name: &str,
         arguments: serde_json::Value,
+        cancel: &tokio_util::sync::CancellationToken,
     ) -> Result<serde_json::Value> {
-        self.mcp_service.call_tool(tool_name, arguments).await
+        self.mcp_service.call_tool(tool_name, arguments, cancel).await
     }
 
     pub async fn switch_provider(&self, provider_type: ProviderType) -> Result<()> {
@@ -282,6 +386,7 @@ impl AIService {
             ProviderType::ClaudeCode,
             ProviderType::Ollama,
             ProviderType::LiteLlm,
+            ProviderType::OpenAiCompatible,
             ProviderType::OpenAiCli,
             ProviderType::HostedApi,
         ];
@@ -0,0 +1,179 @@
+//! API Key Pool - Rotation across several Anthropic API keys
+//!
+//! Extra keys live in the secrets store as `claude_api_key_1`, `claude_api_key_2`, ... next
+//! to the primary `claude_api_key`. The hosted provider asks for the keys in the order to
+//! try them and reports back which one was rate limited or served the request.
+
+use crate::models::ai::KeyRotation;
+use crate::services::secrets_service::{Secrets, SecretsService};
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const CLAUDE_KEY_PREFIX: &str = "claude_api_key";
+
+/// How long a rate-limited key is moved to the back of the queue
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// A stored key as shown in settings; the value itself is never returned
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeySummary {
+    pub id: String,
+    pub redacted: String,
+    pub rate_limited: bool,
+}
+
+#[derive(Default)]
+struct PoolState {
+    next: usize,
+    cooldowns: HashMap<String, Instant>,
+    last_served: Option<String>,
+}
+
+static POOL: Lazy<Mutex<PoolState>> = Lazy::new(|| Mutex::new(PoolState::default()));
+
+pub struct ApiKeyPool;
+
+impl ApiKeyPool {
+    /// Every stored Anthropic key as `(id, value)`: the configured secret first, then
+    /// `claude_api_key` and the numbered keys in order. Duplicate values are dropped.
+    pub fn keys(primary_secret_id: &str) -> Result<Vec<(String, String)>> {
+        let mut ids = vec![primary_secret_id.to_string(), CLAUDE_KEY_PREFIX.to_string()];
+        ids.extend(Self::numbered_ids(&SecretsService::list_saved_secret_ids()?));
+
+        let mut keys: Vec<(String, String)> = Vec::new();
+        for id in ids {
+            let Some(value) = SecretsService::get_secret(&id)?.filter(|v| !v.trim().is_empty()) else {
+                continue;
+            };
+            if !keys.iter().any(|(_, existing)| *existing == value) {
+                keys.push((id, value));
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Keys in the order they should be tried for the next request. Round-robin starts
+    /// one further along on each call; keys still cooling down from a 429 go last.
+    pub fn rotation(primary_secret_id: &str, strategy: KeyRotation) -> Result<Vec<(String, String)>> {
+        let mut keys = Self::keys(primary_secret_id)?;
+        if keys.is_empty() {
+            return Ok(keys);
+        }
+
+        let mut state = POOL.lock().unwrap_or_else(|p| p.into_inner());
+        if strategy == KeyRotation::RoundRobin {
+            let start = state.next % keys.len();
+            keys.rotate_left(start);
+            state.next = state.next.wrapping_add(1);
+        }
+
+        state.cooldowns.retain(|_, since| since.elapsed() < RATE_LIMIT_COOLDOWN);
+        keys.sort_by_key(|(id, _)| state.cooldowns.contains_key(id));
+        Ok(keys)
+    }
+
+    pub fn mark_rate_limited(id: &str) {
+        let mut state = POOL.lock().unwrap_or_else(|p| p.into_inner());
+        state.cooldowns.insert(id.to_string(), Instant::now());
+    }
+
+    /// Record the key that answered, for the trace log
+    pub fn mark_served(id: &str, key: &str) {
+        let mut state = POOL.lock().unwrap_or_else(|p| p.into_inner());
+        state.last_served = Some(format!("{} ({})", id, Self::redact(key)));
+    }
+
+    /// Redacted description of the key that served the latest request, cleared on read
+    pub fn take_last_served() -> Option<String> {
+        POOL.lock().unwrap_or_else(|p| p.into_inner()).last_served.take()
+    }
+
+    pub fn list_keys(primary_secret_id: &str) -> Result<Vec<ApiKeySummary>> {
+        let state = POOL.lock().unwrap_or_else(|p| p.into_inner());
+        Ok(Self::keys(primary_secret_id)?
+            .into_iter()
+            .map(|(id, value)| ApiKeySummary {
+                rate_limited: state
+                    .cooldowns
+                    .get(&id)
+                    .is_some_and(|since| since.elapsed() < RATE_LIMIT_COOLDOWN),
+                redacted: Self::redact(&value),
+                id,
+            })
+            .collect())
+    }
+
+    /// Store `key` under the next free `claude_api_key_N` id and return the id
+    pub fn add_key(key: &str) -> Result<String> {
+        let key = key.trim();
+        if key.is_empty() {
+            anyhow::bail!("API key is empty");
+        }
+
+        let saved = SecretsService::list_saved_secret_ids()?;
+        let next = Self::numbered_ids(&saved)
+            .iter()
+            .filter_map(|id| Self::key_number(id))
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let id = format!("{}_{}", CLAUDE_KEY_PREFIX, next);
+
+        let secrets = Secrets {
+            custom_api_keys: HashMap::from([(id.clone(), key.to_string())]),
+            ..Default::default()
+        };
+        SecretsService::save_secrets(&secrets)?;
+        Ok(id)
+    }
+
+    /// Show only enough of a key to tell keys apart, e.g. `sk-ant…f3a9`
+    pub fn redact(key: &str) -> String {
+        let chars: Vec<char> = key.chars().collect();
+        if chars.len() <= 12 {
+            return "****".to_string();
+        }
+        let head: String = chars[..6].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}…{}", head, tail)
+    }
+
+    /// `claude_api_key_N` ids, sorted by N
+    fn numbered_ids(ids: &[String]) -> Vec<String> {
+        let mut numbered: Vec<(u32, String)> = ids
+            .iter()
+            .filter_map(|id| Self::key_number(id).map(|n| (n, id.clone())))
+            .collect();
+        numbered.sort();
+        numbered.into_iter().map(|(_, id)| id).collect()
+    }
+
+    fn key_number(id: &str) -> Option<u32> {
+        id.strip_prefix(CLAUDE_KEY_PREFIX)?.strip_prefix('_')?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbered_ids_sort_numerically() {
+        let ids: Vec<String> = ["claude_api_key_10", "claude_api_key", "claude_api_key_2", "claude_api_key_x", "other"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(ApiKeyPool::numbered_ids(&ids), vec!["claude_api_key_2", "claude_api_key_10"]);
+    }
+
+    #[test]
+    fn test_redact_keeps_only_prefix_and_suffix() {
+        assert_eq!(ApiKeyPool::redact("sk-ant-REDACTED"), "sk-ant…wxyz");
+        assert_eq!(ApiKeyPool::redact("short"), "****");
+    }
+}
@@ -1,11 +1,17 @@
 use crate::models::workflow::*;
+use crate::services::agent_orchestrator::EventSink;
+use crate::services::cancellation_service::CancellationService;
 use crate::services::workflow_service::WorkflowService;
 use crate::services::project_service::ProjectService;
+use crate::utils::fs::write_atomic;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tauri::{AppHandle, Emitter};
 use chrono::Utc;
 use std::fs;
+use tokio_util::sync::CancellationToken;
 
 use once_cell::sync::Lazy;
 
@@ -31,16 +37,17 @@ impl BackgroundWorkflowService {
         };
         let workflow_name = workflow.name.clone();
 
-        let mut active_runs = ACTIVE_RUNS.lock().unwrap();
-        active_runs.insert(composite_key.clone(), WorkflowExecution {
+        // Scoped so the guard is gone before the await below
+        ACTIVE_RUNS.lock().unwrap().insert(composite_key.clone(), WorkflowExecution {
             workflow_id: workflow_id.clone(),
             started: Utc::now().to_rfc3339(),
             completed: None,
             status: ExecutionStatus::Running,
             error: None,
             step_results: HashMap::new(),
+            step_outputs: HashMap::new(),
+            total_usage: StepUsage::default(),
         });
-        drop(active_runs);
 
         // Update workflow status in main file
         let mut workflow = workflow;
@@ -54,15 +61,28 @@ impl BackgroundWorkflowService {
         let app_handle_clone = app_handle.clone();
         let composite_key_clone = composite_key.clone();
 
+        // Registered before spawning so `cancel_workflow` works as soon as this returns
+        let cancel_key = CancellationService::workflow_key(&project_id, &workflow_id);
+        let cancel = CancellationToken::new();
+        CancellationService::global()
+            .register_token(cancel_key.clone(), cancel.clone())
+            .await;
+
         tauri::async_runtime::spawn(async move {
+            let events_handle = app_handle_clone.clone();
+            let events: EventSink = Arc::new(move |event: &str, payload: serde_json::Value| {
+                let _ = events_handle.emit(event, payload);
+            });
+            let run_started = Instant::now();
             let execution_result = WorkflowService::execute_workflow(
                 &project_id_clone,
                 &workflow_id_clone,
                 parameters,
-                |progress| {
-                    let _ = app_handle_clone.emit("workflow-progress", &progress);
-                }
+                &events,
+                &cancel,
             ).await;
+            CancellationService::global().release_token(&cancel_key).await;
+            let duration_ms = Some(run_started.elapsed().as_millis() as u64);
 
             let (status, error_msg) = match &execution_result {
                 Ok(exec) => (exec.status.clone(), exec.error.clone()),
@@ -82,6 +102,9 @@ impl BackgroundWorkflowService {
                     error: exec.error.clone(),
                     trigger: trigger.clone(),
                     step_results: exec.step_results.clone(),
+                    step_outputs: exec.step_outputs.clone(),
+                    duration_ms,
+                    total_usage: exec.total_usage.clone(),
                 },
                 Err(e) => WorkflowRunRecord {
                     id: run_id_clone.clone(),
@@ -94,9 +117,14 @@ impl BackgroundWorkflowService {
                     error: Some(e.to_string()),
                     trigger: trigger.clone(),
                     step_results: HashMap::new(),
+                    step_outputs: HashMap::new(),
+                    duration_ms,
+                    total_usage: StepUsage::default(),
                 },
             };
-            let _ = Self::save_run_record(&record);
+            if let Err(e) = Self::save_run_record(&record) {
+                log::warn!("Failed to save run record for workflow {}: {}", workflow_id_clone, e);
+            }
 
             // Cleanup active run
             let mut active_runs = ACTIVE_RUNS.lock().unwrap();
@@ -114,7 +142,8 @@ impl BackgroundWorkflowService {
                 "workflow_id": workflow_id_clone,
                 "run_id": run_id_clone,
                 "status": status,
-                "error": error_msg
+                "error": error_msg,
+                "total_usage": record.total_usage
             }));
             
             // Also emit workflow-changed to trigger list refreshes
@@ -128,46 +157,102 @@ impl BackgroundWorkflowService {
         ACTIVE_RUNS.lock().unwrap().clone()
     }
 
+    /// Where run records are written
+    pub fn runs_dir(project_path: &Path) -> PathBuf {
+        project_path.join(".workflows").join("runs")
+    }
+
+    /// Where run records were written before they moved next to the workflows
+    pub fn legacy_runs_dir(project_path: &Path) -> PathBuf {
+        project_path.join(".metadata").join("workflow_runs")
+    }
+
     fn save_run_record(record: &WorkflowRunRecord) -> Result<(), String> {
         let project_path = ProjectService::resolve_project_path(&record.project_id)
             .map_err(|e| e.to_string())?;
-        let history_dir = project_path.join(".metadata").join("workflow_runs");
-        
-        if !history_dir.exists() {
-            fs::create_dir_all(&history_dir).map_err(|e| e.to_string())?;
-        }
+        Self::write_run_record(&Self::runs_dir(&project_path), record)
+    }
 
-        let file_path = history_dir.join(format!("{}.json", record.id));
+    fn write_run_record(runs_dir: &Path, record: &WorkflowRunRecord) -> Result<(), String> {
+        fs::create_dir_all(runs_dir).map_err(|e| e.to_string())?;
+
+        // Runs of different workflows can start in the same millisecond
+        let mut file_path = runs_dir.join(record.file_name());
+        if file_path.exists() {
+            file_path.set_extension("");
+            file_path = PathBuf::from(format!("{}-{}.json", file_path.display(), record.id));
+        }
         let content = serde_json::to_string_pretty(record).map_err(|e| e.to_string())?;
-        fs::write(file_path, content).map_err(|e| e.to_string())?;
-        
+        write_atomic(&file_path, content).map_err(|e| e.to_string())?;
+
         Ok(())
     }
 
-    pub fn get_workflow_history(project_id: &str, workflow_id: &str) -> Vec<WorkflowRunRecord> {
-        let mut history = Vec::new();
+    /// Saved runs of a workflow, newest first
+    pub fn get_workflow_runs(project_id: &str, workflow_id: &str) -> Vec<WorkflowRunRecord> {
         let Ok(project_path) = ProjectService::resolve_project_path(project_id) else {
-            return history;
+            return Vec::new();
         };
-        let history_dir = project_path.join(".metadata").join("workflow_runs");
-        
-        if !history_dir.exists() {
-            return history;
-        }
+        let mut runs = Self::read_run_records(&Self::runs_dir(&project_path), workflow_id);
+        runs.extend(Self::read_run_records(&Self::legacy_runs_dir(&project_path), workflow_id));
+        runs.sort_by(|a, b| b.started.cmp(&a.started));
+        runs
+    }
 
-        if let Ok(entries) = fs::read_dir(history_dir) {
-            for entry in entries.flatten() {
-                if let Ok(content) = fs::read_to_string(entry.path()) {
-                    if let Ok(record) = serde_json::from_str::<WorkflowRunRecord>(&content) {
-                        if record.workflow_id == workflow_id {
-                            history.push(record);
-                        }
-                    }
-                }
-            }
+    fn read_run_records(runs_dir: &Path, workflow_id: &str) -> Vec<WorkflowRunRecord> {
+        let Ok(entries) = fs::read_dir(runs_dir) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .filter_map(|content| serde_json::from_str::<WorkflowRunRecord>(&content).ok())
+            .filter(|record| record.workflow_id == workflow_id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, workflow_id: &str, started: &str) -> WorkflowRunRecord {
+        WorkflowRunRecord {
+            id: id.to_string(),
+            workflow_id: workflow_id.to_string(),
+            workflow_name: "Weekly report".to_string(),
+            project_id: "project".to_string(),
+            started: started.to_string(),
+            completed: Some(started.to_string()),
+            status: ExecutionStatus::Completed,
+            error: None,
+            trigger: "manual".to_string(),
+            step_results: HashMap::new(),
+            step_outputs: HashMap::from([("collect".to_string(), serde_json::json!({ "count": 3 }))]),
+            duration_ms: Some(1200),
+            total_usage: StepUsage::default(),
         }
+    }
+
+    #[test]
+    fn test_run_records_are_named_by_start_time() {
+        let temp = tempfile::tempdir().unwrap();
+        let runs_dir = temp.path().join("runs");
+
+        let first = record("a", "wf", "2026-03-01T09:30:00.250Z");
+        let same_time = record("b", "other", "2026-03-01T09:30:00.250Z");
+        BackgroundWorkflowService::write_run_record(&runs_dir, &first).unwrap();
+        BackgroundWorkflowService::write_run_record(&runs_dir, &same_time).unwrap();
+        BackgroundWorkflowService::write_run_record(&runs_dir, &record("c", "wf", "2026-03-02T09:30:00Z")).unwrap();
+
+        assert!(runs_dir.join("20260301T093000.250Z.json").is_file());
+        assert!(runs_dir.join("20260301T093000.250Z-b.json").is_file());
 
-        history.sort_by(|a, b| b.started.cmp(&a.started));
-        history
+        let runs = BackgroundWorkflowService::read_run_records(&runs_dir, "wf");
+        assert_eq!(runs.len(), 2);
+        let saved = runs.iter().find(|r| r.id == "a").unwrap();
+        assert_eq!(saved.step_outputs["collect"]["count"], 3);
+        assert_eq!(saved.duration_ms, Some(1200));
     }
 }
@@ -0,0 +1,175 @@
+//! Backup Service - Project snapshots taken before risky operations
+//!
+//! Unlike the full app backup made during updates, these copy a single project into
+//! `backups/projects/{id}/<timestamp>/`. Only the newest `project_backups_to_keep`
+//! snapshots of each project are kept; snapshots taken before deleting a project are
+//! never pruned.
+
+use crate::services::project_service::ProjectService;
+use crate::services::settings_service::SettingsService;
+use crate::utils::paths;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Project-relative paths that are derived data or snapshots of their own
+const SKIPPED_PATHS: &[&str] = &[".metadata/replace_snapshots", ".metadata/exports", ".trash"];
+
+/// Suffix of snapshots taken before a project is deleted, which pruning leaves alone
+const DELETED_SUFFIX: &str = "_deleted";
+
+pub struct BackupService;
+
+impl BackupService {
+    /// Snapshot the project before `operation` if `auto_backup_before_risky_ops` is on.
+    /// Returns the snapshot directory, or `None` when backups are disabled.
+    pub fn backup_before_risky_op(project_id: &str, operation: &str) -> Result<Option<PathBuf>> {
+        let settings = SettingsService::load_global_settings()?;
+        if !settings.auto_backup_before_risky_ops {
+            return Ok(None);
+        }
+        let snapshot = Self::backup_project_keeping(project_id, settings.project_backups_to_keep)?;
+        log::info!("Backed up project '{}' before {} to {:?}", project_id, operation, snapshot);
+        Ok(Some(snapshot))
+    }
+
+    /// Snapshot the project now, regardless of the auto-backup setting
+    pub fn backup_project(project_id: &str) -> Result<PathBuf> {
+        let keep = SettingsService::load_global_settings()?.project_backups_to_keep;
+        Self::backup_project_keeping(project_id, keep)
+    }
+
+    /// Snapshot the project before it is deleted. The snapshot is exempt from pruning, so
+    /// later projects reusing the id cannot push it out.
+    pub fn backup_before_delete(project_id: &str) -> Result<PathBuf> {
+        let backups_dir = paths::get_project_backups_dir(project_id)?;
+        let name = format!("{}{}", Utc::now().format("%Y%m%d_%H%M%S_%3f"), DELETED_SUFFIX);
+        Self::snapshot_into(project_id, &backups_dir.join(name))
+    }
+
+    fn backup_project_keeping(project_id: &str, keep: usize) -> Result<PathBuf> {
+        let backups_dir = paths::get_project_backups_dir(project_id)?;
+        let snapshot = Self::snapshot_into(
+            project_id,
+            &backups_dir.join(Utc::now().format("%Y%m%d_%H%M%S_%3f").to_string()),
+        )?;
+        Self::prune(&backups_dir, keep)?;
+        Ok(snapshot)
+    }
+
+    fn snapshot_into(project_id: &str, snapshot: &Path) -> Result<PathBuf> {
+        let project_path = ProjectService::resolve_project_path(project_id)?;
+        if !project_path.is_dir() {
+            anyhow::bail!("Project '{}' not found", project_id);
+        }
+
+        Self::copy_project(&project_path, snapshot)
+            .with_context(|| format!("Failed to back up project '{}'", project_id))?;
+        Ok(snapshot.to_path_buf())
+    }
+
+    fn copy_project(project_path: &Path, target: &Path) -> Result<()> {
+        fs::create_dir_all(target)?;
+        let entries = WalkDir::new(project_path)
+            .into_iter()
+            .filter_entry(|e| !Self::is_skipped(project_path, e.path()))
+            .filter_map(|e| e.ok());
+        for entry in entries {
+            let relative = entry.path().strip_prefix(project_path)?;
+            let destination = target.join(relative);
+            if entry.file_type().is_dir() {
+                fs::create_dir_all(&destination)?;
+            } else if entry.file_type().is_file() {
+                fs::copy(entry.path(), &destination)
+                    .with_context(|| format!("Failed to copy '{}'", relative.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn is_skipped(project_path: &Path, path: &Path) -> bool {
+        path.strip_prefix(project_path)
+            .map(Self::is_derived_data)
+            .unwrap_or(false)
+    }
+
+    /// Whether a project-relative path is derived data or snapshots that copies leave out
+    pub(crate) fn is_derived_data(relative: &Path) -> bool {
+        SKIPPED_PATHS.iter().any(|skipped| relative == Path::new(skipped))
+    }
+
+    /// Remove all but the newest `keep` snapshots. Snapshot names sort by time; pre-delete
+    /// snapshots are not counted or removed.
+    fn prune(backups_dir: &Path, keep: usize) -> Result<()> {
+        let mut snapshots: Vec<PathBuf> = fs::read_dir(backups_dir)?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .filter(|e| !e.file_name().to_string_lossy().ends_with(DELETED_SUFFIX))
+            .map(|e| e.path())
+            .collect();
+        snapshots.sort();
+
+        let excess = snapshots.len().saturating_sub(keep.max(1));
+        for old in snapshots.into_iter().take(excess) {
+            fs::remove_dir_all(&old).with_context(|| format!("Failed to remove old backup {:?}", old))?;
+            log::info!("Removed old project backup: {:?}", old);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copy_project_skips_derived_data() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        fs::create_dir_all(project.join(".metadata/exports")).unwrap();
+        fs::create_dir_all(project.join("notes")).unwrap();
+        fs::write(project.join(".project.md"), "# Project").unwrap();
+        fs::write(project.join("notes/a.md"), "note").unwrap();
+        fs::write(project.join(".metadata/chat.json"), "[]").unwrap();
+        fs::write(project.join(".metadata/exports/record.json"), "{}").unwrap();
+
+        let target = temp.path().join("snapshot");
+        BackupService::copy_project(&project, &target).unwrap();
+
+        assert_eq!(fs::read_to_string(target.join("notes/a.md")).unwrap(), "note");
+        assert!(target.join(".project.md").exists());
+        assert!(target.join(".metadata/chat.json").exists());
+        assert!(!target.join(".metadata/exports").exists());
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_snapshots() {
+        let temp = TempDir::new().unwrap();
+        for name in ["20250101_000000_000", "20250102_000000_000", "20250103_000000_000"] {
+            fs::create_dir_all(temp.path().join(name)).unwrap();
+        }
+
+        BackupService::prune(temp.path(), 2).unwrap();
+
+        assert!(!temp.path().join("20250101_000000_000").exists());
+        assert!(temp.path().join("20250102_000000_000").exists());
+        assert!(temp.path().join("20250103_000000_000").exists());
+    }
+
+    #[test]
+    fn test_prune_keeps_pre_delete_snapshots() {
+        let temp = TempDir::new().unwrap();
+        for name in ["20250101_000000_000_deleted", "20250102_000000_000", "20250103_000000_000"] {
+            fs::create_dir_all(temp.path().join(name)).unwrap();
+        }
+
+        BackupService::prune(temp.path(), 1).unwrap();
+
+        assert!(temp.path().join("20250101_000000_000_deleted").exists());
+        assert!(!temp.path().join("20250102_000000_000").exists());
+        assert!(temp.path().join("20250103_000000_000").exists());
+    }
+}
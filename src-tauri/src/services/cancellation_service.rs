@@ -36,6 +36,20 @@ impl CancellationService {
         tokens.insert(id, token);
     }
 
+    /// Token key for the chat request running in a project, or in scratch chat
+    pub fn chat_key(project_id: Option<&str>) -> String {
+        format!("chat:{}", project_id.unwrap_or("scratch"))
+    }
+
+    /// Token key for a running workflow
+    pub fn workflow_key(project_id: &str, workflow_id: &str) -> String {
+        format!("workflow:{}::{}", project_id, workflow_id)
+    }
+
+    pub async fn release_token(&self, id: &str) {
+        self.active_tokens.lock().await.remove(id);
+    }
+
     pub async fn cancel_process(&self, id: &str) -> Result<()> {
         log::info!("Canceling execution for: {}", id);
         
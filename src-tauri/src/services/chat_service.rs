@@ -1,19 +1,140 @@
-use crate::models::chat::ChatMessage;
+use crate::models::chat::{ChatMessage, ChatParseError};
 use anyhow::{Context, Result};
 use chrono::Utc;
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
+use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::PathBuf;
-use tempfile::NamedTempFile;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use crate::services::markdown_service::MarkdownService;
+use crate::services::project_service::ProjectService;
+use crate::services::research_record_service::ChatRecord;
+use crate::utils::fs::write_atomic;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct ChatMetadata {
     pub created: String,
+    #[serde(default)]
+    pub updated: Option<String>,
+    /// Provider that produced the responses (e.g. "HostedApi", "Ollama")
+    #[serde(default)]
+    pub provider: Option<String>,
     pub model: String,
     pub message_count: usize,
+    #[serde(default)]
+    pub tokens_in: Option<u64>,
+    #[serde(default)]
+    pub tokens_out: Option<u64>,
+}
+
+/// Parse result of each chat file, keyed by path, with the modification time and size it
+/// was computed for
+static CHECK_CACHE: Lazy<Mutex<HashMap<PathBuf, ((SystemTime, u64), Option<ChatParseError>)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Where a conversation came from, recorded in the chat metadata sidecar
+#[derive(Debug, Clone, Default)]
+pub struct ChatSource {
+    pub provider: Option<String>,
+    pub model: String,
+    pub tokens_in: Option<u64>,
+    pub tokens_out: Option<u64>,
+}
+
+/// A chat file that fails to parse, listed in the history but not loadable
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CorruptChatFile {
+    pub file_name: String,
+    pub error: ChatParseError,
+}
+
+/// Result of scanning a project's chat directory
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChatScanReport {
+    pub valid: Vec<String>,
+    pub corrupt: Vec<CorruptChatFile>,
+}
+
+/// A chat file together with its metadata, for the history list
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatSummary {
+    pub file_name: String,
+    pub metadata: Option<ChatMetadata>,
+    /// Set when the chat file fails to parse; it is listed but can't be opened
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub corrupt: Option<ChatParseError>,
+}
+
+/// Outcome of moving older chats into the consolidated archive
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChatArchiveResult {
+    pub archived: Vec<String>,
+    /// Bytes freed by removing the chats and their sidecars, minus what the archive grew by
+    pub bytes_reclaimed: u64,
+}
+
+/// A message in a chat that matched a search, trimmed to the text around the match
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatSearchSnippet {
+    /// Position of the message in the conversation (0-based)
+    pub message_index: usize,
+    pub role: String,
+    pub text: String,
+}
+
+/// A chat file that matched a search
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatSearchResult {
+    pub file_name: String,
+    pub match_count: usize,
+    pub snippets: Vec<ChatSearchSnippet>,
 }
 
+/// Shareable formats for a single chat
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatExportFormat {
+    /// Standalone page with styling and speaker labels
+    Html,
+    /// The chat markdown, with its metadata as frontmatter
+    Markdown,
+    /// File name, metadata and messages, as in a research record
+    Json,
+}
+
+/// Characters of context kept on each side of a search match
+const SEARCH_SNIPPET_CONTEXT: usize = 60;
+/// Snippets returned per matching chat
+const SEARCH_MAX_SNIPPETS: usize = 5;
+
+/// Stylesheet embedded in HTML chat exports
+const CHAT_EXPORT_STYLE: &str = "\
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif; \
+max-width: 820px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; line-height: 1.6; }
+header { border-bottom: 1px solid #d0d7de; margin-bottom: 1.5rem; }
+.meta { display: grid; grid-template-columns: max-content 1fr; gap: 0.25rem 1rem; \
+color: #57606a; font-size: 0.9rem; }
+.meta dt { font-weight: 600; }
+.meta dd { margin: 0; }
+.message { border-radius: 8px; padding: 0.75rem 1rem; margin-bottom: 1rem; }
+.message.user { background: #ddf4ff; }
+.message.assistant { background: #f6f8fa; }
+.speaker { font-weight: 600; font-size: 0.85rem; text-transform: uppercase; color: #57606a; }
+pre { background: #eaeef2; padding: 0.75rem; border-radius: 6px; overflow-x: auto; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d0d7de; padding: 0.25rem 0.5rem; }
+";
+
+/// Older chats are appended here, inside the chat directory, so they stay out of the chat list
+const CHAT_ARCHIVE_DIR: &str = "archive";
+const CHAT_ARCHIVE_FILE: &str = "chats_archive.md";
+
 pub struct ChatService;
 
 impl ChatService {
@@ -22,21 +143,43 @@ impl ChatService {
         project_id: &str,
         messages: Vec<ChatMessage>,
         model: &str,
+    ) -> Result<String> {
+        let source = ChatSource {
+            model: model.to_string(),
+            ..Default::default()
+        };
+        Self::save_chat_with_source(project_id, messages, &source).await
+    }
+
+    /// Save chat conversation, recording which provider/model produced it and token usage
+    pub async fn save_chat_with_source(
+        project_id: &str,
+        messages: Vec<ChatMessage>,
+        source: &ChatSource,
     ) -> Result<String> {
         let chat_dir = Self::get_chat_directory(project_id)?;
-        fs::create_dir_all(&chat_dir).context("Failed to create chat directory")?;
+        Self::save_chat_in(&chat_dir, messages, source)
+    }
+
+    fn save_chat_in(chat_dir: &Path, messages: Vec<ChatMessage>, source: &ChatSource) -> Result<String> {
+        fs::create_dir_all(chat_dir).context("Failed to create chat directory")?;
 
         let timestamp = Utc::now();
-        let file_prefix = format!("chat_{}", timestamp.format("%Y%m%d_%H%M%S"));
+        let base_prefix = format!("chat_{}", timestamp.format("%Y%m%d_%H%M%S"));
+        // Two saves in the same second (e.g. forking twice) must not overwrite each other
+        let mut file_prefix = base_prefix.clone();
+        let mut suffix = 1;
+        while chat_dir.join(format!("{}.md", file_prefix)).exists() {
+            file_prefix = format!("{}_{}", base_prefix, suffix);
+            suffix += 1;
+        }
         let md_file_name = format!("{}.md", file_prefix);
         let md_file_path = chat_dir.join(&md_file_name);
 
         // 1. Save Content (Pure Markdown)
         let md_content = Self::format_chat_markdown(&messages);
-        let mut temp_md =
-            NamedTempFile::new_in(&chat_dir).context("Failed to create temporary file for chat")?;
-        temp_md.write_all(md_content.as_bytes())?;
-        temp_md.persist(&md_file_path)?;
+        write_atomic(&md_file_path, &md_content)
+            .context("Failed to write chat file")?;
 
         // 2. Save Metadata (JSON Sidecar)
         let metadata_dir = chat_dir.join(".metadata").join("chats");
@@ -45,11 +188,16 @@ impl ChatService {
 
         let metadata = ChatMetadata {
             created: timestamp.to_rfc3339(),
-            model: model.to_string(),
+            updated: Some(timestamp.to_rfc3339()),
+            provider: source.provider.clone(),
+            model: source.model.clone(),
             message_count: messages.len(),
+            tokens_in: source.tokens_in,
+            tokens_out: source.tokens_out,
         };
         let meta_json = serde_json::to_string_pretty(&metadata)?;
-        fs::write(metadata_path, meta_json)?;
+        write_atomic(&metadata_path, &meta_json)
+            .context("Failed to write chat metadata")?;
 
         Ok(md_file_name)
     }
@@ -57,7 +205,12 @@ impl ChatService {
     /// Get the chat directory for a project
     fn get_chat_directory(project_id: &str) -> Result<PathBuf> {
         let base_dir = crate::utils::paths::get_app_data_dir()?;
-        Ok(base_dir.join(project_id).join("chats"))
+        Ok(Self::chat_directory_in(&base_dir, project_id))
+    }
+
+    /// Where a project's chats live under `app_data`; they are kept outside the project folder
+    pub(crate) fn chat_directory_in(app_data: &Path, project_id: &str) -> PathBuf {
+        app_data.join(project_id).join("chats")
     }
 
     /// Format chat messages as markdown (Pure content, no frontmatter)
@@ -84,6 +237,10 @@ impl ChatService {
         file_name: &str,
     ) -> Result<Vec<ChatMessage>> {
         let chat_dir = Self::get_chat_directory(project_id)?;
+        Self::load_chat_in(&chat_dir, file_name)
+    }
+
+    fn load_chat_in(chat_dir: &Path, file_name: &str) -> Result<Vec<ChatMessage>> {
         let file_path = chat_dir.join(file_name);
 
         let content = fs::read_to_string(&file_path).context("Failed to read chat file")?;
@@ -91,83 +248,251 @@ impl ChatService {
         // Note: Metadata loading can be added here if needed for the UI,
         // but this method only returns messages.
         Self::parse_chat_markdown(&content)
+            .with_context(|| format!("Chat file {} is corrupt", file_name))
     }
 
-    /// Parse chat messages from markdown
-    fn parse_chat_markdown(content: &str) -> Result<Vec<ChatMessage>> {
-        let mut messages = Vec::new();
-        let mut current_role: Option<String> = None;
-        let mut current_content = String::new();
-        let mut in_conversation = false;
+    /// Copy messages `0..=up_to_message_index` of a chat into a new chat with fresh metadata,
+    /// keeping the original's provider, model and token usage. Returns the new file name.
+    pub async fn fork_chat(
+        project_id: &str,
+        file_name: &str,
+        up_to_message_index: usize,
+    ) -> Result<String> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        Self::fork_chat_in(&chat_dir, file_name, up_to_message_index)
+    }
 
-        for line in content.lines() {
-            // Skip frontmatter
-            if line.trim() == "---" {
-                continue;
-            }
+    fn fork_chat_in(chat_dir: &Path, file_name: &str, up_to_message_index: usize) -> Result<String> {
+        Self::validate_chat_name(file_name)?;
+        let mut messages = Self::load_chat_in(chat_dir, file_name)?;
+        if up_to_message_index >= messages.len() {
+            return Err(anyhow::anyhow!(
+                "Chat has {} messages, cannot fork after message {}",
+                messages.len(),
+                up_to_message_index
+            ));
+        }
+        messages.truncate(up_to_message_index + 1);
+
+        let source = match Self::load_chat_metadata_in(chat_dir, file_name).ok().flatten() {
+            Some(metadata) => ChatSource {
+                provider: metadata.provider,
+                model: metadata.model,
+                tokens_in: metadata.tokens_in,
+                tokens_out: metadata.tokens_out,
+            },
+            None => ChatSource {
+                model: "unknown".to_string(),
+                ..Default::default()
+            },
+        };
+        Self::save_chat_in(chat_dir, messages, &source)
+    }
+
+    /// Replace the user message at `message_index` in a saved chat and drop everything after
+    /// it. The file is rewritten in place; returns the remaining conversation.
+    pub async fn edit_chat_message(
+        project_id: &str,
+        file_name: &str,
+        message_index: usize,
+        content: &str,
+    ) -> Result<Vec<ChatMessage>> {
+        Self::validate_chat_name(file_name)?;
+        let mut messages = Self::load_chat_from_file(project_id, file_name).await?;
+        Self::truncate_at_edit(&mut messages, message_index, content)?;
+        Self::rewrite_chat(project_id, file_name, &messages)?;
+        Ok(messages)
+    }
 
-            // Start of conversation section
-            if line.trim() == "# Conversation" {
-                in_conversation = true;
-                continue;
+    /// Edit the user message at `message_index` and drop the messages after it, leaving the
+    /// history a provider should answer next
+    pub fn truncate_at_edit(
+        messages: &mut Vec<ChatMessage>,
+        message_index: usize,
+        content: &str,
+    ) -> Result<()> {
+        let message = messages
+            .get_mut(message_index)
+            .ok_or_else(|| anyhow::anyhow!("Chat has no message {}", message_index))?;
+        if message.role != "user" {
+            return Err(anyhow::anyhow!("Only user messages can be edited"));
+        }
+
+        let content = content.trim();
+        if content.is_empty() {
+            return Err(anyhow::anyhow!("Message cannot be empty"));
+        }
+        // A line that looks like a role header would split the message when the file is parsed
+        if content
+            .lines()
+            .any(|line| matches!(line.trim_end(), "## User" | "## Assistant"))
+        {
+            return Err(anyhow::anyhow!(
+                "Message cannot contain a '## User' or '## Assistant' line"
+            ));
+        }
+
+        message.content = content.to_string();
+        messages.truncate(message_index + 1);
+        Ok(())
+    }
+
+    /// Overwrite a saved chat with `messages`, keeping its metadata. Legacy frontmatter is
+    /// moved into the JSON sidecar, since the rewritten markdown is pure content.
+    pub fn rewrite_chat(project_id: &str, file_name: &str, messages: &[ChatMessage]) -> Result<()> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        let metadata = Self::load_chat_metadata(project_id, file_name)?;
+        Self::write_chat_in(&chat_dir, file_name, messages, metadata)
+    }
+
+    fn write_chat_in(
+        chat_dir: &Path,
+        file_name: &str,
+        messages: &[ChatMessage],
+        metadata: Option<ChatMetadata>,
+    ) -> Result<()> {
+        let md_path = chat_dir.join(file_name);
+        if !md_path.is_file() {
+            return Err(anyhow::anyhow!("Chat file {} not found", file_name));
+        }
+        write_atomic(&md_path, &Self::format_chat_markdown(messages))
+            .context("Failed to write chat file")?;
+
+        if let Some(mut metadata) = metadata {
+            metadata.message_count = messages.len();
+            metadata.updated = Some(Utc::now().to_rfc3339());
+            let sidecar = Self::sidecar_path(chat_dir, file_name);
+            if let Some(parent) = sidecar.parent() {
+                fs::create_dir_all(parent)?;
             }
+            write_atomic(&sidecar, &serde_json::to_string_pretty(&metadata)?)
+                .context("Failed to write chat metadata")?;
+        }
+        Ok(())
+    }
+
+    /// Load the metadata for a chat file.
+    /// Prefers the JSON sidecar and falls back to legacy YAML frontmatter in the markdown.
+    pub fn load_chat_metadata(project_id: &str, file_name: &str) -> Result<Option<ChatMetadata>> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        Self::load_chat_metadata_in(&chat_dir, file_name)
+    }
+
+    fn load_chat_metadata_in(chat_dir: &Path, file_name: &str) -> Result<Option<ChatMetadata>> {
+        let sidecar = Self::sidecar_path(chat_dir, file_name);
 
-            if !in_conversation {
-                continue;
+        if sidecar.exists() {
+            let content = fs::read_to_string(&sidecar).context("Failed to read chat metadata")?;
+            let metadata: ChatMetadata =
+                serde_json::from_str(&content).context("Failed to parse chat metadata")?;
+            return Ok(Some(metadata));
+        }
+
+        let md_path = chat_dir.join(file_name);
+        if md_path.exists() {
+            let content = fs::read_to_string(&md_path).context("Failed to read chat file")?;
+            return Ok(Self::parse_chat_frontmatter(&content));
+        }
+
+        Ok(None)
+    }
+
+    /// Extract metadata from legacy chat files that carried YAML frontmatter
+    fn parse_chat_frontmatter(content: &str) -> Option<ChatMetadata> {
+        let rest = content.strip_prefix("---")?;
+        let end = rest.find("\n---")?;
+        serde_yaml::from_str(&rest[..end]).ok()
+    }
+
+    /// Parse chat messages from markdown.
+    ///
+    /// Accepts an optional leading frontmatter block, then requires a `# Conversation`
+    /// header followed by `## User` / `## Assistant` sections. Truncated or hand-mangled
+    /// files are reported with the offending line instead of yielding garbage messages.
+    fn parse_chat_markdown(content: &str) -> Result<Vec<ChatMessage>, ChatParseError> {
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.iter().all(|l| l.trim().is_empty()) {
+            return Err(ChatParseError::new(1, "chat file is empty"));
+        }
+
+        let mut idx = 0;
+
+        // Optional frontmatter, only at the very top of the file
+        if lines[0].trim() == "---" {
+            match lines[1..].iter().position(|l| l.trim() == "---") {
+                Some(offset) => idx = offset + 2,
+                None => return Err(ChatParseError::new(1, "unterminated frontmatter block")),
             }
+        }
 
-            // Check for message headers
-            if line.starts_with("## User") {
-                // Save previous message if any
-                if let Some(role) = current_role.take() {
-                    messages.push(ChatMessage {
-                        role,
-                        content: current_content.trim().to_string(),
-                    });
-                    current_content.clear();
-                }
-                current_role = Some("user".to_string());
-            } else if line.starts_with("## Assistant") {
-                // Save previous message if any
-                if let Some(role) = current_role.take() {
+        // Conversation header
+        while idx < lines.len() && lines[idx].trim().is_empty() {
+            idx += 1;
+        }
+        if idx >= lines.len() || lines[idx].trim() != "# Conversation" {
+            return Err(ChatParseError::new(idx + 1, "expected '# Conversation' header"));
+        }
+        idx += 1;
+
+        let mut messages = Vec::new();
+        let mut current: Option<(String, usize)> = None;
+        let mut current_content = String::new();
+
+        for (offset, line) in lines[idx..].iter().enumerate() {
+            let line_no = idx + offset + 1;
+            let role = match line.trim_end() {
+                "## User" => Some("user"),
+                "## Assistant" => Some("assistant"),
+                _ => None,
+            };
+
+            if let Some(role) = role {
+                if let Some((prev_role, _)) = current.take() {
                     messages.push(ChatMessage {
-                        role,
+                        role: prev_role,
                         content: current_content.trim().to_string(),
                     });
                     current_content.clear();
                 }
-                current_role = Some("assistant".to_string());
-            } else if current_role.is_some() {
-                // Accumulate message content
+                current = Some((role.to_string(), line_no));
+            } else if current.is_some() {
                 if !current_content.is_empty() {
                     current_content.push('\n');
                 }
                 current_content.push_str(line);
+            } else if !line.trim().is_empty() {
+                return Err(ChatParseError::new(
+                    line_no,
+                    "unexpected content before the first message header",
+                ));
             }
         }
 
-        // Save the last message
-        if let Some(role) = current_role {
-            messages.push(ChatMessage {
+        match current {
+            // An interrupted or tool-only turn can leave the last message empty
+            Some((role, _)) => messages.push(ChatMessage {
                 role,
                 content: current_content.trim().to_string(),
-            });
+            }),
+            None => {
+                return Err(ChatParseError::new(lines.len(), "conversation contains no messages"));
+            }
         }
 
         Ok(messages)
     }
 
-    /// Get list of chat files for a project
-    pub async fn get_chat_files(project_id: &str) -> Result<Vec<String>> {
-        let chat_dir = Self::get_chat_directory(project_id)?;
-
-        if !chat_dir.exists() {
-            return Ok(Vec::new());
-        }
-
+    /// List every chat markdown file in the project, without validating contents
+    fn list_chat_file_names(chat_dir: &Path) -> Result<Vec<String>> {
         let mut files = Vec::new();
-        for entry in fs::read_dir(&chat_dir).context("Failed to read chat directory")? {
-            let entry = entry.context("Failed to read directory entry")?;
+        for entry in fs::read_dir(chat_dir).context("Failed to read chat directory")? {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    log::warn!("Skipping unreadable chat directory entry: {}", e);
+                    continue;
+                }
+            };
             let path = entry.path();
             if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
                 if let Some(file_name) = path.file_name() {
@@ -178,7 +503,766 @@ impl ChatService {
 
         // Sort files by name (newest first due to timestamp format)
         files.sort_by(|a, b| b.cmp(a));
-
         Ok(files)
     }
+
+    /// Split the project's chat files into loadable and corrupt ones
+    pub async fn scan_chat_files(project_id: &str) -> Result<ChatScanReport> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        Self::scan_chat_dir(&chat_dir)
+    }
+
+    fn scan_chat_dir(chat_dir: &Path) -> Result<ChatScanReport> {
+        if !chat_dir.exists() {
+            return Ok(ChatScanReport::default());
+        }
+
+        let mut report = ChatScanReport::default();
+        for file_name in Self::list_chat_file_names(chat_dir)? {
+            match Self::check_chat_file(&chat_dir.join(&file_name)) {
+                None => report.valid.push(file_name),
+                Some(error) => {
+                    log::warn!("Chat file {} is corrupt: {}", file_name, error);
+                    report.corrupt.push(CorruptChatFile { file_name, error });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Parse error of a chat file, if any. Results are cached by modification time and
+    /// size, so listing the history only re-parses chats that changed.
+    fn check_chat_file(path: &Path) -> Option<ChatParseError> {
+        let stamp = fs::metadata(path)
+            .ok()
+            .and_then(|m| Some((m.modified().ok()?, m.len())));
+        if let Some(stamp) = stamp {
+            let cache = CHECK_CACHE.lock().unwrap_or_else(|p| p.into_inner());
+            if let Some((cached_stamp, result)) = cache.get(path) {
+                if *cached_stamp == stamp {
+                    return result.clone();
+                }
+            }
+        }
+
+        let result = fs::read_to_string(path)
+            .map_err(|e| ChatParseError::new(0, format!("failed to read file: {}", e)))
+            .and_then(|content| Self::parse_chat_markdown(&content))
+            .err();
+        if let Some(stamp) = stamp {
+            let mut cache = CHECK_CACHE.lock().unwrap_or_else(|p| p.into_inner());
+            cache.insert(path.to_path_buf(), (stamp, result.clone()));
+        }
+        result
+    }
+
+    /// Get every chat file of a project, newest first. Corrupt files are listed too;
+    /// `get_chat_summaries` and `scan_chat_files` flag them.
+    pub async fn get_chat_files(project_id: &str) -> Result<Vec<String>> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        if !chat_dir.exists() {
+            return Ok(Vec::new());
+        }
+        Self::list_chat_file_names(&chat_dir)
+    }
+
+    /// Get chat files with their metadata (provider, model, timestamps), newest first.
+    /// Corrupt files are included with `corrupt` set.
+    pub async fn get_chat_summaries(project_id: &str) -> Result<Vec<ChatSummary>> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        let files = Self::get_chat_files(project_id).await?;
+        Ok(files
+            .into_iter()
+            .map(|file_name| {
+                let metadata = Self::load_chat_metadata(project_id, &file_name).unwrap_or_else(|e| {
+                    log::warn!("Failed to load metadata for chat {}: {}", file_name, e);
+                    None
+                });
+                let corrupt = Self::check_chat_file(&chat_dir.join(&file_name));
+                ChatSummary { file_name, metadata, corrupt }
+            })
+            .collect())
+    }
+
+    /// Case-insensitive search through every chat in the project, optionally matching whole
+    /// words only. Chats with the most matches come first; ties go to the newest chat.
+    pub fn search_chats(
+        project_id: &str,
+        query: &str,
+        whole_word: bool,
+    ) -> Result<Vec<ChatSearchResult>> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        Self::search_chats_in(&chat_dir, query, whole_word)
+    }
+
+    fn search_chats_in(
+        chat_dir: &Path,
+        query: &str,
+        whole_word: bool,
+    ) -> Result<Vec<ChatSearchResult>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(anyhow::anyhow!("Search query is empty"));
+        }
+        if !chat_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let escaped = regex::escape(query);
+        let pattern = if whole_word {
+            format!(r"\b{}\b", escaped)
+        } else {
+            escaped
+        };
+        let pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .context("Invalid search query")?;
+
+        let mut results = Vec::new();
+        for file_name in Self::list_chat_file_names(chat_dir)? {
+            let messages = match fs::read_to_string(chat_dir.join(&file_name))
+                .map_err(|e| ChatParseError::new(0, format!("failed to read file: {}", e)))
+                .and_then(|content| Self::parse_chat_markdown(&content))
+            {
+                Ok(messages) => messages,
+                Err(e) => {
+                    log::warn!("Skipping chat {} in search: {}", file_name, e);
+                    continue;
+                }
+            };
+
+            if let Some(result) = Self::search_messages(&file_name, &messages, &pattern) {
+                results.push(result);
+            }
+        }
+
+        // Files are listed newest first and the sort is stable, so ties stay newest first
+        results.sort_by(|a, b| b.match_count.cmp(&a.match_count));
+        Ok(results)
+    }
+
+    fn search_messages(
+        file_name: &str,
+        messages: &[ChatMessage],
+        pattern: &Regex,
+    ) -> Option<ChatSearchResult> {
+        let mut match_count = 0;
+        let mut snippets = Vec::new();
+
+        for (message_index, message) in messages.iter().enumerate() {
+            for found in pattern.find_iter(&message.content) {
+                match_count += 1;
+                if snippets.len() < SEARCH_MAX_SNIPPETS {
+                    snippets.push(ChatSearchSnippet {
+                        message_index,
+                        role: message.role.clone(),
+                        text: Self::snippet(&message.content, found.start(), found.end()),
+                    });
+                }
+            }
+        }
+
+        (match_count > 0).then(|| ChatSearchResult {
+            file_name: file_name.to_string(),
+            match_count,
+            snippets,
+        })
+    }
+
+    /// The match with up to `SEARCH_SNIPPET_CONTEXT` characters either side, on one line
+    pub(crate) fn snippet(content: &str, start: usize, end: usize) -> String {
+        let before: String = content[..start]
+            .chars()
+            .rev()
+            .take(SEARCH_SNIPPET_CONTEXT)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let after: String = content[end..].chars().take(SEARCH_SNIPPET_CONTEXT).collect();
+
+        let mut text = String::new();
+        if before.len() < start {
+            text.push('…');
+        }
+        text.push_str(&before);
+        text.push_str(&content[start..end]);
+        text.push_str(&after);
+        if end + after.len() < content.len() {
+            text.push('…');
+        }
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Write a chat to the project's `.metadata/exports/` in `format` and return the path
+    pub async fn export_chat(
+        project_id: &str,
+        file_name: &str,
+        format: ChatExportFormat,
+    ) -> Result<PathBuf> {
+        // The name ends up in the export path too, so it must stay a plain file name
+        Self::validate_chat_name(file_name)?;
+        let messages = Self::load_chat_from_file(project_id, file_name).await?;
+        let metadata = Self::load_chat_metadata(project_id, file_name)?;
+        let (content, extension) = Self::render_export(file_name, metadata, messages, format)?;
+
+        let project_path = ProjectService::resolve_project_path(project_id)?;
+        let export_dir = project_path.join(".metadata").join("exports");
+        fs::create_dir_all(&export_dir)?;
+        let stem = file_name.strip_suffix(".md").unwrap_or(file_name);
+        let path = export_dir.join(format!("{}.{}", stem, extension));
+        write_atomic(&path, &content).context("Failed to write chat export")?;
+        Ok(path)
+    }
+
+    /// The export's content and file extension
+    fn render_export(
+        file_name: &str,
+        metadata: Option<ChatMetadata>,
+        messages: Vec<ChatMessage>,
+        format: ChatExportFormat,
+    ) -> Result<(String, &'static str)> {
+        Ok(match format {
+            ChatExportFormat::Html => {
+                (Self::format_chat_html(metadata.as_ref(), &messages), "html")
+            }
+            ChatExportFormat::Markdown => {
+                let mut content = String::new();
+                if let Some(metadata) = &metadata {
+                    content.push_str("---\n");
+                    content.push_str(&serde_yaml::to_string(metadata)?);
+                    content.push_str("---\n\n");
+                }
+                content.push_str(&Self::format_chat_markdown(&messages));
+                (content, "md")
+            }
+            ChatExportFormat::Json => {
+                let record = ChatRecord {
+                    file_name: file_name.to_string(),
+                    metadata,
+                    messages,
+                };
+                (serde_json::to_string_pretty(&record)?, "json")
+            }
+        })
+    }
+
+    /// A standalone HTML page: metadata header, then each message rendered from markdown
+    fn format_chat_html(metadata: Option<&ChatMetadata>, messages: &[ChatMessage]) -> String {
+        let mut html = String::from(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Conversation</title>\n<style>\n",
+        );
+        html.push_str(CHAT_EXPORT_STYLE);
+        html.push_str("</style>\n</head>\n<body>\n<header>\n<h1>Conversation</h1>\n");
+
+        if let Some(metadata) = metadata {
+            html.push_str("<dl class=\"meta\">\n");
+            let mut fields = vec![
+                ("Model", metadata.model.clone()),
+                ("Created", metadata.created.clone()),
+                ("Messages", metadata.message_count.to_string()),
+            ];
+            if let Some(provider) = &metadata.provider {
+                fields.insert(0, ("Provider", provider.clone()));
+            }
+            for (label, value) in fields {
+                html.push_str(&format!(
+                    "<dt>{}</dt><dd>{}</dd>\n",
+                    label,
+                    Self::escape_html(&value)
+                ));
+            }
+            html.push_str("</dl>\n");
+        }
+        html.push_str("</header>\n");
+
+        for message in messages {
+            let (class, speaker) = if message.role == "user" {
+                ("user", "User")
+            } else {
+                ("assistant", "Assistant")
+            };
+            html.push_str(&format!(
+                "<section class=\"message {}\">\n<div class=\"speaker\">{}</div>\n\
+                 <div class=\"content\">\n{}</div>\n</section>\n",
+                class,
+                speaker,
+                MarkdownService::render_to_html(&message.content)
+            ));
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    /// Rename a chat, keeping the `.md` extension, and return the new file name.
+    /// The metadata sidecar is renamed with it.
+    pub fn rename_chat(project_id: &str, old_name: &str, new_name: &str) -> Result<String> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        Self::rename_chat_in(&chat_dir, old_name, new_name)
+    }
+
+    /// Delete a chat and its metadata sidecar
+    pub fn delete_chat(project_id: &str, file_name: &str) -> Result<()> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        Self::delete_chat_in(&chat_dir, file_name)
+    }
+
+    fn rename_chat_in(chat_dir: &Path, old_name: &str, new_name: &str) -> Result<String> {
+        Self::validate_chat_name(old_name)?;
+        let new_name = new_name.trim();
+        let new_name = if new_name.ends_with(".md") {
+            new_name.to_string()
+        } else {
+            format!("{}.md", new_name)
+        };
+        Self::validate_chat_name(&new_name)?;
+
+        let old_path = chat_dir.join(old_name);
+        let new_path = chat_dir.join(&new_name);
+        if !old_path.is_file() {
+            return Err(anyhow::anyhow!("Chat file {} not found", old_name));
+        }
+        if new_path.exists() {
+            return Err(anyhow::anyhow!("A chat named {} already exists", new_name));
+        }
+        fs::rename(&old_path, &new_path).context("Failed to rename chat file")?;
+
+        let old_sidecar = Self::sidecar_path(chat_dir, old_name);
+        if old_sidecar.exists() {
+            fs::rename(&old_sidecar, Self::sidecar_path(chat_dir, &new_name))
+                .context("Failed to rename chat metadata")?;
+        }
+        Ok(new_name)
+    }
+
+    fn delete_chat_in(chat_dir: &Path, file_name: &str) -> Result<()> {
+        Self::validate_chat_name(file_name)?;
+        let md_path = chat_dir.join(file_name);
+        if !md_path.is_file() {
+            return Err(anyhow::anyhow!("Chat file {} not found", file_name));
+        }
+        fs::remove_file(&md_path).context("Failed to delete chat file")?;
+
+        let sidecar = Self::sidecar_path(chat_dir, file_name);
+        if sidecar.exists() {
+            fs::remove_file(&sidecar).context("Failed to delete chat metadata")?;
+        }
+        Ok(())
+    }
+
+    /// Chat names must be plain markdown file names inside the chat directory
+    pub(crate) fn validate_chat_name(file_name: &str) -> Result<()> {
+        let stem = file_name.strip_suffix(".md").unwrap_or("");
+        if stem.trim().is_empty()
+            || file_name.contains('/')
+            || file_name.contains('\\')
+            || file_name.contains("..")
+            || file_name.starts_with('.')
+        {
+            return Err(anyhow::anyhow!("Invalid chat file name: {}", file_name));
+        }
+        Ok(())
+    }
+
+    fn sidecar_path(chat_dir: &Path, file_name: &str) -> PathBuf {
+        let stem = file_name.strip_suffix(".md").unwrap_or(file_name);
+        chat_dir.join(".metadata").join("chats").join(format!("{}.json", stem))
+    }
+
+    /// Keep the `keep_recent` newest chats live and append the rest to `archive/chats_archive.md`
+    pub fn archive_old_chats(project_id: &str, keep_recent: usize) -> Result<ChatArchiveResult> {
+        let chat_dir = Self::get_chat_directory(project_id)?;
+        Self::archive_chats_in(&chat_dir, keep_recent)
+    }
+
+    fn archive_chats_in(chat_dir: &Path, keep_recent: usize) -> Result<ChatArchiveResult> {
+        if !chat_dir.exists() {
+            return Ok(ChatArchiveResult::default());
+        }
+
+        // Listed newest first; archive the remainder oldest first so the archive reads in order
+        let mut old: Vec<String> = Self::list_chat_file_names(chat_dir)?
+            .into_iter()
+            .skip(keep_recent)
+            .collect();
+        if old.is_empty() {
+            return Ok(ChatArchiveResult::default());
+        }
+        old.reverse();
+
+        let metadata_dir = chat_dir.join(".metadata").join("chats");
+        let mut archive = String::new();
+        let mut removed: Vec<PathBuf> = Vec::new();
+        for file_name in &old {
+            let md_path = chat_dir.join(file_name);
+            let content = fs::read_to_string(&md_path)
+                .with_context(|| format!("Failed to read chat file {}", file_name))?;
+            let stem = file_name.strip_suffix(".md").unwrap_or(file_name);
+            let sidecar = metadata_dir.join(format!("{}.json", stem));
+            let metadata = fs::read_to_string(&sidecar)
+                .ok()
+                .and_then(|json| serde_json::from_str::<ChatMetadata>(&json).ok());
+
+            archive.push_str(&format!("\n---\n\n<!-- archived chat: {} -->\n", file_name));
+            if let Some(metadata) = metadata {
+                archive.push_str(&format!(
+                    "_Created {} with {} ({} messages)_\n\n",
+                    metadata.created, metadata.model, metadata.message_count
+                ));
+            }
+            archive.push_str(content.trim_end());
+            archive.push('\n');
+
+            removed.push(md_path);
+            if sidecar.exists() {
+                removed.push(sidecar);
+            }
+        }
+
+        // Append before deleting anything, so an interrupted run duplicates rather than loses chats
+        let archive_dir = chat_dir.join(CHAT_ARCHIVE_DIR);
+        fs::create_dir_all(&archive_dir).context("Failed to create chat archive directory")?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(archive_dir.join(CHAT_ARCHIVE_FILE))
+            .context("Failed to open chat archive")?;
+        file.write_all(archive.as_bytes())?;
+        file.sync_all()?;
+
+        let mut freed = 0u64;
+        for path in removed {
+            freed += fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove archived chat {}", path.display()))?;
+        }
+
+        Ok(ChatArchiveResult {
+            archived: old,
+            bytes_reclaimed: freed.saturating_sub(archive.len() as u64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_markdown() {
+        let messages = vec![
+            ChatMessage { role: "user".to_string(), content: "Hello".to_string() },
+            ChatMessage { role: "assistant".to_string(), content: "Hi!\n\n---\n\nA rule above".to_string() },
+        ];
+        let parsed = ChatService::parse_chat_markdown(&ChatService::format_chat_markdown(&messages)).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].content, "Hi!\n\n---\n\nA rule above");
+    }
+
+    #[test]
+    fn test_parse_empty_file_is_error() {
+        let err = ChatService::parse_chat_markdown("").unwrap_err();
+        assert_eq!(err.line, 1);
+    }
+
+    #[test]
+    fn test_parse_unterminated_frontmatter() {
+        let err = ChatService::parse_chat_markdown("---\nmodel: x\n# Conversation\n").unwrap_err();
+        assert_eq!(err.line, 1);
+        assert!(err.message.contains("frontmatter"));
+    }
+
+    #[test]
+    fn test_parse_missing_header_reports_line() {
+        let err = ChatService::parse_chat_markdown("\nrandom notes\n## User\nhi").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn test_parse_empty_last_message() {
+        let messages = ChatService::parse_chat_markdown("# Conversation\n\n## User\nhi\n\n## Assistant\n").unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].role, "assistant");
+        assert!(messages[1].content.is_empty());
+    }
+
+    #[test]
+    fn test_scan_flags_corrupt_chats_and_notices_changes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let chat_dir = temp.path();
+        fs::write(chat_dir.join("chat_20250101_000000.md"), "# Conversation\n\n## User\nhi\n").unwrap();
+        fs::write(chat_dir.join("chat_20250102_000000.md"), "stray\n## User\nhi\n").unwrap();
+
+        let report = ChatService::scan_chat_dir(chat_dir).unwrap();
+        assert_eq!(report.valid, vec!["chat_20250101_000000.md"]);
+        assert_eq!(report.corrupt[0].file_name, "chat_20250102_000000.md");
+
+        // A repaired file is re-parsed even though an earlier result is cached
+        fs::write(chat_dir.join("chat_20250102_000000.md"), "# Conversation\n\n## User\nfixed, longer\n").unwrap();
+        let report = ChatService::scan_chat_dir(chat_dir).unwrap();
+        assert_eq!(report.valid.len(), 2);
+        assert!(report.corrupt.is_empty());
+    }
+
+    #[test]
+    fn test_parse_stray_content_before_first_message() {
+        let err = ChatService::parse_chat_markdown("# Conversation\nstray\n## User\nhi").unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn test_parse_legacy_frontmatter() {
+        let content = "---\ncreated: 2025-01-01T00:00:00Z\nmodel: llama3\nmessage_count: 2\n---\n\n# Conversation\n\n## User\nhi\n";
+        let metadata = ChatService::parse_chat_frontmatter(content).unwrap();
+        assert_eq!(metadata.model, "llama3");
+        assert_eq!(metadata.message_count, 2);
+        assert!(metadata.provider.is_none());
+    }
+
+    #[test]
+    fn test_metadata_without_new_fields_still_parses() {
+        let json = r#"{"created":"2025-01-01T00:00:00Z","model":"UnifiedAI","message_count":3}"#;
+        let metadata: ChatMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(metadata.model, "UnifiedAI");
+        assert!(metadata.tokens_in.is_none());
+    }
+
+    #[test]
+    fn test_pure_markdown_has_no_frontmatter() {
+        assert!(ChatService::parse_chat_frontmatter("# Conversation\n\n## User\nhi").is_none());
+    }
+
+    #[test]
+    fn test_search_chats_ranks_by_matches() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let chat_dir = temp.path();
+        let chats = [
+            ("chat_20250101_000000.md", "Tell me about transformers", "Transformers use attention."),
+            ("chat_20250102_000000.md", "What is attention?", "Attention weighs tokens; self-attention relates them."),
+            ("chat_20250103_000000.md", "Unrelated", "Nothing to see here."),
+        ];
+        for (file_name, question, answer) in chats {
+            let messages = [
+                ChatMessage { role: "user".to_string(), content: question.to_string() },
+                ChatMessage { role: "assistant".to_string(), content: answer.to_string() },
+            ];
+            fs::write(chat_dir.join(file_name), ChatService::format_chat_markdown(&messages)).unwrap();
+        }
+
+        let results = ChatService::search_chats_in(chat_dir, "ATTENTION", false).unwrap();
+        let files: Vec<&str> = results.iter().map(|r| r.file_name.as_str()).collect();
+        assert_eq!(files, vec!["chat_20250102_000000.md", "chat_20250101_000000.md"]);
+        assert_eq!(results[0].match_count, 3);
+        assert_eq!(results[0].snippets[0].role, "user");
+        assert_eq!(results[0].snippets[0].text, "What is attention?");
+        assert_eq!(results[1].snippets[0].message_index, 1);
+
+        // Whole-word matching skips partial words
+        let whole = ChatService::search_chats_in(chat_dir, "attent", true).unwrap();
+        assert!(whole.is_empty());
+        assert!(ChatService::search_chats_in(chat_dir, "  ", false).is_err());
+    }
+
+    #[test]
+    fn test_search_snippet_trims_long_messages() {
+        let content = format!("{} needle {}", "a".repeat(200), "é".repeat(200));
+        let snippet = ChatService::snippet(&content, 201, 207);
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+        assert_eq!(snippet.chars().count(), 1 + 60 + 6 + 60 + 1);
+    }
+
+    #[test]
+    fn test_truncate_at_edit() {
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+        let history = vec![
+            message("user", "Summarise paper A"),
+            message("assistant", "Paper A is about B."),
+            message("user", "And paper C?"),
+            message("assistant", "Paper C is about D."),
+        ];
+
+        let mut messages = history.clone();
+        ChatService::truncate_at_edit(&mut messages, 2, " And paper E? ").unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[2].content, "And paper E?");
+
+        let mut messages = history.clone();
+        assert!(ChatService::truncate_at_edit(&mut messages, 1, "edit").is_err());
+        assert!(ChatService::truncate_at_edit(&mut messages, 9, "edit").is_err());
+        assert!(ChatService::truncate_at_edit(&mut messages, 0, "  ").is_err());
+        assert!(ChatService::truncate_at_edit(&mut messages, 0, "a\n## Assistant\nb").is_err());
+        assert_eq!(messages.len(), 4);
+    }
+
+    #[test]
+    fn test_rewrite_moves_legacy_frontmatter_to_sidecar() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let chat_dir = temp.path();
+        let legacy = "---\ncreated: 2025-01-01T00:00:00Z\nmodel: llama3\nmessage_count: 2\n---\n\n\
+                      # Conversation\n\n## User\nhi\n\n## Assistant\nhello\n";
+        fs::write(chat_dir.join("chat_1.md"), legacy).unwrap();
+
+        let metadata = ChatService::parse_chat_frontmatter(legacy);
+        let messages = vec![ChatMessage { role: "user".to_string(), content: "hey".to_string() }];
+        ChatService::write_chat_in(chat_dir, "chat_1.md", &messages, metadata).unwrap();
+
+        let content = fs::read_to_string(chat_dir.join("chat_1.md")).unwrap();
+        assert_eq!(ChatService::parse_chat_markdown(&content).unwrap()[0].content, "hey");
+        assert!(ChatService::parse_chat_frontmatter(&content).is_none());
+        let sidecar = chat_dir.join(".metadata").join("chats").join("chat_1.json");
+        let metadata: ChatMetadata =
+            serde_json::from_str(&fs::read_to_string(sidecar).unwrap()).unwrap();
+        assert_eq!(metadata.model, "llama3");
+        assert_eq!(metadata.message_count, 1);
+        assert!(metadata.updated.is_some());
+
+        assert!(ChatService::write_chat_in(chat_dir, "missing.md", &messages, None).is_err());
+    }
+
+    #[test]
+    fn test_fork_chat_keeps_prefix_and_source() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let chat_dir = temp.path();
+        let messages: Vec<ChatMessage> = ["q1", "a1", "q2", "a2"]
+            .iter()
+            .enumerate()
+            .map(|(i, content)| ChatMessage {
+                role: if i % 2 == 0 { "user" } else { "assistant" }.to_string(),
+                content: content.to_string(),
+            })
+            .collect();
+        let source = ChatSource {
+            provider: Some("Ollama".to_string()),
+            model: "llama3".to_string(),
+            tokens_in: Some(120),
+            tokens_out: Some(80),
+        };
+        let original = ChatService::save_chat_in(chat_dir, messages, &source).unwrap();
+
+        // Saved within the same second as the source, so it needs a name of its own
+        let fork = ChatService::fork_chat_in(chat_dir, &original, 1).unwrap();
+        assert_ne!(fork, original);
+        assert_eq!(ChatService::load_chat_in(chat_dir, &original).unwrap().len(), 4);
+
+        let forked = ChatService::load_chat_in(chat_dir, &fork).unwrap();
+        assert_eq!(forked.len(), 2);
+        assert_eq!(forked[1].content, "a1");
+        let metadata = ChatService::load_chat_metadata_in(chat_dir, &fork).unwrap().unwrap();
+        assert_eq!(metadata.provider.as_deref(), Some("Ollama"));
+        assert_eq!(metadata.model, "llama3");
+        assert_eq!(metadata.tokens_in, Some(120));
+        assert_eq!(metadata.message_count, 2);
+
+        assert!(ChatService::fork_chat_in(chat_dir, &original, 4).is_err());
+        assert!(ChatService::fork_chat_in(chat_dir, "../chat_1.md", 0).is_err());
+    }
+
+    #[test]
+    fn test_rename_and_delete_chat() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let chat_dir = temp.path();
+        let message = ChatMessage { role: "user".to_string(), content: "hi".to_string() };
+        fs::write(chat_dir.join("chat_1.md"), ChatService::format_chat_markdown(&[message])).unwrap();
+        fs::write(chat_dir.join("other.md"), "").unwrap();
+        fs::create_dir_all(chat_dir.join(".metadata").join("chats")).unwrap();
+        fs::write(ChatService::sidecar_path(chat_dir, "chat_1.md"), "{}").unwrap();
+
+        let renamed = ChatService::rename_chat_in(chat_dir, "chat_1.md", " Literature review ").unwrap();
+        assert_eq!(renamed, "Literature review.md");
+        assert!(chat_dir.join("Literature review.md").is_file());
+        assert!(!chat_dir.join("chat_1.md").exists());
+        assert!(ChatService::sidecar_path(chat_dir, &renamed).exists());
+
+        for bad in ["../escape", "nested/chat", ".hidden", "  ", "a\\b"] {
+            assert!(ChatService::rename_chat_in(chat_dir, &renamed, bad).is_err(), "{}", bad);
+        }
+        assert!(ChatService::rename_chat_in(chat_dir, &renamed, "other").is_err());
+        assert!(ChatService::delete_chat_in(chat_dir, "../chat_1.md").is_err());
+
+        ChatService::delete_chat_in(chat_dir, &renamed).unwrap();
+        assert!(!chat_dir.join(&renamed).exists());
+        assert!(!ChatService::sidecar_path(chat_dir, &renamed).exists());
+        assert!(ChatService::delete_chat_in(chat_dir, &renamed).is_err());
+    }
+
+    #[test]
+    fn test_export_formats_keep_metadata() {
+        let metadata = ChatMetadata {
+            created: "2025-01-01T00:00:00Z".to_string(),
+            model: "llama3".to_string(),
+            message_count: 2,
+            ..Default::default()
+        };
+        let messages = vec![
+            ChatMessage { role: "user".to_string(), content: "Compare <b>A</b> & B".to_string() },
+            ChatMessage { role: "assistant".to_string(), content: "**A** is faster.".to_string() },
+        ];
+        let export = |format| {
+            let metadata = Some(metadata.clone());
+            ChatService::render_export("chat_1.md", metadata, messages.clone(), format).unwrap()
+        };
+
+        let (html, ext) = export(ChatExportFormat::Html);
+        assert_eq!(ext, "html");
+        assert!(html.contains("<dt>Model</dt><dd>llama3</dd>"));
+        assert!(html.contains("<div class=\"speaker\">Assistant</div>"));
+        assert!(html.contains("<strong>A</strong> is faster."));
+
+        // The markdown export still loads as a chat, and its frontmatter as metadata
+        let (markdown, _) = export(ChatExportFormat::Markdown);
+        assert_eq!(ChatService::parse_chat_markdown(&markdown).unwrap().len(), 2);
+        let parsed = ChatService::parse_chat_frontmatter(&markdown).unwrap();
+        assert_eq!(parsed.model, "llama3");
+        assert_eq!(parsed.message_count, 2);
+
+        let (json, _) = export(ChatExportFormat::Json);
+        let record: ChatRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(record.file_name, "chat_1.md");
+        assert_eq!(record.messages[1].content, "**A** is faster.");
+    }
+
+    #[test]
+    fn test_archive_keeps_newest_chats_live() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let chat_dir = temp.path();
+        let metadata_dir = chat_dir.join(".metadata").join("chats");
+        fs::create_dir_all(&metadata_dir).unwrap();
+        for (i, stem) in ["chat_20250101_000000", "chat_20250102_000000", "chat_20250103_000000"].iter().enumerate() {
+            let message = ChatMessage { role: "user".to_string(), content: format!("message {}", i) };
+            fs::write(chat_dir.join(format!("{}.md", stem)), ChatService::format_chat_markdown(&[message])).unwrap();
+            fs::write(
+                metadata_dir.join(format!("{}.json", stem)),
+                r#"{"created":"2025-01-01T00:00:00Z","model":"llama3","message_count":1}"#,
+            )
+            .unwrap();
+        }
+
+        let result = ChatService::archive_chats_in(chat_dir, 1).unwrap();
+        assert_eq!(result.archived, vec!["chat_20250101_000000.md", "chat_20250102_000000.md"]);
+        assert_eq!(ChatService::list_chat_file_names(chat_dir).unwrap(), vec!["chat_20250103_000000.md"]);
+        assert!(!metadata_dir.join("chat_20250101_000000.json").exists());
+
+        let archive = fs::read_to_string(chat_dir.join(CHAT_ARCHIVE_DIR).join(CHAT_ARCHIVE_FILE)).unwrap();
+        assert!(archive.find("message 0").unwrap() < archive.find("message 1").unwrap());
+        assert!(archive.contains("with llama3"));
+
+        // Nothing left to archive on a second pass
+        assert!(ChatService::archive_chats_in(chat_dir, 1).unwrap().archived.is_empty());
+    }
 }
@@ -1,16 +1,60 @@
-use crate::models::ai::{ChatResponse, Message, Tool, ToolCall};
+use crate::models::ai::chat_models::ChatOptions;
+use crate::models::ai::{ChatResponse, Message, Tool, ToolCall, ToolCallSink, UsageSink};
 use crate::models::chat::ChatRequest;
 use crate::models::llm::LlmProvider;
 use anyhow::Result;
 use async_trait::async_trait;
 use futures::stream::Stream;
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, RETRY_AFTER};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::pin::Pin;
+use std::time::Duration;
+use thiserror::Error;
 
 const CLAUDE_API_URL: &str = "https://api.anthropic.com/v1/messages";
 const CLAUDE_API_VERSION: &str = "2023-06-01";
+/// Used when the request doesn't set `max_tokens`
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+const PROMPT_CACHING_BETA: &str = "prompt-caching-2024-07-31";
+/// System prompts at least this long are cached unless the request says otherwise.
+/// Roughly the 1024-token minimum the API will cache.
+pub const DEFAULT_PROMPT_CACHE_MIN_CHARS: usize = 4096;
+
+/// Longest wait between retries, even if `retry-after` asks for more
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Non-success HTTP response from the Messages API
+#[derive(Debug, Error)]
+#[error("Claude API error: {body}")]
+pub struct ClaudeApiError {
+    pub status: u16,
+    pub body: String,
+    /// Parsed `retry-after` header, when the API sent one
+    pub retry_after: Option<Duration>,
+}
+
+impl ClaudeApiError {
+    /// Rate limited (429) or overloaded (529); worth trying again after a pause
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.status, 429 | 529)
+    }
+
+    /// True when `error` is a 429 from the API, i.e. the key hit its rate limit
+    pub fn is_rate_limited(error: &anyhow::Error) -> bool {
+        error
+            .downcast_ref::<ClaudeApiError>()
+            .is_some_and(|e| e.status == 429)
+    }
+
+    /// True when `error` is a 429 or 529 from the API
+    pub fn is_retryable_error(error: &anyhow::Error) -> bool {
+        error
+            .downcast_ref::<ClaudeApiError>()
+            .is_some_and(ClaudeApiError::is_retryable)
+    }
+}
 
 #[derive(Debug, Serialize)]
 struct ClaudeApiRequest {
@@ -18,10 +62,20 @@ struct ClaudeApiRequest {
     messages: Vec<ClaudeApiMessage>,
     max_tokens: u32,
     stream: bool,
+    /// A plain string, or a text block marked for prompt caching
     #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
+    system: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<Tool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+    /// Sends the prompt caching beta header; not part of the body
+    #[serde(skip)]
+    prompt_caching: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -74,10 +128,45 @@ struct ClaudeContentBlockResponse {
     id: Option<String>,
 }
 
+/// How often and how long to back off when the API answers 429 or 529
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub const NONE: Self = Self {
+        max_retries: 0,
+        base_delay: Duration::ZERO,
+    };
+
+    /// Wait before retry `attempt` (0-based): the server's `retry-after` when given,
+    /// otherwise `base_delay * 2^attempt`, capped at a minute
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| self.base_delay.saturating_mul(2u32.saturating_pow(attempt)))
+            .min(MAX_RETRY_DELAY)
+    }
+}
+
 pub struct ClaudeService {
     api_key: String,
     model: String,
     client: reqwest::Client,
+    beta_flags: Vec<String>,
+    extra_headers: HashMap<String, String>,
+    retry_policy: RetryPolicy,
+    prompt_cache_min_chars: usize,
 }
 
 impl ClaudeService {
@@ -87,15 +176,38 @@ impl ClaudeService {
             api_key,
             model,
             client,
+            beta_flags: Vec::new(),
+            extra_headers: HashMap::new(),
+            retry_policy: RetryPolicy::default(),
+            prompt_cache_min_chars: DEFAULT_PROMPT_CACHE_MIN_CHARS,
         }
     }
 
-    pub async fn send_message_sync(
-        &self,
-        messages: Vec<Message>,
-        system_prompt: Option<String>,
-        tools: Option<Vec<Tool>>,
-    ) -> Result<ChatResponse> {
+    /// Cache system prompts of at least `min_chars` characters
+    pub fn with_prompt_cache_threshold(mut self, min_chars: usize) -> Self {
+        self.prompt_cache_min_chars = min_chars;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Enable Anthropic beta features, sent comma-joined in the `anthropic-beta` header
+    pub fn with_beta_flags(mut self, beta_flags: Vec<String>) -> Self {
+        self.beta_flags = beta_flags;
+        self
+    }
+
+    /// Add headers to every request. They are applied last and passed through verbatim,
+    /// so they can also override the defaults.
+    pub fn with_extra_headers(mut self, extra_headers: HashMap<String, String>) -> Self {
+        self.extra_headers = extra_headers;
+        self
+    }
+
+    fn headers(&self, prompt_caching: bool) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.insert("x-api-key", HeaderValue::from_str(&self.api_key)?);
         headers.insert(
@@ -104,6 +216,105 @@ impl ClaudeService {
         );
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
+        let mut beta_flags: Vec<&str> = self
+            .beta_flags
+            .iter()
+            .map(|f| f.trim())
+            .filter(|f| !f.is_empty())
+            .collect();
+        if prompt_caching && !beta_flags.contains(&PROMPT_CACHING_BETA) {
+            beta_flags.push(PROMPT_CACHING_BETA);
+        }
+        if !beta_flags.is_empty() {
+            headers.insert("anthropic-beta", HeaderValue::from_str(&beta_flags.join(","))?);
+        }
+
+        for (name, value) in &self.extra_headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow::anyhow!("Invalid header name in extra headers: {}", name))?;
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|_| anyhow::anyhow!("Invalid value for header {}", name))?;
+            headers.insert(header_name, header_value);
+        }
+
+        Ok(headers)
+    }
+
+    pub async fn send_message_sync(
+        &self,
+        messages: Vec<Message>,
+        system_prompt: Option<String>,
+        tools: Option<Vec<Tool>>,
+        options: &ChatOptions,
+    ) -> Result<ChatResponse> {
+        let api_request = self.api_request(messages, system_prompt, tools, options, false);
+
+        let response = self.post(&api_request, options.cancel.as_ref()).await?;
+
+        let api_response: ClaudeApiResponse = response.json().await?;
+        Ok(Self::into_chat_response(api_response, &self.model))
+    }
+
+    /// POST to the Messages API, retrying 429 and 529 responses with exponential backoff.
+    /// Any other failure is returned straight away, and firing `cancel` cuts a backoff short.
+    async fn post(
+        &self,
+        api_request: &ClaudeApiRequest,
+        cancel: Option<&tokio_util::sync::CancellationToken>,
+    ) -> Result<reqwest::Response> {
+        let cancel = cancel.cloned().unwrap_or_default();
+        let headers = self.headers(api_request.prompt_caching)?;
+        let mut attempt = 0;
+        loop {
+            let response = self
+                .client
+                .post(CLAUDE_API_URL)
+                .headers(headers.clone())
+                .json(api_request)
+                .send()
+                .await?;
+            if response.status().is_success() {
+                return Ok(response);
+            }
+
+            let status = response.status().as_u16();
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                .map(Duration::from_secs_f64);
+            let body = response.text().await?;
+            let error = ClaudeApiError { status, body, retry_after };
+            if !error.is_retryable() || attempt >= self.retry_policy.max_retries {
+                return Err(error.into());
+            }
+
+            let delay = self.retry_policy.delay(attempt, retry_after);
+            attempt += 1;
+            log::warn!(
+                "Claude API returned {}; retry {}/{} in {:?}",
+                status,
+                attempt,
+                self.retry_policy.max_retries,
+                delay
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = cancel.cancelled() => anyhow::bail!("Claude API request was cancelled"),
+            }
+        }
+    }
+
+    fn api_request(
+        &self,
+        messages: Vec<Message>,
+        system_prompt: Option<String>,
+        tools: Option<Vec<Tool>>,
+        options: &ChatOptions,
+        stream: bool,
+    ) -> ClaudeApiRequest {
         let mut api_messages = Vec::new();
         for msg in messages {
             let mut content_blocks = Vec::new();
@@ -141,29 +352,96 @@ impl ClaudeService {
             });
         }
 
-        let api_request = ClaudeApiRequest {
+        let prompt_caching = system_prompt.as_ref().is_some_and(|prompt| {
+            options
+                .prompt_caching
+                .unwrap_or(prompt.chars().count() >= self.prompt_cache_min_chars)
+        });
+        let system = system_prompt.map(|prompt| {
+            if prompt_caching {
+                json!([{ "type": "text", "text": prompt, "cache_control": { "type": "ephemeral" } }])
+            } else {
+                Value::String(prompt)
+            }
+        });
+
+        ClaudeApiRequest {
             model: self.model.clone(),
             messages: api_messages,
-            max_tokens: 4096,
-            stream: false,
-            system: system_prompt,
+            max_tokens: options.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            stream,
+            system,
             tools,
+            temperature: options.temperature,
+            top_p: options.top_p,
+            stop_sequences: options.stop_sequences.clone(),
+            prompt_caching,
+        }
+    }
+
+    /// `message_start` carries the input tokens and `message_delta` the cumulative
+    /// output tokens
+    fn record_stream_usage(event: &Value, sink: &UsageSink) {
+        let usage = match event.get("type").and_then(|t| t.as_str()) {
+            Some("message_start") => event.pointer("/message/usage"),
+            Some("message_delta") => event.get("usage"),
+            _ => None,
+        };
+        let Some(usage) = usage else { return };
+        let count = |key: &str| {
+            usage
+                .get(key)
+                .and_then(|v| v.as_u64())
+                .map(|n| u32::try_from(n).unwrap_or(u32::MAX))
         };
 
-        let response = self
-            .client
-            .post(CLAUDE_API_URL)
-            .headers(headers)
-            .json(&api_request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            anyhow::bail!("Claude API error: {}", error_text);
+        let mut total = sink.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(input) = count("input_tokens") {
+            total.input = input;
         }
+        if let Some(output) = count("output_tokens") {
+            total.output = output;
+        }
+    }
 
-        let api_response: ClaudeApiResponse = response.json().await?;
+    /// Assemble streamed `tool_use` blocks: `content_block_start` names the tool,
+    /// `input_json_delta` events carry its input in pieces and `content_block_stop` ends it
+    fn record_stream_tool_call(event: &Value, pending: &mut Option<ToolCall>, sink: &ToolCallSink) {
+        match event.get("type").and_then(|t| t.as_str()) {
+            Some("content_block_start") => {
+                let block = &event["content_block"];
+                if block.get("type").and_then(|t| t.as_str()) == Some("tool_use") {
+                    *pending = Some(ToolCall {
+                        id: block["id"].as_str().unwrap_or_default().to_string(),
+                        tool_type: "function".to_string(),
+                        function: crate::models::ai::ToolFunction {
+                            name: block["name"].as_str().unwrap_or_default().to_string(),
+                            arguments: String::new(),
+                        },
+                    });
+                }
+            }
+            Some("content_block_delta") => {
+                if let (Some(call), Some(json)) = (pending.as_mut(), event.pointer("/delta/partial_json")) {
+                    call.function.arguments.push_str(json.as_str().unwrap_or_default());
+                }
+            }
+            Some("content_block_stop") => {
+                if let Some(mut call) = pending.take() {
+                    if call.function.arguments.trim().is_empty() {
+                        call.function.arguments = "{}".to_string();
+                    }
+                    sink.lock().unwrap_or_else(|p| p.into_inner()).push(call);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Flatten a Messages API response. A response can hold any mix of blocks in any
+    /// order: all text blocks are joined in order, tool-use blocks become tool calls and
+    /// other block types (e.g. thinking) are skipped.
+    fn into_chat_response(api_response: ClaudeApiResponse, model: &str) -> ChatResponse {
         let mut content_text = String::new();
         let mut tool_calls = Vec::new();
 
@@ -175,14 +453,13 @@ impl ClaudeService {
                     }
                 }
                 "tool_use" => {
-                    if let (Some(name), Some(input), Some(id)) = (block.name, block.input, block.id)
-                    {
+                    if let (Some(name), Some(id)) = (block.name, block.id) {
                         tool_calls.push(ToolCall {
                             id,
                             tool_type: "function".to_string(),
                             function: crate::models::ai::ToolFunction {
                                 name,
-                                arguments: input.to_string(),
+                                arguments: block.input.unwrap_or_else(|| json!({})).to_string(),
                             },
                         });
                     }
@@ -194,15 +471,16 @@ impl ClaudeService {
         let metadata = api_response.usage.map(|u| crate::models::ai::GenerationMetadata {
             confidence: 1.0,
             cost_usd: 0.0, // Calculated later based on model
-            model_used: self.model.clone(),
+            model_used: model.to_string(),
             tokens_in: u.input_tokens,
             tokens_out: u.output_tokens,
             tokens_cache_read: u.cache_read_input_tokens.unwrap_or(0),
             tokens_cache_write: u.cache_creation_input_tokens.unwrap_or(0),
             tokens_reasoning: 0,
+            tool_iterations: 0,
         });
 
-        Ok(ChatResponse {
+        ChatResponse {
             content: content_text,
             tool_calls: if tool_calls.is_empty() {
                 None
@@ -210,74 +488,24 @@ impl ClaudeService {
                 Some(tool_calls)
             },
             metadata,
-        })
+        }
     }
 
+    /// Stream the response text. Tool calls go to `options.tool_calls`, if set. Firing
+    /// `options.cancel` ends the stream and drops the connection between chunks.
     pub async fn send_message_stream(
         &self,
         messages: Vec<Message>,
         system_prompt: Option<String>,
         tools: Option<Vec<Tool>>,
+        options: &ChatOptions,
     ) -> Result<Pin<Box<dyn futures::Stream<Item = Result<String>> + Send>>> {
-        let mut headers = HeaderMap::new();
-        headers.insert("x-api-key", HeaderValue::from_str(&self.api_key)?);
-        headers.insert(
-            "anthropic-version",
-            HeaderValue::from_static(CLAUDE_API_VERSION),
-        );
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let api_request = self.api_request(messages, system_prompt, tools, options, true);
+        let cancel = options.cancel.clone().unwrap_or_default();
+        let usage = options.usage.clone();
+        let tool_calls = options.tool_calls.clone();
 
-        let mut api_messages = Vec::new();
-        for msg in messages {
-            let mut content_blocks = Vec::new();
-            if !msg.content.is_empty() {
-                content_blocks.push(ClaudeContentBlock::Text { text: msg.content });
-            }
-            if let Some(tool_calls) = msg.tool_calls {
-                for tc in tool_calls {
-                    content_blocks.push(ClaudeContentBlock::ToolUse {
-                        id: tc.id,
-                        name: tc.function.name,
-                        input: serde_json::from_str(&tc.function.arguments).unwrap_or(json!({})),
-                    });
-                }
-            }
-            if let Some(tool_results) = msg.tool_results {
-                for tr in tool_results {
-                    content_blocks.push(ClaudeContentBlock::ToolResult {
-                        tool_use_id: tr.tool_use_id,
-                        content: tr.content,
-                        is_error: tr.is_error,
-                    });
-                }
-            }
-            api_messages.push(ClaudeApiMessage {
-                role: msg.role,
-                content: json!(content_blocks),
-            });
-        }
-
-        let api_request = ClaudeApiRequest {
-            model: self.model.clone(),
-            messages: api_messages,
-            max_tokens: 4096,
-            stream: true,
-            system: system_prompt,
-            tools,
-        };
-
-        let response = self
-            .client
-            .post(CLAUDE_API_URL)
-            .headers(headers)
-            .json(&api_request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            anyhow::bail!("Claude API error: {}", error_text);
-        }
+        let response = self.post(&api_request, Some(&cancel)).await?;
 
         use futures_util::StreamExt;
         let event_stream = response.bytes_stream();
@@ -291,13 +519,28 @@ impl ClaudeService {
                 tokio_util::codec::LinesCodec::new()
             );
 
-            while let Some(line) = reader.next().await {
+            let mut pending_tool_call = None;
+
+            // Ending the stream on cancellation drops the reader and with it the connection
+            loop {
+                let next = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => None,
+                    line = reader.next() => line,
+                };
+                let Some(line) = next else { break };
                 let line = line?;
                 if line.trim().is_empty() { continue; }
                 if line.starts_with("data: ") {
                     let data = &line[6..];
                     if data == "[DONE]" { break; }
                     let val: Value = serde_json::from_str(data)?;
+                    if let Some(sink) = &usage {
+                        Self::record_stream_usage(&val, sink);
+                    }
+                    if let Some(sink) = &tool_calls {
+                        Self::record_stream_tool_call(&val, &mut pending_tool_call, sink);
+                    }
                     if let Some(delta) = val.get("delta") {
                         if let Some(text) = delta.get("text").and_then(|t| t.as_str()) {
                             yield text.to_string();
@@ -341,8 +584,179 @@ impl LlmProvider for ClaudeService {
                 tool_results: None,
             })
             .collect();
-        self.send_message_sync(messages, request.system_prompt, None)
+        self.send_message_sync(messages, request.system_prompt, None, &ChatOptions::default())
             .await
             .map(|r| r.content)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beta_flags_and_extra_headers_are_applied() {
+        let service = ClaudeService::new("key".to_string(), "model".to_string())
+            .with_beta_flags(vec![
+                "prompt-caching-2024-07-31".to_string(),
+                " ".to_string(),
+                "computer-use-2024-10-22".to_string(),
+            ])
+            .with_extra_headers(HashMap::from([("X-Trace-Id".to_string(), "abc".to_string())]));
+
+        let headers = service.headers(false).unwrap();
+        assert_eq!(
+            headers["anthropic-beta"],
+            "prompt-caching-2024-07-31,computer-use-2024-10-22"
+        );
+        assert_eq!(headers["x-trace-id"], "abc");
+        assert_eq!(headers["anthropic-version"], CLAUDE_API_VERSION);
+    }
+
+    #[test]
+    fn test_no_beta_header_by_default_and_invalid_names_rejected() {
+        let service = ClaudeService::new("key".to_string(), "model".to_string());
+        assert!(!service.headers(false).unwrap().contains_key("anthropic-beta"));
+
+        let service = service.with_extra_headers(HashMap::from([("bad header".to_string(), "v".to_string())]));
+        assert!(service.headers(false).is_err());
+    }
+
+    #[test]
+    fn test_request_options_are_serialized_only_when_set() {
+        let service = ClaudeService::new("key".to_string(), "model".to_string());
+        let request = service.api_request(Vec::new(), None, None, &ChatOptions::default(), false);
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["max_tokens"], DEFAULT_MAX_TOKENS);
+        assert!(body.get("temperature").is_none());
+        assert!(body.get("stop_sequences").is_none());
+
+        let options = ChatOptions {
+            max_tokens: Some(256),
+            temperature: Some(0.0),
+            stop_sequences: vec!["</answer>".to_string()],
+            ..Default::default()
+        };
+        let body = serde_json::to_value(service.api_request(Vec::new(), None, None, &options, true)).unwrap();
+        assert_eq!(body["max_tokens"], 256);
+        assert_eq!(body["temperature"], 0.0);
+        assert_eq!(body["stop_sequences"], json!(["</answer>"]));
+        assert!(body.get("top_p").is_none());
+    }
+
+    #[test]
+    fn test_long_system_prompts_are_cached() {
+        let service = ClaudeService::new("key".to_string(), "model".to_string()).with_prompt_cache_threshold(10);
+
+        let short = service.api_request(Vec::new(), Some("short".to_string()), None, &ChatOptions::default(), false);
+        assert!(!short.prompt_caching);
+        assert_eq!(serde_json::to_value(&short).unwrap()["system"], "short");
+
+        let long = service.api_request(Vec::new(), Some("a long system prompt".to_string()), None, &ChatOptions::default(), false);
+        assert!(long.prompt_caching);
+        let body = serde_json::to_value(&long).unwrap();
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        assert!(body.get("prompt_caching").is_none());
+        assert!(service.headers(true).unwrap()["anthropic-beta"].to_str().unwrap().contains(PROMPT_CACHING_BETA));
+
+        let forced_off = ChatOptions {
+            prompt_caching: Some(false),
+            ..Default::default()
+        };
+        let request = service.api_request(Vec::new(), Some("a long system prompt".to_string()), None, &forced_off, false);
+        assert!(!request.prompt_caching);
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_and_honors_retry_after() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(0, None), Duration::from_secs(1));
+        assert_eq!(policy.delay(2, None), Duration::from_secs(4));
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(7))), Duration::from_secs(7));
+        assert_eq!(policy.delay(20, None), MAX_RETRY_DELAY);
+
+        let error = |status| ClaudeApiError { status, body: String::new(), retry_after: None };
+        assert!(error(429).is_retryable());
+        assert!(error(529).is_retryable());
+        assert!(!error(400).is_retryable());
+        assert!(!error(401).is_retryable());
+    }
+
+    #[test]
+    fn test_stream_usage_events_are_recorded() {
+        let sink = UsageSink::default();
+        let events = [
+            json!({ "type": "message_start", "message": { "usage": { "input_tokens": 120, "output_tokens": 1 } } }),
+            json!({ "type": "content_block_delta", "delta": { "text": "Hi" } }),
+            json!({ "type": "message_delta", "delta": { "stop_reason": "end_turn" }, "usage": { "output_tokens": 42 } }),
+        ];
+        for event in &events {
+            ClaudeService::record_stream_usage(event, &sink);
+        }
+        assert_eq!(*sink.lock().unwrap(), crate::models::ai::TokenUsage { input: 120, output: 42 });
+    }
+
+    #[test]
+    fn test_stream_tool_use_blocks_are_assembled() {
+        let sink = ToolCallSink::default();
+        let mut pending = None;
+        let events = [
+            json!({ "type": "content_block_start", "index": 0, "content_block": { "type": "text", "text": "" } }),
+            json!({ "type": "content_block_delta", "index": 0, "delta": { "type": "text_delta", "text": "Searching" } }),
+            json!({ "type": "content_block_stop", "index": 0 }),
+            json!({ "type": "content_block_start", "index": 1, "content_block": { "type": "tool_use", "id": "toolu_1", "name": "search", "input": {} } }),
+            json!({ "type": "content_block_delta", "index": 1, "delta": { "type": "input_json_delta", "partial_json": "{\"q\": \"ru" } }),
+            json!({ "type": "content_block_delta", "index": 1, "delta": { "type": "input_json_delta", "partial_json": "st\"}" } }),
+            json!({ "type": "content_block_stop", "index": 1 }),
+            json!({ "type": "content_block_start", "index": 2, "content_block": { "type": "tool_use", "id": "toolu_2", "name": "list", "input": {} } }),
+            json!({ "type": "content_block_stop", "index": 2 }),
+        ];
+        for event in &events {
+            ClaudeService::record_stream_tool_call(event, &mut pending, &sink);
+        }
+
+        let calls = sink.lock().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!((calls[0].id.as_str(), calls[0].function.name.as_str()), ("toolu_1", "search"));
+        assert_eq!(calls[0].function.arguments, r#"{"q": "rust"}"#);
+        assert_eq!(calls[1].function.arguments, "{}");
+    }
+
+    #[test]
+    fn test_multi_block_response_keeps_all_text_and_tool_calls() {
+        let api_response: ClaudeApiResponse = serde_json::from_value(json!({
+            "content": [
+                { "type": "tool_use", "id": "toolu_1", "name": "search", "input": { "q": "rust" } },
+                { "type": "text", "text": "First part. " },
+                { "type": "thinking", "thinking": "hidden" },
+                { "type": "text", "text": "Second part." },
+                { "type": "tool_use", "id": "toolu_2", "name": "list" }
+            ],
+            "usage": { "input_tokens": 10, "output_tokens": 5 }
+        }))
+        .unwrap();
+
+        let response = ClaudeService::into_chat_response(api_response, "model");
+        assert_eq!(response.content, "First part. Second part.");
+
+        let tool_calls = response.tool_calls.unwrap();
+        assert_eq!(tool_calls.len(), 2);
+        assert_eq!(tool_calls[0].function.name, "search");
+        assert_eq!(tool_calls[0].function.arguments, r#"{"q":"rust"}"#);
+        assert_eq!(tool_calls[1].function.arguments, "{}");
+        assert_eq!(response.metadata.unwrap().tokens_out, 5);
+    }
+
+    #[test]
+    fn test_tool_only_response_has_empty_text() {
+        let api_response: ClaudeApiResponse = serde_json::from_value(json!({
+            "content": [{ "type": "tool_use", "id": "toolu_1", "name": "search", "input": {} }]
+        }))
+        .unwrap();
+
+        let response = ClaudeService::into_chat_response(api_response, "model");
+        assert!(response.content.is_empty());
+        assert_eq!(response.tool_calls.map(|c| c.len()), Some(1));
+        assert!(response.metadata.is_none());
+    }
+}
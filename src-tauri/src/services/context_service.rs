@@ -1,12 +1,249 @@
+use crate::models::project::FileSort;
+use crate::models::settings::ProjectSettings;
 use crate::services::file_service::FileService;
 use crate::services::project_service::ProjectService;
+use crate::services::settings_service::SettingsService;
 use anyhow::{Context, Result};
+use glob::Pattern;
+use serde::Serialize;
+
+/// Default for the `max_context_tokens` setting
+pub const DEFAULT_CONTEXT_TOKEN_BUDGET: usize = 16_000;
+/// Upper bound on the tokens spent on pinned files
+const PINNED_CONTEXT_TOKEN_BUDGET: usize = 8_000;
+/// Token estimates assume ~4 chars per token
+const CHARS_PER_TOKEN: usize = 4;
+/// Most recent research log lines considered for the history section
+const RESEARCH_LOG_TAIL_LINES: usize = 50;
+
+/// What `build_project_context` included and what it left out to stay in budget
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ContextReport {
+    pub budget_chars: usize,
+    pub included_chars: usize,
+    pub included_files: Vec<String>,
+    pub dropped_files: Vec<String>,
+    /// Research log lines left out, oldest first
+    pub dropped_log_lines: usize,
+}
+
+impl ContextReport {
+    /// One line for the trace log
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Project context: {} of {} chars, {} file(s) included, {} dropped",
+            self.included_chars,
+            self.budget_chars,
+            self.included_files.len(),
+            self.dropped_files.len()
+        );
+        if !self.dropped_files.is_empty() {
+            summary.push_str(&format!(" ({})", self.dropped_files.join(", ")));
+        }
+        if self.dropped_log_lines > 0 {
+            summary.push_str(&format!(
+                ", {} research log line(s) dropped",
+                self.dropped_log_lines
+            ));
+        }
+        summary
+    }
+}
+
+/// The project's `context_include` / `context_exclude` globs
+#[derive(Debug, Default)]
+struct ContextFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl ContextFilter {
+    fn from_settings(settings: &ProjectSettings) -> Self {
+        Self {
+            include: Self::compile(&settings.context_include),
+            exclude: Self::compile(&settings.context_exclude),
+        }
+    }
+
+    /// Invalid patterns are logged and ignored rather than failing the whole context
+    fn compile(patterns: &[String]) -> Vec<Pattern> {
+        patterns
+            .iter()
+            .filter_map(|p| match Pattern::new(p.trim()) {
+                Ok(pattern) => Some(pattern),
+                Err(e) => {
+                    log::warn!("Ignoring invalid context pattern '{}': {}", p, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the project-relative `file` may go into the automatic context
+    fn allows(&self, file: &str) -> bool {
+        let name = file.rsplit('/').next().unwrap_or(file);
+        let matches = |pattern: &Pattern| {
+            pattern.matches(file) || (!pattern.as_str().contains('/') && pattern.matches(name))
+        };
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
 
 pub struct ContextService;
 
 impl ContextService {
-    /// Gather project context as a formatted string
+    /// Files the user pinned to always be included in this project's context
+    pub fn get_pinned_files(project_id: &str) -> Result<Vec<String>> {
+        let project_path = ProjectService::resolve_project_path(project_id)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        Ok(SettingsService::load_project_settings(&project_path)?
+            .map(|s| s.pinned_context_files)
+            .unwrap_or_default())
+    }
+
+    /// Pin a project file so it is always included in context. Returns the updated list.
+    pub fn pin_file(project_id: &str, file_name: &str) -> Result<Vec<String>> {
+        let file_name = file_name.trim().trim_start_matches("./").to_string();
+        // Validates the path and that the file exists
+        FileService::read_file(project_id, &file_name)?;
+        Self::update_pinned_files(project_id, |pinned| {
+            if !pinned.contains(&file_name) {
+                pinned.push(file_name.clone());
+            }
+        })
+    }
+
+    /// Remove a file from the pinned list. Returns the updated list.
+    pub fn unpin_file(project_id: &str, file_name: &str) -> Result<Vec<String>> {
+        let file_name = file_name.trim().trim_start_matches("./");
+        Self::update_pinned_files(project_id, |pinned| pinned.retain(|f| f != file_name))
+    }
+
+    fn update_pinned_files(project_id: &str, update: impl FnOnce(&mut Vec<String>)) -> Result<Vec<String>> {
+        let project_path = ProjectService::resolve_project_path(project_id)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let mut settings = SettingsService::load_project_settings(&project_path)?.unwrap_or_default();
+        update(&mut settings.pinned_context_files);
+        SettingsService::save_project_settings(&project_path, &settings)?;
+        Ok(settings.pinned_context_files)
+    }
+
+    /// Render pinned files in full, in pin order, until the pinned-file budget or
+    /// `budget_chars` is spent. Returns the section and the files that were included.
+    fn pinned_files_section(
+        project_id: &str,
+        pinned: &[String],
+        budget_chars: usize,
+    ) -> (String, Vec<String>) {
+        const HEADER: &str = "## Pinned Files\n\n";
+        let mut section = String::new();
+        let mut included = Vec::new();
+        let mut remaining_chars = (PINNED_CONTEXT_TOKEN_BUDGET * CHARS_PER_TOKEN)
+            .min(budget_chars)
+            .saturating_sub(HEADER.len());
+
+        for file in pinned {
+            let content = match FileService::read_file(project_id, file) {
+                Ok(content) => content,
+                Err(e) => {
+                    log::warn!("Skipping pinned context file {}: {}", file, e);
+                    continue;
+                }
+            };
+            let entry = format!("### File: {}\n\n{}\n\n", file, content);
+            if entry.len() > remaining_chars {
+                log::warn!("Pinned context file {} exceeds the remaining context budget, skipping", file);
+                continue;
+            }
+            remaining_chars -= entry.len();
+            section.push_str(&entry);
+            included.push(file.clone());
+        }
+
+        if section.is_empty() {
+            return (section, included);
+        }
+        (format!("{}{}", HEADER, section), included)
+    }
+
+    /// Whether a file's YAML frontmatter opts it out of the context with `context: false`
+    fn opted_out(content: &str) -> bool {
+        let Some(rest) = content.strip_prefix("---") else {
+            return false;
+        };
+        let Some(end) = rest.find("\n---") else {
+            return false;
+        };
+        serde_yaml::from_str::<serde_yaml::Value>(&rest[..end])
+            .ok()
+            .and_then(|yaml| yaml.get("context").and_then(|v| v.as_bool()))
+            == Some(false)
+    }
+
+    /// Preview of a file (its first 10 lines) for the files overview
+    fn file_preview(file: &str, content: Option<&str>) -> String {
+        let Some(content) = content else {
+            return format!("### File: {}\n- (Unable to read content)\n", file);
+        };
+        // Detect extension for markdown fence
+        let ext = std::path::Path::new(file)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("text");
+        let preview = content.lines().take(10).collect::<Vec<_>>().join("\n");
+        format!("### File: {}\n```{}\n{}\n[...]\n```\n\n", file, ext, preview)
+    }
+
+    /// The newest research log lines that fit in `budget_chars`, oldest dropped first.
+    /// Returns the section and how many lines of the log were left out.
+    fn history_section(log: &str, budget_chars: usize) -> (String, usize) {
+        const HEADER: &str = "## Recent Research History (from research_log.md)\n\n";
+        let lines: Vec<&str> = log.lines().collect();
+        let tail = &lines[lines.len().saturating_sub(RESEARCH_LOG_TAIL_LINES)..];
+
+        let mut remaining = budget_chars.saturating_sub(HEADER.len() + 2);
+        let mut kept = 0;
+        for line in tail.iter().rev() {
+            if line.len() + 1 > remaining {
+                break;
+            }
+            remaining -= line.len() + 1;
+            kept += 1;
+        }
+
+        let dropped = lines.len() - kept;
+        if kept == 0 {
+            return (String::new(), dropped);
+        }
+        let history = tail[tail.len() - kept..].join("\n");
+        (format!("{}{}\n\n", HEADER, history), dropped)
+    }
+
+    /// Context budget from the global settings
+    pub fn token_budget() -> usize {
+        SettingsService::load_global_settings()
+            .map(|settings| settings.max_context_tokens)
+            .unwrap_or(DEFAULT_CONTEXT_TOKEN_BUDGET)
+    }
+
+    /// Gather project context as a formatted string, within the configured budget
     pub fn get_project_context(project_id: &str) -> Result<String> {
+        Self::build_project_context(project_id, Self::token_budget()).map(|(context, _)| context)
+    }
+
+    /// Gather project context within `budget_tokens`. The name and goal always go in;
+    /// pinned files, the README and previews of the most recently modified files come
+    /// next, and the research log history gets what is left. The README and previews
+    /// skip files the project's context globs or a `context: false` frontmatter leave out.
+    pub fn build_project_context(
+        project_id: &str,
+        budget_tokens: usize,
+    ) -> Result<(String, ContextReport)> {
+        let mut report = ContextReport {
+            budget_chars: budget_tokens * CHARS_PER_TOKEN,
+            ..Default::default()
+        };
         let mut context = String::from("# Project Context\n\n");
 
         // 1. Add Project Metadata
@@ -15,54 +252,154 @@ impl ContextService {
 
         context.push_str(&format!("**Project Name**: {}\n", project.name));
         context.push_str(&format!("**Project Goal**: {}\n\n", project.goal));
+        let mut remaining = report.budget_chars.saturating_sub(context.len());
+        let filter = SettingsService::load_project_settings(&project.path)
+            .ok()
+            .flatten()
+            .map(|settings| ContextFilter::from_settings(&settings))
+            .unwrap_or_default();
 
-        // 2. Add README content if it exists
-        if let Ok(readme) = FileService::read_file(project_id, "README.md") {
-            context.push_str("## README.md\n\n");
-            context.push_str(&readme);
-            context.push_str("\n\n");
-        }
+        // 2. Pinned files come first, in full
+        let pinned = Self::get_pinned_files(project_id).unwrap_or_default();
+        let (pinned_section, pinned_included) =
+            Self::pinned_files_section(project_id, &pinned, remaining);
+        remaining -= pinned_section.len();
+        report.included_files.extend(pinned_included.iter().cloned());
+        report
+            .dropped_files
+            .extend(pinned.iter().filter(|f| !pinned_included.contains(*f)).cloned());
 
-        // 3. Add Research Log (Last 5 entries to give context of what happened)
-        if let Ok(log) = FileService::read_file(project_id, "research_log.md") {
-            context.push_str("## Recent Research History (from research_log.md)\n\n");
-            // Just take the tail for now to avoid token overflow
-            let lines: Vec<&str> = log.lines().collect();
-            let tail = if lines.len() > 50 {
-                lines[lines.len() - 50..].join("\n")
-            } else {
-                log
-            };
-            context.push_str(&tail);
-            context.push_str("\n\n");
+        // 3. README content, unless it is pinned and so already included in full
+        let mut readme_section = String::new();
+        if !pinned_included.iter().any(|f| f == "README.md") && filter.allows("README.md") {
+            let readme = FileService::read_file(project_id, "README.md").ok();
+            if let Some(readme) = readme.filter(|readme| !Self::opted_out(readme)) {
+                let section = format!("## README.md\n\n{}\n\n", readme);
+                if section.len() <= remaining {
+                    remaining -= section.len();
+                    readme_section = section;
+                    report.included_files.push("README.md".to_string());
+                } else {
+                    report.dropped_files.push("README.md".to_string());
+                }
+            }
         }
 
-        // 4. Add list of other files with summaries (first 10 lines)
-        if let Ok(files) = ProjectService::list_project_files(project_id) {
-            context.push_str("## Project Files Overview & Previews\n");
-            for file in files {
-                if file != "README.md" && file != "research_log.md" && !file.starts_with('.') {
-                    context.push_str(&format!("### File: {}\n", file));
-                    if let Ok(content) = FileService::read_file(project_id, &file) {
-                        // Detect extension for markdown fence
-                        let ext = std::path::Path::new(&file)
-                            .extension()
-                            .and_then(|e| e.to_str())
-                            .unwrap_or("text");
-
-                        // Take first 10 lines as a preview
-                        let preview: String =
-                            content.lines().take(10).collect::<Vec<_>>().join("\n");
-                        context.push_str(&format!("```{}\n", ext));
-                        context.push_str(&preview);
-                        context.push_str("\n[...]\n```\n\n");
-                    } else {
-                        context.push_str("- (Unable to read content)\n");
-                    }
+        // 4. Previews of the other files, most recently modified first
+        const FILES_HEADER: &str = "## Project Files Overview & Previews\n";
+        let mut previews = String::new();
+        let recent = ProjectService::list_project_files_page(project_id, 0, None, FileSort::Modified);
+        if let Ok(page) = recent {
+            let mut budget = remaining.saturating_sub(FILES_HEADER.len());
+            for file in page.files.into_iter().map(|info| info.path) {
+                if file == "README.md"
+                    || file == "research_log.md"
+                    || file.starts_with('.')
+                    || pinned_included.contains(&file)
+                    || !filter.allows(&file)
+                {
+                    continue;
+                }
+                let content = FileService::read_file(project_id, &file).ok();
+                if content.as_deref().is_some_and(Self::opted_out) {
+                    continue;
                 }
+                let preview = Self::file_preview(&file, content.as_deref());
+                if preview.len() <= budget {
+                    budget -= preview.len();
+                    previews.push_str(&preview);
+                    report.included_files.push(file);
+                } else {
+                    report.dropped_files.push(file);
+                }
+            }
+            if !previews.is_empty() {
+                remaining -= FILES_HEADER.len() + previews.len();
             }
         }
 
-        Ok(context)
+        // 5. Research log history with whatever budget is left
+        let mut history_section = String::new();
+        if let Ok(log) = FileService::read_file(project_id, "research_log.md") {
+            let (section, dropped) = Self::history_section(&log, remaining);
+            history_section = section;
+            report.dropped_log_lines = dropped;
+        }
+
+        context.push_str(&pinned_section);
+        context.push_str(&readme_section);
+        context.push_str(&history_section);
+        if !previews.is_empty() {
+            context.push_str(FILES_HEADER);
+            context.push_str(&previews);
+        }
+
+        report.included_chars = context.len();
+        Ok((context, report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_keeps_newest_lines() {
+        let log: String = (1..=60).map(|i| format!("entry {:02}\n", i)).collect();
+
+        // Only the last RESEARCH_LOG_TAIL_LINES lines are considered
+        let (section, dropped) = ContextService::history_section(&log, usize::MAX);
+        assert_eq!(dropped, 60 - RESEARCH_LOG_TAIL_LINES);
+        assert!(section.contains("entry 11\nentry 12") && !section.contains("entry 10"));
+
+        // A tight budget keeps the newest lines that fit
+        let header_len = "## Recent Research History (from research_log.md)\n\n".len();
+        let (section, dropped) = ContextService::history_section(&log, header_len + 2 + 18);
+        assert_eq!(dropped, 58);
+        assert!(section.ends_with("entry 59\nentry 60\n\n"));
+
+        assert_eq!(ContextService::history_section(&log, 10), (String::new(), 60));
+    }
+
+    #[test]
+    fn test_context_filter() {
+        let settings = ProjectSettings {
+            context_include: vec!["*.md".to_string(), "data/*.csv".to_string()],
+            context_exclude: vec!["drafts/**".to_string(), "[".to_string()],
+            ..Default::default()
+        };
+        let filter = ContextFilter::from_settings(&settings);
+        assert_eq!(filter.exclude.len(), 1);
+
+        assert!(filter.allows("notes.md"));
+        assert!(filter.allows("interviews/acme.md"));
+        assert!(filter.allows("data/survey.csv"));
+        assert!(!filter.allows("raw/survey.csv"));
+        assert!(!filter.allows("drafts/intro.md"));
+        assert!(ContextFilter::default().allows("anything.txt"));
+    }
+
+    #[test]
+    fn test_frontmatter_opt_out() {
+        assert!(ContextService::opted_out("---\ntitle: Raw\ncontext: false\n---\n# Raw"));
+        assert!(!ContextService::opted_out("---\ncontext: true\n---\n# Kept"));
+        assert!(!ContextService::opted_out("---\ntitle: Kept\n---\ncontext: false"));
+        assert!(!ContextService::opted_out("# No frontmatter\ncontext: false"));
+    }
+
+    #[test]
+    fn test_report_summary() {
+        let report = ContextReport {
+            budget_chars: 64_000,
+            included_chars: 12_345,
+            included_files: vec!["README.md".to_string()],
+            dropped_files: vec!["big.md".to_string(), "old.md".to_string()],
+            dropped_log_lines: 20,
+        };
+        assert_eq!(
+            report.summary(),
+            "Project context: 12345 of 64000 chars, 1 file(s) included, 2 dropped \
+             (big.md, old.md), 20 research log line(s) dropped"
+        );
     }
 }
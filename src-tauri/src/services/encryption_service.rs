@@ -2,14 +2,40 @@ use aes_gcm::{
     aead::{Aead, KeyInit, OsRng},
     Aes256Gcm, Nonce,
 };
+use crate::models::settings::{GlobalSettings, KeyStore};
+use crate::utils::fs::write_atomic;
+use crate::utils::paths;
 use anyhow::Context;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use keyring::Entry;
 use rand::RngCore;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
 use std::sync::Mutex;
 
 pub struct EncryptionService;
 
+/// Auditable description of how secrets are encrypted. Never contains key material.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionInfo {
+    pub algorithm: String,
+    pub key_size_bits: u32,
+    pub nonce: String,
+    pub key_store: KeyStore,
+    /// Human readable name of the key store, e.g. "macOS Keychain"
+    pub key_store_name: String,
+    /// Keychain service/account or file path where the key is kept
+    pub key_location: String,
+    pub master_password_enabled: bool,
+    /// Whether the key has already been loaded in this session
+    pub key_loaded: bool,
+    /// When the key was last rotated, if ever
+    pub key_rotated_at: Option<String>,
+    pub secrets_path: String,
+}
+
 #[derive(Clone, Debug)]
 enum KeyCacheState {
     Uninitialized,
@@ -39,8 +65,8 @@ impl EncryptionService {
             KeyCacheState::Uninitialized => {}
         }
 
-        // Cache miss - fetch from keyring
-        match Self::fetch_from_keyring() {
+        // Cache miss - fetch from the configured key store
+        match Self::fetch_key() {
             Ok(key) => {
                 *guard = KeyCacheState::Key(key.clone());
                 Ok(key)
@@ -55,8 +81,188 @@ impl EncryptionService {
         }
     }
 
+    /// Global settings read directly from disk, since this can run outside of the async runtime
+    fn global_settings() -> Option<GlobalSettings> {
+        paths::get_global_settings_path()
+            .ok()
+            .and_then(|path| GlobalSettings::load(path).ok())
+    }
+
+    /// Key store selected in global settings
+    fn configured_key_store() -> KeyStore {
+        Self::global_settings()
+            .map(|settings| settings.encryption_key_store)
+            .unwrap_or_default()
+    }
+
+    /// Make `key` the master key in the configured key store, replacing the current one.
+    /// Callers must re-encrypt existing data first; see `SecretsService::rotate_encryption_key`.
+    pub(crate) fn replace_master_key(key: &[u8]) -> Result<(), anyhow::Error> {
+        let mut guard = MASTER_KEY_CACHE
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Failed to lock mutex"))?;
+
+        Self::store_key(Self::configured_key_store(), key, &paths::get_master_key_path()?)?;
+        *guard = KeyCacheState::Key(key.to_vec());
+        Ok(())
+    }
+
+    /// Write `key` to `store` and drop the copy in the other store. Both fetches prefer a
+    /// key that is already present, so a stale copy would win after switching stores.
+    fn store_key(store: KeyStore, key: &[u8], key_path: &Path) -> Result<(), anyhow::Error> {
+        match store {
+            KeyStore::OsKeychain => {
+                Entry::new(APP_NAME, MASTER_KEY_NAME)?
+                    .set_password(&BASE64.encode(key))
+                    .context("Failed to store master key in keyring")?;
+                if key_path.exists() {
+                    fs::remove_file(key_path).context("Failed to remove the old master key file")?;
+                }
+            }
+            KeyStore::File => {
+                Self::write_key_file(key_path, key)?;
+                match Entry::new(APP_NAME, MASTER_KEY_NAME).and_then(|entry| entry.delete_password()) {
+                    Ok(()) | Err(keyring::Error::NoEntry) => {}
+                    Err(e) => log::warn!("Failed to remove the old master key from the keyring: {}", e),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Remember when the key was rotated in global settings
+    pub(crate) fn record_rotation(rotated_at: &str) -> Result<(), anyhow::Error> {
+        let path = paths::get_global_settings_path()?;
+        let mut settings = GlobalSettings::load(&path)?;
+        settings.encryption_key_rotated_at = Some(rotated_at.to_string());
+        settings.save(&path)?;
+        Ok(())
+    }
+
+    /// Fetch from the configured key store directly (no cache)
+    fn fetch_key() -> Result<Vec<u8>, anyhow::Error> {
+        Self::fetch_key_from(Self::configured_key_store(), &paths::get_master_key_path()?)
+    }
+
+    fn fetch_key_from(store: KeyStore, key_path: &Path) -> Result<Vec<u8>, anyhow::Error> {
+        match store {
+            KeyStore::OsKeychain => Self::fetch_from_keyring(key_path),
+            KeyStore::File => Self::fetch_from_file(key_path),
+        }
+    }
+
+    pub(crate) fn generate_key() -> Vec<u8> {
+        let mut key = vec![0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        key
+    }
+
+    /// Read the key file, if present
+    fn read_key_file(path: &Path) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let key_b64 = fs::read_to_string(path).context("Failed to read master key file")?;
+        let key = BASE64
+            .decode(key_b64.trim())
+            .context("Failed to decode master key file")?;
+        Ok(Some(key))
+    }
+
+    /// Write the key file, readable only by the current user. The temp file behind the
+    /// atomic write is created with mode 0600, so the key is never briefly world-readable.
+    fn write_key_file(path: &Path, key: &[u8]) -> Result<(), anyhow::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(path, BASE64.encode(key)).context("Failed to write master key file")?;
+        Ok(())
+    }
+
+    /// Read an existing key from the OS keyring without creating one
+    fn read_keyring_key() -> Result<Option<Vec<u8>>, anyhow::Error> {
+        let entry = Entry::new(APP_NAME, MASTER_KEY_NAME)?;
+        match entry.get_password() {
+            Ok(key_b64) => Ok(Some(
+                BASE64
+                    .decode(key_b64)
+                    .context("Failed to decode master key from base64")?,
+            )),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Fetch from the key file, migrating an existing keyring key on first use
+    fn fetch_from_file(key_path: &Path) -> Result<Vec<u8>, anyhow::Error> {
+        if let Some(key) = Self::read_key_file(key_path)? {
+            return Ok(key);
+        }
+
+        // Carry the keyring key over so existing secrets stay readable after switching stores
+        let key = match Self::read_keyring_key() {
+            Ok(Some(key)) => key,
+            Ok(None) => Self::generate_key(),
+            Err(e) => {
+                if paths::get_secrets_path()?.exists() {
+                    return Err(anyhow::anyhow!(
+                        "Cannot move the master key out of the OS keychain: {}. Switch the key store back to the OS keychain or reset the encryption key.",
+                        e
+                    ));
+                }
+                Self::generate_key()
+            }
+        };
+
+        Self::write_key_file(key_path, &key)?;
+        Ok(key)
+    }
+
+    /// Describe the encryption setup without exposing the key
+    pub fn get_info() -> Result<EncryptionInfo, anyhow::Error> {
+        let key_store = Self::configured_key_store();
+        let (key_store_name, key_location) = match key_store {
+            KeyStore::OsKeychain => {
+                let name = if cfg!(target_os = "macos") {
+                    "macOS Keychain"
+                } else if cfg!(target_os = "windows") {
+                    "Windows Credential Manager"
+                } else {
+                    "Secret Service (GNOME Keyring / KWallet)"
+                };
+                (
+                    name.to_string(),
+                    format!("service \"{}\", account \"{}\"", APP_NAME, MASTER_KEY_NAME),
+                )
+            }
+            KeyStore::File => (
+                "Key file".to_string(),
+                paths::get_master_key_path()?.to_string_lossy().to_string(),
+            ),
+        };
+
+        let key_rotated_at = Self::global_settings().and_then(|s| s.encryption_key_rotated_at);
+        let key_loaded = MASTER_KEY_CACHE
+            .lock()
+            .map(|guard| matches!(*guard, KeyCacheState::Key(_)))
+            .unwrap_or(false);
+
+        Ok(EncryptionInfo {
+            algorithm: "AES-256-GCM".to_string(),
+            key_size_bits: 256,
+            nonce: "96-bit random nonce per encryption".to_string(),
+            key_store,
+            key_store_name,
+            key_location,
+            master_password_enabled: false,
+            key_loaded,
+            key_rotated_at,
+            secrets_path: paths::get_secrets_path()?.to_string_lossy().to_string(),
+        })
+    }
+
     /// Fetch from keyring directly (no cache)
-    fn fetch_from_keyring() -> Result<Vec<u8>, anyhow::Error> {
+    fn fetch_from_keyring(key_path: &Path) -> Result<Vec<u8>, anyhow::Error> {
         let entry_result = Entry::new(APP_NAME, MASTER_KEY_NAME);
 
         #[cfg(test)]
@@ -109,9 +315,11 @@ impl EncryptionService {
                     return Err(anyhow::anyhow!("Keyring access failed: {}. If you denied access, please restart the app and allow it.", e));
                 }
 
-                // Generate new key
-                let mut key = vec![0u8; 32];
-                OsRng.fill_bytes(&mut key);
+                // Reuse a key file left over from the file key store, otherwise generate a new key
+                let key = match Self::read_key_file(key_path) {
+                    Ok(Some(key)) => key,
+                    _ => Self::generate_key(),
+                };
 
                 // Store in keyring
                 let key_b64 = BASE64.encode(&key);
@@ -134,8 +342,12 @@ impl EncryptionService {
 
     /// Encrypt data using AES-256-GCM
     pub fn encrypt(data: &str) -> Result<String, anyhow::Error> {
-        let key = Self::get_or_create_master_key()?;
-        let cipher = Aes256Gcm::new_from_slice(&key)
+        Self::encrypt_with_key(&Self::get_or_create_master_key()?, data)
+    }
+
+    /// Encrypt with the given key instead of the master key
+    pub fn encrypt_with_key(key: &[u8], data: &str) -> Result<String, anyhow::Error> {
+        let cipher = Aes256Gcm::new_from_slice(key)
             .map_err(|e| anyhow::anyhow!("Invalid key length: {}", e))?;
 
         // Generate random nonce
@@ -157,9 +369,13 @@ impl EncryptionService {
 
     /// Decrypt data using AES-256-GCM
     pub fn decrypt(encrypted_data: &str) -> Result<String, anyhow::Error> {
-        let key = Self::get_or_create_master_key()?;
+        Self::decrypt_with_key(&Self::get_or_create_master_key()?, encrypted_data)
+    }
+
+    /// Decrypt with the given key instead of the master key
+    pub fn decrypt_with_key(key: &[u8], encrypted_data: &str) -> Result<String, anyhow::Error> {
         let cipher =
-            Aes256Gcm::new_from_slice(&key).map_err(|_e| anyhow::anyhow!("Invalid key length"))?;
+            Aes256Gcm::new_from_slice(key).map_err(|_e| anyhow::anyhow!("Invalid key length"))?;
 
         // Decode
         let combined = BASE64.decode(encrypted_data)?;
@@ -191,6 +407,13 @@ impl EncryptionService {
             *guard = KeyCacheState::Uninitialized;
         }
 
+        if Self::configured_key_store() == KeyStore::File {
+            let path = paths::get_master_key_path()?;
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+
         let entry = Entry::new(APP_NAME, MASTER_KEY_NAME)?;
         let _ = entry.delete_password();
         Ok(())
@@ -316,4 +539,58 @@ mod tests {
         // Clean up
         let _ = EncryptionService::delete_master_key();
     }
+
+    #[test]
+    fn test_encryption_info_does_not_expose_key() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+
+        let key = EncryptionService::get_or_create_master_key().unwrap();
+        let info = EncryptionService::get_info().unwrap();
+        assert_eq!(info.algorithm, "AES-256-GCM");
+        assert_eq!(info.key_size_bits, 256);
+        assert!(!info.master_password_enabled);
+
+        let serialized = serde_json::to_string(&info).unwrap();
+        assert!(!serialized.contains(&BASE64.encode(&key)));
+    }
+
+    #[test]
+    fn test_rotated_key_survives_switching_key_store() {
+        let _lock = TEST_MUTEX.lock().unwrap();
+
+        // Needs a working OS keyring; the in-test fallbacks would hide a stale key
+        let keyring_works = Entry::new(APP_NAME, "keyring_probe")
+            .and_then(|entry| {
+                entry.set_password("probe")?;
+                entry.delete_password()
+            })
+            .is_ok();
+        if !keyring_works {
+            return;
+        }
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let key_path = temp_dir.path().join("master.key");
+
+        // Rotate under the file store, then switch back to the keychain
+        let (old_key, new_key) = (EncryptionService::generate_key(), EncryptionService::generate_key());
+        EncryptionService::store_key(KeyStore::OsKeychain, &old_key, &key_path).unwrap();
+        EncryptionService::store_key(KeyStore::File, &new_key, &key_path).unwrap();
+        assert_eq!(
+            EncryptionService::fetch_key_from(KeyStore::OsKeychain, &key_path).unwrap(),
+            new_key
+        );
+
+        // Rotate under the keychain, then switch to the file store
+        let (old_key, new_key) = (EncryptionService::generate_key(), EncryptionService::generate_key());
+        EncryptionService::store_key(KeyStore::File, &old_key, &key_path).unwrap();
+        EncryptionService::store_key(KeyStore::OsKeychain, &new_key, &key_path).unwrap();
+        assert!(!key_path.exists());
+        assert_eq!(
+            EncryptionService::fetch_key_from(KeyStore::File, &key_path).unwrap(),
+            new_key
+        );
+
+        let _ = EncryptionService::delete_master_key();
+    }
 }
@@ -1,9 +1,30 @@
+use crate::services::skill_service::SKILL_DIR_MAX_DEPTH;
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::thread;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+/// How long a file must stay quiet before its `FileChanged` event fires. Editors often
+/// save in several bursts, and each burst would otherwise trigger a re-read.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Globs for paths that never reach the UI. See `FileWatcherService::with_ignore_patterns`.
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    ".*",     // Dotfiles and dot folders such as .workflows, .metadata and .git
+    "*~",     // Editor backup copies
+    "*.swp",  // Vim swap files
+    "*.swo",
+    "*.swx",
+    "*.tmp",
+    "*.temp",
+    "#*#",    // Emacs auto-save files
+    "4913",   // Vim's write-permission probe
+];
+
 #[derive(Debug, Error)]
 pub enum FileWatcherError {
     #[error("Failed to initialize watcher: {0}")]
@@ -14,12 +35,15 @@ pub enum FileWatcherError {
 
     #[error("Watcher not running")]
     NotRunning,
+
+    #[error("Invalid ignore pattern '{0}': {1}")]
+    InvalidPattern(String, String),
 }
 
 pub type Result<T> = std::result::Result<T, FileWatcherError>;
 
 /// Events emitted by the file watcher
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum WatchEvent {
     /// A new project directory was added
     ProjectAdded(String),
@@ -29,23 +53,118 @@ pub enum WatchEvent {
 
     /// A file within a project was changed (project_id, file_path)
     FileChanged(String, String),
+
+    /// A skill file appeared in the skills directory (skill_id)
+    SkillAdded(String),
+
+    /// A skill file was edited (skill_id)
+    SkillChanged(String),
+
+    /// A skill file was deleted or moved away (skill_id)
+    SkillRemoved(String),
 }
 
-/// Service for watching file system changes in the projects directory
+impl WatchEvent {
+    /// Change events that editors fire in bursts and are held back until the file settles
+    fn is_debounced(&self) -> bool {
+        matches!(self, WatchEvent::FileChanged(..) | WatchEvent::SkillChanged(_))
+    }
+}
+
+/// Which directory layout a watcher interprets events against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchTarget {
+    /// `{projects}/{project_id}/...`
+    Projects,
+    /// `{skills}/{skill_id}.md`
+    Skills,
+}
+
+/// Compiled ignore patterns
+#[derive(Debug, Clone, Default)]
+struct IgnoreList {
+    patterns: Vec<glob::Pattern>,
+}
+
+impl IgnoreList {
+    fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| {
+                glob::Pattern::new(p)
+                    .map_err(|e| FileWatcherError::InvalidPattern(p.clone(), e.to_string()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Whether `relative` (a path inside the watched directory; for projects it starts with
+    /// the project id) should be ignored
+    fn is_ignored(&self, relative: &Path) -> bool {
+        let components: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let whole = components.join("/");
+        let in_project = components.get(1..).unwrap_or_default().join("/");
+
+        self.patterns.iter().any(|pattern| {
+            pattern.matches(&whole)
+                || pattern.matches(&in_project)
+                || components.iter().any(|c| pattern.matches(c))
+        })
+    }
+}
+
+/// Service for watching file system changes in the projects directory (or, with
+/// `for_skills`, the skills directory)
 pub struct FileWatcherService {
     watcher: Option<Arc<Mutex<RecommendedWatcher>>>,
     watch_path: Option<PathBuf>,
+    debounce: Duration,
+    ignore_patterns: Vec<String>,
+    target: WatchTarget,
 }
 
+/// Managed state for the skills directory watcher. A separate type from the projects
+/// watcher, which the health check looks up as `Mutex<FileWatcherService>`.
+pub struct SkillsWatcher(pub Mutex<FileWatcherService>);
+
 impl FileWatcherService {
     /// Create a new FileWatcherService instance
     pub fn new() -> Self {
         Self {
             watcher: None,
             watch_path: None,
+            debounce: DEFAULT_DEBOUNCE,
+            ignore_patterns: DEFAULT_IGNORE_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            target: WatchTarget::Projects,
         }
     }
 
+    /// A watcher for the skills directory, reporting `SkillAdded`, `SkillChanged` and
+    /// `SkillRemoved` for the `.md` files in it
+    pub fn for_skills() -> Self {
+        let mut watcher = Self::new();
+        watcher.target = WatchTarget::Skills;
+        watcher
+    }
+
+    /// Coalesce `FileChanged` events for the same file within `window` (default 300ms).
+    /// This is separate from the 2s poll interval used by polling backends.
+    pub fn with_debounce(mut self, window: Duration) -> Self {
+        self.debounce = window;
+        self
+    }
+
+    /// Replace the ignore list (default `DEFAULT_IGNORE_PATTERNS`). Each glob is checked
+    /// against every path component and against the path inside the project, so both
+    /// `*.bak` and `drafts/*` work. Patterns are validated when watching starts.
+    pub fn with_ignore_patterns<S: AsRef<str>>(mut self, patterns: &[S]) -> Self {
+        self.ignore_patterns = patterns.iter().map(|p| p.as_ref().to_string()).collect();
+        self
+    }
+
     /// Start watching the projects directory for changes
     ///
     /// # Arguments
@@ -62,6 +181,7 @@ impl FileWatcherService {
     ///         WatchEvent::ProjectAdded(id) => println!("Project added: {}", id),
     ///         WatchEvent::ProjectRemoved(id) => println!("Project removed: {}", id),
     ///         WatchEvent::FileChanged(id, file) => println!("File changed: {}/{}", id, file),
+    ///         _ => {}
     ///     }
     /// }).unwrap();
     /// ```
@@ -79,8 +199,17 @@ impl FileWatcherService {
             )));
         }
 
-        let callback = Arc::new(callback);
         let projects_path = watch_path.clone();
+        let ignore = IgnoreList::new(&self.ignore_patterns)?;
+        let target = self.target;
+
+        // Events go through a debounce thread, which exits once the watcher is dropped
+        let (tx, rx) = mpsc::channel();
+        let debounce = self.debounce;
+        thread::Builder::new()
+            .name("file-watcher-debounce".to_string())
+            .spawn(move || Self::debounce_events(rx, debounce, callback))
+            .map_err(|e| FileWatcherError::InitError(e.to_string()))?;
 
         // Create watcher with custom config
         let config = Config::default().with_poll_interval(Duration::from_secs(2));
@@ -88,8 +217,16 @@ impl FileWatcherService {
         let watcher = RecommendedWatcher::new(
             move |res: notify::Result<Event>| match res {
                 Ok(event) => {
-                    if let Some(watch_event) = Self::process_event(&event, &projects_path) {
-                        callback(watch_event);
+                    let watch_event = match target {
+                        WatchTarget::Projects => {
+                            Self::process_event(&event, &projects_path, &ignore)
+                        }
+                        WatchTarget::Skills => {
+                            Self::process_skill_event(&event, &projects_path, &ignore)
+                        }
+                    };
+                    if let Some(watch_event) = watch_event {
+                        let _ = tx.send(watch_event);
                     }
                 }
                 Err(e) => {
@@ -131,12 +268,64 @@ impl FileWatcherService {
         }
     }
 
+    /// Deliver events to `callback`, holding back each change event until its file has
+    /// been quiet for `window`. Add/remove events pass straight through. Pending changes
+    /// are flushed when the watcher goes away.
+    fn debounce_events<F>(rx: Receiver<WatchEvent>, window: Duration, callback: F)
+    where
+        F: Fn(WatchEvent),
+    {
+        let mut pending: HashMap<WatchEvent, Instant> = HashMap::new();
+
+        loop {
+            let received = match pending.values().min() {
+                Some(deadline) => {
+                    rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            match received {
+                Ok(event) if event.is_debounced() => {
+                    pending.insert(event, Instant::now() + window);
+                }
+                Ok(event) => callback(event),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    pending.into_keys().for_each(&callback);
+                    return;
+                }
+            }
+
+            let now = Instant::now();
+            let mut due: Vec<(WatchEvent, Instant)> = pending
+                .iter()
+                .filter(|(_, deadline)| **deadline <= now)
+                .map(|(event, deadline)| (event.clone(), *deadline))
+                .collect();
+            due.sort_by_key(|(_, deadline)| *deadline);
+            for (event, _) in due {
+                pending.remove(&event);
+                callback(event);
+            }
+        }
+    }
+
     /// Process a notify event and convert it to a WatchEvent if relevant
-    fn process_event(event: &Event, projects_path: &Path) -> Option<WatchEvent> {
+    fn process_event(
+        event: &Event,
+        projects_path: &Path,
+        ignore: &IgnoreList,
+    ) -> Option<WatchEvent> {
+        let is_ignored = |path: &Path| {
+            path.strip_prefix(projects_path)
+                .map_or(true, |relative| ignore.is_ignored(relative))
+        };
+
         match event.kind {
             // Directory creation - potential new project
             EventKind::Create(_) => {
-                for path in &event.paths {
+                for path in event.paths.iter().filter(|p| !is_ignored(p)) {
                     if path.is_dir() {
                         // Check if this is a direct child of projects directory
                         if let Some(parent) = path.parent() {
@@ -157,7 +346,7 @@ impl FileWatcherService {
 
             // Directory or file removal
             EventKind::Remove(_) => {
-                for path in &event.paths {
+                for path in event.paths.iter().filter(|p| !is_ignored(p)) {
                     // Check if this is a direct child of projects directory (project removed)
                     if let Some(parent) = path.parent() {
                         if parent == projects_path {
@@ -173,7 +362,7 @@ impl FileWatcherService {
 
             // File modification
             EventKind::Modify(_) => {
-                for path in &event.paths {
+                for path in event.paths.iter().filter(|p| !is_ignored(p)) {
                     if path.is_file() {
                         return Self::extract_file_change(path, projects_path);
                     }
@@ -186,6 +375,43 @@ impl FileWatcherService {
         None
     }
 
+    /// Process a notify event in the skills directory. `.md` files count as skills, in
+    /// subfolders too (their id then includes the folder), except the top-level
+    /// `template.md` scaffold.
+    fn process_skill_event(
+        event: &Event,
+        skills_path: &Path,
+        ignore: &IgnoreList,
+    ) -> Option<WatchEvent> {
+        let skill_id = |path: &Path| -> Option<String> {
+            let relative = path.strip_prefix(skills_path).ok()?;
+            if relative.components().count() > SKILL_DIR_MAX_DEPTH || ignore.is_ignored(relative) {
+                return None;
+            }
+            if path.extension()? != "md" || relative == Path::new("template.md") {
+                return None;
+            }
+            let id: Vec<String> = relative
+                .with_extension("")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            Some(id.join("/"))
+        };
+
+        event.paths.iter().find_map(|path| {
+            let id = skill_id(path)?;
+            match event.kind {
+                EventKind::Create(_) => Some(WatchEvent::SkillAdded(id)),
+                EventKind::Remove(_) => Some(WatchEvent::SkillRemoved(id)),
+                // Renames arrive as modifications of a path that may no longer exist
+                EventKind::Modify(_) if path.is_file() => Some(WatchEvent::SkillChanged(id)),
+                EventKind::Modify(_) => Some(WatchEvent::SkillRemoved(id)),
+                _ => None,
+            }
+        })
+    }
+
     /// Extract project_id and file_path from a file path within projects directory
     fn extract_file_change(file_path: &Path, projects_path: &Path) -> Option<WatchEvent> {
         // Get the relative path from projects directory
@@ -194,14 +420,10 @@ impl FileWatcherService {
             if let Some(project_id) = relative.components().next() {
                 let project_id = project_id.as_os_str().to_string_lossy().to_string();
 
-                // Get the file name
+                // Get the file name (ignored paths were filtered out by the caller)
                 if let Some(file_name) = file_path.file_name() {
                     let file_name = file_name.to_string_lossy().to_string();
-
-                    // Skip hidden files and directories (starting with .)
-                    if !file_name.starts_with('.') {
-                        return Some(WatchEvent::FileChanged(project_id, file_name));
-                    }
+                    return Some(WatchEvent::FileChanged(project_id, file_name));
                 }
             }
         }
@@ -326,6 +548,103 @@ mod tests {
         service.stop_watching().unwrap();
     }
 
+    #[test]
+    fn test_rapid_writes_produce_single_event() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_path = temp_dir.path();
+        let project_path = projects_path.join("test-project");
+        fs::create_dir(&project_path).unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let mut service = FileWatcherService::new().with_debounce(Duration::from_millis(300));
+        service
+            .start_watching(projects_path, move |event| {
+                let _ = tx.send(event);
+            })
+            .unwrap();
+
+        // An editor saving in bursts
+        let file_path = project_path.join("notes.md");
+        for i in 0..5 {
+            fs::write(&file_path, format!("# Draft {}", i)).unwrap();
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        thread::sleep(Duration::from_millis(1000));
+
+        let changes = rx
+            .try_iter()
+            .filter(|event| {
+                matches!(event, WatchEvent::FileChanged(project_id, file_name)
+                    if project_id == "test-project" && file_name == "notes.md")
+            })
+            .count();
+        assert_eq!(changes, 1);
+
+        service.stop_watching().unwrap();
+    }
+
+    #[test]
+    fn test_default_ignore_list_skips_temp_files_and_dot_folders() {
+        let patterns: Vec<String> = DEFAULT_IGNORE_PATTERNS.iter().map(|p| p.to_string()).collect();
+        let ignore = IgnoreList::new(&patterns).unwrap();
+
+        assert!(ignore.is_ignored(Path::new("alpha/notes.md.swp")));
+        assert!(ignore.is_ignored(Path::new("alpha/notes.md~")));
+        assert!(ignore.is_ignored(Path::new("alpha/upload.tmp")));
+        assert!(ignore.is_ignored(Path::new("alpha/.workflows/run.json")));
+        assert!(ignore.is_ignored(Path::new("alpha/.metadata/chat.json")));
+        assert!(!ignore.is_ignored(Path::new("alpha/notes.md")));
+        assert!(!ignore.is_ignored(Path::new("alpha/drafts/intro.md")));
+    }
+
+    #[test]
+    fn test_custom_ignore_patterns() {
+        let ignore = IgnoreList::new(&["drafts/*".to_string(), "*.bak".to_string()]).unwrap();
+        assert!(ignore.is_ignored(Path::new("alpha/old.bak")));
+        assert!(ignore.is_ignored(Path::new("alpha/drafts/intro.md")));
+        assert!(!ignore.is_ignored(Path::new("alpha/notes/drafts.md")));
+        assert!(!ignore.is_ignored(Path::new("alpha/.hidden.md")));
+
+        let mut service = FileWatcherService::new().with_ignore_patterns(&["[unclosed"]);
+        let temp_dir = TempDir::new().unwrap();
+        let result = service.start_watching(temp_dir.path(), |_| {});
+        assert!(matches!(result, Err(FileWatcherError::InvalidPattern(_, _))));
+    }
+
+    #[test]
+    fn test_skill_events_for_markdown_files() {
+        let skills_path = Path::new("/data/skills");
+        let ignore = IgnoreList::new(&[".*".to_string(), "*~".to_string()]).unwrap();
+        let skill_event = |kind: EventKind, path: &str| {
+            let event = Event::new(kind).add_path(skills_path.join(path));
+            FileWatcherService::process_skill_event(&event, skills_path, &ignore)
+        };
+        let create = EventKind::Create(notify::event::CreateKind::File);
+        let remove = EventKind::Remove(notify::event::RemoveKind::File);
+
+        assert_eq!(
+            skill_event(create, "summarize.md"),
+            Some(WatchEvent::SkillAdded("summarize".to_string()))
+        );
+        assert_eq!(
+            skill_event(remove, "summarize.md"),
+            Some(WatchEvent::SkillRemoved("summarize".to_string()))
+        );
+        assert_eq!(
+            skill_event(create, "research/papers/reviewer.md"),
+            Some(WatchEvent::SkillAdded("research/papers/reviewer".to_string()))
+        );
+        for skipped in ["template.md", ".draft.md", "summarize.md~", "notes.txt", ".old/x.md"] {
+            assert_eq!(
+                skill_event(create, skipped),
+                None,
+                "{} should not be a skill event",
+                skipped
+            );
+        }
+    }
+
     #[test]
     fn test_stop_watching_not_running() {
         let mut service = FileWatcherService::new();
@@ -0,0 +1,274 @@
+//! Global Search Service - Find text across every project, skill and chat
+//!
+//! Project files are the markdown files `ProjectService::project_files` lists, skills are the
+//! files `SkillService::discover_skills` loads, and chats are searched with
+//! `ChatService::search_chats`. Matching is literal and case-insensitive. Hits come back
+//! grouped by kind, in that order, until the result cap is reached.
+
+use crate::services::chat_service::ChatService;
+use crate::services::project_service::ProjectService;
+use crate::services::skill_service::SkillService;
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Hits returned when the caller doesn't set a limit
+pub const DEFAULT_GLOBAL_SEARCH_LIMIT: usize = 200;
+/// Files larger than this are skipped, matching `search_in_files`
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+/// Matching lines reported per file
+const MAX_LINES_PER_FILE: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlobalSearchKind {
+    ProjectFile,
+    Skill,
+    Chat,
+}
+
+impl GlobalSearchKind {
+    pub const ALL: [GlobalSearchKind; 3] = [Self::ProjectFile, Self::Skill, Self::Chat];
+}
+
+/// One matching line in a project file or skill, or one matching message in a chat
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalSearchHit {
+    pub kind: GlobalSearchKind,
+    /// Set for project files and chats
+    pub project_id: Option<String>,
+    /// Set for skills
+    pub skill_id: Option<String>,
+    /// Path within the project, or the skill or chat file name
+    pub file_name: String,
+    /// 1-based line of the match in project files and skills
+    pub line_number: Option<usize>,
+    /// Position of the matching message in a chat (0-based)
+    pub message_index: Option<usize>,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalSearchResults {
+    pub hits: Vec<GlobalSearchHit>,
+    /// More hits were found than the limit allowed
+    pub truncated: bool,
+}
+
+pub struct GlobalSearchService;
+
+impl GlobalSearchService {
+    /// Search the kinds in `kinds` (every kind when empty), stopping after `limit` hits
+    pub fn search(
+        query: &str,
+        kinds: &[GlobalSearchKind],
+        limit: usize,
+    ) -> Result<GlobalSearchResults> {
+        let query = query.trim();
+        let pattern = Self::build_pattern(query)?;
+        let kinds = if kinds.is_empty() { &GlobalSearchKind::ALL[..] } else { kinds };
+        let wants = |kind: GlobalSearchKind| kinds.contains(&kind);
+
+        // One hit past the limit tells us the results were cut short
+        let full = |hits: &Vec<GlobalSearchHit>| hits.len() > limit;
+        let mut hits = Vec::new();
+
+        let projects = if wants(GlobalSearchKind::ProjectFile) || wants(GlobalSearchKind::Chat) {
+            ProjectService::discover_projects().context("Failed to list projects")?
+        } else {
+            Vec::new()
+        };
+
+        if wants(GlobalSearchKind::ProjectFile) {
+            for project in &projects {
+                if full(&hits) {
+                    break;
+                }
+                let (project_path, files) = match ProjectService::project_files(&project.id) {
+                    Ok(found) => found,
+                    Err(e) => {
+                        log::warn!("Skipping project {} in global search: {}", project.id, e);
+                        continue;
+                    }
+                };
+                for file_name in files.into_iter().filter(|f| f.ends_with(".md")) {
+                    if full(&hits) {
+                        break;
+                    }
+                    for (line_number, snippet) in
+                        Self::file_matches(&project_path.join(&file_name), &pattern)
+                    {
+                        hits.push(GlobalSearchHit {
+                            kind: GlobalSearchKind::ProjectFile,
+                            project_id: Some(project.id.clone()),
+                            skill_id: None,
+                            file_name: file_name.clone(),
+                            line_number: Some(line_number),
+                            message_index: None,
+                            snippet,
+                        });
+                    }
+                }
+            }
+        }
+
+        if wants(GlobalSearchKind::Skill) && !full(&hits) {
+            let skills = SkillService::discover_skills().context("Failed to list skills")?;
+            for skill in skills {
+                if full(&hits) {
+                    break;
+                }
+                let file_name = skill
+                    .file_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| format!("{}.md", skill.id));
+                for (line_number, snippet) in Self::file_matches(&skill.file_path, &pattern) {
+                    hits.push(GlobalSearchHit {
+                        kind: GlobalSearchKind::Skill,
+                        project_id: None,
+                        skill_id: Some(skill.id.clone()),
+                        file_name: file_name.clone(),
+                        line_number: Some(line_number),
+                        message_index: None,
+                        snippet,
+                    });
+                }
+            }
+        }
+
+        if wants(GlobalSearchKind::Chat) {
+            for project in &projects {
+                if full(&hits) {
+                    break;
+                }
+                let chats = match ChatService::search_chats(&project.id, query, false) {
+                    Ok(chats) => chats,
+                    Err(e) => {
+                        log::warn!("Skipping chats of {} in global search: {}", project.id, e);
+                        continue;
+                    }
+                };
+                for chat in chats {
+                    for found in chat.snippets {
+                        hits.push(GlobalSearchHit {
+                            kind: GlobalSearchKind::Chat,
+                            project_id: Some(project.id.clone()),
+                            skill_id: None,
+                            file_name: chat.file_name.clone(),
+                            line_number: None,
+                            message_index: Some(found.message_index),
+                            snippet: found.text,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(Self::finish(hits, limit))
+    }
+
+    fn build_pattern(query: &str) -> Result<Regex> {
+        if query.is_empty() {
+            return Err(anyhow::anyhow!("Search query is empty"));
+        }
+        RegexBuilder::new(&regex::escape(query))
+            .case_insensitive(true)
+            .build()
+            .context("Invalid search query")
+    }
+
+    /// Line numbers and snippets of the first matching lines in a file
+    fn file_matches(path: &Path, pattern: &Regex) -> Vec<(usize, String)> {
+        let Ok(metadata) = fs::metadata(path) else {
+            return Vec::new();
+        };
+        if metadata.len() > MAX_FILE_SIZE {
+            log::warn!("Skipping {:?} in global search: larger than {} bytes", path, MAX_FILE_SIZE);
+            return Vec::new();
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                pattern
+                    .find(line)
+                    .map(|found| (i + 1, ChatService::snippet(line, found.start(), found.end())))
+            })
+            .take(MAX_LINES_PER_FILE)
+            .collect()
+    }
+
+    fn finish(mut hits: Vec<GlobalSearchHit>, limit: usize) -> GlobalSearchResults {
+        let truncated = hits.len() > limit;
+        hits.truncate(limit);
+        GlobalSearchResults { hits, truncated }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_matches_reports_lines() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("notes.md");
+        let mut content = String::from("# Notes\nNothing here\nInterview with ACME\n");
+        for i in 0..10 {
+            content.push_str(&format!("acme follow-up {}\n", i));
+        }
+        fs::write(&path, content).unwrap();
+
+        let pattern = GlobalSearchService::build_pattern("acme").unwrap();
+        let matches = GlobalSearchService::file_matches(&path, &pattern);
+        assert_eq!(matches.len(), MAX_LINES_PER_FILE);
+        assert_eq!(matches[0], (3, "Interview with ACME".to_string()));
+        assert_eq!(matches[1].0, 4);
+
+        // Missing files and regex metacharacters are handled quietly
+        let literal = GlobalSearchService::build_pattern("a.c(").unwrap();
+        assert!(GlobalSearchService::file_matches(&path, &literal).is_empty());
+        let missing = temp.path().join("gone.md");
+        assert!(GlobalSearchService::file_matches(&missing, &pattern).is_empty());
+        assert!(GlobalSearchService::build_pattern("").is_err());
+    }
+
+    #[test]
+    fn test_finish_caps_results() {
+        let hit = |i: usize| GlobalSearchHit {
+            kind: GlobalSearchKind::Skill,
+            project_id: None,
+            skill_id: Some(format!("skill-{}", i)),
+            file_name: format!("skill-{}.md", i),
+            line_number: Some(1),
+            message_index: None,
+            snippet: String::new(),
+        };
+
+        let capped = GlobalSearchService::finish((0..3).map(hit).collect(), 2);
+        assert_eq!(capped.hits.len(), 2);
+        assert!(capped.truncated);
+
+        let all = GlobalSearchService::finish((0..2).map(hit).collect(), 2);
+        assert_eq!(all.hits.len(), 2);
+        assert!(!all.truncated);
+    }
+
+    #[test]
+    fn test_kind_serializes_snake_case() {
+        assert_eq!(
+            serde_json::to_string(&GlobalSearchKind::ProjectFile).unwrap(),
+            "\"project_file\""
+        );
+        let kinds: Vec<GlobalSearchKind> = serde_json::from_str(r#"["skill","chat"]"#).unwrap();
+        assert_eq!(kinds, vec![GlobalSearchKind::Skill, GlobalSearchKind::Chat]);
+    }
+}
@@ -0,0 +1,265 @@
+//! Health Service - One readiness probe for the whole app
+//!
+//! Each component check is independent and never fails the probe itself; problems are
+//! reported as a component status so the UI and scripts can see what is wrong.
+
+use crate::models::ai::ProviderType;
+use crate::models::settings::GlobalSettings;
+use crate::services::ai_service::AIService;
+use crate::services::encryption_service::EncryptionService;
+use crate::services::mcp_service::McpService;
+use crate::services::settings_service::SettingsService;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Free space below which the disk check reports `Degraded`
+pub const MIN_FREE_DISK_BYTES: u64 = 500 * 1024 * 1024;
+const MCP_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    /// The check could not run on this platform; doesn't affect the overall status
+    Unknown,
+    Ok,
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// Worst status among the components
+    pub status: HealthStatus,
+    pub components: Vec<ComponentHealth>,
+    pub checked_at: DateTime<Utc>,
+}
+
+pub struct HealthService;
+
+impl HealthService {
+    pub async fn check(provider_type: ProviderType, file_watcher_running: bool) -> HealthReport {
+        let (data_dir, disk_space) = Self::check_storage();
+        let (provider, mcp_servers) = match SettingsService::load_global_settings() {
+            Ok(settings) => (
+                Self::check_provider(&provider_type, &settings).await,
+                Self::check_mcp_servers(&settings).await,
+            ),
+            Err(e) => {
+                let message = Some(format!("Settings unavailable: {}", e));
+                (
+                    Self::component("provider", HealthStatus::Down, message.clone()),
+                    Self::component("mcpServers", HealthStatus::Unknown, message),
+                )
+            }
+        };
+        let components = vec![
+            data_dir,
+            Self::check_encryption(),
+            provider,
+            mcp_servers,
+            Self::check_file_watcher(file_watcher_running),
+            disk_space,
+        ];
+
+        HealthReport {
+            status: Self::overall(&components),
+            components,
+            checked_at: Utc::now(),
+        }
+    }
+
+    fn overall(components: &[ComponentHealth]) -> HealthStatus {
+        components
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .filter(|s| *s != HealthStatus::Unknown)
+            .unwrap_or(HealthStatus::Ok)
+    }
+
+    fn component(name: &str, status: HealthStatus, message: Option<String>) -> ComponentHealth {
+        ComponentHealth {
+            name: name.to_string(),
+            status,
+            message,
+        }
+    }
+
+    /// Whether the app data directory accepts writes, and the free space on its disk
+    fn check_storage() -> (ComponentHealth, ComponentHealth) {
+        let data_dir = match crate::utils::paths::get_app_data_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                let message = Some(format!("App data directory unavailable: {}", e));
+                return (
+                    Self::component("dataDir", HealthStatus::Down, message),
+                    Self::component("diskSpace", HealthStatus::Unknown, None),
+                );
+            }
+        };
+
+        let writable = std::fs::create_dir_all(&data_dir)
+            .and_then(|_| tempfile::NamedTempFile::new_in(&data_dir).map(|_| ()));
+        let data_dir_health = match writable {
+            Ok(()) => Self::component("dataDir", HealthStatus::Ok, None),
+            Err(e) => Self::component(
+                "dataDir",
+                HealthStatus::Down,
+                Some(format!("{} is not writable: {}", data_dir.display(), e)),
+            ),
+        };
+
+        let disk_health = match free_disk_bytes(&data_dir) {
+            Some(free) if free < MIN_FREE_DISK_BYTES => Self::component(
+                "diskSpace",
+                HealthStatus::Degraded,
+                Some(format!("Only {} MB free", free / (1024 * 1024))),
+            ),
+            Some(free) => Self::component(
+                "diskSpace",
+                HealthStatus::Ok,
+                Some(format!("{} MB free", free / (1024 * 1024))),
+            ),
+            None => Self::component(
+                "diskSpace",
+                HealthStatus::Unknown,
+                Some("Free space could not be determined".to_string()),
+            ),
+        };
+
+        (data_dir_health, disk_health)
+    }
+
+    fn check_encryption() -> ComponentHealth {
+        let probe = "health-check";
+        let round_trip = EncryptionService::encrypt(probe).and_then(|e| EncryptionService::decrypt(&e));
+        match round_trip {
+            Ok(decrypted) if decrypted == probe => Self::component("encryption", HealthStatus::Ok, None),
+            Ok(_) => Self::component(
+                "encryption",
+                HealthStatus::Down,
+                Some("Decrypted value did not match".to_string()),
+            ),
+            Err(e) => Self::component("encryption", HealthStatus::Down, Some(e.to_string())),
+        }
+    }
+
+    async fn check_provider(provider_type: &ProviderType, settings: &GlobalSettings) -> ComponentHealth {
+        let provider = match AIService::create_provider(provider_type, settings) {
+            Ok(provider) => provider,
+            Err(e) => return Self::component("provider", HealthStatus::Down, Some(e.to_string())),
+        };
+
+        if !provider.is_available() {
+            return Self::component(
+                "provider",
+                HealthStatus::Down,
+                Some(format!("{:?} is not installed or not configured", provider_type)),
+            );
+        }
+        match provider.check_authentication().await {
+            Ok(true) => Self::component("provider", HealthStatus::Ok, Some(format!("{:?}", provider_type))),
+            Ok(false) => Self::component(
+                "provider",
+                HealthStatus::Degraded,
+                Some(format!("{:?} may not be authenticated", provider_type)),
+            ),
+            Err(e) => Self::component("provider", HealthStatus::Degraded, Some(e.to_string())),
+        }
+    }
+
+    /// Every enabled MCP server must answer `tools/list`
+    async fn check_mcp_servers(settings: &GlobalSettings) -> ComponentHealth {
+        let servers: Vec<_> = settings.mcp_servers.iter().filter(|s| s.enabled).collect();
+        if servers.is_empty() {
+            return Self::component("mcpServers", HealthStatus::Ok, Some("No MCP servers enabled".to_string()));
+        }
+
+        let mcp = &McpService::new();
+        let checks = servers.iter().map(|server| async move {
+            let result = tokio::time::timeout(MCP_CHECK_TIMEOUT, mcp.list_server_tools(server, false)).await;
+            match result {
+                Ok(Ok(_)) => None,
+                Ok(Err(e)) => Some(format!("{}: {}", server.name, e)),
+                Err(_) => Some(format!("{}: timed out", server.name)),
+            }
+        });
+        let failures: Vec<String> = futures::future::join_all(checks).await.into_iter().flatten().collect();
+
+        if failures.is_empty() {
+            Self::component("mcpServers", HealthStatus::Ok, Some(format!("{} server(s) healthy", servers.len())))
+        } else {
+            Self::component("mcpServers", HealthStatus::Degraded, Some(failures.join("; ")))
+        }
+    }
+
+    fn check_file_watcher(running: bool) -> ComponentHealth {
+        if running {
+            Self::component("fileWatcher", HealthStatus::Ok, None)
+        } else {
+            Self::component(
+                "fileWatcher",
+                HealthStatus::Degraded,
+                Some("Not running; external file changes won't refresh the UI".to_string()),
+            )
+        }
+    }
+}
+
+/// Free bytes on the filesystem holding `path`, from `df`
+#[cfg(unix)]
+fn free_disk_bytes(path: &Path) -> Option<u64> {
+    let output = std::process::Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df_available_kb(&String::from_utf8_lossy(&output.stdout)).map(|kb| kb * 1024)
+}
+
+#[cfg(not(unix))]
+fn free_disk_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// The "Available" column (in KB) of POSIX `df -P` output
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_df_available_kb(output: &str) -> Option<u64> {
+    output.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overall_status_is_worst_known_component() {
+        let c = |status| HealthService::component("c", status, None);
+        assert_eq!(HealthService::overall(&[c(HealthStatus::Ok), c(HealthStatus::Unknown)]), HealthStatus::Ok);
+        assert_eq!(
+            HealthService::overall(&[c(HealthStatus::Degraded), c(HealthStatus::Ok)]),
+            HealthStatus::Degraded
+        );
+        assert_eq!(
+            HealthService::overall(&[c(HealthStatus::Degraded), c(HealthStatus::Down)]),
+            HealthStatus::Down
+        );
+    }
+
+    #[test]
+    fn test_parse_df_output() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n/dev/disk1s1     488245288 231234567 245678901      49% /\n";
+        assert_eq!(parse_df_available_kb(output), Some(245678901));
+        assert_eq!(parse_df_available_kb("garbage"), None);
+    }
+}
@@ -1,5 +1,22 @@
-use pulldown_cmark::{html, Event, Options, Parser, Tag};
+use crate::services::project_service::ProjectService;
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use pulldown_cmark::escape::{escape_href, escape_html};
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use syntect::highlighting::ThemeSet;
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+static SYNTAXES: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEMES: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+/// `[[Note Name]]`, `[[Note Name#Heading]]` or `[[Note Name|label]]`
+static WIKI_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\[\[([^\[\]|#]+)(?:#[^\[\]|]*)?(?:\|([^\[\]]+))?\]\]").unwrap()
+});
 
 pub struct MarkdownService;
 
@@ -10,9 +27,49 @@ pub struct TocEntry {
     pub slug: String,
 }
 
+/// Color scheme for highlighted code blocks, following the app's light/dark theme
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CodeTheme {
+    Light,
+    #[default]
+    Dark,
+}
+
+impl CodeTheme {
+    /// Map the app's `theme` setting; anything but "light" (including "system") is dark
+    pub fn from_setting(theme: &str) -> Self {
+        if theme.trim().eq_ignore_ascii_case("light") {
+            Self::Light
+        } else {
+            Self::Dark
+        }
+    }
+
+    /// Name of the bundled syntect theme
+    fn syntect_theme(self) -> &'static str {
+        match self {
+            Self::Light => "InspiredGitHub",
+            Self::Dark => "base16-ocean.dark",
+        }
+    }
+}
+
 impl MarkdownService {
-    /// Render markdown to HTML with advanced features
+    /// Render markdown to HTML with advanced features, highlighting code in the dark theme
     pub fn render_to_html(markdown: &str) -> String {
+        Self::render_to_html_themed(markdown, CodeTheme::default(), None)
+    }
+
+    /// Render markdown to HTML. Fenced code blocks in a known language are highlighted
+    /// with inline styles from `theme`; fenced `mermaid` blocks become
+    /// `<div class="mermaid">` elements for the frontend to draw; other code blocks are
+    /// rendered as plain `<pre>`. With the project's files in `notes`, `[[Note]]` links
+    /// become internal links to the matching file, or broken-link markers.
+    pub fn render_to_html_themed(
+        markdown: &str,
+        theme: CodeTheme,
+        notes: Option<&[String]>,
+    ) -> String {
         let mut options = Options::empty();
         options.insert(Options::ENABLE_TABLES);
         options.insert(Options::ENABLE_FOOTNOTES);
@@ -22,25 +79,262 @@ impl MarkdownService {
 
         let parser = Parser::new_ext(markdown, options);
         let mut html_output = String::new();
-        html::push_html(&mut html_output, parser);
+        let mut events = Self::code_blocks(parser, theme);
+        if let Some(notes) = notes {
+            events = Self::wiki_links(events, notes);
+        }
+        html::push_html(&mut html_output, events.into_iter());
 
         html_output
     }
 
-    /// Extract all links from markdown
+    /// Replace each fenced code block that is mermaid or in a known language with a
+    /// single HTML event. Unknown languages keep their original events.
+    fn code_blocks<'a>(
+        events: impl Iterator<Item = Event<'a>>,
+        theme: CodeTheme,
+    ) -> Vec<Event<'a>> {
+        let mut output = Vec::new();
+        // Info string and source of the fenced block being collected
+        let mut block: Option<(CowStr<'a>, String)> = None;
+
+        for event in events {
+            if let Some((_, source)) = block.as_mut() {
+                match event {
+                    Event::Text(text) => source.push_str(&text),
+                    Event::End(Tag::CodeBlock(kind)) => {
+                        let Some((info, source)) = block.take() else {
+                            continue;
+                        };
+                        match Self::render_code_block(&info, &source, theme) {
+                            Some(html) => output.push(Event::Html(CowStr::from(html))),
+                            None => {
+                                output.push(Event::Start(Tag::CodeBlock(kind.clone())));
+                                output.push(Event::Text(CowStr::from(source)));
+                                output.push(Event::End(Tag::CodeBlock(kind)));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            match event {
+                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                    block = Some((info, String::new()));
+                }
+                event => output.push(event),
+            }
+        }
+
+        output
+    }
+
+    /// HTML for a fenced block, or `None` when its language isn't recognised
+    fn render_code_block(info: &str, source: &str, theme: CodeTheme) -> Option<String> {
+        let lang = info.split_whitespace().next()?;
+        if lang.eq_ignore_ascii_case("mermaid") {
+            let mut html = String::from("<div class=\"mermaid\">");
+            // Writing to a String can't fail
+            let _ = escape_html(&mut html, source);
+            html.push_str("</div>\n");
+            return Some(html);
+        }
+
+        let syntax = SYNTAXES.find_syntax_by_token(lang)?;
+        let theme = THEMES.themes.get(theme.syntect_theme())?;
+        highlighted_html_for_string(source, &SYNTAXES, syntax, theme)
+            .map_err(|e| log::warn!("Failed to highlight {} code block: {}", lang, e))
+            .ok()
+    }
+
+    /// Replace `[[Note]]` links in text outside code with internal links to the matching
+    /// file in `notes`, or with a broken-link marker when none matches
+    fn wiki_links<'a>(events: Vec<Event<'a>>, notes: &[String]) -> Vec<Event<'a>> {
+        let mut output = Vec::new();
+        // Adjacent text events are joined since the parser may split text at brackets
+        let mut text = String::new();
+        let mut in_code = false;
+
+        for event in events {
+            if let Event::Text(chunk) = &event {
+                if !in_code {
+                    text.push_str(chunk);
+                    continue;
+                }
+            }
+            Self::push_wiki_text(&mut output, std::mem::take(&mut text), notes);
+            match &event {
+                Event::Start(Tag::CodeBlock(_)) => in_code = true,
+                Event::End(Tag::CodeBlock(_)) => in_code = false,
+                _ => {}
+            }
+            output.push(event);
+        }
+        Self::push_wiki_text(&mut output, text, notes);
+
+        output
+    }
+
+    fn push_wiki_text<'a>(output: &mut Vec<Event<'a>>, text: String, notes: &[String]) {
+        let mut last = 0;
+        for link in WIKI_LINK.captures_iter(&text) {
+            let (Some(whole), Some(name)) = (link.get(0), link.get(1)) else {
+                continue;
+            };
+            let name = name.as_str().trim();
+            let label = link.get(2).map_or(name, |label| label.as_str().trim());
+
+            let mut html = String::new();
+            // Writing to a String can't fail
+            match Self::resolve_wiki_link(name, notes) {
+                Some(file) => {
+                    html.push_str("<a class=\"wiki-link\" href=\"");
+                    let _ = escape_href(&mut html, &file);
+                    html.push_str("\" data-file=\"");
+                    let _ = escape_html(&mut html, &file);
+                    html.push_str("\">");
+                    let _ = escape_html(&mut html, label);
+                    html.push_str("</a>");
+                }
+                None => {
+                    html.push_str("<span class=\"wiki-link wiki-link-broken\" data-note=\"");
+                    let _ = escape_html(&mut html, name);
+                    html.push_str("\">");
+                    let _ = escape_html(&mut html, label);
+                    html.push_str("</span>");
+                }
+            }
+
+            if whole.start() > last {
+                output.push(Event::Text(CowStr::from(text[last..whole.start()].to_string())));
+            }
+            output.push(Event::Html(CowStr::from(html)));
+            last = whole.end();
+        }
+        if last < text.len() {
+            output.push(Event::Text(CowStr::from(text[last..].to_string())));
+        }
+    }
+
+    /// The markdown file in `notes` a `[[name]]` link points to: the file at that path
+    /// (with or without `.md`), else the first file with that name in any folder.
+    /// Matching ignores case.
+    pub fn resolve_wiki_link(name: &str, notes: &[String]) -> Option<String> {
+        let name = name.trim().trim_start_matches("./").to_lowercase();
+        let with_ext = format!("{}.md", name);
+        let markdown = notes.iter().filter(|f| f.to_lowercase().ends_with(".md"));
+
+        markdown
+            .clone()
+            .find(|f| f.to_lowercase() == name || f.to_lowercase() == with_ext)
+            .or_else(|| {
+                markdown.clone().find(|f| {
+                    let file_name = f.rsplit('/').next().unwrap_or(f).to_lowercase();
+                    file_name == with_ext
+                })
+            })
+            .cloned()
+    }
+
+    /// Extract all links from markdown, including the names in `[[Note]]` links
     pub fn extract_links(markdown: &str) -> Vec<String> {
-        let parser = Parser::new(markdown);
+        Self::collect_links(markdown, None)
+    }
+
+    /// Like `extract_links`, with `[[Note]]` links resolved to their file in `notes`.
+    /// Links that match no file are returned as written.
+    pub fn extract_project_links(markdown: &str, notes: &[String]) -> Vec<String> {
+        Self::collect_links(markdown, Some(notes))
+    }
+
+    fn collect_links(markdown: &str, notes: Option<&[String]>) -> Vec<String> {
         let mut links = Vec::new();
+        let mut text = String::new();
+        let mut in_code = false;
 
-        for event in parser {
-            if let Event::Start(Tag::Link(_, dest_url, _)) = event {
-                links.push(dest_url.to_string());
+        let push_wiki_links = |text: &mut String, links: &mut Vec<String>| {
+            for link in WIKI_LINK.captures_iter(text.as_str()) {
+                let name = link[1].trim();
+                let resolved = notes.and_then(|notes| Self::resolve_wiki_link(name, notes));
+                links.push(resolved.unwrap_or_else(|| name.to_string()));
+            }
+            text.clear();
+        };
+
+        for event in Parser::new(markdown) {
+            if let Event::Text(chunk) = &event {
+                if !in_code {
+                    text.push_str(chunk);
+                    continue;
+                }
+            }
+            push_wiki_links(&mut text, &mut links);
+            match event {
+                Event::Start(Tag::CodeBlock(_)) => in_code = true,
+                Event::End(Tag::CodeBlock(_)) => in_code = false,
+                Event::Start(Tag::Link(_, dest_url, _)) => links.push(dest_url.to_string()),
+                _ => {}
             }
         }
+        push_wiki_links(&mut text, &mut links);
 
         links
     }
 
+    /// Markdown files in the project, for resolving `[[Note]]` links
+    pub fn project_notes(project_id: &str) -> Result<Vec<String>> {
+        let (_, files) = ProjectService::project_files(project_id)?;
+        Ok(Self::notes_only(files))
+    }
+
+    fn notes_only(files: Vec<String>) -> Vec<String> {
+        files.into_iter().filter(|f| f.to_lowercase().ends_with(".md")).collect()
+    }
+
+    /// Markdown files in the project that link to `file_name`, with `[[Note]]` or regular
+    /// relative links, sorted by path
+    pub fn get_backlinks(project_id: &str, file_name: &str) -> Result<Vec<String>> {
+        let (project_path, files) = ProjectService::project_files(project_id)?;
+        Ok(Self::backlinks_in(&project_path, &Self::notes_only(files), file_name))
+    }
+
+    fn backlinks_in(project_path: &Path, notes: &[String], file_name: &str) -> Vec<String> {
+        let target = file_name.trim().trim_start_matches("./");
+        notes
+            .iter()
+            .filter(|note| note.as_str() != target)
+            .filter(|note| {
+                let Ok(content) = fs::read_to_string(project_path.join(note)) else {
+                    return false;
+                };
+                let folder = note.rsplit_once('/').map_or("", |(folder, _)| folder);
+                Self::extract_project_links(&content, notes).iter().any(|link| {
+                    let link = link.split('#').next().unwrap_or_default();
+                    let link = urlencoding::decode(link).map_or(link.into(), |l| l);
+                    // Resolved wiki links are project paths, regular links are relative
+                    link == target || Self::join_relative(folder, &link) == target
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// `link` relative to `folder`, with `.` and `..` segments resolved
+    fn join_relative(folder: &str, link: &str) -> String {
+        let mut parts: Vec<&str> = folder.split('/').filter(|p| !p.is_empty()).collect();
+        for part in link.split('/') {
+            match part {
+                "" | "." => {}
+                ".." => {
+                    parts.pop();
+                }
+                part => parts.push(part),
+            }
+        }
+        parts.join("/")
+    }
+
     /// Generate table of contents from markdown headings
     pub fn generate_toc(markdown: &str) -> Vec<TocEntry> {
         let parser = Parser::new(markdown);
@@ -147,6 +441,91 @@ mod tests {
         assert!(html.contains("<del>strikethrough</del>"));
     }
 
+    #[test]
+    fn test_render_mermaid_blocks() {
+        let markdown = "```mermaid\ngraph TD\n  A[Collect] --> B[Clean]\n```";
+        let html = MarkdownService::render_to_html(markdown);
+        assert!(html.contains(
+            "<div class=\"mermaid\">graph TD\n  A[Collect] --&gt; B[Clean]\n</div>"
+        ));
+        assert!(!html.contains("language-mermaid"));
+    }
+
+    #[test]
+    fn test_render_highlights_known_languages() {
+        let markdown = "```rust\nfn main() {}\n```\n\n```klingon\nQapla\n```";
+        let dark = MarkdownService::render_to_html_themed(markdown, CodeTheme::Dark, None);
+        assert!(dark.contains("<span style="));
+        assert!(!dark.contains("language-rust"));
+        // Unknown languages fall back to a plain block
+        assert!(dark.contains("<pre><code class=\"language-klingon\">Qapla\n</code></pre>"));
+
+        let light = MarkdownService::render_to_html_themed(markdown, CodeTheme::Light, None);
+        assert_ne!(dark, light);
+        assert_eq!(CodeTheme::from_setting("Light"), CodeTheme::Light);
+        assert_eq!(CodeTheme::from_setting("system"), CodeTheme::Dark);
+    }
+
+    #[test]
+    fn test_wiki_links() {
+        let notes = vec![
+            "README.md".to_string(),
+            "data/results.csv".to_string(),
+            "experiments/Pipeline Design.md".to_string(),
+            "pipeline design.md".to_string(),
+        ];
+        let resolve = |name| MarkdownService::resolve_wiki_link(name, &notes);
+        assert_eq!(resolve("Pipeline Design").as_deref(), Some("pipeline design.md"));
+        assert_eq!(
+            resolve("experiments/pipeline design").as_deref(),
+            Some("experiments/Pipeline Design.md")
+        );
+        assert_eq!(resolve("readme.md").as_deref(), Some("README.md"));
+        assert_eq!(resolve("results"), None);
+
+        let markdown = "See [[README|the intro]] and [[Missing Note]].\n\n`[[README]]`";
+        let html =
+            MarkdownService::render_to_html_themed(markdown, CodeTheme::Dark, Some(&notes[..]));
+        assert!(html.contains(
+            "<a class=\"wiki-link\" href=\"README.md\" data-file=\"README.md\">the intro</a>"
+        ));
+        assert!(html.contains(
+            "<span class=\"wiki-link wiki-link-broken\" data-note=\"Missing Note\">\
+             Missing Note</span>."
+        ));
+        assert!(html.contains("<code>[[README]]</code>"));
+
+        let links = MarkdownService::extract_project_links(
+            "[[Pipeline Design#Setup]] then [site](https://example.com) and [[Gone]]",
+            &notes,
+        );
+        assert_eq!(links, vec!["pipeline design.md", "https://example.com", "Gone"]);
+    }
+
+    #[test]
+    fn test_backlinks() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("notes")).unwrap();
+        fs::write(temp.path().join("a.md"), "Builds on [[Target]].").unwrap();
+        fs::write(temp.path().join("notes").join("b.md"), "[Target](../target.md)").unwrap();
+        fs::write(temp.path().join("notes").join("c.md"), "[Same folder](d.md)").unwrap();
+        fs::write(temp.path().join("notes").join("d.md"), "No links").unwrap();
+        fs::write(temp.path().join("target.md"), "Links to [[target]] itself").unwrap();
+        let notes: Vec<String> = ["a.md", "notes/b.md", "notes/c.md", "notes/d.md", "target.md"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+
+        assert_eq!(
+            MarkdownService::backlinks_in(temp.path(), &notes, "target.md"),
+            vec!["a.md", "notes/b.md"]
+        );
+        assert_eq!(
+            MarkdownService::backlinks_in(temp.path(), &notes, "notes/d.md"),
+            vec!["notes/c.md"]
+        );
+    }
+
     #[test]
     fn test_render_with_task_lists() {
         let markdown = "- [ ] Task 1\n- [x] Task 2";
@@ -0,0 +1,130 @@
+//! MCP Marketplace Cache - The merged marketplace listing, persisted to disk
+//!
+//! Building the listing takes a request to mcpmarket.com plus several pages of the official
+//! registry. The unfiltered result is written to `{APP_DATA}/cache/mcp_marketplace.json`
+//! and served while it is younger than `CACHE_TTL`; older copies are still used when the
+//! network is unreachable, so the marketplace keeps working offline.
+
+use crate::models::mcp::McpServerConfig;
+use crate::utils::paths;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CACHE_TTL: chrono::Duration = chrono::Duration::hours(6);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedListing {
+    fetched_at: DateTime<Utc>,
+    servers: Vec<McpServerConfig>,
+}
+
+pub struct McpMarketplaceCache;
+
+impl McpMarketplaceCache {
+    /// The cached listing, if it was fetched within `CACHE_TTL`
+    pub fn load_fresh() -> Option<Vec<McpServerConfig>> {
+        let listing = Self::read_from(&Self::cache_path().ok()?)?;
+        Self::is_fresh(listing.fetched_at, Utc::now()).then_some(listing.servers)
+    }
+
+    /// The cached listing regardless of age, for when the network is unavailable
+    pub fn load_any() -> Option<Vec<McpServerConfig>> {
+        Self::read_from(&Self::cache_path().ok()?).map(|listing| listing.servers)
+    }
+
+    pub fn store(servers: &[McpServerConfig]) -> Result<()> {
+        Self::write_to(&Self::cache_path()?, servers, Utc::now())
+    }
+
+    fn cache_path() -> Result<PathBuf> {
+        Ok(paths::get_cache_dir()?.join("mcp_marketplace.json"))
+    }
+
+    fn is_fresh(fetched_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(fetched_at) < CACHE_TTL
+    }
+
+    fn read_from(path: &Path) -> Option<CachedListing> {
+        let content = fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(listing) => Some(listing),
+            Err(e) => {
+                log::warn!("Ignoring unreadable MCP marketplace cache {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    fn write_to(path: &Path, servers: &[McpServerConfig], fetched_at: DateTime<Utc>) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create cache directory: {:?}", dir))?;
+        }
+        let listing = CachedListing {
+            fetched_at,
+            servers: servers.to_vec(),
+        };
+        fs::write(path, serde_json::to_string(&listing)?)
+            .with_context(|| format!("Failed to write MCP marketplace cache: {:?}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str) -> McpServerConfig {
+        McpServerConfig {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), format!("@test/{}", id)],
+            env: None,
+            secrets_env: None,
+            enabled: false,
+            stars: None,
+            author: None,
+            source: Some("registry".to_string()),
+            categories: None,
+            icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_keeps_servers_and_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("mcp_marketplace.json");
+        let fetched_at = Utc::now() - chrono::Duration::hours(1);
+
+        McpMarketplaceCache::write_to(&path, &[server("github"), server("jira")], fetched_at)
+            .unwrap();
+        let listing = McpMarketplaceCache::read_from(&path).unwrap();
+
+        assert_eq!(listing.fetched_at, fetched_at);
+        let ids: Vec<_> = listing.servers.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["github", "jira"]);
+    }
+
+    #[test]
+    fn test_freshness_window() {
+        let now = Utc::now();
+        assert!(McpMarketplaceCache::is_fresh(now - chrono::Duration::hours(5), now));
+        assert!(!McpMarketplaceCache::is_fresh(now - chrono::Duration::hours(7), now));
+    }
+
+    #[test]
+    fn test_corrupt_cache_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mcp_marketplace.json");
+        fs::write(&path, "{ not json").unwrap();
+        assert!(McpMarketplaceCache::read_from(&path).is_none());
+    }
+}
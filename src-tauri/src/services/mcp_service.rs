@@ -1,20 +1,34 @@
-use crate::models::mcp::McpServerConfig;
+use crate::models::mcp::{McpServerConfig, McpServerTestResult, McpToolFilter};
+use crate::services::project_service::ProjectService;
 use crate::services::secrets_service::SecretsService;
 use crate::services::settings_service::SettingsService;
 use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
 use std::process::Stdio;
+use std::sync::Mutex;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
+use tokio_util::sync::CancellationToken;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpTool {
     pub name: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(alias = "inputSchema", default)]
     pub input_schema: Value,
 }
 
+/// `tools/list` results per server id, keyed by a fingerprint of the launch config so that
+/// editing a server's command, args or env invalidates its entry
+static TOOL_CACHE: Lazy<Mutex<HashMap<String, (u64, Vec<McpTool>)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ToolCallResponse {
     pub content: Vec<Value>,
@@ -22,6 +36,15 @@ pub struct ToolCallResponse {
     pub is_error: bool,
 }
 
+/// A tool call that was abandoned rather than answered
+#[derive(Debug, thiserror::Error)]
+pub enum McpCallError {
+    #[error("MCP tool '{tool}' timed out after {seconds}s")]
+    TimedOut { tool: String, seconds: u64 },
+    #[error("MCP tool '{tool}' was cancelled")]
+    Cancelled { tool: String },
+}
+
 pub struct McpServer {
     pub config: McpServerConfig,
     child: Option<Child>,
@@ -70,8 +93,40 @@ impl McpService {
     }
 
     async fn get_server_tools(&self, config: &McpServerConfig) -> Result<Vec<McpTool>> {
-        let mut server = self.start_server(config).await?;
+        let tools = self.list_server_tools(config, false).await?;
+
+        // Prefix tool names with server ID to avoid collisions, dropping tools the
+        // server's allow/block lists exclude
+        let prefixed_tools = tools
+            .into_iter()
+            .filter(|t| config.permits_tool(&t.name))
+            .map(|mut t| {
+                t.name = format!("{}__{}", config.id, t.name);
+                t
+            })
+            .collect();
+
+        Ok(prefixed_tools)
+    }
+
+    /// Tools exposed by a single server, with their original (unprefixed) names.
+    /// Served from cache unless the server's launch config changed or `refresh` is set.
+    pub async fn list_server_tools(
+        &self,
+        config: &McpServerConfig,
+        refresh: bool,
+    ) -> Result<Vec<McpTool>> {
+        let fingerprint = Self::config_fingerprint(config);
+        if !refresh {
+            let cache = TOOL_CACHE.lock().unwrap_or_else(|p| p.into_inner());
+            if let Some((cached_fingerprint, tools)) = cache.get(&config.id) {
+                if *cached_fingerprint == fingerprint {
+                    return Ok(tools.clone());
+                }
+            }
+        }
 
+        let mut server = self.start_server(config).await?;
         let response = self
             .call_json_rpc(&mut server, "tools/list", json!({}))
             .await?;
@@ -81,19 +136,108 @@ impl McpService {
             .ok_or_else(|| anyhow!("No tools in response"))?;
         let tools: Vec<McpTool> = serde_json::from_value(tools_val.clone())?;
 
-        // Prefix tool names with server ID to avoid collisions
-        let prefixed_tools = tools
-            .into_iter()
-            .map(|mut t| {
-                t.name = format!("{}__{}", config.id, t.name);
-                t
+        TOOL_CACHE
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(config.id.clone(), (fingerprint, tools.clone()));
+
+        Ok(tools)
+    }
+
+    /// Start the server, run the initialize handshake and list its tools, giving up
+    /// after `timeout`. Failures are reported in the result rather than as errors.
+    pub async fn test_server(
+        &self,
+        config: &McpServerConfig,
+        timeout: std::time::Duration,
+    ) -> McpServerTestResult {
+        match tokio::time::timeout(timeout, self.list_server_tools(config, true)).await {
+            Ok(Ok(tools)) => McpServerTestResult {
+                ok: true,
+                tools: tools.into_iter().map(|t| t.name).collect(),
+                error: None,
+            },
+            Ok(Err(e)) => McpServerTestResult::failed(e.to_string()),
+            Err(_) => McpServerTestResult::failed(format!(
+                "MCP server '{}' did not respond within {}s",
+                config.name,
+                timeout.as_secs()
+            )),
+        }
+    }
+
+    /// Environment variables the server would start without: `secrets_env` entries whose
+    /// secret is unset or empty, and `env` entries left blank. Sorted by name.
+    pub fn missing_credentials(config: &McpServerConfig) -> Vec<String> {
+        Self::missing_credentials_with(config, |secret_id| {
+            SecretsService::get_secret(secret_id).ok().flatten()
+        })
+    }
+
+    fn missing_credentials_with(
+        config: &McpServerConfig,
+        resolve_secret: impl Fn(&str) -> Option<String>,
+    ) -> Vec<String> {
+        let blank_env = config
+            .env
+            .iter()
+            .flatten()
+            .filter(|(_, value)| value.trim().is_empty())
+            .map(|(key, _)| key.clone());
+        let unresolved_secrets = config
+            .secrets_env
+            .iter()
+            .flatten()
+            .filter(|(_, secret_id)| {
+                resolve_secret(secret_id).map_or(true, |value| value.trim().is_empty())
             })
-            .collect();
+            .map(|(key, _)| key.clone());
 
-        Ok(prefixed_tools)
+        let mut missing: Vec<String> = blank_env.chain(unresolved_secrets).collect();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+
+    /// The tool filter configured in a project's settings, if any
+    pub fn project_tool_filter(project_id: &str) -> Option<McpToolFilter> {
+        let project_path = ProjectService::resolve_project_path(project_id).ok()?;
+        SettingsService::load_project_settings(&project_path)
+            .ok()
+            .flatten()?
+            .tool_filter
     }
 
-    pub async fn call_tool(&self, tool_name: &str, arguments: Value) -> Result<Value> {
+    /// Drop the cached tool list for a server (e.g. after it was removed)
+    pub fn invalidate_tool_cache(server_id: &str) {
+        TOOL_CACHE
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .remove(server_id);
+    }
+
+    /// Hash of everything that affects which process gets launched
+    fn config_fingerprint(config: &McpServerConfig) -> u64 {
+        let sorted = |map: &Option<HashMap<String, String>>| -> BTreeMap<String, String> {
+            map.clone().unwrap_or_default().into_iter().collect()
+        };
+
+        let mut hasher = DefaultHasher::new();
+        config.command.hash(&mut hasher);
+        config.args.hash(&mut hasher);
+        sorted(&config.env).hash(&mut hasher);
+        sorted(&config.secrets_env).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Run a namespaced `server__tool` call. Gives up with `McpCallError` when the
+    /// server's tool timeout elapses or `cancel` fires, killing the server process.
+    pub async fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        cancel: &CancellationToken,
+    ) -> Result<Value> {
         let parts: Vec<&str> = tool_name.split("__").collect();
         if parts.len() < 2 {
             return Err(anyhow!("Invalid tool name format: {}", tool_name));
@@ -109,20 +253,55 @@ impl McpService {
             .find(|s| s.id == server_id)
             .ok_or_else(|| anyhow!("MCP server {} not found", server_id))?;
 
-        let mut server = self.start_server(config).await?;
+        // The model only sees permitted tools, but don't trust it not to name others
+        if !config.permits_tool(&original_tool_name) {
+            return Err(anyhow!(
+                "Tool '{}' is blocked in the settings of MCP server '{}'",
+                original_tool_name,
+                config.name
+            ));
+        }
 
-        let response = self
-            .call_json_rpc(
+        self.call_server_tool(config, &original_tool_name, arguments, cancel)
+            .await
+    }
+
+    async fn call_server_tool(
+        &self,
+        config: &McpServerConfig,
+        tool_name: &str,
+        arguments: Value,
+        cancel: &CancellationToken,
+    ) -> Result<Value> {
+        let call = async {
+            let mut server = self.start_server(config).await?;
+            self.call_json_rpc(
                 &mut server,
                 "tools/call",
                 json!({
-                    "name": original_tool_name,
+                    "name": tool_name,
                     "arguments": arguments
                 }),
             )
-            .await?;
+            .await
+        };
 
-        Ok(response)
+        // Dropping `call` drops the child, and `kill_on_drop` stops the wedged server
+        let timeout = config.tool_timeout();
+        tokio::select! {
+            result = tokio::time::timeout(timeout, call) => match result {
+                Ok(response) => response,
+                Err(_) => Err(McpCallError::TimedOut {
+                    tool: tool_name.to_string(),
+                    seconds: timeout.as_secs(),
+                }
+                .into()),
+            },
+            _ = cancel.cancelled() => Err(McpCallError::Cancelled {
+                tool: tool_name.to_string(),
+            }
+            .into()),
+        }
     }
 
     async fn start_server(&self, config: &McpServerConfig) -> Result<McpServer> {
@@ -130,11 +309,12 @@ impl McpService {
         // In the future, we could keep processes alive
 
         let mut command = Command::new(&config.command);
-        command.args(&config.args);
+        command.args(config.launch_args());
         command
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
 
         // Setup environment
         if let Some(env) = &config.env {
@@ -293,3 +473,141 @@ impl McpService {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> McpServerConfig {
+        McpServerConfig {
+            id: "test-server".to_string(),
+            name: "Test".to_string(),
+            description: None,
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "@test/server".to_string()],
+            env: Some(HashMap::from([
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "2".to_string()),
+            ])),
+            secrets_env: None,
+            enabled: false,
+            stars: None,
+            author: None,
+            source: Some("registry".to_string()),
+            categories: None,
+            icon_url: None,
+            tool_timeout_secs: None,
+            allowed_tools: None,
+            blocked_tools: None,
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_metadata_but_tracks_launch_config() {
+        let base = config();
+
+        let mut toggled = base.clone();
+        toggled.enabled = true;
+        toggled.description = Some("changed".to_string());
+        assert_eq!(
+            McpService::config_fingerprint(&base),
+            McpService::config_fingerprint(&toggled)
+        );
+
+        let mut new_args = base.clone();
+        new_args.args.push("--verbose".to_string());
+        assert_ne!(
+            McpService::config_fingerprint(&base),
+            McpService::config_fingerprint(&new_args)
+        );
+
+        let mut new_env = base.clone();
+        new_env.env.as_mut().unwrap().insert("A".to_string(), "3".to_string());
+        assert_ne!(
+            McpService::config_fingerprint(&base),
+            McpService::config_fingerprint(&new_env)
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hung_tool_call_times_out_or_cancels() {
+        // A "server" that reads requests but never answers
+        let mut hung = config();
+        hung.command = "sh".to_string();
+        hung.args = vec!["-c".to_string(), "sleep 30".to_string()];
+        hung.env = None;
+        hung.tool_timeout_secs = Some(1);
+
+        let service = McpService::new();
+        let started = std::time::Instant::now();
+        let err = service
+            .call_server_tool(&hung, "search", json!({}), &CancellationToken::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<McpCallError>(), Some(McpCallError::TimedOut { seconds: 1, .. })));
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let err = service
+            .call_server_tool(&hung, "search", json!({}), &cancel)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.downcast_ref::<McpCallError>(), Some(McpCallError::Cancelled { .. })));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_server_test_reports_failures() {
+        let service = McpService::new();
+
+        let mut missing = config();
+        missing.command = "definitely-not-an-mcp-server".to_string();
+        let result = service.test_server(&missing, std::time::Duration::from_secs(5)).await;
+        assert!(!result.ok);
+        assert!(result.error.unwrap().contains("Failed to spawn"));
+
+        let mut hung = config();
+        hung.command = "sh".to_string();
+        hung.args = vec!["-c".to_string(), "sleep 30".to_string()];
+        hung.env = None;
+        let result = service.test_server(&hung, std::time::Duration::from_secs(1)).await;
+        assert!(!result.ok);
+        assert!(result.error.unwrap().contains("did not respond within 1s"));
+    }
+
+    #[test]
+    fn test_missing_credentials_lists_blank_env_and_unset_secrets() {
+        let mut server = config();
+        server.env = Some(HashMap::from([
+            ("JIRA_DOMAIN".to_string(), "acme.atlassian.net".to_string()),
+            ("JIRA_EMAIL".to_string(), "  ".to_string()),
+        ]));
+        server.secrets_env = Some(HashMap::from([
+            ("JIRA_API_TOKEN".to_string(), "jira_api_key".to_string()),
+            ("GITHUB_TOKEN".to_string(), "github_token".to_string()),
+        ]));
+
+        let resolve = |id: &str| (id == "jira_api_key").then(|| "secret".to_string());
+        assert_eq!(
+            McpService::missing_credentials_with(&server, resolve),
+            vec!["GITHUB_TOKEN", "JIRA_EMAIL"]
+        );
+
+        let all_set = |_: &str| Some("secret".to_string());
+        server.env = None;
+        assert!(McpService::missing_credentials_with(&server, all_set).is_empty());
+    }
+
+    #[test]
+    fn test_tool_schema_accepts_mcp_field_names() {
+        let tool: McpTool = serde_json::from_value(json!({
+            "name": "search",
+            "inputSchema": {"type": "object", "properties": {"query": {"type": "string"}}}
+        }))
+        .unwrap();
+        assert_eq!(tool.description, "");
+        assert_eq!(tool.input_schema["properties"]["query"]["type"], "string");
+    }
+}
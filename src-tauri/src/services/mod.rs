@@ -4,7 +4,9 @@ pub mod agent_orchestrator;
 pub mod cancellation_service;
 pub mod ai_provider;
 pub mod ai_service;
+pub mod api_key_pool;
 pub mod artifact_service;
+pub mod backup_service;
 pub mod chat_service;
 pub mod claude_service;
 pub mod cli_config_service;
@@ -12,17 +14,30 @@ pub mod context_service;
 pub mod encryption_service;
 pub mod file_service;
 pub mod file_watcher;
+pub mod global_search_service;
+pub mod health_service;
 pub mod markdown_service;
+pub mod mcp_marketplace_cache;
 pub mod mcp_service;
+pub mod model_registry;
+pub mod onboarding_service;
 pub mod output_cleaner_service;
 pub mod output_parser_service;
 pub mod pm_skills;
+pub mod project_archive_service;
+pub mod project_maintenance_service;
 pub mod project_service;
+pub mod project_validation_service;
+pub mod proposed_changes_service;
 pub mod providers;
 pub mod research_log_service;
+pub mod research_record_service;
+pub mod search_replace_service;
 pub mod secrets_service;
 pub mod settings_service;
+pub mod tool_approval_service;
 pub mod openai_oauth;
+pub mod skill_marketplace_service;
 pub mod skill_service;
 pub mod background_workflow_service;
 pub mod workflow_scheduler_service;
@@ -0,0 +1,249 @@
+//! Model Registry - Canonical model ids per provider
+//!
+//! Model names typed into settings drift from what the APIs accept: `claude-sonnet-4.5`
+//! instead of `claude-sonnet-4-5`, odd casing, `anthropic/` prefixes, small typos.
+//! `resolve` maps those onto a known id. Names from the provider's model family that
+//! aren't listed pass through with a warning, since new models ship faster than this
+//! table; anything else is rejected with the list of valid ids.
+//!
+//! Providers whose model set is open (Ollama, LiteLLM, custom CLIs) are not checked.
+
+use crate::models::ai::ProviderType;
+use crate::services::ai_provider::AIProvider;
+use anyhow::Result;
+
+/// Largest edit distance treated as a typo of a known model
+const MAX_TYPO_DISTANCE: usize = 2;
+
+pub struct KnownModel {
+    pub id: &'static str,
+    pub aliases: &'static [&'static str],
+}
+
+const fn model(id: &'static str, aliases: &'static [&'static str]) -> KnownModel {
+    KnownModel { id, aliases }
+}
+
+const ANTHROPIC_MODELS: &[KnownModel] = &[
+    model("claude-opus-4-1-20250805", &["claude-opus-4-1"]),
+    model("claude-opus-4-20250514", &["claude-opus-4-0", "claude-opus-4"]),
+    model("claude-sonnet-4-5-20250929", &["claude-sonnet-4-5"]),
+    model("claude-sonnet-4-20250514", &["claude-sonnet-4-0", "claude-sonnet-4"]),
+    model("claude-haiku-4-5-20251001", &["claude-haiku-4-5"]),
+    model("claude-3-7-sonnet-20250219", &["claude-3-7-sonnet-latest", "claude-3-7-sonnet"]),
+    model("claude-3-5-sonnet-20241022", &["claude-3-5-sonnet-latest", "claude-3-5-sonnet"]),
+    model("claude-3-5-haiku-20241022", &["claude-3-5-haiku-latest", "claude-3-5-haiku"]),
+    model("claude-3-opus-20240229", &["claude-3-opus-latest", "claude-3-opus"]),
+    model("claude-3-sonnet-20240229", &["claude-3-sonnet"]),
+    model("claude-3-haiku-20240307", &["claude-3-haiku"]),
+];
+
+const GEMINI_MODELS: &[KnownModel] = &[
+    model("auto", &["default"]),
+    model("gemini-2.5-pro", &["pro"]),
+    model("gemini-2.5-flash", &["flash"]),
+    model("gemini-2.5-flash-lite", &["flash-lite"]),
+    model("gemini-2.0-flash", &[]),
+    model("gemini-1.5-pro", &[]),
+    model("gemini-1.5-flash", &[]),
+];
+
+const OPENAI_MODELS: &[KnownModel] = &[
+    model("auto", &["default"]),
+    model("gpt-5", &[]),
+    model("gpt-5-codex", &[]),
+    model("gpt-5-mini", &[]),
+    model("gpt-4.1", &[]),
+    model("gpt-4.1-mini", &[]),
+    model("gpt-4o", &[]),
+    model("o3", &[]),
+    model("o4-mini", &[]),
+];
+
+/// Outcome of checking a configured model name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelResolution {
+    /// Already a canonical id, or the provider accepts any model name
+    Known(String),
+    /// An alias, spelling variant or typo of `model`
+    Normalized { requested: String, model: String },
+    /// Looks like the provider's model family but isn't listed; passed through as-is
+    Unknown(String),
+}
+
+impl ModelResolution {
+    pub fn model(&self) -> &str {
+        match self {
+            Self::Known(model) | Self::Unknown(model) => model,
+            Self::Normalized { model, .. } => model,
+        }
+    }
+}
+
+pub struct ModelRegistry;
+
+impl ModelRegistry {
+    /// Known models for a provider, or `None` when it accepts arbitrary model names
+    pub fn known_models(provider: &ProviderType) -> Option<&'static [KnownModel]> {
+        match provider {
+            ProviderType::HostedApi | ProviderType::AutoRouter => Some(ANTHROPIC_MODELS),
+            ProviderType::GeminiCli => Some(GEMINI_MODELS),
+            ProviderType::OpenAiCli => Some(OPENAI_MODELS),
+            _ => None,
+        }
+    }
+
+    pub fn resolve(provider: &ProviderType, model: &str) -> Result<ModelResolution> {
+        let requested = model.trim();
+        if requested.is_empty() {
+            anyhow::bail!("No model is configured for {:?}", provider);
+        }
+        let Some(models) = Self::known_models(provider) else {
+            return Ok(ModelResolution::Known(requested.to_string()));
+        };
+        if models.iter().any(|m| m.id == requested) {
+            return Ok(ModelResolution::Known(requested.to_string()));
+        }
+
+        let key = Self::key(requested);
+        let normalized = |id: &str| ModelResolution::Normalized {
+            requested: requested.to_string(),
+            model: id.to_string(),
+        };
+        if let Some(m) = models
+            .iter()
+            .find(|m| Self::key(m.id) == key || m.aliases.iter().any(|a| Self::key(a) == key))
+        {
+            return Ok(normalized(m.id));
+        }
+        if let Some(m) = Self::closest(models, &key) {
+            return Ok(normalized(m.id));
+        }
+        if Self::in_family(provider, &key) {
+            return Ok(ModelResolution::Unknown(requested.to_string()));
+        }
+
+        let valid: Vec<&str> = models.iter().map(|m| m.id).collect();
+        anyhow::bail!(
+            "Unknown model '{}' for {:?}. Valid models: {}",
+            requested,
+            provider,
+            valid.join(", ")
+        )
+    }
+
+    /// The model to configure the provider with. Aliases and typos are replaced with a
+    /// warning; unresolvable names are kept so `check_provider` can report them per request.
+    pub fn normalize(provider: &ProviderType, model: &str) -> String {
+        match Self::resolve(provider, model) {
+            Ok(ModelResolution::Normalized { requested, model }) => {
+                log::warn!("Using model '{}' for '{}' ({:?})", model, requested, provider);
+                model
+            }
+            Ok(ModelResolution::Unknown(model)) => {
+                log::warn!("Model '{}' is not a known {:?} model; sending it as-is", model, provider);
+                model
+            }
+            Ok(ModelResolution::Known(model)) => model,
+            Err(_) => model.to_string(),
+        }
+    }
+
+    /// Reject a request up front when the provider's model clearly doesn't exist
+    pub async fn check_provider(provider: &dyn AIProvider) -> Result<()> {
+        let model = provider.resolve_model().await;
+        if model.is_empty() {
+            return Ok(());
+        }
+        Self::resolve(&provider.provider_type(), &model).map(|_| ())
+    }
+
+    /// Comparison form: lowercase, no vendor prefix, `.`, `_` and spaces as `-`
+    fn key(model: &str) -> String {
+        let lower = model.trim().to_lowercase();
+        let bare = lower.rsplit('/').next().unwrap_or(&lower);
+        bare.chars()
+            .map(|c| if matches!(c, '.' | '_' | ' ') { '-' } else { c })
+            .collect()
+    }
+
+    /// A known model within typo distance whose version numbers match exactly, so
+    /// `claude-sonet-4-5` is corrected but `claude-opus-4-2` is not turned into 4-1
+    fn closest(models: &'static [KnownModel], key: &str) -> Option<&'static KnownModel> {
+        let digits = |s: &str| s.chars().filter(|c| c.is_ascii_digit()).collect::<String>();
+        let wanted = digits(key);
+        models
+            .iter()
+            .flat_map(|m| std::iter::once(m.id).chain(m.aliases.iter().copied()).map(move |name| (m, name)))
+            .map(|(m, name)| (m, Self::key(name)))
+            .filter(|(_, name)| digits(name) == wanted)
+            .map(|(m, name)| (m, edit_distance(&name, key)))
+            .filter(|(_, distance)| *distance <= MAX_TYPO_DISTANCE)
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(m, _)| m)
+    }
+
+    fn in_family(provider: &ProviderType, key: &str) -> bool {
+        let prefixes: &[&str] = match provider {
+            ProviderType::HostedApi | ProviderType::AutoRouter => &["claude-"],
+            ProviderType::GeminiCli => &["gemini-"],
+            ProviderType::OpenAiCli => &["gpt-", "o1", "o3", "o4", "codex"],
+            _ => &[],
+        };
+        prefixes.iter().any(|p| key.starts_with(p))
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolved(provider: ProviderType, model: &str) -> String {
+        ModelRegistry::resolve(&provider, model).unwrap().model().to_string()
+    }
+
+    #[test]
+    fn test_aliases_and_spelling_variants_normalize() {
+        assert_eq!(resolved(ProviderType::HostedApi, "claude-sonnet-4.5"), "claude-sonnet-4-5-20250929");
+        assert_eq!(resolved(ProviderType::HostedApi, "Claude-3-5-Sonnet"), "claude-3-5-sonnet-20241022");
+        assert_eq!(resolved(ProviderType::HostedApi, "anthropic/claude_3_haiku"), "claude-3-haiku-20240307");
+        assert_eq!(resolved(ProviderType::GeminiCli, "gemini-2-5-pro"), "gemini-2.5-pro");
+        assert_eq!(resolved(ProviderType::OpenAiCli, "GPT-4o"), "gpt-4o");
+    }
+
+    #[test]
+    fn test_typos_are_corrected_only_when_versions_match() {
+        assert_eq!(resolved(ProviderType::HostedApi, "claude-sonet-4-5"), "claude-sonnet-4-5-20250929");
+        assert_eq!(
+            ModelRegistry::resolve(&ProviderType::HostedApi, "claude-opus-4-2").unwrap(),
+            ModelResolution::Unknown("claude-opus-4-2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unrelated_models_are_rejected_with_valid_options() {
+        let err = ModelRegistry::resolve(&ProviderType::HostedApi, "gpt-4o").unwrap_err().to_string();
+        assert!(err.contains("Valid models"));
+        assert!(err.contains("claude-sonnet-4-5-20250929"));
+        assert!(ModelRegistry::resolve(&ProviderType::HostedApi, "  ").is_err());
+    }
+
+    #[test]
+    fn test_open_providers_accept_any_model() {
+        assert_eq!(resolved(ProviderType::Ollama, "llama3.1:8b"), "llama3.1:8b");
+    }
+}
@@ -0,0 +1,224 @@
+//! Onboarding Service - Reports which first-run setup steps are still incomplete
+//!
+//! The checks are deliberately cheap (binary lookups, a saved-secret lookup and a short
+//! Ollama ping) so the UI can call this on every launch and drive a guided setup instead
+//! of letting the first chat fail with a provider error.
+
+use crate::models::ai::ProviderType;
+use crate::models::settings::GlobalSettings;
+use crate::services::ai_service::AIService;
+use crate::services::secrets_service::SecretsService;
+use crate::services::settings_service::SettingsService;
+use anyhow::Result;
+use serde::Serialize;
+use std::time::Duration;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStep {
+    pub id: String,
+    pub title: String,
+    /// What the user should do to complete this step
+    pub action: String,
+    pub complete: bool,
+    /// Required steps block chatting; the others are recommended
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStatus {
+    /// True when every required step is complete
+    pub ready: bool,
+    pub active_provider: ProviderType,
+    pub steps: Vec<OnboardingStep>,
+    /// Incomplete steps, required ones first, in the order they should be done
+    pub next_steps: Vec<OnboardingStep>,
+}
+
+/// Raw results of the environment checks
+#[derive(Debug, Clone, Default)]
+struct OnboardingChecks {
+    provider_ready: bool,
+    /// `None` when the active provider doesn't need an API key
+    api_key_saved: Option<bool>,
+    ollama_running: bool,
+    enabled_mcp_servers: usize,
+}
+
+pub struct OnboardingService;
+
+impl OnboardingService {
+    pub async fn status() -> Result<OnboardingStatus> {
+        let settings = SettingsService::load_global_settings()?;
+        let checks = Self::run_checks(&settings).await;
+        Ok(Self::build_status(&settings.active_provider, &checks))
+    }
+
+    async fn run_checks(settings: &GlobalSettings) -> OnboardingChecks {
+        let ollama_running = Self::ollama_running(&settings.ollama.api_url).await;
+
+        let api_key_saved = match settings.active_provider {
+            ProviderType::HostedApi | ProviderType::AutoRouter => Some(
+                SecretsService::get_secret(&settings.hosted.api_key_secret_id)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|key| !key.is_empty()),
+            ),
+            _ => None,
+        };
+
+        let provider_ready = match settings.active_provider {
+            ProviderType::Ollama => ollama_running,
+            ProviderType::Mock => true,
+            _ => match AIService::create_provider(&settings.active_provider, settings) {
+                Ok(provider) => {
+                    provider.is_available()
+                        && tokio::time::timeout(CHECK_TIMEOUT, provider.check_authentication())
+                            .await
+                            .ok()
+                            .and_then(|r| r.ok())
+                            .unwrap_or(false)
+                }
+                Err(e) => {
+                    log::warn!("Onboarding: failed to create provider: {}", e);
+                    false
+                }
+            },
+        };
+
+        OnboardingChecks {
+            provider_ready,
+            api_key_saved,
+            ollama_running,
+            enabled_mcp_servers: settings.mcp_servers.iter().filter(|s| s.enabled).count(),
+        }
+    }
+
+    async fn ollama_running(api_url: &str) -> bool {
+        let url = format!("{}/api/tags", api_url.trim_end_matches('/'));
+        let client = reqwest::Client::new();
+        matches!(
+            client.get(&url).timeout(CHECK_TIMEOUT).send().await,
+            Ok(res) if res.status().is_success()
+        )
+    }
+
+    fn build_status(active_provider: &ProviderType, checks: &OnboardingChecks) -> OnboardingStatus {
+        let is_ollama = matches!(active_provider, ProviderType::Ollama);
+        let mut steps = Vec::new();
+
+        if let Some(saved) = checks.api_key_saved {
+            steps.push(OnboardingStep {
+                id: "api_key".to_string(),
+                title: "Add an API key".to_string(),
+                action: "Open Settings → AI Configuration and save your Anthropic API key, or switch to a local provider.".to_string(),
+                complete: saved,
+                required: true,
+            });
+        }
+
+        steps.push(OnboardingStep {
+            id: "ollama_running".to_string(),
+            title: "Start Ollama".to_string(),
+            action: "Install Ollama from https://ollama.com and run `ollama serve` to chat with local models.".to_string(),
+            complete: checks.ollama_running,
+            required: is_ollama,
+        });
+
+        steps.push(OnboardingStep {
+            id: "provider_ready".to_string(),
+            title: "Connect an AI provider".to_string(),
+            action: match active_provider {
+                ProviderType::Ollama => "Start Ollama so the selected provider can be reached.".to_string(),
+                ProviderType::HostedApi | ProviderType::AutoRouter => {
+                    "Save a valid API key for the hosted provider.".to_string()
+                }
+                ProviderType::ClaudeCode => {
+                    "Install and sign in to the Claude Code CLI (`claude`), or pick another provider in Settings.".to_string()
+                }
+                ProviderType::GeminiCli => {
+                    "Install and sign in to the Gemini CLI (`gemini`), or pick another provider in Settings.".to_string()
+                }
+                ProviderType::OpenAiCli => {
+                    "Install and sign in to the Codex CLI (`codex`), or pick another provider in Settings.".to_string()
+                }
+                _ => "Pick an AI provider in Settings and complete its setup.".to_string(),
+            },
+            complete: checks.provider_ready,
+            required: true,
+        });
+
+        steps.push(OnboardingStep {
+            id: "mcp_servers".to_string(),
+            title: "Enable a tool server".to_string(),
+            action: "Browse the MCP marketplace in Settings and enable a server (e.g. Filesystem or GitHub) to give the agent tools.".to_string(),
+            complete: checks.enabled_mcp_servers > 0,
+            required: false,
+        });
+
+        let mut next_steps: Vec<OnboardingStep> = steps.iter().filter(|s| !s.complete).cloned().collect();
+        // Stable sort keeps the dependency order above within each group
+        next_steps.sort_by_key(|s| !s.required);
+
+        OnboardingStatus {
+            ready: steps.iter().all(|s| s.complete || !s.required),
+            active_provider: active_provider.clone(),
+            steps,
+            next_steps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(steps: &[OnboardingStep]) -> Vec<&str> {
+        steps.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fresh_install_with_hosted_provider() {
+        let checks = OnboardingChecks {
+            api_key_saved: Some(false),
+            ..Default::default()
+        };
+        let status = OnboardingService::build_status(&ProviderType::HostedApi, &checks);
+
+        assert!(!status.ready);
+        assert_eq!(
+            ids(&status.next_steps),
+            vec!["api_key", "provider_ready", "ollama_running", "mcp_servers"]
+        );
+    }
+
+    #[test]
+    fn test_ollama_is_required_only_when_selected() {
+        let checks = OnboardingChecks::default();
+        let status = OnboardingService::build_status(&ProviderType::Ollama, &checks);
+        assert_eq!(ids(&status.next_steps)[..2], ["ollama_running", "provider_ready"]);
+        assert!(status.steps.iter().all(|s| s.id != "api_key"));
+
+        let status = OnboardingService::build_status(&ProviderType::GeminiCli, &checks);
+        let ollama = status.steps.iter().find(|s| s.id == "ollama_running").unwrap();
+        assert!(!ollama.required);
+    }
+
+    #[test]
+    fn test_ready_when_required_steps_done() {
+        let checks = OnboardingChecks {
+            provider_ready: true,
+            api_key_saved: Some(true),
+            ollama_running: false,
+            enabled_mcp_servers: 0,
+        };
+        let status = OnboardingService::build_status(&ProviderType::HostedApi, &checks);
+
+        assert!(status.ready);
+        assert_eq!(ids(&status.next_steps), vec!["ollama_running", "mcp_servers"]);
+        assert!(status.next_steps.iter().all(|s| !s.required));
+    }
+}
@@ -177,6 +177,7 @@ impl OutputParserService {
                 tokens_cache_read,
                 tokens_cache_write,
                 tokens_reasoning,
+                tool_iterations: 0,
             })
         } else {
             None
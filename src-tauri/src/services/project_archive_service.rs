@@ -0,0 +1,193 @@
+//! Project Archive Service - Portable zips for moving projects between machines
+//!
+//! An export is the project folder zipped under its id, without the derived data that
+//! `BackupService` leaves out of snapshots and without files that may hold credentials.
+//! Importing extracts into a staging folder inside the projects directory, checks that
+//! it holds a valid project, and moves it into place under a free id.
+
+use crate::models::project::Project;
+use crate::services::backup_service::BackupService;
+use crate::services::project_service::ProjectService;
+use crate::services::settings_service::SettingsService;
+use crate::utils::{archive, slug};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+pub struct ProjectArchiveService;
+
+impl ProjectArchiveService {
+    /// Zip the project into `.metadata/exports/{id}.zip` and return the zip's path
+    pub fn export(project_id: &str) -> Result<PathBuf> {
+        let project_path = ProjectService::resolve_project_path(project_id)?;
+        if !project_path.is_dir() {
+            anyhow::bail!("Project '{}' not found", project_id);
+        }
+        let projects_path = project_path
+            .parent()
+            .context("Project has no parent directory")?;
+
+        let archive_path = project_path
+            .join(".metadata")
+            .join("exports")
+            .join(format!("{}.zip", project_id));
+        archive::write_archive_filtered(
+            projects_path,
+            &[project_id],
+            &archive_path,
+            Self::is_exported,
+        )
+        .with_context(|| format!("Failed to write {:?}", archive_path))?;
+
+        log::info!("Exported project {} to {:?}", project_id, archive_path);
+        Ok(archive_path)
+    }
+
+    /// Import a zip made by `export`. The project keeps its id unless that is taken, in which
+    /// case it gets the first free `-2`, `-3`, ... suffix.
+    pub fn import(zip_path: &Path) -> Result<Project> {
+        let projects_path = SettingsService::get_projects_path()?;
+        fs::create_dir_all(&projects_path)?;
+        Self::import_into(zip_path, &projects_path)
+    }
+
+    fn import_into(zip_path: &Path, projects_path: &Path) -> Result<Project> {
+        // Staged next to the projects so the final move is a rename; the leading dot keeps
+        // project discovery and the file watcher away from it
+        let staging = TempDir::new_in(projects_path)?;
+        archive::extract_archive(zip_path, staging.path())?;
+
+        let extracted = Self::extracted_project(staging.path())?;
+        if !ProjectService::is_valid_project(&extracted) {
+            anyhow::bail!("The archive doesn't contain a valid project");
+        }
+        let mut project = Project::load(&extracted)?;
+
+        let id = match slug::unique_slug(&project.id, |id| projects_path.join(id).exists()) {
+            Ok(id) => id,
+            Err(collision) => collision.suggestion,
+        };
+        let project_path = projects_path.join(&id);
+        fs::rename(&extracted, &project_path)
+            .with_context(|| format!("Failed to move the project to {:?}", project_path))?;
+
+        if id != project.id {
+            log::info!("Importing project {} as {} since the id is taken", project.id, id);
+            project.id = id;
+            project.path = project_path.clone();
+            project.save()?;
+            ProjectService::retarget_workflows(&project_path, &project.id)?;
+        }
+
+        Ok(ProjectService::load_project(&project_path)?)
+    }
+
+    /// The single folder an export holds at its top level
+    fn extracted_project(staging: &Path) -> Result<PathBuf> {
+        let mut folders = fs::read_dir(staging)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|path| path.is_dir());
+        match (folders.next(), folders.next()) {
+            (Some(folder), None) => Ok(folder),
+            _ => anyhow::bail!("Expected the archive to hold a single project folder"),
+        }
+    }
+
+    /// Whether a path in an export (starting with the project folder) is included
+    fn is_exported(relative: &Path) -> bool {
+        let mut components = relative.components();
+        components.next();
+        let inside = components.as_path();
+        !BackupService::is_derived_data(inside) && !Self::is_secret(inside)
+    }
+
+    /// Files that may hold credentials, which are never exported
+    fn is_secret(relative: &Path) -> bool {
+        let name = relative
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        name == ".env"
+            || name.starts_with(".env.")
+            || name.starts_with("secrets.")
+            || name.ends_with(".pem")
+            || name.ends_with(".key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_project(path: &Path, id: &str) {
+        fs::create_dir_all(path.join(".metadata").join("exports")).unwrap();
+        fs::create_dir_all(path.join(".workflows")).unwrap();
+        let metadata = serde_json::json!({
+            "id": id,
+            "name": "Alpha",
+            "goal": "Test exports",
+            "skills": [],
+            "created": "2025-01-01T00:00:00Z"
+        });
+        fs::write(path.join(".metadata").join("project.json"), metadata.to_string()).unwrap();
+        fs::write(path.join(".metadata").join("exports").join("old.html"), "old").unwrap();
+        fs::write(path.join(".workflows").join("w.json"), r#"{"project_id":"alpha"}"#).unwrap();
+        fs::write(path.join("notes.md"), "# Notes").unwrap();
+        fs::write(path.join(".env"), "OPENAI_API_KEY=sk-test").unwrap();
+        fs::write(path.join("secrets.encrypted.json"), "{}").unwrap();
+    }
+
+    #[test]
+    fn test_exported_paths() {
+        assert!(ProjectArchiveService::is_exported(Path::new("alpha/notes.md")));
+        assert!(ProjectArchiveService::is_exported(Path::new("alpha/chats/chat_1.md")));
+        assert!(ProjectArchiveService::is_exported(Path::new("alpha/.metadata/project.json")));
+        assert!(!ProjectArchiveService::is_exported(Path::new("alpha/.metadata/exports")));
+        assert!(!ProjectArchiveService::is_exported(Path::new("alpha/.env")));
+        assert!(!ProjectArchiveService::is_exported(Path::new("alpha/data/.env.local")));
+        assert!(!ProjectArchiveService::is_exported(Path::new("alpha/secrets.json")));
+        assert!(!ProjectArchiveService::is_exported(Path::new("alpha/certs/server.PEM")));
+    }
+
+    #[test]
+    fn test_import_dedupes_taken_id() {
+        let temp = TempDir::new().unwrap();
+        let projects = temp.path().join("projects");
+        write_project(&projects.join("alpha"), "alpha");
+
+        let archive_path = temp.path().join("alpha.zip");
+        let include = ProjectArchiveService::is_exported;
+        archive::write_archive_filtered(&projects, &["alpha"], &archive_path, include).unwrap();
+
+        let imported = ProjectArchiveService::import_into(&archive_path, &projects).unwrap();
+        assert_eq!(imported.id, "alpha-2");
+        assert_eq!(imported.path, projects.join("alpha-2"));
+        assert_eq!(fs::read_to_string(imported.path.join("notes.md")).unwrap(), "# Notes");
+        assert!(!imported.path.join(".env").exists());
+        assert!(!imported.path.join("secrets.encrypted.json").exists());
+        assert!(!imported.path.join(".metadata").join("exports").exists());
+
+        let workflow = fs::read_to_string(imported.path.join(".workflows").join("w.json")).unwrap();
+        assert!(workflow.contains("\"alpha-2\""));
+
+        // The staging folder is gone, leaving only the two projects
+        assert_eq!(fs::read_dir(&projects).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_import_rejects_archives_without_a_project() {
+        let temp = TempDir::new().unwrap();
+        let projects = temp.path().join("projects");
+        fs::create_dir_all(projects.join("loose")).unwrap();
+        fs::write(projects.join("loose").join("notes.md"), "# Notes").unwrap();
+
+        let archive_path = temp.path().join("loose.zip");
+        archive::write_archive(&projects, &["loose"], &archive_path).unwrap();
+        fs::remove_dir_all(projects.join("loose")).unwrap();
+
+        assert!(ProjectArchiveService::import_into(&archive_path, &projects).is_err());
+        assert_eq!(fs::read_dir(&projects).unwrap().count(), 0);
+    }
+}
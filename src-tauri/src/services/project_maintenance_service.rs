@@ -0,0 +1,212 @@
+//! Project Maintenance Service - Compaction for long-lived projects
+//!
+//! Projects accumulate timestamped chat files, workflow run records and trashed files.
+//! Compaction folds older chats into one archive file and drops run records and trash
+//! past the retention window, keeping the chat list and context injection fast.
+
+use crate::models::workflow::WorkflowRunRecord;
+use crate::services::background_workflow_service::BackgroundWorkflowService;
+use crate::services::chat_service::ChatService;
+use crate::services::project_service::ProjectService;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Chats kept live when the caller doesn't say otherwise
+pub const DEFAULT_KEEP_RECENT_CHATS: usize = 20;
+/// Age in days after which workflow runs and trash are purged
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactionReport {
+    pub project_id: String,
+    pub chats_archived: usize,
+    pub workflow_runs_purged: usize,
+    pub trash_items_purged: usize,
+    pub bytes_reclaimed: u64,
+}
+
+pub struct ProjectMaintenanceService;
+
+impl ProjectMaintenanceService {
+    /// Archive all but the newest `keep_recent_chats` chats and purge workflow runs and
+    /// trash older than `retention_days`
+    pub fn compact_project(
+        project_id: &str,
+        keep_recent_chats: usize,
+        retention_days: u32,
+    ) -> Result<CompactionReport> {
+        let project_path = ProjectService::resolve_project_path(project_id)?;
+        let cutoff = Utc::now() - Duration::days(i64::from(retention_days));
+
+        let chats = ChatService::archive_old_chats(project_id, keep_recent_chats)?;
+        let mut workflow_runs_purged = 0;
+        let mut runs_bytes = 0;
+        for runs_dir in [
+            BackgroundWorkflowService::runs_dir(&project_path),
+            BackgroundWorkflowService::legacy_runs_dir(&project_path),
+        ] {
+            let (count, bytes) = Self::purge_workflow_runs(&runs_dir, cutoff)?;
+            workflow_runs_purged += count;
+            runs_bytes += bytes;
+        }
+        let (trash_items_purged, trash_bytes) = Self::purge_trash(&project_path.join(".trash"), cutoff)?;
+
+        let report = CompactionReport {
+            project_id: project_id.to_string(),
+            chats_archived: chats.archived.len(),
+            workflow_runs_purged,
+            trash_items_purged,
+            bytes_reclaimed: chats.bytes_reclaimed + runs_bytes + trash_bytes,
+        };
+        log::info!(
+            "Compacted project {}: {} chats archived, {} runs and {} trash items purged, {} bytes reclaimed",
+            project_id,
+            report.chats_archived,
+            report.workflow_runs_purged,
+            report.trash_items_purged,
+            report.bytes_reclaimed
+        );
+        Ok(report)
+    }
+
+    /// Remove run records that finished (or, if unfinished, started) before `cutoff`
+    fn purge_workflow_runs(runs_dir: &Path, cutoff: DateTime<Utc>) -> Result<(usize, u64)> {
+        if !runs_dir.exists() {
+            return Ok((0, 0));
+        }
+
+        let mut purged = (0, 0);
+        for entry in fs::read_dir(runs_dir)? {
+            let path = entry?.path();
+            if !path.is_file() || !path.extension().is_some_and(|ext| ext == "json") {
+                continue;
+            }
+
+            // Fall back to the file time for records that can't be parsed
+            let finished = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<WorkflowRunRecord>(&content).ok())
+                .and_then(|record| {
+                    let when = record.completed.unwrap_or(record.started);
+                    DateTime::parse_from_rfc3339(&when).ok()
+                })
+                .map(|when| when.with_timezone(&Utc))
+                .or_else(|| Self::modified(&path));
+
+            if finished.is_some_and(|when| when < cutoff) {
+                purged.1 += fs::metadata(&path)?.len();
+                fs::remove_file(&path)?;
+                purged.0 += 1;
+            }
+        }
+        Ok(purged)
+    }
+
+    /// Remove top-level trash entries last modified before `cutoff`
+    fn purge_trash(trash_dir: &Path, cutoff: DateTime<Utc>) -> Result<(usize, u64)> {
+        if !trash_dir.exists() {
+            return Ok((0, 0));
+        }
+
+        let mut purged = (0, 0);
+        for entry in fs::read_dir(trash_dir)? {
+            let path = entry?.path();
+            if !Self::modified(&path).is_some_and(|when| when < cutoff) {
+                continue;
+            }
+
+            purged.1 += Self::size_of(&path);
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+            purged.0 += 1;
+        }
+        Ok(purged)
+    }
+
+    fn modified(path: &Path) -> Option<DateTime<Utc>> {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(DateTime::<Utc>::from)
+    }
+
+    fn size_of(path: &Path) -> u64 {
+        if path.is_dir() {
+            fs::read_dir(path)
+                .map(|entries| entries.flatten().map(|e| Self::size_of(&e.path())).sum())
+                .unwrap_or(0)
+        } else {
+            fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    fn age(path: &Path, days: u64) {
+        let when = SystemTime::now() - std::time::Duration::from_secs(days * 24 * 60 * 60);
+        File::options().write(true).open(path).unwrap().set_modified(when).unwrap();
+    }
+
+    #[test]
+    fn test_purge_workflow_runs_past_retention() {
+        let temp = TempDir::new().unwrap();
+        let record = |id: &str, completed: &str| {
+            serde_json::json!({
+                "id": id,
+                "workflow_id": "wf",
+                "workflow_name": "Workflow",
+                "project_id": "p",
+                "started": completed,
+                "completed": completed,
+                "status": "Completed",
+                "error": null,
+                "trigger": "manual",
+                "step_results": {}
+            })
+            .to_string()
+        };
+        let old = (Utc::now() - Duration::days(90)).to_rfc3339();
+        let recent = Utc::now().to_rfc3339();
+        fs::write(temp.path().join("old.json"), record("old", &old)).unwrap();
+        fs::write(temp.path().join("recent.json"), record("recent", &recent)).unwrap();
+        fs::write(temp.path().join("garbled.json"), "{").unwrap();
+        age(&temp.path().join("garbled.json"), 90);
+
+        let cutoff = Utc::now() - Duration::days(30);
+        let (count, bytes) = ProjectMaintenanceService::purge_workflow_runs(temp.path(), cutoff).unwrap();
+        assert_eq!(count, 2);
+        assert!(bytes > 0);
+        assert!(temp.path().join("recent.json").exists());
+        assert!(!temp.path().join("old.json").exists());
+    }
+
+    #[test]
+    fn test_purge_trash_only_removes_old_entries() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("old-folder")).unwrap();
+        fs::write(temp.path().join("old-folder").join("notes.md"), "12345").unwrap();
+        fs::write(temp.path().join("fresh.md"), "keep").unwrap();
+        let folder = File::open(temp.path().join("old-folder")).unwrap();
+        folder
+            .set_modified(SystemTime::now() - std::time::Duration::from_secs(60 * 24 * 60 * 60))
+            .unwrap();
+
+        let cutoff = Utc::now() - Duration::days(30);
+        let (count, bytes) = ProjectMaintenanceService::purge_trash(temp.path(), cutoff).unwrap();
+        assert_eq!((count, bytes), (1, 5));
+        assert!(temp.path().join("fresh.md").exists());
+    }
+}
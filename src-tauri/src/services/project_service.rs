@@ -1,5 +1,10 @@
-use crate::models::project::{Project, ProjectError};
+use crate::models::project::{
+    FileSort, Project, ProjectDetails, ProjectError, ProjectFileInfo, ProjectFilePage,
+};
+use crate::services::chat_service::ChatService;
 use crate::services::settings_service::SettingsService;
+use crate::utils::fs::write_atomic;
+use crate::utils::slug;
 use chrono::Utc;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -11,6 +16,13 @@ pub struct ProjectService;
 impl ProjectService {
     /// Scan projects directory and return all valid projects
     pub fn discover_projects() -> Result<Vec<Project>, ProjectError> {
+        Self::discover_projects_each(|_| {})
+    }
+
+    /// Same as `discover_projects`, calling `on_project` as each project is loaded
+    pub fn discover_projects_each(
+        mut on_project: impl FnMut(&Project),
+    ) -> Result<Vec<Project>, ProjectError> {
         let projects_path = SettingsService::get_projects_path().map_err(|e| {
             ProjectError::ReadError(std::io::Error::other(format!(
                 "Failed to get projects path: {}",
@@ -47,6 +59,7 @@ impl ProjectService {
                             project.name,
                             project.id
                         );
+                        on_project(&project);
                         projects.push(project)
                     }
                     Err(e) => {
@@ -176,26 +189,12 @@ impl ProjectService {
             log::info!("projects path created");
         }
 
-        // Generate project ID from name (lowercase, replace spaces with hyphens)
-        let project_id = name
-            .to_lowercase()
-            .replace(' ', "-")
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
-            .collect::<String>();
+        // Generate project ID from name, refusing ids that are already taken
+        let project_id = slug::unique_slug(name, |id| projects_path.join(id).exists())?;
 
         let project_path = projects_path.join(&project_id);
         log::info!("Attempting to create project folder at {:?}", project_path);
 
-        // Check if project already exists
-        if project_path.exists() {
-            log::error!("Creation error: path already exists: {:?}", project_path);
-            return Err(ProjectError::InvalidStructure(format!(
-                "Project directory already exists at {:?}",
-                project_path
-            )));
-        }
-
         // Create project directory
         fs::create_dir_all(&project_path)?;
         log::info!("project folder created");
@@ -207,8 +206,10 @@ impl ProjectService {
             name: name.to_string(),
             goal: goal.to_string(),
             skills: skills.clone(),
+            tags: Vec::new(),
             created,
             path: project_path.clone(),
+            details: ProjectDetails::default(),
         };
 
         project.save()?;
@@ -228,6 +229,161 @@ impl ProjectService {
         Self::load_project(&project_path)
     }
 
+    /// Start a new project named `new_name` from an existing one: its goal, skills, details,
+    /// settings, workflows and markdown files are copied, but not chats, workflow runs or
+    /// the rest of `.metadata` (costs, logs, exports)
+    pub fn duplicate_project(project_id: &str, new_name: &str) -> Result<Project, ProjectError> {
+        let source = Self::load_project_by_id(project_id)?;
+        let projects_path = SettingsService::get_projects_path().map_err(|e| {
+            ProjectError::ReadError(std::io::Error::other(format!(
+                "Failed to get projects path: {}",
+                e
+            )))
+        })?;
+
+        // Same id scheme as create_project
+        let new_id = slug::unique_slug(new_name, |id| projects_path.join(id).exists())?;
+        let project_path = projects_path.join(&new_id);
+        fs::create_dir_all(&project_path)?;
+
+        let project = Project {
+            id: new_id,
+            name: new_name.to_string(),
+            goal: source.goal,
+            skills: source.skills,
+            tags: source.tags,
+            created: Utc::now(),
+            path: project_path.clone(),
+            details: source.details,
+        };
+        let copied = Self::copy_template_files(&source.path, &project_path)
+            .and_then(|_| Self::reset_copied_workflows(&project_path, &project.id))
+            .and_then(|_| project.save());
+        if let Err(e) = copied {
+            let _ = fs::remove_dir_all(&project_path);
+            return Err(e);
+        }
+
+        log::info!("Duplicated project {} as {}", project_id, project.id);
+        Self::load_project(&project_path)
+    }
+
+    /// Copy the files `is_template_file` accepts
+    fn copy_template_files(source: &Path, target: &Path) -> Result<(), ProjectError> {
+        for entry in WalkDir::new(source).into_iter().filter_map(|e| e.ok()) {
+            let Ok(relative) = entry.path().strip_prefix(source) else {
+                continue;
+            };
+            if !entry.file_type().is_file() || !Self::is_template_file(relative) {
+                continue;
+            }
+
+            let destination = target.join(relative);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(entry.path(), &destination)?;
+        }
+        Ok(())
+    }
+
+    /// Point the workflow definitions in `.workflows/` at `project_id`, for project files
+    /// copied or moved under a new id. Files that aren't JSON objects are left alone.
+    pub(crate) fn retarget_workflows(
+        project_path: &Path,
+        project_id: &str,
+    ) -> Result<(), ProjectError> {
+        let workflows_dir = project_path.join(".workflows");
+        if !workflows_dir.is_dir() {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(&workflows_dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let mut workflow = match serde_json::from_str::<serde_json::Value>(
+                &fs::read_to_string(&path)?,
+            ) {
+                Ok(workflow) if workflow.is_object() => workflow,
+                _ => {
+                    log::warn!("Leaving unreadable workflow {:?} as it is", path);
+                    continue;
+                }
+            };
+            workflow["project_id"] = project_id.into();
+            let json = serde_json::to_string_pretty(&workflow)
+                .map_err(|e| ProjectError::ParseError(e.to_string()))?;
+            fs::write(&path, json)?;
+        }
+        Ok(())
+    }
+
+    /// Prepare the workflows copied by `duplicate_project`: point them at `project_id`, drop
+    /// their run state and switch off their schedules so the copy doesn't run alongside the
+    /// source. Files that can't be read as JSON objects are logged and left out of the copy.
+    fn reset_copied_workflows(project_path: &Path, project_id: &str) -> Result<(), ProjectError> {
+        let workflows_dir = project_path.join(".workflows");
+        if !workflows_dir.is_dir() {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(&workflows_dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).map_err(|e| e.to_string()));
+            let mut workflow = match parsed {
+                Ok(workflow) if workflow.is_object() => workflow,
+                Ok(_) => {
+                    log::warn!("Skipping workflow file {:?}: not a JSON object", path);
+                    fs::remove_file(&path)?;
+                    continue;
+                }
+                Err(e) => {
+                    log::warn!("Skipping workflow file {:?}: {}", path, e);
+                    fs::remove_file(&path)?;
+                    continue;
+                }
+            };
+
+            workflow["project_id"] = project_id.into();
+            workflow["last_run"] = serde_json::Value::Null;
+            workflow["active_execution_id"] = serde_json::Value::Null;
+            if let Some(schedule) = workflow.get_mut("schedule").and_then(|s| s.as_object_mut()) {
+                schedule.insert("enabled".to_string(), false.into());
+                schedule.insert("next_run_at".to_string(), serde_json::Value::Null);
+                schedule.insert("last_triggered_at".to_string(), serde_json::Value::Null);
+            }
+            let json = serde_json::to_string_pretty(&workflow)
+                .map_err(|e| ProjectError::ParseError(e.to_string()))?;
+            write_atomic(&path, json)?;
+        }
+        Ok(())
+    }
+
+    /// Whether `duplicate_project` copies this project-relative file: the project settings,
+    /// workflow definitions and markdown files outside `chats/` and hidden folders
+    fn is_template_file(relative: &Path) -> bool {
+        if relative == Path::new(".metadata/settings.json") {
+            return true;
+        }
+        if relative.starts_with(".workflows") {
+            return relative.parent() == Some(Path::new(".workflows"))
+                && relative.extension().is_some_and(|ext| ext == "json");
+        }
+        let hidden = relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+        !hidden
+            && !relative.starts_with("chats")
+            && relative.extension().is_some_and(|ext| ext == "md")
+    }
+
     /// Update project metadata in .metadata/project.json
     pub fn update_project_metadata(
         project_id: &str,
@@ -249,8 +405,204 @@ impl ProjectService {
         Ok(())
     }
 
+    /// Rename a project. With `rename_directory` its folder and id also change to the slug of
+    /// the new name, and its workflows are pointed at the new id; a taken slug is an error.
+    pub fn rename_project(
+        project_id: &str,
+        new_name: &str,
+        rename_directory: bool,
+    ) -> Result<Project, ProjectError> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(ProjectError::InvalidStructure(
+                "Project name cannot be empty".to_string(),
+            ));
+        }
+
+        let mut project = Self::load_project_by_id(project_id)?;
+        project.name = new_name.to_string();
+
+        let mut moved_from = None;
+        if rename_directory && slug::slugify(new_name) != project_id {
+            let projects_path = SettingsService::get_projects_path().map_err(|e| {
+                ProjectError::ReadError(std::io::Error::other(format!(
+                    "Failed to get projects path: {}",
+                    e
+                )))
+            })?;
+            let app_data = crate::utils::paths::get_app_data_dir().map_err(|e| {
+                ProjectError::ReadError(std::io::Error::other(format!(
+                    "Failed to get app data path: {}",
+                    e
+                )))
+            })?;
+            let original = (project.id.clone(), project.path.clone());
+            Self::move_project(&mut project, &projects_path, &app_data)?;
+            moved_from = Some((original, app_data));
+        }
+
+        if let Err(e) = project.save() {
+            // Don't leave the folder under the new id with metadata still naming the old one
+            if let Some(((old_id, old_path), app_data)) = moved_from {
+                Self::undo_move(&mut project, &old_id, &old_path, &app_data);
+            }
+            return Err(e);
+        }
+        Ok(project)
+    }
+
+    /// Move the project's folder to the slug of its name and update its id to match.
+    /// The chat history under `app_data` moves with it, so a slug that still has chats
+    /// left over from another project counts as taken.
+    fn move_project(
+        project: &mut Project,
+        projects_path: &Path,
+        app_data: &Path,
+    ) -> Result<(), ProjectError> {
+        let new_id = slug::unique_slug(&project.name, |id| {
+            projects_path.join(id).exists() || ChatService::chat_directory_in(app_data, id).exists()
+        })?;
+        let new_path = projects_path.join(&new_id);
+
+        log::info!("Moving project {:?} to {:?}", project.path, new_path);
+        fs::rename(&project.path, &new_path)?;
+
+        let old_chats = ChatService::chat_directory_in(app_data, &project.id);
+        if old_chats.exists() {
+            let new_chats = ChatService::chat_directory_in(app_data, &new_id);
+            let moved = new_chats
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::rename(&old_chats, &new_chats));
+            if let Err(e) = moved {
+                // Keep the folder and its chats together under the old id
+                fs::rename(&new_path, &project.path)?;
+                return Err(e.into());
+            }
+            if let Some(old_parent) = old_chats.parent() {
+                let _ = fs::remove_dir(old_parent);
+            }
+        }
+
+        let (old_id, old_path) = (project.id.clone(), project.path.clone());
+        project.id = new_id;
+        project.path = new_path;
+        if let Err(e) = Self::retarget_workflows(&project.path, &project.id) {
+            Self::undo_move(project, &old_id, &old_path, app_data);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Put a project moved by `move_project` back under `old_id`, with its chats and
+    /// workflows. Best effort: failures are logged, since the caller is already failing.
+    fn undo_move(project: &mut Project, old_id: &str, old_path: &Path, app_data: &Path) {
+        log::warn!("Moving project {:?} back to {:?}", project.path, old_path);
+        if let Err(e) = fs::rename(&project.path, old_path) {
+            log::error!("Failed to move project {:?} back to {:?}: {}", project.path, old_path, e);
+            return;
+        }
+
+        let new_chats = ChatService::chat_directory_in(app_data, &project.id);
+        if new_chats.exists() {
+            let old_chats = ChatService::chat_directory_in(app_data, old_id);
+            let moved = old_chats
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::rename(&new_chats, &old_chats));
+            match moved {
+                Ok(()) => {
+                    if let Some(new_parent) = new_chats.parent() {
+                        let _ = fs::remove_dir(new_parent);
+                    }
+                }
+                Err(e) => log::error!("Failed to move chats {:?} back: {}", new_chats, e),
+            }
+        }
+
+        project.id = old_id.to_string();
+        project.path = old_path.to_path_buf();
+        if let Err(e) = Self::retarget_workflows(&project.path, &project.id) {
+            log::error!("Failed to point workflows back at project {}: {}", old_id, e);
+        }
+    }
+
+    /// Replace a project's tags, normalized with `Project::normalize_tags`
+    pub fn update_project_tags(
+        project_id: &str,
+        tags: Vec<String>,
+    ) -> Result<Project, ProjectError> {
+        let mut project = Self::load_project_by_id(project_id)?;
+        project.tags = Project::normalize_tags(tags);
+        project.save()?;
+
+        Ok(project)
+    }
+
+    /// Projects tagged with `tag`, ignoring case
+    pub fn get_projects_by_tag(tag: &str) -> Result<Vec<Project>, ProjectError> {
+        let projects = Self::discover_projects()?;
+        Ok(projects.into_iter().filter(|p| p.has_tag(tag)).collect())
+    }
+
+    /// Replace the structured details (status, collaborators, links, due date) of a project
+    pub fn update_project_details(
+        project_id: &str,
+        details: ProjectDetails,
+    ) -> Result<Project, ProjectError> {
+        let mut project = Self::load_project_by_id(project_id)?;
+        project.details = details;
+        project.save()?;
+
+        Ok(project)
+    }
+
     /// List all markdown files in a project (excluding hidden metadata)
     pub fn list_project_files(project_id: &str) -> Result<Vec<String>, ProjectError> {
+        Self::project_files(project_id).map(|(_, files)| files)
+    }
+
+    /// One page of the project's files with their size and modification time.
+    /// `total` in the result counts every file, so the UI can load more on demand.
+    pub fn list_project_files_page(
+        project_id: &str,
+        offset: usize,
+        limit: Option<usize>,
+        sort: FileSort,
+    ) -> Result<ProjectFilePage, ProjectError> {
+        let (project_path, files) = Self::project_files(project_id)?;
+        let total = files.len();
+
+        // Name order is already known, so only the requested page needs a stat
+        let (files, page_offset) = match sort {
+            FileSort::Name => (
+                files.into_iter().skip(offset).take(limit.unwrap_or(usize::MAX)).collect(),
+                0,
+            ),
+            FileSort::Modified => (files, offset),
+        };
+        let infos: Vec<ProjectFileInfo> = files
+            .into_iter()
+            .map(|path| {
+                let metadata = fs::metadata(project_path.join(&path)).ok();
+                ProjectFileInfo {
+                    size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                    modified: metadata
+                        .and_then(|m| m.modified().ok())
+                        .map(chrono::DateTime::<Utc>::from),
+                    path,
+                }
+            })
+            .collect();
+
+        let mut page = ProjectFilePage::paginate(infos, sort, page_offset, limit);
+        page.total = total;
+        page.offset = offset;
+        Ok(page)
+    }
+
+    /// Resolve the project folder and collect its relevant files, sorted by path
+    pub(crate) fn project_files(project_id: &str) -> Result<(PathBuf, Vec<String>), ProjectError> {
         let project_id = project_id.trim();
         let projects_path = SettingsService::get_projects_path().map_err(|e| {
             ProjectError::ReadError(std::io::Error::other(format!(
@@ -352,8 +704,10 @@ impl ProjectService {
         // Sort alphabetically
         markdown_files.sort();
 
-        Ok(markdown_files)
+        Ok((project_path, markdown_files))
     }
+
+    /// Delete a project directory. Callers wanting a way back should back it up first.
     pub fn delete_project(project_id: &str) -> Result<(), ProjectError> {
         let projects_path = SettingsService::get_projects_path().map_err(|e| {
             ProjectError::ReadError(std::io::Error::other(format!(
@@ -362,6 +716,13 @@ impl ProjectService {
             )))
         })?;
 
+        Self::delete_project_in(&projects_path, project_id)
+    }
+
+    /// Only a directory directly inside `projects_path` is removed, so neither the id nor a
+    /// symlinked project can point the delete somewhere else
+    fn delete_project_in(projects_path: &Path, project_id: &str) -> Result<(), ProjectError> {
+        Self::validate_project_id(project_id)?;
         let project_path = projects_path.join(project_id);
 
         if !project_path.exists() {
@@ -371,6 +732,16 @@ impl ProjectService {
             ))));
         }
 
+        let metadata = fs::symlink_metadata(&project_path)?;
+        let canonical_base = projects_path.canonicalize()?;
+        let canonical_project = project_path.canonicalize()?;
+        if !metadata.is_dir() || canonical_project.parent() != Some(canonical_base.as_path()) {
+            return Err(ProjectError::InvalidStructure(format!(
+                "'{}' is not a project directory inside the projects directory",
+                project_id
+            )));
+        }
+
         log::info!("Deleting project at {:?}", project_path);
         fs::remove_dir_all(project_path)?;
 
@@ -385,14 +756,10 @@ mod tests {
 
     #[test]
     fn test_project_id_generation() {
-        let name = "My Project_Name 123!";
-        let id: String = name
-            .to_lowercase()
-            .replace(' ', "-")
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
-            .collect();
+        let id = slug::slugify("My Project_Name 123!");
         assert_eq!(id, "my-project_name-123");
+        assert!(ProjectService::validate_project_id(&id).is_ok());
+        assert!(ProjectService::validate_project_id(&slug::slugify("???")).is_ok());
     }
 
     #[test]
@@ -429,6 +796,183 @@ mod tests {
         assert!(ProjectService::is_valid_project(&project_path));
     }
 
+    #[test]
+    fn test_delete_project_stays_in_projects_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects = temp_dir.path().join("projects");
+        fs::create_dir_all(projects.join("doomed").join(".metadata")).unwrap();
+        fs::write(projects.join("doomed").join("notes.md"), "# Notes").unwrap();
+        fs::write(projects.join("stray"), "not a project").unwrap();
+        let outside = temp_dir.path().join("outside");
+        fs::create_dir(&outside).unwrap();
+
+        assert!(ProjectService::delete_project_in(&projects, "..").is_err());
+        assert!(ProjectService::delete_project_in(&projects, "../outside").is_err());
+        assert!(ProjectService::delete_project_in(&projects, "missing").is_err());
+        assert!(ProjectService::delete_project_in(&projects, "stray").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, projects.join("linked")).unwrap();
+            assert!(ProjectService::delete_project_in(&projects, "linked").is_err());
+        }
+        assert!(outside.exists());
+
+        ProjectService::delete_project_in(&projects, "doomed").unwrap();
+        assert!(!projects.join("doomed").exists());
+        assert!(projects.join("stray").exists());
+    }
+
+    #[test]
+    fn test_template_files_for_duplicate() {
+        for copied in [
+            "README.md",
+            "notes/interview-1.md",
+            ".metadata/settings.json",
+            ".workflows/weekly-review.json",
+        ] {
+            assert!(ProjectService::is_template_file(Path::new(copied)), "{}", copied);
+        }
+        for skipped in [
+            "chats/chat_20250101_120000.md",
+            "chats/.metadata/chats/chat_20250101_120000.json",
+            ".workflows/runs/run-1.json",
+            ".metadata/project.json",
+            ".metadata/cost_log.json",
+            ".trash/old.md",
+            "data.csv",
+        ] {
+            assert!(!ProjectService::is_template_file(Path::new(skipped)), "{}", skipped);
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let (source, target) = (temp_dir.path().join("a"), temp_dir.path().join("b"));
+        fs::create_dir_all(source.join(".workflows").join("runs")).unwrap();
+        fs::create_dir_all(source.join("chats")).unwrap();
+        fs::write(source.join("README.md"), "# A").unwrap();
+        fs::write(source.join("chats").join("chat_1.md"), "## User\nhi").unwrap();
+        fs::write(
+            source.join(".workflows").join("review.json"),
+            r#"{"id":"review","project_id":"a","steps":[]}"#,
+        )
+        .unwrap();
+        fs::write(
+            source.join(".workflows").join("nightly.json"),
+            r#"{"id":"nightly","project_id":"a","steps":[],"last_run":"2025-01-01T00:00:00Z",
+                "active_execution_id":"exec-1",
+                "schedule":{"enabled":true,"cron":"0 0 * * *","next_run_at":"2025-01-02T00:00:00Z"}}"#,
+        )
+        .unwrap();
+        fs::write(source.join(".workflows").join("broken.json"), "{ not json").unwrap();
+        fs::write(source.join(".workflows").join("runs").join("r.json"), "{}").unwrap();
+
+        ProjectService::copy_template_files(&source, &target).unwrap();
+        ProjectService::reset_copied_workflows(&target, "b").unwrap();
+        assert!(!target.join(".workflows").join("broken.json").exists());
+        let nightly: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(target.join(".workflows").join("nightly.json")).unwrap(),
+        )
+        .unwrap();
+        assert!(nightly["last_run"].is_null());
+        assert!(nightly["active_execution_id"].is_null());
+        assert_eq!(nightly["schedule"]["enabled"], false);
+        assert!(nightly["schedule"]["next_run_at"].is_null());
+        assert_eq!(nightly["schedule"]["cron"], "0 0 * * *");
+        assert_eq!(fs::read_to_string(target.join("README.md")).unwrap(), "# A");
+        assert!(!target.join("chats").exists());
+        assert!(!target.join(".workflows").join("runs").exists());
+        let workflow: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(target.join(".workflows").join("review.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(workflow["project_id"], "b");
+        assert_eq!(workflow["id"], "review");
+    }
+
+    #[test]
+    fn test_move_project_to_new_slug() {
+        let temp_dir = TempDir::new().unwrap();
+        let app_data = TempDir::new().unwrap();
+        let projects = temp_dir.path();
+        fs::create_dir_all(projects.join("taken")).unwrap();
+        fs::create_dir_all(projects.join("typo-projct").join(".workflows")).unwrap();
+        fs::write(
+            projects.join("typo-projct").join(".workflows").join("w.json"),
+            r#"{"id":"w","project_id":"typo-projct"}"#,
+        )
+        .unwrap();
+        let mut project = Project {
+            id: "typo-projct".to_string(),
+            name: "Taken".to_string(),
+            goal: "Test renames".to_string(),
+            skills: vec![],
+            tags: vec![],
+            created: Utc::now(),
+            path: projects.join("typo-projct"),
+            details: ProjectDetails::default(),
+        };
+
+        // A taken slug is refused and nothing moves
+        assert!(matches!(
+            ProjectService::move_project(&mut project, projects, app_data.path()),
+            Err(ProjectError::IdCollision(_))
+        ));
+        assert!(projects.join("typo-projct").exists());
+
+        project.name = "Typo Project".to_string();
+        ProjectService::move_project(&mut project, projects, app_data.path()).unwrap();
+        assert_eq!(project.id, "typo-project");
+        assert!(!projects.join("typo-projct").exists());
+        let workflow = fs::read_to_string(project.path.join(".workflows").join("w.json")).unwrap();
+        assert!(workflow.contains("\"typo-project\""));
+    }
+
+    #[test]
+    fn test_move_project_takes_chats_along() {
+        let temp_dir = TempDir::new().unwrap();
+        let app_data = TempDir::new().unwrap();
+        let projects = temp_dir.path();
+        fs::create_dir_all(projects.join("draft")).unwrap();
+        let old_chats = ChatService::chat_directory_in(app_data.path(), "draft");
+        fs::create_dir_all(&old_chats).unwrap();
+        fs::write(old_chats.join("chat_1.md"), "## User\nhi").unwrap();
+        // Chats left behind by a deleted "stale" project
+        fs::create_dir_all(ChatService::chat_directory_in(app_data.path(), "stale")).unwrap();
+
+        let mut project = Project {
+            id: "draft".to_string(),
+            name: "Stale".to_string(),
+            goal: "Test renames".to_string(),
+            skills: vec![],
+            tags: vec![],
+            created: Utc::now(),
+            path: projects.join("draft"),
+            details: ProjectDetails::default(),
+        };
+
+        // Moving onto a slug with someone else's chats would hand them to this project
+        assert!(matches!(
+            ProjectService::move_project(&mut project, projects, app_data.path()),
+            Err(ProjectError::IdCollision(_))
+        ));
+        assert!(old_chats.join("chat_1.md").exists());
+
+        project.name = "Final".to_string();
+        ProjectService::move_project(&mut project, projects, app_data.path()).unwrap();
+        assert_eq!(project.id, "final");
+        let new_chats = ChatService::chat_directory_in(app_data.path(), "final");
+        assert!(new_chats.join("chat_1.md").exists());
+        // A new project reusing the old slug starts without history
+        assert!(!app_data.path().join("draft").exists());
+
+        // Undoing puts the folder and chats back under the old id
+        ProjectService::undo_move(&mut project, "draft", &projects.join("draft"), app_data.path());
+        assert_eq!(project.id, "draft");
+        assert!(projects.join("draft").is_dir());
+        assert!(!projects.join("final").exists());
+        assert!(old_chats.join("chat_1.md").exists());
+        assert!(!ChatService::chat_directory_in(app_data.path(), "final").exists());
+    }
+
     #[test]
     fn test_is_invalid_project_no_file() {
         let temp_dir = TempDir::new().unwrap();
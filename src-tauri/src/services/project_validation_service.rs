@@ -0,0 +1,191 @@
+//! Project Validation Service - Health report across every project
+//!
+//! Walks the projects directory and checks each project's metadata, layout, referenced
+//! skills and workflows, so broken projects can be found and fixed from one place instead
+//! of surfacing one feature at a time.
+
+use crate::models::project::Project;
+use crate::services::settings_service::SettingsService;
+use crate::services::skill_service::SkillService;
+use crate::services::workflow_service::WorkflowService;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectIssue {
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectValidationReport {
+    /// Folder name under the projects directory
+    pub project_id: String,
+    /// Project name from metadata, when it could be loaded
+    pub name: Option<String>,
+    pub path: PathBuf,
+    /// True when there are no errors (warnings are allowed)
+    pub valid: bool,
+    pub issues: Vec<ProjectIssue>,
+}
+
+pub struct ProjectValidationService;
+
+impl ProjectValidationService {
+    /// Validate every folder in the projects directory, sorted by folder name
+    pub fn validate_all() -> Result<Vec<ProjectValidationReport>> {
+        let projects_path = SettingsService::get_projects_path()?;
+        if !projects_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        // Without the skill list the skill checks would all be false alarms, so skip them
+        let known_skills = match SkillService::discover_skills() {
+            Ok(skills) => Some(skills.into_iter().map(|s| s.id).collect::<HashSet<_>>()),
+            Err(e) => {
+                log::warn!("Skipping skill checks, failed to load skills: {}", e);
+                None
+            }
+        };
+
+        let mut reports = Vec::new();
+        for entry in fs::read_dir(&projects_path)? {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .unwrap_or(true);
+            if path.is_dir() && !hidden {
+                reports.push(Self::validate_project(&path, known_skills.as_ref()));
+            }
+        }
+
+        reports.sort_by(|a, b| a.project_id.cmp(&b.project_id));
+        Ok(reports)
+    }
+
+    fn validate_project(path: &Path, known_skills: Option<&HashSet<String>>) -> ProjectValidationReport {
+        let project_id = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut report = ProjectValidationReport {
+            project_id: project_id.clone(),
+            name: None,
+            path: path.to_path_buf(),
+            valid: true,
+            issues: Vec::new(),
+        };
+
+        let has_metadata = path.join(".metadata").join("project.json").exists()
+            || path.join(".project.md").exists();
+        if !has_metadata {
+            report.warn("Folder is not a project (no .metadata/project.json)".to_string());
+            return report;
+        }
+
+        let project = match Project::load(path) {
+            Ok(project) => project,
+            Err(e) => {
+                report.error(format!("Project metadata could not be loaded: {}", e));
+                return report;
+            }
+        };
+        report.name = Some(project.name.clone());
+
+        for (field, value) in [("id", &project.id), ("name", &project.name), ("goal", &project.goal)] {
+            if value.trim().is_empty() {
+                report.error(format!("Project {} is empty", field));
+            }
+        }
+        if project.id != project_id {
+            report.warn(format!(
+                "Project id '{}' doesn't match its folder name '{}'",
+                project.id, project_id
+            ));
+        }
+        if let Err(e) = project.validate_structure() {
+            report.error(e.to_string());
+        }
+
+        match SettingsService::load_project_settings(path) {
+            Ok(Some(_)) => {}
+            Ok(None) => report.warn("No project settings file; defaults will be used".to_string()),
+            Err(e) => report.error(format!("Project settings are invalid: {}", e)),
+        }
+
+        if let Some(known) = known_skills {
+            for skill in project.skills.iter().filter(|s| !known.contains(*s)) {
+                report.warn(format!("Skill '{}' is referenced but not installed", skill));
+            }
+        }
+
+        Self::check_workflows(&mut report, &project_id, known_skills);
+        report
+    }
+
+    fn check_workflows(
+        report: &mut ProjectValidationReport,
+        project_id: &str,
+        known_skills: Option<&HashSet<String>>,
+    ) {
+        let workflows = match WorkflowService::scan_project_workflows(project_id) {
+            Ok(scan) => {
+                for failed in scan.errors {
+                    report.error(format!("Workflow file {} failed to load: {}", failed.file_name, failed.error));
+                }
+                scan.workflows
+            }
+            Err(e) => {
+                report.error(format!("Workflows could not be read: {}", e));
+                return;
+            }
+        };
+
+        for workflow in workflows {
+            if let Err(errors) = workflow.validate() {
+                report.error(format!("Workflow '{}' is invalid: {}", workflow.id, errors.join("; ")));
+            }
+            let Some(known) = known_skills else {
+                continue;
+            };
+            for step in &workflow.steps {
+                if let Some(skill_id) = step.config.skill_id.as_ref().filter(|id| !known.contains(*id)) {
+                    report.warn(format!(
+                        "Workflow '{}' step '{}' uses skill '{}', which is not installed",
+                        workflow.id, step.id, skill_id
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl ProjectValidationReport {
+    fn warn(&mut self, message: String) {
+        self.issues.push(ProjectIssue {
+            severity: IssueSeverity::Warning,
+            message,
+        });
+    }
+
+    fn error(&mut self, message: String) {
+        self.valid = false;
+        self.issues.push(ProjectIssue {
+            severity: IssueSeverity::Error,
+            message,
+        });
+    }
+}
@@ -1,6 +1,6 @@
 use crate::services::project_service::ProjectService;
 use crate::services::settings_service::SettingsService;
-use crate::services::context_service::ContextService;
+use crate::services::context_service::{ContextReport, ContextService};
 use crate::services::skill_service::SkillService;
 
 pub enum PromptMode {
@@ -19,7 +19,16 @@ impl PromptService {
         project_id: Option<&str>,
         mode: PromptMode,
     ) -> String {
+        Self::build_system_prompt_with_report(project_id, mode).0
+    }
+
+    /// `build_system_prompt`, plus what the project context included and dropped
+    pub fn build_system_prompt_with_report(
+        project_id: Option<&str>,
+        mode: PromptMode,
+    ) -> (String, Option<ContextReport>) {
         let mut prompt = String::new();
+        let mut context_report = None;
 
         // 1. Base Identity & Rules
         prompt.push_str("You are a helpful AI research assistant.\n\n");
@@ -67,9 +76,11 @@ impl PromptService {
                 }
 
                 // Automatic Context Injection (Recent Files, History)
-                if let Ok(project_context) = ContextService::get_project_context(pid) {
+                let budget = ContextService::token_budget();
+                if let Ok((project_context, report)) = ContextService::build_project_context(pid, budget) {
                     prompt.push_str("\n\n---\nAUTOMATIC CONTEXT INJECTION (Project Files & History):\n");
                     prompt.push_str(&project_context);
+                    context_report = Some(report);
                 }
             }
         }
@@ -85,7 +96,7 @@ impl PromptService {
             }
         }
 
-        prompt
+        (prompt, context_report)
     }
 
     fn get_file_modification_rules() -> String {
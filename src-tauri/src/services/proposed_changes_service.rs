@@ -0,0 +1,148 @@
+use crate::services::file_service::FileService;
+use crate::services::output_parser_service::FileChange;
+use crate::services::project_service::ProjectService;
+use crate::utils::fs::write_atomic;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// A file change parsed from model output that is waiting for user review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedFileChange {
+    pub id: String,
+    pub path: String,
+    pub content: String,
+    /// Whether applying this change would overwrite an existing file
+    pub overwrites: bool,
+    pub created: String,
+}
+
+/// A file written from model output, kept for the project's research record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedFileChange {
+    pub path: String,
+    pub applied: String,
+    /// "auto" when written straight from a response, "review" when accepted from proposals
+    pub source: String,
+}
+
+/// Stores proposed changes in `.metadata/proposed_changes.json` until they are applied or discarded,
+/// and a history of applied changes in `.metadata/applied_changes.json`
+pub struct ProposedChangesService;
+
+impl ProposedChangesService {
+    fn store_path(project_id: &str) -> Result<PathBuf> {
+        let project_path = ProjectService::resolve_project_path(project_id)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        Ok(project_path.join(".metadata").join("proposed_changes.json"))
+    }
+
+    fn save(project_id: &str, changes: &[ProposedFileChange]) -> Result<()> {
+        let path = Self::store_path(project_id)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(changes)?;
+        write_atomic(&path, content).context("Failed to write proposed changes")
+    }
+
+    /// List the changes currently pending review for a project
+    pub fn list(project_id: &str) -> Result<Vec<ProposedFileChange>> {
+        let path = Self::store_path(project_id)?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read proposed changes")?;
+        serde_json::from_str(&content).context("Failed to parse proposed changes")
+    }
+
+    /// Record parsed file changes as pending proposals and return them with their new ids
+    pub fn propose(project_id: &str, changes: &[FileChange]) -> Result<Vec<ProposedFileChange>> {
+        let project_path = ProjectService::resolve_project_path(project_id)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let now = Utc::now().to_rfc3339();
+
+        let proposed: Vec<ProposedFileChange> = changes
+            .iter()
+            .map(|change| ProposedFileChange {
+                id: uuid::Uuid::new_v4().to_string(),
+                path: change.path.clone(),
+                content: change.content.clone(),
+                overwrites: project_path.join(&change.path).exists(),
+                created: now.clone(),
+            })
+            .collect();
+
+        let mut pending = Self::list(project_id)?;
+        pending.extend(proposed.iter().cloned());
+        Self::save(project_id, &pending)?;
+
+        Ok(proposed)
+    }
+
+    /// Write the selected proposals to disk and drop them from the pending list.
+    /// Returns the paths that were written.
+    pub fn apply(project_id: &str, ids: &[String]) -> Result<Vec<String>> {
+        let pending = Self::list(project_id)?;
+        let (selected, remaining): (Vec<_>, Vec<_>) =
+            pending.into_iter().partition(|c| ids.contains(&c.id));
+
+        let mut applied = Vec::new();
+        for change in &selected {
+            FileService::write_file(project_id, &change.path, &change.content)?;
+            applied.push(change.path.clone());
+        }
+
+        Self::save(project_id, &remaining)?;
+        Self::record_applied(project_id, &applied, "review")?;
+        Ok(applied)
+    }
+
+    /// Append written paths to the applied-changes history
+    pub fn record_applied(project_id: &str, paths: &[String], source: &str) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now().to_rfc3339();
+        let mut history = Self::list_applied(project_id)?;
+        history.extend(paths.iter().map(|path| AppliedFileChange {
+            path: path.clone(),
+            applied: now.clone(),
+            source: source.to_string(),
+        }));
+
+        let path = Self::applied_path(project_id)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&history)?;
+        write_atomic(&path, content).context("Failed to write applied changes")
+    }
+
+    /// Every file change applied in the project, oldest first
+    pub fn list_applied(project_id: &str) -> Result<Vec<AppliedFileChange>> {
+        let path = Self::applied_path(project_id)?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read applied changes")?;
+        serde_json::from_str(&content).context("Failed to parse applied changes")
+    }
+
+    fn applied_path(project_id: &str) -> Result<PathBuf> {
+        Ok(Self::store_path(project_id)?.with_file_name("applied_changes.json"))
+    }
+
+    /// Drop the selected proposals without writing them
+    pub fn discard(project_id: &str, ids: &[String]) -> Result<()> {
+        let remaining: Vec<_> = Self::list(project_id)?
+            .into_iter()
+            .filter(|c| !ids.contains(&c.id))
+            .collect();
+        Self::save(project_id, &remaining)
+    }
+}
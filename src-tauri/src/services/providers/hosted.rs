@@ -1,10 +1,15 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use std::future::Future;
+use std::time::Duration;
 
 use crate::models::ai::{ChatResponse, HostedConfig, ProviderType};
 use crate::services::ai_provider::AIProvider;
-use crate::services::claude_service::ClaudeService;
-use crate::services::secrets_service::SecretsService;
+use crate::services::api_key_pool::ApiKeyPool;
+use crate::services::claude_service::{ClaudeApiError, ClaudeService, RetryPolicy};
+
+const MISSING_KEY_MESSAGE: &str =
+    "API key not found. Please ensure 'Anthropic API Key' is set in Settings -> API Configuration.";
 
 pub struct HostedAPIProvider {
     pub config: HostedConfig,
@@ -14,6 +19,62 @@ impl HostedAPIProvider {
     pub fn new(config: HostedConfig) -> Self {
         Self { config }
     }
+
+    fn claude_service(&self, api_key: String, retry_policy: RetryPolicy) -> ClaudeService {
+        ClaudeService::new(api_key, self.config.model.clone())
+            .with_retry_policy(retry_policy)
+            .with_prompt_cache_threshold(self.config.prompt_cache_min_chars)
+            .with_beta_flags(self.config.beta_flags.clone())
+            .with_extra_headers(self.config.extra_headers.clone())
+    }
+
+    /// Run `send` with each stored key in rotation order, moving on to the next key
+    /// whenever one is rate limited or the API is overloaded. Only the last key backs off
+    /// and retries, so spare keys are tried before waiting.
+    async fn with_key_rotation<T, F, Fut>(&self, send: F) -> Result<T>
+    where
+        F: Fn(ClaudeService) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let keys = ApiKeyPool::rotation(&self.config.api_key_secret_id, self.config.key_rotation)?;
+        let retry_policy = RetryPolicy {
+            max_retries: self.config.max_retries,
+            base_delay: Duration::from_millis(self.config.retry_base_delay_ms),
+        };
+        Self::rotate(keys, retry_policy, |key, policy| send(self.claude_service(key, policy))).await
+    }
+
+    async fn rotate<T, F, Fut>(keys: Vec<(String, String)>, retry_policy: RetryPolicy, send: F) -> Result<T>
+    where
+        F: Fn(String, RetryPolicy) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+        let last = keys.len().saturating_sub(1);
+        for (i, (id, key)) in keys.into_iter().enumerate() {
+            let policy = if i == last { retry_policy } else { RetryPolicy::NONE };
+            match send(key.clone(), policy).await {
+                Err(e) if i < last && ClaudeApiError::is_retryable_error(&e) => {
+                    // An overloaded API is not the key's fault, so only 429s put it on cooldown
+                    if ClaudeApiError::is_rate_limited(&e) {
+                        log::warn!("API key {} is rate limited, trying the next key", ApiKeyPool::redact(&key));
+                        ApiKeyPool::mark_rate_limited(&id);
+                    } else {
+                        log::warn!("API is overloaded for key {}, trying the next key", ApiKeyPool::redact(&key));
+                    }
+                    last_error = Some(e);
+                }
+                result => {
+                    if result.is_ok() {
+                        ApiKeyPool::mark_served(&id, &key);
+                    }
+                    return result;
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!(MISSING_KEY_MESSAGE)))
+    }
 }
 
 use crate::models::ai::chat_models::{ChatRequest, ProviderCapability, ProviderMetadata};
@@ -24,32 +85,23 @@ impl AIProvider for HostedAPIProvider {
         &self,
         request: ChatRequest,
     ) -> Result<ChatResponse> {
-        let api_key = match SecretsService::get_secret(&self.config.api_key_secret_id)? {
-            Some(key) => key,
-            None => {
-                SecretsService::get_secret("claude_api_key")?
-                    .or(SecretsService::get_secret("ANTHROPIC_API_KEY")?)
-                    .ok_or_else(|| anyhow!("API key not found. Please ensure 'Anthropic API Key' is set in Settings -> API Configuration."))?
-            }
-        };
-
-        let model_id = match self.config.model.as_str() {
-            "claude-3-opus" => "claude-3-opus-20240229",
-            "claude-3-sonnet" => "claude-3-sonnet-20240229",
-            "claude-3-haiku" => "claude-3-haiku-20240307",
-            "claude-3-5-sonnet" => "claude-3-5-sonnet-20241022",
-            m => m,
-        };
-
-        let service = ClaudeService::new(api_key, model_id.to_string());
-        
         let token = tokio_util::sync::CancellationToken::new();
         crate::services::cancellation_service::CancellationService::global()
             .register_token("chat".to_string(), token.clone())
             .await;
 
+        let send = self.with_key_rotation(|service| {
+            let (messages, system_prompt, tools, options) = (
+                request.messages.clone(),
+                request.system_prompt.clone(),
+                request.tools.clone(),
+                request.options.clone(),
+            );
+            async move { service.send_message_sync(messages, system_prompt, tools, &options).await }
+        });
+
         tokio::select! {
-            result = service.send_message_sync(request.messages, request.system_prompt, request.tools) => {
+            result = send => {
                 {
                     let manager = crate::services::cancellation_service::CANCELLATION_MANAGER.clone();
                     let mut tokens = manager.active_tokens.lock().await;
@@ -67,26 +119,20 @@ impl AIProvider for HostedAPIProvider {
         &self,
         request: ChatRequest,
     ) -> Result<std::pin::Pin<Box<dyn futures_util::Stream<Item = Result<String>> + Send>>> {
-        let api_key = match SecretsService::get_secret(&self.config.api_key_secret_id)? {
-            Some(key) => key,
-            None => {
-                SecretsService::get_secret("claude_api_key")?
-                    .or(SecretsService::get_secret("ANTHROPIC_API_KEY")?)
-                    .ok_or_else(|| anyhow!("API key not found. Please ensure 'Anthropic API Key' is set in Settings -> API Configuration."))?
-            }
-        };
-
-        let model_id = match self.config.model.as_str() {
-            "claude-3-opus" => "claude-3-opus-20240229",
-            "claude-3-sonnet" => "claude-3-sonnet-20240229",
-            "claude-3-haiku" => "claude-3-haiku-20240307",
-            "claude-3-5-sonnet" => "claude-3-5-sonnet-20241022",
-            m => m,
-        };
+        self.with_key_rotation(|service| {
+            let (messages, system_prompt, tools, options) = (
+                request.messages.clone(),
+                request.system_prompt.clone(),
+                request.tools.clone(),
+                request.options.clone(),
+            );
+            async move { service.send_message_stream(messages, system_prompt, tools, &options).await }
+        })
+        .await
+    }
 
-        let service = ClaudeService::new(api_key, model_id.to_string());
-        let stream = service.send_message_stream(request.messages, request.system_prompt, request.tools).await?;
-        Ok(stream)
+    async fn resolve_model(&self) -> String {
+        self.config.model.clone()
     }
 
     async fn list_models(&self) -> Result<Vec<String>> {
@@ -97,16 +143,19 @@ impl AIProvider for HostedAPIProvider {
         true
     }
 
+    fn streams_tool_calls(&self) -> bool {
+        true
+    }
+
     fn provider_type(&self) -> ProviderType {
         ProviderType::HostedApi
     }
 
     fn is_available(&self) -> bool {
         // Hosted API is available if an API key can be resolved
-        let key = SecretsService::get_secret(&self.config.api_key_secret_id).ok().flatten()
-            .or(SecretsService::get_secret("claude_api_key").ok().flatten())
-            .or(SecretsService::get_secret("ANTHROPIC_API_KEY").ok().flatten());
-        key.is_some()
+        ApiKeyPool::keys(&self.config.api_key_secret_id)
+            .map(|keys| !keys.is_empty())
+            .unwrap_or(false)
     }
 
     async fn check_authentication(&self) -> Result<bool> {
@@ -127,3 +176,35 @@ impl AIProvider for HostedAPIProvider {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_overloaded_key_rotates_to_the_next() {
+        let keys = vec![
+            ("first".to_string(), "sk-first-key-0001".to_string()),
+            ("second".to_string(), "sk-second-key-0002".to_string()),
+        ];
+        let tried = Mutex::new(Vec::new());
+
+        let result = HostedAPIProvider::rotate(keys, RetryPolicy::default(), |key, policy| {
+            tried.lock().unwrap().push((key.clone(), policy));
+            async move {
+                if key == "sk-first-key-0001" {
+                    Err(ClaudeApiError { status: 529, body: "overloaded".to_string(), retry_after: None }.into())
+                } else {
+                    Ok(key)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "sk-second-key-0002");
+        let tried = tried.into_inner().unwrap();
+        assert_eq!(tried[0], ("sk-first-key-0001".to_string(), RetryPolicy::NONE));
+        assert_eq!(tried[1], ("sk-second-key-0002".to_string(), RetryPolicy::default()));
+    }
+}
@@ -176,6 +176,7 @@ impl AIProvider for LiteLlmProvider {
                 tokens_cache_read,
                 tokens_cache_write,
                 tokens_reasoning,
+                tool_iterations: 0,
             })
         } else {
             None
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::models::ai::{ChatResponse, ProviderType};
+use crate::models::ai::chat_models::{ChatRequest, HealthStatus, ProviderCapability, ProviderMetadata};
+use crate::services::ai_provider::AIProvider;
+
+/// Offline provider that never touches the network.
+///
+/// By default it echoes the last user message (and any tools offered to it) back as a
+/// deterministic canned response. Tests can queue scripted responses with
+/// [`MockProvider::with_responses`]; those are returned in order before falling back to echo.
+/// Every request received is recorded and can be inspected through [`MockProvider::request_log`].
+pub struct MockProvider {
+    scripted: Mutex<VecDeque<ChatResponse>>,
+    requests: Arc<Mutex<Vec<ChatRequest>>>,
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self::with_responses(Vec::new())
+    }
+
+    pub fn with_responses(responses: Vec<ChatResponse>) -> Self {
+        Self {
+            scripted: Mutex::new(responses.into()),
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Shared handle to the requests this provider has received, in order
+    pub fn request_log(&self) -> Arc<Mutex<Vec<ChatRequest>>> {
+        self.requests.clone()
+    }
+
+    fn echo_response(request: &ChatRequest) -> ChatResponse {
+        let last_user = request
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "user")
+            .map(|m| m.content.as_str())
+            .unwrap_or("");
+
+        let mut content = format!("[mock] You said: {}", last_user);
+
+        if let Some(tools) = &request.tools {
+            if !tools.is_empty() {
+                let names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+                content.push_str(&format!("\n\n[mock] Available tools: {}", names.join(", ")));
+            }
+        }
+
+        ChatResponse {
+            content,
+            tool_calls: None,
+            metadata: None,
+        }
+    }
+
+    fn next_response(&self, request: &ChatRequest) -> ChatResponse {
+        self.requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(request.clone());
+
+        let scripted = self
+            .scripted
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .pop_front();
+        scripted.unwrap_or_else(|| Self::echo_response(request))
+    }
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AIProvider for MockProvider {
+    async fn chat(
+        &self,
+        request: ChatRequest,
+    ) -> Result<ChatResponse> {
+        Ok(self.next_response(&request))
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<std::pin::Pin<Box<dyn futures_util::Stream<Item = Result<String>> + Send>>> {
+        let response = self.next_response(&request);
+        let chunks: Vec<Result<String>> = response
+            .content
+            .split_inclusive(' ')
+            .map(|chunk| Ok(chunk.to_string()))
+            .collect();
+        Ok(Box::pin(futures_util::stream::iter(chunks)))
+    }
+
+    async fn resolve_model(&self) -> String {
+        "mock-echo".to_string()
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        Ok(vec!["mock-echo".to_string()])
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::Mock
+    }
+
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn check_authentication(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn check_health(&self) -> Result<HealthStatus> {
+        Ok(HealthStatus::Healthy)
+    }
+
+    fn metadata(&self) -> ProviderMetadata {
+        ProviderMetadata {
+            id: "mock".to_string(),
+            name: "Offline Echo (Mock)".to_string(),
+            description: "Deterministic offline provider for demos and tests. Echoes your last message.".to_string(),
+            capabilities: vec![
+                ProviderCapability::Chat,
+                ProviderCapability::Stream,
+            ],
+            models: vec!["mock-echo".to_string()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ai::chat_models::ChatOptions;
+    use crate::models::ai::{Message, Tool};
+    use futures_util::StreamExt;
+
+    fn request(text: &str, tools: Option<Vec<Tool>>) -> ChatRequest {
+        ChatRequest {
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: text.to_string(),
+                tool_calls: None,
+                tool_results: None,
+            }],
+            system_prompt: None,
+            tools,
+            project_path: None,
+            options: ChatOptions::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_echoes_last_user_message() {
+        let provider = MockProvider::new();
+        let response = provider.chat(request("hello there", None)).await.unwrap();
+        assert_eq!(response.content, "[mock] You said: hello there");
+        assert!(response.tool_calls.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lists_detected_tools() {
+        let provider = MockProvider::new();
+        let tools = vec![Tool {
+            name: "search".to_string(),
+            description: "Search the web".to_string(),
+            input_schema: serde_json::json!({}),
+            tool_type: "function".to_string(),
+        }];
+        let response = provider.chat(request("hi", Some(tools))).await.unwrap();
+        assert!(response.content.contains("Available tools: search"));
+    }
+
+    #[tokio::test]
+    async fn test_scripted_responses_then_echo() {
+        let provider = MockProvider::with_responses(vec![ChatResponse {
+            content: "scripted".to_string(),
+            tool_calls: None,
+            metadata: None,
+        }]);
+        assert_eq!(provider.chat(request("a", None)).await.unwrap().content, "scripted");
+        assert_eq!(
+            provider.chat(request("b", None)).await.unwrap().content,
+            "[mock] You said: b"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_reassembles_to_echo() {
+        let provider = MockProvider::new();
+        let stream = provider.chat_stream(request("stream me", None)).await.unwrap();
+        let chunks: Vec<String> = stream.map(|c| c.unwrap()).collect().await;
+        assert_eq!(chunks.concat(), "[mock] You said: stream me");
+    }
+}
@@ -3,6 +3,8 @@ pub mod custom_cli;
 pub mod gemini_cli;
 pub mod hosted;
 pub mod litellm;
+pub mod mock;
 pub mod ollama;
 pub mod openai_cli;
+pub mod openai_compatible;
 pub mod cli_executor;
@@ -2,10 +2,40 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::json;
+use thiserror::Error;
 
 use crate::models::ai::{ChatResponse, OllamaConfig, ProviderType};
 use crate::services::ai_provider::AIProvider;
 
+/// Returned when the configured model hasn't been pulled, so callers can offer to pull it
+#[derive(Debug, Error)]
+#[error("Ollama model '{model}' is not installed. Run `ollama pull {model}` in a terminal, or enable automatic model downloads in Settings → Ollama.")]
+pub struct OllamaModelMissing {
+    pub model: String,
+}
+
+/// A progress update from `ollama pull`
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaPullProgress {
+    pub model: String,
+    pub status: String,
+    /// 0-100 while a layer is downloading
+    pub percent: Option<u8>,
+}
+
+/// A model currently loaded by Ollama, as reported by `/api/ps`
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OllamaRunningModel {
+    pub name: String,
+    /// Total memory used by the loaded model, in bytes
+    pub size_bytes: u64,
+    /// Portion of `size_bytes` held in GPU memory
+    pub size_vram_bytes: u64,
+    pub expires_at: Option<String>,
+}
+
 pub struct OllamaProvider {
     pub config: OllamaConfig,
     client: Client,
@@ -18,6 +48,197 @@ impl OllamaProvider {
             client: Client::new(),
         }
     }
+
+    /// Ollama answers 404 with `model "x" not found, try pulling it first`
+    fn is_model_missing(status: reqwest::StatusCode, body: &str) -> bool {
+        let body = body.to_lowercase();
+        status == reqwest::StatusCode::NOT_FOUND
+            && (body.contains("not found") || body.contains("try pulling"))
+    }
+
+    /// Turn a failed `/api/chat` response into an error, singling out missing models
+    async fn chat_error(&self, res: reqwest::Response) -> anyhow::Error {
+        let status = res.status();
+        let text = res.text().await.unwrap_or_default();
+        if Self::is_model_missing(status, &text) {
+            return OllamaModelMissing {
+                model: self.config.model.clone(),
+            }
+            .into();
+        }
+
+        if let Some(guidance) = Self::memory_guidance(&self.config.model, &text) {
+            return anyhow!("{}\n\nDetails: {}", guidance, text);
+        }
+
+        let err_msg = format!("HTTP {}: {}", status, text);
+        crate::services::ai_error_service::AIErrorService::map_error(
+            &err_msg,
+            &self.provider_type(),
+            Some(&self.config.model),
+        )
+    }
+
+    /// Explain out-of-memory failures, which Ollama reports in several low-level ways
+    fn memory_guidance(model: &str, body: &str) -> Option<String> {
+        let lower = body.to_lowercase();
+        let out_of_memory = lower.contains("requires more system memory")
+            || lower.contains("out of memory")
+            || lower.contains("cudamalloc failed")
+            || lower.contains("insufficient memory")
+            || lower.contains("failed to allocate")
+            || (lower.contains("llama runner process has terminated") && lower.contains("killed"));
+        if !out_of_memory {
+            return None;
+        }
+
+        let base = model.split(':').next().unwrap_or(model);
+        let requirement = match (
+            Self::memory_amount_after(&lower, "requires more system memory ("),
+            Self::memory_amount_after(&lower, "than is available ("),
+        ) {
+            (Some(required), Some(available)) => format!(
+                "it requires ~{} of memory but only {} is available",
+                required, available
+            ),
+            (Some(required), None) => format!("it requires ~{} of memory", required),
+            _ => "there isn't enough RAM/VRAM to load it".to_string(),
+        };
+
+        Some(format!(
+            "Ollama couldn't load model '{}': {}. Try a smaller quantization (e.g. `{}:q4_0`) or a smaller model, or free memory by closing other apps and unloading idle models.",
+            model, requirement, base
+        ))
+    }
+
+    /// Read an amount like `5.6 gib` that follows `marker` and format it as GB
+    fn memory_amount_after(text: &str, marker: &str) -> Option<String> {
+        let start = text.find(marker)? + marker.len();
+        let amount = &text[start..start + text[start..].find(')')?];
+        let (value, unit) = amount.trim().split_once(' ')?;
+        let value: f64 = value.parse().ok()?;
+        let gb = match unit.trim() {
+            "gib" | "gb" => value,
+            "mib" | "mb" => value / 1024.0,
+            _ => return None,
+        };
+        Some(format!("{:.1} GB", gb))
+    }
+
+    /// Models currently loaded into memory, from `/api/ps`
+    pub async fn running_models(&self) -> Result<Vec<OllamaRunningModel>> {
+        let url = format!("{}/api/ps", self.config.api_url.trim_end_matches('/'));
+        let res = self.client.get(&url).send().await?;
+        if !res.status().is_success() {
+            return Err(anyhow!("Failed to list running models: {}", res.status()));
+        }
+
+        let res_json: serde_json::Value = res.json().await?;
+        Ok(Self::parse_running_models(&res_json))
+    }
+
+    fn parse_running_models(res_json: &serde_json::Value) -> Vec<OllamaRunningModel> {
+        res_json
+            .get("models")
+            .and_then(|v| v.as_array())
+            .map(|list| {
+                list.iter()
+                    .filter_map(|item| {
+                        Some(OllamaRunningModel {
+                            name: item.get("name")?.as_str()?.to_string(),
+                            size_bytes: item.get("size").and_then(|v| v.as_u64()).unwrap_or(0),
+                            size_vram_bytes: item.get("size_vram").and_then(|v| v.as_u64()).unwrap_or(0),
+                            expires_at: item
+                                .get("expires_at")
+                                .and_then(|v| v.as_str())
+                                .map(str::to_string),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Download `model` via `/api/pull`, calling `on_progress` when the status changes or a
+    /// download advances by at least 10%. Returns once the pull has finished.
+    pub async fn pull_model<F>(&self, model: &str, on_progress: F) -> Result<()>
+    where
+        F: Fn(OllamaPullProgress),
+    {
+        use futures_util::StreamExt;
+
+        let url = format!("{}/api/pull", self.config.api_url.trim_end_matches('/'));
+        let response = self
+            .client
+            .post(&url)
+            .json(&json!({ "model": model, "stream": true }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Failed to pull Ollama model '{}': HTTP {}: {}", model, status, text));
+        }
+
+        let stream_reader = tokio_util::io::StreamReader::new(futures_util::TryStreamExt::map_err(
+            response.bytes_stream(),
+            |e| std::io::Error::new(std::io::ErrorKind::Other, e),
+        ));
+        let mut reader =
+            tokio_util::codec::FramedRead::new(stream_reader, tokio_util::codec::LinesCodec::new());
+
+        let mut last: Option<OllamaPullProgress> = None;
+        while let Some(line) = reader.next().await {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let val: serde_json::Value = serde_json::from_str(&line)?;
+            if let Some(err) = val.get("error").and_then(|e| e.as_str()) {
+                return Err(anyhow!("Failed to pull Ollama model '{}': {}", model, err));
+            }
+
+            let progress = Self::parse_pull_progress(model, &val);
+            if Self::should_report(last.as_ref(), &progress) {
+                on_progress(progress.clone());
+                last = Some(progress);
+            }
+            if val.get("status").and_then(|s| s.as_str()) == Some("success") {
+                return Ok(());
+            }
+        }
+
+        Err(anyhow!("Ollama pull of '{}' ended before completing", model))
+    }
+
+    fn parse_pull_progress(model: &str, val: &serde_json::Value) -> OllamaPullProgress {
+        let completed = val.get("completed").and_then(|v| v.as_u64());
+        let total = val.get("total").and_then(|v| v.as_u64()).filter(|t| *t > 0);
+        OllamaPullProgress {
+            model: model.to_string(),
+            status: val
+                .get("status")
+                .and_then(|s| s.as_str())
+                .unwrap_or("pulling")
+                .to_string(),
+            percent: completed
+                .zip(total)
+                .map(|(done, total)| (done.min(total) * 100 / total) as u8),
+        }
+    }
+
+    fn should_report(last: Option<&OllamaPullProgress>, next: &OllamaPullProgress) -> bool {
+        match last {
+            None => true,
+            Some(last) if last.status != next.status => true,
+            Some(last) => match (last.percent, next.percent) {
+                (Some(prev), Some(now)) => now >= prev.saturating_add(10) || (now == 100 && prev != 100),
+                (None, Some(_)) => true,
+                _ => false,
+            },
+        }
+    }
 }
 
 use crate::models::ai::chat_models::{ChatRequest, HealthStatus, ProviderCapability, ProviderMetadata};
@@ -67,14 +288,7 @@ impl AIProvider for OllamaProvider {
         };
 
         if !res.status().is_success() {
-            let status = res.status();
-            let text = res.text().await.unwrap_or_default();
-            let err_msg = format!("HTTP {}: {}", status, text);
-            return Err(crate::services::ai_error_service::AIErrorService::map_error(
-                &err_msg,
-                &self.provider_type(),
-                Some(&self.config.model),
-            ));
+            return Err(self.chat_error(res).await);
         }
 
         let res_json: serde_json::Value = res.json().await?;
@@ -121,12 +335,13 @@ impl AIProvider for OllamaProvider {
         let response = self.client.post(&url).json(&body).send().await?;
 
         if !response.status().is_success() {
-            return Err(anyhow!("Ollama API error: status {}", response.status()));
+            return Err(self.chat_error(response).await);
         }
 
         use futures_util::StreamExt;
         let event_stream = response.bytes_stream();
 
+        let model = self.config.model.clone();
         let s = async_stream::try_stream! {
             let stream_reader = tokio_util::io::StreamReader::new(
                 futures_util::TryStreamExt::map_err(event_stream, |e| std::io::Error::new(std::io::ErrorKind::Other, e))
@@ -140,6 +355,12 @@ impl AIProvider for OllamaProvider {
                 let line = line?;
                 if line.trim().is_empty() { continue; }
                 let val: serde_json::Value = serde_json::from_str(&line)?;
+                if let Some(err) = val.get("error").and_then(|e| e.as_str()) {
+                    Err::<(), _>(match Self::memory_guidance(&model, err) {
+                        Some(guidance) => anyhow!("{}\n\nDetails: {}", guidance, err),
+                        None => anyhow!("Ollama error: {}", err),
+                    })?;
+                }
                 if let Some(msg) = val.get("message") {
                     if let Some(content) = msg.get("content").and_then(|c| c.as_str()) {
                         yield content.to_string();
@@ -214,3 +435,75 @@ impl AIProvider for OllamaProvider {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_missing_model_response() {
+        let body = r#"{"error":"model \"llama3\" not found, try pulling it first"}"#;
+        assert!(OllamaProvider::is_model_missing(reqwest::StatusCode::NOT_FOUND, body));
+        assert!(!OllamaProvider::is_model_missing(
+            reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+            body
+        ));
+
+        let err: anyhow::Error = OllamaModelMissing {
+            model: "llama3".to_string(),
+        }
+        .into();
+        assert!(err.to_string().contains("`ollama pull llama3`"));
+        assert!(err.downcast_ref::<OllamaModelMissing>().is_some());
+    }
+
+    #[test]
+    fn test_memory_errors_get_guidance() {
+        let body = r#"{"error":"model requires more system memory (5.6 GiB) than is available (3.2 GiB)"}"#;
+        let guidance = OllamaProvider::memory_guidance("llama3:8b", body).unwrap();
+        assert!(guidance.contains("requires ~5.6 GB of memory but only 3.2 GB is available"));
+        assert!(guidance.contains("`llama3:q4_0`"));
+
+        let cuda = OllamaProvider::memory_guidance("mistral", "CUDA error: out of memory").unwrap();
+        assert!(cuda.contains("there isn't enough RAM/VRAM"));
+
+        assert!(OllamaProvider::memory_guidance("llama3", "connection refused").is_none());
+    }
+
+    #[test]
+    fn test_parse_running_models() {
+        let models = OllamaProvider::parse_running_models(&json!({
+            "models": [{
+                "name": "llama3:latest",
+                "model": "llama3:latest",
+                "size": 5137025024u64,
+                "size_vram": 5137025024u64,
+                "expires_at": "2024-06-04T14:38:31.83753-07:00"
+            }]
+        }));
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "llama3:latest");
+        assert_eq!(models[0].size_vram_bytes, 5137025024);
+    }
+
+    #[test]
+    fn test_pull_progress_is_throttled() {
+        let line = |status: &str, completed: u64| {
+            OllamaProvider::parse_pull_progress(
+                "llama3",
+                &json!({ "status": status, "completed": completed, "total": 200 }),
+            )
+        };
+
+        let start = line("pulling abc", 0);
+        assert_eq!(start.percent, Some(0));
+        assert!(OllamaProvider::should_report(None, &start));
+        assert!(!OllamaProvider::should_report(Some(&start), &line("pulling abc", 10)));
+        assert!(OllamaProvider::should_report(Some(&start), &line("pulling abc", 20)));
+        assert!(OllamaProvider::should_report(Some(&start), &line("verifying sha256 digest", 200)));
+
+        let manifest =
+            OllamaProvider::parse_pull_progress("llama3", &json!({ "status": "pulling manifest" }));
+        assert_eq!(manifest.percent, None);
+    }
+}
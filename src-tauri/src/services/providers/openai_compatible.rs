@@ -0,0 +1,318 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+
+use crate::models::ai::chat_models::{ChatRequest, HealthStatus, ProviderCapability, ProviderMetadata};
+use crate::models::ai::{ChatResponse, GenerationMetadata, Message, OpenAiCompatibleConfig, ProviderType};
+use crate::services::ai_error_service::AIErrorService;
+use crate::services::ai_provider::AIProvider;
+use crate::services::secrets_service::SecretsService;
+
+/// Chat over OpenAI's `/v1/chat/completions` wire format, for self-hosted servers and
+/// gateways that mimic it
+pub struct OpenAiCompatibleProvider {
+    pub config: OpenAiCompatibleConfig,
+    client: Client,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn new(config: OpenAiCompatibleConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+
+    /// `{base_url}/v1/{path}`, without doubling a `/v1` already present in the base URL
+    fn endpoint(&self, path: &str) -> String {
+        let base = self.config.base_url.trim().trim_end_matches('/');
+        let base = base.strip_suffix("/v1").unwrap_or(base);
+        format!("{}/v1/{}", base, path)
+    }
+
+    fn wire_messages(messages: &[Message], system_prompt: Option<&str>) -> Vec<Value> {
+        let system = system_prompt.map(|s| json!({ "role": "system", "content": s }));
+        system
+            .into_iter()
+            .chain(messages.iter().map(|msg| {
+                json!({
+                    "role": msg.role.to_lowercase(),
+                    "content": msg.content,
+                })
+            }))
+            .collect()
+    }
+
+    fn request_body(&self, request: &ChatRequest, stream: bool) -> Value {
+        let mut body = json!({
+            "model": self.config.model,
+            "messages": Self::wire_messages(&request.messages, request.system_prompt.as_deref()),
+            "stream": stream,
+        });
+        let options = &request.options;
+        if let Some(temperature) = options.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(max_tokens) = options.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        if let Some(top_p) = options.top_p {
+            body["top_p"] = json!(top_p);
+        }
+        if !options.stop_sequences.is_empty() {
+            body["stop"] = json!(options.stop_sequences);
+        }
+        body
+    }
+
+    fn api_key(&self) -> Option<String> {
+        SecretsService::get_secret(&self.config.api_key_secret_id)
+            .ok()
+            .flatten()
+            .filter(|key| !key.is_empty())
+    }
+
+    fn with_auth(&self, builder: RequestBuilder) -> RequestBuilder {
+        match self.api_key() {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    async fn post_completion(&self, body: &Value) -> Result<reqwest::Response> {
+        if self.config.model.trim().is_empty() {
+            return Err(anyhow!(
+                "No model is configured for the OpenAI-compatible provider. Set one in Settings."
+            ));
+        }
+        let response = self
+            .with_auth(self.client.post(self.endpoint("chat/completions")))
+            .json(body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            let err_msg = format!("HTTP {}: {}", status, text);
+            return Err(AIErrorService::map_error(
+                &err_msg,
+                &self.provider_type(),
+                Some(&self.config.model),
+            ));
+        }
+        Ok(response)
+    }
+
+    fn parse_completion(json: &Value, model: &str) -> Result<ChatResponse> {
+        let content = json
+            .pointer("/choices/0/message/content")
+            .and_then(|c| c.as_str())
+            .ok_or_else(|| anyhow!("Response missing choices[0].message.content"))?
+            .to_string();
+
+        let metadata = json.get("usage").map(|usage| {
+            let count = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+            GenerationMetadata {
+                confidence: 1.0,
+                cost_usd: 0.0,
+                model_used: json
+                    .get("model")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or(model)
+                    .to_string(),
+                tokens_in: count("prompt_tokens"),
+                tokens_out: count("completion_tokens"),
+                tokens_cache_read: 0,
+                tokens_cache_write: 0,
+                tokens_reasoning: 0,
+                tool_iterations: 0,
+            }
+        });
+
+        Ok(ChatResponse {
+            content,
+            tool_calls: None,
+            metadata,
+        })
+    }
+}
+
+#[async_trait]
+impl AIProvider for OpenAiCompatibleProvider {
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let body = self.request_body(&request, false);
+        let response = self.post_completion(&body).await?;
+        let json: Value = response.json().await?;
+        Self::parse_completion(&json, &self.config.model)
+    }
+
+    async fn chat_stream(
+        &self,
+        request: ChatRequest,
+    ) -> Result<std::pin::Pin<Box<dyn futures_util::Stream<Item = Result<String>> + Send>>> {
+        let body = self.request_body(&request, true);
+        let cancel = request.options.cancel.clone().unwrap_or_default();
+        let response = self.post_completion(&body).await?;
+
+        use futures_util::StreamExt;
+        let event_stream = response.bytes_stream();
+
+        let s = async_stream::try_stream! {
+            let stream_reader = tokio_util::io::StreamReader::new(
+                futures_util::TryStreamExt::map_err(event_stream, |e| std::io::Error::new(std::io::ErrorKind::Other, e))
+            );
+            let mut reader = tokio_util::codec::FramedRead::new(
+                stream_reader,
+                tokio_util::codec::LinesCodec::new()
+            );
+
+            loop {
+                let next = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => None,
+                    line = reader.next() => line,
+                };
+                let Some(line) = next else { break };
+                let line = line?;
+                let Some(data) = line.trim().strip_prefix("data:") else { continue };
+                let data = data.trim();
+                if data == "[DONE]" { break; }
+                let val: Value = serde_json::from_str(data)?;
+                if let Some(content) = val.pointer("/choices/0/delta/content").and_then(|c| c.as_str()) {
+                    yield content.to_string();
+                }
+            }
+        };
+
+        Ok(Box::pin(s))
+    }
+
+    async fn resolve_model(&self) -> String {
+        self.config.model.clone()
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let response = self
+            .with_auth(self.client.get(self.endpoint("models")))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Ok(vec![self.config.model.clone()]);
+        }
+        let json: Value = response.json().await?;
+        let models = json
+            .get("data")
+            .and_then(|d| d.as_array())
+            .map(|data| {
+                data.iter()
+                    .filter_map(|m| m.get("id").and_then(|id| id.as_str()))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(models)
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::OpenAiCompatible
+    }
+
+    fn is_available(&self) -> bool {
+        !self.config.base_url.trim().is_empty() && !self.config.model.trim().is_empty()
+    }
+
+    /// Many local servers need no key, so a missing one isn't an authentication failure
+    async fn check_authentication(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn check_health(&self) -> Result<HealthStatus> {
+        match self.with_auth(self.client.get(self.endpoint("models"))).send().await {
+            Ok(res) if res.status().is_success() => Ok(HealthStatus::Healthy),
+            Ok(res) => Ok(HealthStatus::Degraded(format!("Server returned status {}", res.status()))),
+            Err(e) => Ok(HealthStatus::Unhealthy(format!("Server unreachable: {}", e))),
+        }
+    }
+
+    fn metadata(&self) -> ProviderMetadata {
+        ProviderMetadata {
+            id: "openai-compatible".to_string(),
+            name: "OpenAI-Compatible Server".to_string(),
+            description: "Any server exposing /v1/chat/completions, such as vLLM, LM Studio or llama.cpp.".to_string(),
+            capabilities: vec![ProviderCapability::Chat, ProviderCapability::Stream],
+            models: vec![self.config.model.clone()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(base_url: &str) -> OpenAiCompatibleProvider {
+        OpenAiCompatibleProvider::new(OpenAiCompatibleConfig {
+            base_url: base_url.to_string(),
+            model: "qwen2.5-7b-instruct".to_string(),
+            api_key_secret_id: "test-key".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_endpoint_does_not_double_v1() {
+        assert_eq!(
+            provider("http://localhost:8000").endpoint("chat/completions"),
+            "http://localhost:8000/v1/chat/completions"
+        );
+        assert_eq!(
+            provider("http://localhost:1234/v1/").endpoint("chat/completions"),
+            "http://localhost:1234/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_request_body_maps_messages_and_options() {
+        let request = ChatRequest {
+            messages: vec![Message {
+                role: "User".to_string(),
+                content: "Hi".to_string(),
+                tool_calls: None,
+                tool_results: None,
+            }],
+            system_prompt: Some("Be brief".to_string()),
+            tools: None,
+            project_path: None,
+            options: crate::models::ai::chat_models::ChatOptions {
+                max_tokens: Some(256),
+                stop_sequences: vec!["END".to_string()],
+                ..Default::default()
+            },
+        };
+
+        let body = provider("http://localhost:8000").request_body(&request, false);
+
+        assert_eq!(body["model"], "qwen2.5-7b-instruct");
+        assert_eq!(body["messages"][0], json!({ "role": "system", "content": "Be brief" }));
+        assert_eq!(body["messages"][1], json!({ "role": "user", "content": "Hi" }));
+        assert_eq!(body["max_tokens"], 256);
+        assert_eq!(body["stop"], json!(["END"]));
+        assert!(body.get("temperature").is_none());
+    }
+
+    #[test]
+    fn test_parse_completion() {
+        let json = json!({
+            "model": "served-model",
+            "choices": [{ "message": { "role": "assistant", "content": "Hello" } }],
+            "usage": { "prompt_tokens": 12, "completion_tokens": 3 }
+        });
+        let response = OpenAiCompatibleProvider::parse_completion(&json, "configured").unwrap();
+        assert_eq!(response.content, "Hello");
+        let metadata = response.metadata.unwrap();
+        assert_eq!(metadata.model_used, "served-model");
+        assert_eq!((metadata.tokens_in, metadata.tokens_out), (12, 3));
+
+        assert!(OpenAiCompatibleProvider::parse_completion(&json!({ "choices": [] }), "m").is_err());
+    }
+}
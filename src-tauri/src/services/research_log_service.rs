@@ -1,35 +1,75 @@
+use crate::models::ai::{GenerationMetadata, TokenUsage};
+use crate::models::settings::LoggingPrivacyLevel;
 use crate::services::project_service::ProjectService;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use std::fs;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Running token totals for the project, under `.metadata/`
+const TOKEN_TOTALS_FILE: &str = "token_usage.json";
 
 pub struct ResearchLogService;
 
 impl ResearchLogService {
-    /// Append a log entry to research_log.md in the project directory
-    pub fn log_event(
+    /// Record a chat turn as allowed by the logging privacy level: the full output,
+    /// only its metadata, or nothing
+    pub fn log_turn(
         project_id: &str,
         provider_name: &str,
-        command: Option<&str>,
+        level: LoggingPrivacyLevel,
         content: &str,
+        metadata: Option<&GenerationMetadata>,
     ) -> Result<()> {
-        let project = ProjectService::load_project_by_id(project_id)
-            .context("Failed to load project for logging")?;
-
-        let log_path = project.path.join("research_log.md");
+        match level {
+            LoggingPrivacyLevel::Full => {
+                Self::write_entry(project_id, provider_name, None, content, metadata)
+            }
+            LoggingPrivacyLevel::MetadataOnly => {
+                Self::log_metadata(project_id, provider_name, "completed", metadata)
+            }
+            LoggingPrivacyLevel::Off => Ok(()),
+        }
+    }
 
-        // Ensure file exists with header if it doesn't
-        if !log_path.exists() {
-            fs::write(&log_path, format!("# Research Log: {}\n\nThis file tracks automatic agent interactions and observations.\n\n", project.name))?;
+    /// Record a failed chat turn as allowed by the logging privacy level
+    pub fn log_failure(
+        project_id: &str,
+        provider_name: &str,
+        level: LoggingPrivacyLevel,
+        error: &str,
+    ) -> Result<()> {
+        match level {
+            LoggingPrivacyLevel::Full => {
+                Self::log_event(project_id, provider_name, None, &format!("ERROR: {}", error))
+            }
+            LoggingPrivacyLevel::MetadataOnly => {
+                Self::log_metadata(project_id, provider_name, "failed", None)
+            }
+            LoggingPrivacyLevel::Off => Ok(()),
         }
+    }
 
-        let mut file = OpenOptions::new()
-            .append(true)
-            .open(&log_path)
-            .context("Failed to open research_log.md for appending")?;
+    /// Append a log entry to research_log.md in the project directory
+    pub fn log_event(
+        project_id: &str,
+        provider_name: &str,
+        command: Option<&str>,
+        content: &str,
+    ) -> Result<()> {
+        Self::write_entry(project_id, provider_name, command, content, None)
+    }
 
+    fn write_entry(
+        project_id: &str,
+        provider_name: &str,
+        command: Option<&str>,
+        content: &str,
+        metadata: Option<&GenerationMetadata>,
+    ) -> Result<()> {
+        let (mut file, project_path) = Self::open_log(project_id)?;
         let timestamp = Utc::now().to_rfc3339();
 
         writeln!(file, "---")?;
@@ -38,10 +78,74 @@ impl ResearchLogService {
         if let Some(cmd) = command {
             writeln!(file, "**Command**: `{}`", cmd)?;
         }
+        if let Some(meta) = metadata {
+            writeln!(file, "**Tokens**: {} in / {} out", meta.tokens_in, meta.tokens_out)?;
+            Self::write_total(&mut file, &project_path, meta)?;
+        }
         writeln!(file, "\n#### Agent Output:\n")?;
         writeln!(file, "{}", content)?;
         writeln!(file, "\n")?;
 
         Ok(())
     }
+
+    /// Append an entry recording that a turn happened, without its prompt or output
+    fn log_metadata(
+        project_id: &str,
+        provider_name: &str,
+        status: &str,
+        metadata: Option<&GenerationMetadata>,
+    ) -> Result<()> {
+        let (mut file, project_path) = Self::open_log(project_id)?;
+        let timestamp = Utc::now().to_rfc3339();
+
+        writeln!(file, "---")?;
+        writeln!(file, "### Interaction: {}", timestamp)?;
+        writeln!(file, "**Provider**: {}", provider_name)?;
+        writeln!(file, "**Status**: {}", status)?;
+        if let Some(meta) = metadata {
+            writeln!(file, "**Model**: {}", meta.model_used)?;
+            writeln!(file, "**Tokens**: {} in / {} out", meta.tokens_in, meta.tokens_out)?;
+            Self::write_total(&mut file, &project_path, meta)?;
+            writeln!(file, "**Cost**: ${:.4}", meta.cost_usd)?;
+        }
+        writeln!(file, "\n_Content not recorded (logging privacy level: metadata only)._\n")?;
+
+        Ok(())
+    }
+
+    /// Add the turn to the project's running token total and log the new total
+    fn write_total(file: &mut File, project_path: &Path, meta: &GenerationMetadata) -> Result<()> {
+        let path = project_path.join(".metadata").join(TOKEN_TOTALS_FILE);
+        let mut total: TokenUsage = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        total.add(TokenUsage::from(meta));
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(&total)?)?;
+        writeln!(file, "**Total Tokens**: {} in / {} out", total.input, total.output)?;
+        Ok(())
+    }
+
+    fn open_log(project_id: &str) -> Result<(File, PathBuf)> {
+        let project = ProjectService::load_project_by_id(project_id)
+            .context("Failed to load project for logging")?;
+
+        let log_path = project.path.join("research_log.md");
+
+        // Ensure file exists with header if it doesn't
+        if !log_path.exists() {
+            fs::write(&log_path, format!("# Research Log: {}\n\nThis file tracks automatic agent interactions and observations.\n\n", project.name))?;
+        }
+
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&log_path)
+            .context("Failed to open research_log.md for appending")?;
+        Ok((file, project.path))
+    }
 }
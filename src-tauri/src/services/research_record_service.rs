@@ -0,0 +1,341 @@
+//! Research Record Service - A citable export of a project's AI-assisted work
+//!
+//! Bundles the research log, token usage from the cost log, chats and the history of
+//! applied file changes into one JSON document or a readable markdown report, written
+//! to `.metadata/exports/` in the project.
+
+use crate::models::chat::ChatMessage;
+use crate::models::cost::{CostLog, CostRecord};
+use crate::services::chat_service::{ChatMetadata, ChatService};
+use crate::services::project_service::ProjectService;
+use crate::services::proposed_changes_service::{AppliedFileChange, ProposedChangesService};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResearchRecordFormat {
+    Json,
+    Markdown,
+}
+
+/// One interaction from `research_log.md`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchLogEntry {
+    pub timestamp: String,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub command: Option<String>,
+    pub status: Option<String>,
+    pub tokens_in: Option<u64>,
+    pub tokens_out: Option<u64>,
+    pub cost_usd: Option<f64>,
+    /// Agent output, absent when the log only recorded metadata
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatRecord {
+    pub file_name: String,
+    pub metadata: Option<ChatMetadata>,
+    pub messages: Vec<ChatMessage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    pub interactions: usize,
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResearchRecord {
+    pub project_id: String,
+    pub project_name: String,
+    pub exported_at: String,
+    pub interactions: Vec<ResearchLogEntry>,
+    /// Per-request token usage from the project's cost log
+    pub usage: Vec<CostRecord>,
+    pub totals: UsageTotals,
+    pub chats: Vec<ChatRecord>,
+    pub applied_changes: Vec<AppliedFileChange>,
+}
+
+pub struct ResearchRecordService;
+
+impl ResearchRecordService {
+    /// Gather the record and write it in `format`, returning the path of the export
+    pub async fn export(project_id: &str, format: ResearchRecordFormat) -> Result<PathBuf> {
+        let record = Self::collect(project_id).await?;
+        let project_path = ProjectService::resolve_project_path(project_id)?;
+
+        let (content, extension) = match format {
+            ResearchRecordFormat::Json => (serde_json::to_string_pretty(&record)?, "json"),
+            ResearchRecordFormat::Markdown => (Self::to_markdown(&record), "md"),
+        };
+
+        let export_dir = project_path.join(".metadata").join("exports");
+        fs::create_dir_all(&export_dir)?;
+        let path = export_dir.join(format!(
+            "research_record_{}.{}",
+            Utc::now().format("%Y%m%d_%H%M%S"),
+            extension
+        ));
+        fs::write(&path, content).context("Failed to write research record")?;
+        Ok(path)
+    }
+
+    pub async fn collect(project_id: &str) -> Result<ResearchRecord> {
+        let project = ProjectService::load_project_by_id(project_id)?;
+
+        let log_path = project.path.join("research_log.md");
+        let interactions = if log_path.exists() {
+            Self::parse_research_log(&fs::read_to_string(&log_path)?)
+        } else {
+            Vec::new()
+        };
+
+        let cost_log = CostLog::load(project.path.join(".metadata").join("cost_log.json"))
+            .map_err(|e| anyhow::anyhow!(e))?;
+        let usage = cost_log.records;
+        let totals = UsageTotals {
+            interactions: interactions.len(),
+            tokens_in: usage.iter().map(|r| r.input_tokens).sum(),
+            tokens_out: usage.iter().map(|r| r.output_tokens).sum(),
+            cost_usd: usage.iter().map(|r| r.cost_usd).sum(),
+        };
+
+        // Chat files are named by timestamp, so sorting puts them in order
+        let mut chat_files = ChatService::get_chat_files(project_id).await?;
+        chat_files.sort();
+        let mut chats = Vec::with_capacity(chat_files.len());
+        for file_name in chat_files {
+            match ChatService::load_chat_from_file(project_id, &file_name).await {
+                Ok(messages) => chats.push(ChatRecord {
+                    metadata: ChatService::load_chat_metadata(project_id, &file_name).ok().flatten(),
+                    file_name,
+                    messages,
+                }),
+                Err(e) => log::warn!("Leaving {} out of the research record: {}", file_name, e),
+            }
+        }
+
+        Ok(ResearchRecord {
+            project_id: project_id.to_string(),
+            project_name: project.name,
+            exported_at: Utc::now().to_rfc3339(),
+            interactions,
+            usage,
+            totals,
+            chats,
+            applied_changes: ProposedChangesService::list_applied(project_id)?,
+        })
+    }
+
+    /// Split `research_log.md` into its `### Interaction:` entries
+    fn parse_research_log(content: &str) -> Vec<ResearchLogEntry> {
+        let mut entries = Vec::new();
+        for block in content.split("### Interaction: ").skip(1) {
+            let mut lines = block.lines();
+            let mut entry = ResearchLogEntry {
+                timestamp: lines.next().unwrap_or_default().trim().to_string(),
+                ..Default::default()
+            };
+
+            let mut output: Option<Vec<&str>> = None;
+            for line in lines {
+                if let Some(out) = output.as_mut() {
+                    out.push(line);
+                    continue;
+                }
+                let field = |name: &str| {
+                    line.strip_prefix(&format!("**{}**: ", name))
+                        .map(|v| v.trim().to_string())
+                };
+                if line.starts_with("#### Agent Output:") {
+                    output = Some(Vec::new());
+                } else if let Some(v) = field("Provider") {
+                    entry.provider = Some(v);
+                } else if let Some(v) = field("Command") {
+                    entry.command = Some(v.trim_matches('`').to_string());
+                } else if let Some(v) = field("Status") {
+                    entry.status = Some(v);
+                } else if let Some(v) = field("Model") {
+                    entry.model = Some(v);
+                } else if let Some(v) = field("Cost") {
+                    entry.cost_usd = v.trim_start_matches('$').parse().ok();
+                } else if let Some(v) = field("Tokens") {
+                    // "<in> in / <out> out"
+                    let mut numbers = v.split_whitespace().filter_map(|t| t.parse::<u64>().ok());
+                    entry.tokens_in = numbers.next();
+                    entry.tokens_out = numbers.next();
+                }
+            }
+
+            entry.output = output
+                .map(|lines| lines.join("\n").trim().trim_end_matches("---").trim().to_string())
+                .filter(|text| !text.is_empty());
+            entries.push(entry);
+        }
+        entries
+    }
+
+    fn to_markdown(record: &ResearchRecord) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# Research Record: {}\n", record.project_name);
+        let _ = writeln!(md, "- **Project ID**: {}", record.project_id);
+        let _ = writeln!(md, "- **Exported**: {}", record.exported_at);
+        let _ = writeln!(md, "- **Interactions**: {}", record.totals.interactions);
+        let _ = writeln!(
+            md,
+            "- **Tokens**: {} in / {} out",
+            record.totals.tokens_in, record.totals.tokens_out
+        );
+        let _ = writeln!(md, "- **Estimated cost**: ${:.4}\n", record.totals.cost_usd);
+
+        md.push_str("## Interactions\n\n");
+        if record.interactions.is_empty() {
+            md.push_str("_No interactions logged._\n\n");
+        }
+        for entry in &record.interactions {
+            let _ = writeln!(md, "### {}\n", entry.timestamp);
+            let detail = |label: &str, value: &Option<String>| {
+                value.as_ref().map(|v| format!("- **{}**: {}\n", label, v)).unwrap_or_default()
+            };
+            md.push_str(&detail("Provider", &entry.provider));
+            md.push_str(&detail("Model", &entry.model));
+            md.push_str(&detail("Command", &entry.command));
+            md.push_str(&detail("Status", &entry.status));
+            if let (Some(tokens_in), Some(tokens_out)) = (entry.tokens_in, entry.tokens_out) {
+                let _ = writeln!(md, "- **Tokens**: {} in / {} out", tokens_in, tokens_out);
+            }
+            if let Some(cost) = entry.cost_usd {
+                let _ = writeln!(md, "- **Cost**: ${:.4}", cost);
+            }
+            if let Some(output) = &entry.output {
+                let _ = writeln!(md, "\n{}", output);
+            }
+            md.push('\n');
+        }
+
+        md.push_str("## Token Usage\n\n");
+        if record.usage.is_empty() {
+            md.push_str("_No usage recorded._\n\n");
+        } else {
+            md.push_str("| Time | Provider | Model | Tokens in | Tokens out | Cost |\n");
+            md.push_str("|---|---|---|---|---|---|\n");
+            for usage in &record.usage {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {} | {} | {} | ${:.4} |",
+                    usage.timestamp.to_rfc3339(),
+                    usage.provider,
+                    usage.model,
+                    usage.input_tokens,
+                    usage.output_tokens,
+                    usage.cost_usd
+                );
+            }
+            md.push('\n');
+        }
+
+        md.push_str("## Chats\n\n");
+        if record.chats.is_empty() {
+            md.push_str("_No chats saved._\n\n");
+        }
+        for chat in &record.chats {
+            let _ = writeln!(md, "### {}\n", chat.file_name);
+            if let Some(meta) = &chat.metadata {
+                let provider = meta.provider.as_deref().unwrap_or("unknown provider");
+                let _ = writeln!(md, "- **Created**: {}", meta.created);
+                let _ = writeln!(md, "- **Model**: {} ({})", meta.model, provider);
+                if let (Some(tokens_in), Some(tokens_out)) = (meta.tokens_in, meta.tokens_out) {
+                    let _ = writeln!(md, "- **Tokens**: {} in / {} out", tokens_in, tokens_out);
+                }
+                md.push('\n');
+            }
+            for message in &chat.messages {
+                let role = if message.role == "user" { "User" } else { "Assistant" };
+                let _ = writeln!(md, "**{}**:\n\n{}\n", role, message.content);
+            }
+        }
+
+        md.push_str("## Applied File Changes\n\n");
+        if record.applied_changes.is_empty() {
+            md.push_str("_No file changes applied._\n");
+        } else {
+            md.push_str("| Time | File | Source |\n");
+            md.push_str("|---|---|---|\n");
+            for change in &record.applied_changes {
+                let _ = writeln!(md, "| {} | {} | {} |", change.applied, change.path, change.source);
+            }
+        }
+
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_research_log_entries() {
+        let log = "# Research Log: Demo\n\nThis file tracks automatic agent interactions and observations.\n\n\
+---\n### Interaction: 2026-01-02T10:00:00+00:00\n**Provider**: HostedApi\n**Command**: `claude -p`\n\n#### Agent Output:\n\nFindings so far\n\nMore detail\n\n\n\
+---\n### Interaction: 2026-01-02T11:00:00+00:00\n**Provider**: Ollama\n**Status**: completed\n**Model**: llama3\n**Tokens**: 120 in / 45 out\n**Cost**: $0.0012\n\n_Content not recorded (logging privacy level: metadata only)._\n\n";
+
+        let entries = ResearchRecordService::parse_research_log(log);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].provider.as_deref(), Some("HostedApi"));
+        assert_eq!(entries[0].command.as_deref(), Some("claude -p"));
+        assert_eq!(entries[0].output.as_deref(), Some("Findings so far\n\nMore detail"));
+
+        assert_eq!(entries[1].timestamp, "2026-01-02T11:00:00+00:00");
+        assert_eq!(entries[1].model.as_deref(), Some("llama3"));
+        assert_eq!((entries[1].tokens_in, entries[1].tokens_out), (Some(120), Some(45)));
+        assert_eq!(entries[1].cost_usd, Some(0.0012));
+        assert_eq!(entries[1].output, None);
+    }
+
+    #[test]
+    fn test_markdown_report_sections() {
+        let record = ResearchRecord {
+            project_id: "demo".to_string(),
+            project_name: "Demo".to_string(),
+            exported_at: "2026-01-03T00:00:00+00:00".to_string(),
+            interactions: vec![ResearchLogEntry {
+                timestamp: "2026-01-02T10:00:00+00:00".to_string(),
+                provider: Some("HostedApi".to_string()),
+                output: Some("Findings".to_string()),
+                ..Default::default()
+            }],
+            usage: Vec::new(),
+            totals: UsageTotals {
+                interactions: 1,
+                ..Default::default()
+            },
+            chats: Vec::new(),
+            applied_changes: vec![AppliedFileChange {
+                path: "notes.md".to_string(),
+                applied: "2026-01-02T10:01:00+00:00".to_string(),
+                source: "auto".to_string(),
+            }],
+        };
+
+        let md = ResearchRecordService::to_markdown(&record);
+        assert!(md.starts_with("# Research Record: Demo"));
+        assert!(md.contains("### 2026-01-02T10:00:00+00:00\n\n- **Provider**: HostedApi"));
+        assert!(md.contains("_No usage recorded._"));
+        assert!(md.contains("| 2026-01-02T10:01:00+00:00 | notes.md | auto |"));
+    }
+}
@@ -0,0 +1,368 @@
+//! Search & Replace Service - Project-wide find and replace with preview and undo
+//!
+//! `preview` reports what would change without touching disk. `apply` copies every file it
+//! is about to change into a snapshot under `.metadata/replace_snapshots/`, then swaps the
+//! new contents in; `undo` copies a snapshot back.
+
+use crate::services::project_service::ProjectService;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+use walkdir::WalkDir;
+
+/// Files larger than this are skipped, matching `search_in_files`
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+/// Changed lines shown per file in a preview
+const MAX_SAMPLES_PER_FILE: usize = 5;
+const PROJECT_FILE: &str = ".project.md";
+const SNAPSHOT_DIR: &str = "replace_snapshots";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReplaceOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Treat `find` as a regular expression; `replace` may then use `$1`-style groups
+    pub use_regex: bool,
+    /// Also search `.project.md`, which is skipped by default
+    pub include_project_file: bool,
+}
+
+/// A changed line, before and after replacement
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceSample {
+    pub line_number: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReplacePreview {
+    pub file_name: String,
+    pub match_count: usize,
+    pub samples: Vec<ReplaceSample>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacePreview {
+    pub files: Vec<FileReplacePreview>,
+    pub total_matches: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceResult {
+    pub files_changed: Vec<String>,
+    pub replacements: usize,
+    /// Pass to `undo` to restore the files as they were before this replace
+    pub snapshot_id: Option<String>,
+}
+
+pub struct SearchReplaceService;
+
+impl SearchReplaceService {
+    /// Files and lines that `apply` would change
+    pub fn preview(
+        project_id: &str,
+        find: &str,
+        replace: &str,
+        options: &ReplaceOptions,
+    ) -> Result<ReplacePreview> {
+        let pattern = Self::build_pattern(find, options)?;
+        let (project_path, files) = Self::candidate_files(project_id, options)?;
+
+        let mut preview = ReplacePreview {
+            files: Vec::new(),
+            total_matches: 0,
+        };
+        for file_name in files {
+            let Some(content) = Self::read_candidate(&project_path, &file_name) else {
+                continue;
+            };
+            let match_count = pattern.find_iter(&content).count();
+            if match_count == 0 {
+                continue;
+            }
+
+            let samples = content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| pattern.is_match(line))
+                .take(MAX_SAMPLES_PER_FILE)
+                .map(|(i, line)| ReplaceSample {
+                    line_number: i + 1,
+                    before: line.to_string(),
+                    after: Self::replace_all(&pattern, line, replace, options),
+                })
+                .collect();
+
+            preview.total_matches += match_count;
+            preview.files.push(FileReplacePreview {
+                file_name,
+                match_count,
+                samples,
+            });
+        }
+        Ok(preview)
+    }
+
+    /// Replace in every matching file, or only in `file_names` when given. Originals are
+    /// snapshotted first and, if any write fails, restored before the error is returned.
+    pub fn apply(
+        project_id: &str,
+        find: &str,
+        replace: &str,
+        options: &ReplaceOptions,
+        file_names: Option<&[String]>,
+    ) -> Result<ReplaceResult> {
+        let pattern = Self::build_pattern(find, options)?;
+        let (project_path, files) = Self::candidate_files(project_id, options)?;
+        let files: Vec<String> = match file_names {
+            Some(selected) => files.into_iter().filter(|f| selected.contains(f)).collect(),
+            None => files,
+        };
+
+        let mut changes = Vec::new();
+        let mut replacements = 0;
+        for file_name in files {
+            let Some(content) = Self::read_candidate(&project_path, &file_name) else {
+                continue;
+            };
+            let count = pattern.find_iter(&content).count();
+            if count > 0 {
+                replacements += count;
+                let new_content = Self::replace_all(&pattern, &content, replace, options);
+                changes.push((file_name, new_content));
+            }
+        }
+
+        if changes.is_empty() {
+            return Ok(ReplaceResult {
+                files_changed: Vec::new(),
+                replacements: 0,
+                snapshot_id: None,
+            });
+        }
+
+        let changed: Vec<String> = changes.iter().map(|(f, _)| f.clone()).collect();
+        let snapshot_id = Self::snapshot(&project_path, &changed)?;
+
+        if let Err(e) = Self::write_all(&project_path, &changes) {
+            Self::restore(&project_path, &snapshot_id)
+                .context("Replace failed and the snapshot could not be restored")?;
+            return Err(e.context("Replace failed; all files were restored"));
+        }
+
+        Ok(ReplaceResult {
+            files_changed: changed,
+            replacements,
+            snapshot_id: Some(snapshot_id),
+        })
+    }
+
+    /// Put back the files saved by `apply`, returning their names
+    pub fn undo(project_id: &str, snapshot_id: &str) -> Result<Vec<String>> {
+        let (project_path, _) = ProjectService::project_files(project_id)?;
+        Self::restore(&project_path, snapshot_id)
+    }
+
+    fn build_pattern(find: &str, options: &ReplaceOptions) -> Result<Regex> {
+        if find.is_empty() {
+            anyhow::bail!("Search text is empty");
+        }
+        let source = if options.use_regex {
+            find.to_string()
+        } else {
+            regex::escape(find)
+        };
+        let source = if options.whole_word {
+            format!(r"\b(?:{})\b", source)
+        } else {
+            source
+        };
+        RegexBuilder::new(&source)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .context("Invalid search pattern")
+    }
+
+    fn replace_all(pattern: &Regex, text: &str, replace: &str, options: &ReplaceOptions) -> String {
+        if options.use_regex {
+            pattern.replace_all(text, replace).into_owned()
+        } else {
+            pattern.replace_all(text, NoExpand(replace)).into_owned()
+        }
+    }
+
+    fn candidate_files(project_id: &str, options: &ReplaceOptions) -> Result<(PathBuf, Vec<String>)> {
+        let (project_path, mut files) = ProjectService::project_files(project_id)?;
+        if options.include_project_file && project_path.join(PROJECT_FILE).is_file() {
+            files.insert(0, PROJECT_FILE.to_string());
+        }
+        Ok((project_path, files))
+    }
+
+    fn read_candidate(project_path: &Path, file_name: &str) -> Option<String> {
+        let path = project_path.join(file_name);
+        let metadata = fs::metadata(&path).ok()?;
+        if metadata.len() > MAX_FILE_SIZE {
+            log::warn!("Skipping '{}' in replace: larger than {} bytes", file_name, MAX_FILE_SIZE);
+            return None;
+        }
+        fs::read_to_string(&path).ok()
+    }
+
+    /// Copy the files about to change into a new snapshot directory
+    fn snapshot(project_path: &Path, files: &[String]) -> Result<String> {
+        let snapshot_id = Utc::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+        let snapshot_dir = Self::snapshot_dir(project_path, &snapshot_id);
+        for file_name in files {
+            let target = snapshot_dir.join(file_name);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(project_path.join(file_name), &target)
+                .with_context(|| format!("Failed to snapshot '{}'", file_name))?;
+        }
+        Ok(snapshot_id)
+    }
+
+    /// Stage every new file next to its target first, then rename them all into place
+    fn write_all(project_path: &Path, changes: &[(String, String)]) -> Result<()> {
+        let mut staged = Vec::new();
+        for (file_name, content) in changes {
+            let path = project_path.join(file_name);
+            let dir = path.parent().unwrap_or(project_path);
+            let mut temp = NamedTempFile::new_in(dir)?;
+            temp.write_all(content.as_bytes())?;
+            // The temp file is created 0600; keep the note's own permissions
+            if let Ok(metadata) = fs::metadata(&path) {
+                temp.as_file().set_permissions(metadata.permissions())?;
+            }
+            temp.as_file().sync_all()?;
+            staged.push((temp, path));
+        }
+        for (temp, path) in staged {
+            temp.persist(&path)
+                .with_context(|| format!("Failed to write '{}'", path.display()))?;
+        }
+        Ok(())
+    }
+
+    fn restore(project_path: &Path, snapshot_id: &str) -> Result<Vec<String>> {
+        if snapshot_id.is_empty() || snapshot_id.contains(['/', '\\', '.']) {
+            anyhow::bail!("Invalid snapshot id '{}'", snapshot_id);
+        }
+        let snapshot_dir = Self::snapshot_dir(project_path, snapshot_id);
+        if !snapshot_dir.is_dir() {
+            anyhow::bail!("Snapshot '{}' not found", snapshot_id);
+        }
+
+        let mut restored = Vec::new();
+        for entry in WalkDir::new(&snapshot_dir).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(&snapshot_dir)?;
+            fs::copy(entry.path(), project_path.join(relative))
+                .with_context(|| format!("Failed to restore '{}'", relative.display()))?;
+            restored.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+        restored.sort();
+        Ok(restored)
+    }
+
+    fn snapshot_dir(project_path: &Path, snapshot_id: &str) -> PathBuf {
+        project_path.join(".metadata").join(SNAPSHOT_DIR).join(snapshot_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn options(case_sensitive: bool, whole_word: bool, use_regex: bool) -> ReplaceOptions {
+        ReplaceOptions {
+            case_sensitive,
+            whole_word,
+            use_regex,
+            include_project_file: false,
+        }
+    }
+
+    fn replace(text: &str, find: &str, with: &str, options: &ReplaceOptions) -> String {
+        let pattern = SearchReplaceService::build_pattern(find, options).unwrap();
+        SearchReplaceService::replace_all(&pattern, text, with, options)
+    }
+
+    #[test]
+    fn test_replace_options() {
+        let text = "User research: users and USER needs";
+        assert_eq!(
+            replace(text, "user", "customer", &options(false, true, false)),
+            "customer research: users and customer needs"
+        );
+        assert_eq!(
+            replace(text, "user", "customer", &options(true, false, false)),
+            "User research: customers and USER needs"
+        );
+        assert_eq!(
+            replace("v1.2 and v1x2", "v1.2", "$0", &options(true, false, false)),
+            "$0 and v1x2",
+            "plain text is matched and substituted literally"
+        );
+        assert_eq!(
+            replace("2025-01-31", r"(\d+)-(\d+)-(\d+)", "$3/$2/$1", &options(true, false, true)),
+            "31/01/2025"
+        );
+        assert!(SearchReplaceService::build_pattern("(", &options(true, false, true)).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_write_and_restore() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path();
+        fs::create_dir_all(project.join("notes")).unwrap();
+        fs::write(project.join("a.md"), "old term").unwrap();
+        fs::write(project.join("notes").join("b.md"), "another old term").unwrap();
+
+        let files = vec!["a.md".to_string(), "notes/b.md".to_string()];
+        let snapshot_id = SearchReplaceService::snapshot(project, &files).unwrap();
+        let changes = vec![
+            ("a.md".to_string(), "new term".to_string()),
+            ("notes/b.md".to_string(), "another new term".to_string()),
+        ];
+        SearchReplaceService::write_all(project, &changes).unwrap();
+        assert_eq!(fs::read_to_string(project.join("a.md")).unwrap(), "new term");
+
+        let restored = SearchReplaceService::restore(project, &snapshot_id).unwrap();
+        assert_eq!(restored, files);
+        assert_eq!(fs::read_to_string(project.join("notes").join("b.md")).unwrap(), "another old term");
+        assert!(SearchReplaceService::restore(project, "../escape").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_all_keeps_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new().unwrap();
+        let project = temp.path();
+        fs::write(project.join("a.md"), "old term").unwrap();
+        fs::set_permissions(project.join("a.md"), fs::Permissions::from_mode(0o644)).unwrap();
+
+        SearchReplaceService::write_all(project, &[("a.md".to_string(), "new term".to_string())]).unwrap();
+
+        let mode = fs::metadata(project.join("a.md")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o644);
+    }
+}
@@ -1,14 +1,61 @@
 use anyhow::{Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rand::RngCore;
 
 use crate::services::encryption_service::EncryptionService;
+use crate::utils::fs::write_atomic;
 use crate::utils::paths;
 
 pub struct SecretsService;
 
+/// Bumped on every save so long-lived consumers can tell their resolved secrets are stale
+static SECRETS_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+const EXPORT_FORMAT: &str = "productos-secrets";
+const EXPORT_VERSION: u32 = 1;
+const EXPORT_MIN_PASSPHRASE_LEN: usize = 8;
+/// Argon2id cost used for new exports (OWASP baseline: 19 MiB, 2 passes)
+const EXPORT_M_COST_KIB: u32 = 19 * 1024;
+const EXPORT_T_COST: u32 = 2;
+const EXPORT_P_COST: u32 = 1;
+/// Upper bound on the memory cost an imported blob may ask for (1 GiB)
+const IMPORT_MAX_M_COST_KIB: u32 = 1024 * 1024;
+
+/// Portable secrets blob, encrypted under a key derived from a passphrase instead of
+/// the machine's master key
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SecretsExport {
+    format: String,
+    version: u32,
+    exported_at: String,
+    kdf: ExportKdf,
+    encrypted_data: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct ExportKdf {
+    algorithm: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+}
+
+/// Payload of the `secrets-changed` event. Carries ids only, never values.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SecretsChangedEvent {
+    pub changed_ids: Vec<String>,
+    pub generation: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Secrets {
     #[serde(default)]
@@ -21,6 +68,33 @@ pub struct Secrets {
     pub custom_api_keys: HashMap<String, String>,
 }
 
+impl Secrets {
+    /// Ids (including aliases) of the secrets set here, as accepted by `get_secret`
+    pub fn provided_ids(&self) -> Vec<String> {
+        let mut ids = Vec::new();
+
+        if self.claude_api_key.is_some() {
+            ids.push("claude_api_key".to_string());
+            ids.push("ANTHROPIC_API_KEY".to_string());
+        }
+        if self.gemini_api_key.is_some() {
+            ids.push("gemini_api_key".to_string());
+            ids.push("GEMINI_API_KEY".to_string());
+        }
+        if self.n8n_webhook_url.is_some() {
+            ids.push("n8n_webhook_url".to_string());
+        }
+
+        for key in self.custom_api_keys.keys() {
+            ids.push(key.clone());
+        }
+
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+}
+
 impl SecretsService {
     /// Load secrets from secrets.encrypted.json
     pub fn load_secrets() -> Result<Secrets> {
@@ -39,17 +113,22 @@ impl SecretsService {
         let content = fs::read_to_string(&secrets_path).context("Failed to read secrets file")?;
 
         Self::parse_encrypted_secrets(&content)
+            .or_else(|e| Self::recover_staged_secrets(&secrets_path).ok_or(e))
     }
 
     /// Parse secrets from encrypted JSON
     fn parse_encrypted_secrets(content: &str) -> Result<Secrets> {
+        Self::parse_encrypted_secrets_with_key(content, &EncryptionService::get_or_create_master_key()?)
+    }
+
+    fn parse_encrypted_secrets_with_key(content: &str, key: &[u8]) -> Result<Secrets> {
         // Parse as JSON (only format supported now)
         let json_wrapper: serde_json::Value =
             serde_json::from_str(content).context("Failed to parse secrets JSON")?;
 
         if let Some(encrypted_data) = json_wrapper.get("encrypted_data").and_then(|v| v.as_str()) {
             // Decrypt the data
-            let decrypted_json = EncryptionService::decrypt(encrypted_data)
+            let decrypted_json = EncryptionService::decrypt_with_key(key, encrypted_data)
                 .context("Failed to decrypt secrets from JSON")?;
 
             // Deserialize from JSON
@@ -94,19 +173,195 @@ impl SecretsService {
         }
 
         let content = Self::format_encrypted_secrets(&secrets)?;
-        fs::write(&secrets_path, content).context("Failed to write secrets file")?;
+        // A torn write here would lock the user out of every stored key
+        write_atomic(&secrets_path, content).context("Failed to write secrets file")?;
+        SECRETS_GENERATION.fetch_add(1, Ordering::SeqCst);
 
         Ok(())
     }
 
+    /// Number of saves since startup; changes whenever any secret may have changed
+    pub fn generation() -> u64 {
+        SECRETS_GENERATION.load(Ordering::SeqCst)
+    }
+
+    /// Re-encrypt every secret under a newly generated master key. Returns the rotation time.
+    ///
+    /// The re-encrypted file is staged next to `secrets.encrypted.json` before the key is
+    /// swapped, then renamed over it. Any failure restores the old key and leaves the old
+    /// file in place; a crash between the key swap and the rename is repaired on the next
+    /// `load_secrets`, which falls back to the staged file.
+    pub fn rotate_encryption_key() -> Result<String> {
+        let secrets_path = paths::get_secrets_path()?;
+        let old_key = EncryptionService::get_or_create_master_key()?;
+        let secrets = Self::load_secrets()
+            .context("Existing secrets could not be decrypted, so the key was not rotated")?;
+
+        let new_key = EncryptionService::generate_key();
+        let content = Self::format_encrypted_secrets_with_key(&secrets, &new_key)?;
+        // Prove the new file opens with the new key before anything is swapped
+        Self::parse_encrypted_secrets_with_key(&content, &new_key)?;
+
+        let staged_path = Self::staged_secrets_path(&secrets_path);
+        let has_secrets_file = secrets_path.exists();
+        if has_secrets_file {
+            write_atomic(&staged_path, &content).context("Failed to stage re-encrypted secrets")?;
+        }
+
+        if let Err(e) = EncryptionService::replace_master_key(&new_key) {
+            let _ = fs::remove_file(&staged_path);
+            return Err(e.context("Failed to store the new master key; the old key is still in use"));
+        }
+
+        if has_secrets_file {
+            if let Err(e) = fs::rename(&staged_path, &secrets_path) {
+                if let Err(restore_err) = EncryptionService::replace_master_key(&old_key) {
+                    log::error!(
+                        "Failed to restore the old master key after a failed rotation: {}",
+                        restore_err
+                    );
+                    return Err(anyhow::anyhow!(e)
+                        .context("Failed to replace the secrets file; re-encrypted copy kept at the staged path"));
+                }
+                let _ = fs::remove_file(&staged_path);
+                return Err(anyhow::anyhow!(e).context("Failed to replace the secrets file"));
+            }
+        }
+        SECRETS_GENERATION.fetch_add(1, Ordering::SeqCst);
+
+        let rotated_at = Utc::now().to_rfc3339();
+        log::info!(
+            "Rotated master encryption key at {} ({} secrets re-encrypted)",
+            rotated_at,
+            secrets.provided_ids().len()
+        );
+        if let Err(e) = EncryptionService::record_rotation(&rotated_at) {
+            log::warn!("Failed to record key rotation time in settings: {}", e);
+        }
+        Ok(rotated_at)
+    }
+
+    /// Export every stored secret as a blob that opens on any machine with `passphrase`.
+    /// Decryption and re-encryption happen in memory; nothing plaintext touches disk.
+    pub fn export_secrets(passphrase: &str) -> Result<String> {
+        let secrets = Self::load_secrets()?;
+        let blob = Self::encrypt_export(&secrets, passphrase)?;
+        log::info!("Exported {} secrets", secrets.provided_ids().len());
+        Ok(blob)
+    }
+
+    /// Decrypt a blob from `export_secrets` and merge it into the local store. Imported
+    /// values replace local ones with the same id. Returns the ids that were imported.
+    pub fn import_secrets(blob: &str, passphrase: &str) -> Result<Vec<String>> {
+        let imported = Self::decrypt_export(blob, passphrase)?;
+        Self::save_secrets(&imported)?;
+        let ids = imported.provided_ids();
+        log::info!("Imported {} secrets", ids.len());
+        Ok(ids)
+    }
+
+    fn encrypt_export(secrets: &Secrets, passphrase: &str) -> Result<String> {
+        if passphrase.chars().count() < EXPORT_MIN_PASSPHRASE_LEN {
+            return Err(anyhow::anyhow!(
+                "Passphrase must be at least {} characters",
+                EXPORT_MIN_PASSPHRASE_LEN
+            ));
+        }
+
+        let mut salt = [0u8; 16];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        let kdf = ExportKdf {
+            algorithm: "argon2id".to_string(),
+            m_cost: EXPORT_M_COST_KIB,
+            t_cost: EXPORT_T_COST,
+            p_cost: EXPORT_P_COST,
+            salt: BASE64.encode(salt),
+        };
+        let key = Self::derive_export_key(passphrase, &kdf)?;
+
+        let json_data = serde_json::to_string(secrets).context("Failed to serialize secrets")?;
+        let encrypted_data = EncryptionService::encrypt_with_key(&key, &json_data)
+            .context("Failed to encrypt secrets")?;
+
+        let export = SecretsExport {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            kdf,
+            encrypted_data,
+        };
+        Ok(serde_json::to_string_pretty(&export)?)
+    }
+
+    fn decrypt_export(blob: &str, passphrase: &str) -> Result<Secrets> {
+        let export: SecretsExport =
+            serde_json::from_str(blob.trim()).context("Not a secrets export")?;
+        if export.format != EXPORT_FORMAT {
+            return Err(anyhow::anyhow!("Not a secrets export (format '{}')", export.format));
+        }
+        if export.version > EXPORT_VERSION {
+            return Err(anyhow::anyhow!(
+                "Secrets export version {} is newer than this app supports",
+                export.version
+            ));
+        }
+
+        let key = Self::derive_export_key(passphrase, &export.kdf)?;
+        // AES-GCM authentication fails the same way for a wrong passphrase and a tampered blob
+        let json_data = EncryptionService::decrypt_with_key(&key, &export.encrypted_data)
+            .map_err(|_| anyhow::anyhow!("Wrong passphrase or corrupted export"))?;
+
+        serde_json::from_str(&json_data).context("Failed to parse exported secrets")
+    }
+
+    fn derive_export_key(passphrase: &str, kdf: &ExportKdf) -> Result<[u8; 32]> {
+        if kdf.algorithm != "argon2id" {
+            return Err(anyhow::anyhow!("Unsupported key derivation '{}'", kdf.algorithm));
+        }
+        if kdf.m_cost > IMPORT_MAX_M_COST_KIB {
+            return Err(anyhow::anyhow!("Key derivation memory cost is unreasonably high"));
+        }
+
+        let salt = BASE64.decode(&kdf.salt).context("Invalid salt")?;
+        let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32))
+            .map_err(|e| anyhow::anyhow!("Invalid key derivation parameters: {}", e))?;
+        let mut key = [0u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| anyhow::anyhow!("Failed to derive key from passphrase: {}", e))?;
+        Ok(key)
+    }
+
+    /// Where a rotation stages the re-encrypted secrets before renaming them into place
+    fn staged_secrets_path(secrets_path: &Path) -> PathBuf {
+        secrets_path.with_extension("json.rotating")
+    }
+
+    /// Finish a rotation interrupted after the key swap by promoting the staged file
+    fn recover_staged_secrets(secrets_path: &Path) -> Option<Secrets> {
+        let staged_path = Self::staged_secrets_path(secrets_path);
+        let secrets = fs::read_to_string(&staged_path)
+            .ok()
+            .and_then(|content| Self::parse_encrypted_secrets(&content).ok())?;
+        log::warn!("Completing an interrupted encryption key rotation from {:?}", staged_path);
+        if let Err(e) = fs::rename(&staged_path, secrets_path) {
+            log::error!("Failed to promote staged secrets file: {}", e);
+        }
+        Some(secrets)
+    }
+
     /// Format secrets as encrypted JSON content
     fn format_encrypted_secrets(secrets: &Secrets) -> Result<String> {
+        Self::format_encrypted_secrets_with_key(secrets, &EncryptionService::get_or_create_master_key()?)
+    }
+
+    fn format_encrypted_secrets_with_key(secrets: &Secrets, key: &[u8]) -> Result<String> {
         // Serialize to JSON
         let json_data = serde_json::to_string(secrets).context("Failed to serialize secrets")?;
 
         // Encrypt
-        let encrypted_data =
-            EncryptionService::encrypt(&json_data).context("Failed to encrypt secrets")?;
+        let encrypted_data = EncryptionService::encrypt_with_key(key, &json_data)
+            .context("Failed to encrypt secrets")?;
 
         // Get current timestamp
         let now: DateTime<Utc> = Utc::now();
@@ -170,28 +425,7 @@ impl SecretsService {
 
     /// List saved secret IDs without returning secret values.
     pub fn list_saved_secret_ids() -> Result<Vec<String>> {
-        let secrets = Self::load_secrets()?;
-        let mut ids = Vec::new();
-
-        if secrets.claude_api_key.is_some() {
-            ids.push("claude_api_key".to_string());
-            ids.push("ANTHROPIC_API_KEY".to_string());
-        }
-        if secrets.gemini_api_key.is_some() {
-            ids.push("gemini_api_key".to_string());
-            ids.push("GEMINI_API_KEY".to_string());
-        }
-        if secrets.n8n_webhook_url.is_some() {
-            ids.push("n8n_webhook_url".to_string());
-        }
-
-        for key in secrets.custom_api_keys.keys() {
-            ids.push(key.clone());
-        }
-
-        ids.sort();
-        ids.dedup();
-        Ok(ids)
+        Ok(Self::load_secrets()?.provided_ids())
     }
 }
 
@@ -237,6 +471,61 @@ mod tests {
         let _ = EncryptionService::delete_master_key();
     }
 
+    #[test]
+    fn test_secrets_reencrypted_under_new_key_only_open_with_it() {
+        let old_key = EncryptionService::generate_key();
+        let new_key = EncryptionService::generate_key();
+        let secrets = Secrets {
+            custom_api_keys: HashMap::from([("OPENAI_API_KEY".to_string(), "sk-rotate".to_string())]),
+            ..Default::default()
+        };
+
+        let old_content = SecretsService::format_encrypted_secrets_with_key(&secrets, &old_key).unwrap();
+        let reopened = SecretsService::parse_encrypted_secrets_with_key(&old_content, &old_key).unwrap();
+        let new_content = SecretsService::format_encrypted_secrets_with_key(&reopened, &new_key).unwrap();
+
+        let rotated = SecretsService::parse_encrypted_secrets_with_key(&new_content, &new_key).unwrap();
+        assert_eq!(rotated.custom_api_keys["OPENAI_API_KEY"], "sk-rotate");
+        assert!(SecretsService::parse_encrypted_secrets_with_key(&new_content, &old_key).is_err());
+    }
+
+    #[test]
+    fn test_staged_secrets_sit_next_to_the_secrets_file() {
+        let path = Path::new("/data/productOS/secrets.encrypted.json");
+        assert_eq!(
+            SecretsService::staged_secrets_path(path),
+            Path::new("/data/productOS/secrets.encrypted.json.rotating")
+        );
+    }
+
+    #[test]
+    fn test_export_opens_with_passphrase_only() {
+        let secrets = Secrets {
+            gemini_api_key: Some("gm-portable".to_string()),
+            custom_api_keys: HashMap::from([("OPENAI_API_KEY".to_string(), "sk-portable".to_string())]),
+            ..Default::default()
+        };
+
+        let blob = SecretsService::encrypt_export(&secrets, "correct horse battery").unwrap();
+        assert!(!blob.contains("sk-portable"));
+        assert!(!blob.contains("gm-portable"));
+
+        let imported = SecretsService::decrypt_export(&blob, "correct horse battery").unwrap();
+        assert_eq!(imported.gemini_api_key.as_deref(), Some("gm-portable"));
+        assert_eq!(imported.custom_api_keys["OPENAI_API_KEY"], "sk-portable");
+
+        let err = SecretsService::decrypt_export(&blob, "wrong horse battery").unwrap_err();
+        assert!(err.to_string().contains("Wrong passphrase"));
+    }
+
+    #[test]
+    fn test_export_rejects_short_passphrase_and_foreign_blobs() {
+        assert!(SecretsService::encrypt_export(&Secrets::default(), "short").is_err());
+
+        let foreign = r#"{"format":"other","version":1,"exported_at":"","kdf":{"algorithm":"argon2id","m_cost":8,"t_cost":1,"p_cost":1,"salt":"AAAAAAAAAAAAAAAAAAAAAA=="},"encrypted_data":""}"#;
+        assert!(SecretsService::decrypt_export(foreign, "whatever-passphrase").is_err());
+    }
+
     #[test]
     fn test_format_encrypted_secrets_structure() {
         let _lock = TEST_MUTEX.lock().unwrap();
@@ -22,10 +22,12 @@ impl SettingsService {
         let path = Self::global_settings_path()?;
         let settings = GlobalSettings::load(&path)?;
         
-        // Background pre-warm of dynamic defaults
-        tokio::spawn(async {
-            let _ = crate::services::defaults_service::DefaultsService::get_recommended_defaults().await;
-        });
+        // Background pre-warm of dynamic defaults, when called from within the runtime
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async {
+                let _ = crate::services::defaults_service::DefaultsService::get_recommended_defaults().await;
+            });
+        }
 
         Ok(settings)
     }
@@ -130,6 +132,11 @@ mod tests {
             encryption_enabled: Some(true),
             personalization_rules: None,
             brand_settings: None,
+            pinned_context_files: Vec::new(),
+            context_include: Vec::new(),
+            context_exclude: Vec::new(),
+            tool_filter: None,
+            require_tool_approval: false,
         };
 
         // Save settings
@@ -0,0 +1,309 @@
+//! Skill Marketplace Service - Lists and installs curated skills
+//!
+//! Entries come from the bundled PM skills plus an optional remote index configured via
+//! `GlobalSettings::skill_marketplace_url`. The combined list is cached in memory for
+//! `CACHE_TTL` so browsing the marketplace doesn't hit the network on every keystroke.
+
+use crate::models::skill::{MarketplaceSkill, Skill, SkillMarketplaceIndex};
+use crate::services::pm_skills;
+use crate::services::settings_service::SettingsService;
+use crate::services::skill_service::SkillService;
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Skill files are plain markdown; anything larger is almost certainly not a skill
+const MAX_SKILL_SIZE: usize = 256 * 1024;
+
+struct CachedIndex {
+    index_url: Option<String>,
+    fetched_at: Instant,
+    skills: Vec<MarketplaceSkill>,
+}
+
+static MARKETPLACE_CACHE: Lazy<Mutex<Option<CachedIndex>>> = Lazy::new(|| Mutex::new(None));
+
+pub struct SkillMarketplaceService;
+
+impl SkillMarketplaceService {
+    /// List marketplace skills matching `query` (name, description or category).
+    /// `installed` reflects the current contents of the skills directory.
+    pub async fn fetch(query: Option<String>) -> Result<Vec<MarketplaceSkill>> {
+        let settings = SettingsService::load_global_settings()?;
+        let index_url = settings
+            .skill_marketplace_url
+            .filter(|url| !url.trim().is_empty());
+
+        let skills = match Self::cached(&index_url) {
+            Some(skills) => skills,
+            None => {
+                let mut skills = Self::bundled_skills();
+                if let Some(url) = &index_url {
+                    match Self::fetch_remote_index(url).await {
+                        Ok(remote) => Self::merge(&mut skills, remote),
+                        Err(e) => log::warn!("Failed to fetch skill marketplace index {}: {}", url, e),
+                    }
+                }
+                Self::store_cache(index_url, skills.clone());
+                skills
+            }
+        };
+
+        let skills_dir = SettingsService::get_skills_path()?;
+        Ok(skills
+            .into_iter()
+            .filter(|skill| Self::matches_query(skill, query.as_deref()))
+            .map(|mut skill| {
+                skill.installed = skills_dir.join(format!("{}.md", skill.id)).exists();
+                skill
+            })
+            .collect())
+    }
+
+    /// Download (or take the bundled copy of) a marketplace skill, validate it and save it
+    /// into the skills directory
+    pub async fn install(id: &str) -> Result<Skill> {
+        let entry = Self::fetch(None)
+            .await?
+            .into_iter()
+            .find(|skill| skill.id == id)
+            .ok_or_else(|| anyhow!("Skill not found in marketplace: {}", id))?;
+
+        if entry.installed {
+            return Err(anyhow!("Skill already installed: {}", id));
+        }
+
+        let markdown = match &entry.download_url {
+            Some(url) => Self::download(url).await?,
+            None => pm_skills::get_pm_skills_definitions()
+                .into_iter()
+                .find(|(bundled_id, _)| *bundled_id == entry.id)
+                .map(|(_, markdown)| markdown.to_string())
+                .ok_or_else(|| anyhow!("Marketplace skill {} has no download URL", id))?,
+        };
+
+        let skill = Self::parse_skill(&entry, &markdown)?;
+        SkillService::save_skill(&skill)?;
+
+        Ok(skill)
+    }
+
+    /// Drop the cached index so the next fetch goes back to the network
+    pub fn clear_cache() {
+        *MARKETPLACE_CACHE.lock().unwrap_or_else(|p| p.into_inner()) = None;
+    }
+
+    fn cached(index_url: &Option<String>) -> Option<Vec<MarketplaceSkill>> {
+        let cache = MARKETPLACE_CACHE.lock().unwrap_or_else(|p| p.into_inner());
+        cache
+            .as_ref()
+            .filter(|c| &c.index_url == index_url && c.fetched_at.elapsed() < CACHE_TTL)
+            .map(|c| c.skills.clone())
+    }
+
+    fn store_cache(index_url: Option<String>, skills: Vec<MarketplaceSkill>) {
+        *MARKETPLACE_CACHE.lock().unwrap_or_else(|p| p.into_inner()) = Some(CachedIndex {
+            index_url,
+            fetched_at: Instant::now(),
+            skills,
+        });
+    }
+
+    fn bundled_skills() -> Vec<MarketplaceSkill> {
+        pm_skills::get_pm_skills_definitions()
+            .into_iter()
+            .map(|(id, markdown)| {
+                let name = markdown
+                    .lines()
+                    .find(|l| l.starts_with("# "))
+                    .map(|l| l.trim_start_matches("# ").trim().trim_end_matches(" Skill").to_string())
+                    .unwrap_or_else(|| id.replace('-', " "));
+                let description = markdown
+                    .lines()
+                    .skip_while(|l| !l.starts_with("## Overview"))
+                    .skip(1)
+                    .take_while(|l| !l.starts_with("## "))
+                    .filter(|l| !l.trim().is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                MarketplaceSkill {
+                    id: id.to_string(),
+                    name,
+                    category: "Product Management".to_string(),
+                    description,
+                    author: Some("productOS".to_string()),
+                    version: Some("1.0.0".to_string()),
+                    download_url: None,
+                    source: "bundled".to_string(),
+                    installed: false,
+                }
+            })
+            .collect()
+    }
+
+    /// Remote entries override bundled ones with the same id
+    fn merge(skills: &mut Vec<MarketplaceSkill>, remote: Vec<MarketplaceSkill>) {
+        for mut entry in remote {
+            entry.source = "remote".to_string();
+            skills.retain(|s| s.id != entry.id);
+            skills.push(entry);
+        }
+    }
+
+    fn matches_query(skill: &MarketplaceSkill, query: Option<&str>) -> bool {
+        match query.map(str::trim).filter(|q| !q.is_empty()) {
+            None => true,
+            Some(q) => {
+                let q = q.to_lowercase();
+                skill.name.to_lowercase().contains(&q)
+                    || skill.description.to_lowercase().contains(&q)
+                    || skill.category.to_lowercase().contains(&q)
+            }
+        }
+    }
+
+    fn client_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(USER_AGENT, HeaderValue::from_static("AI-Researcher-App/0.1"));
+        headers
+    }
+
+    async fn fetch_remote_index(url: &str) -> Result<Vec<MarketplaceSkill>> {
+        let res = reqwest::Client::new()
+            .get(url)
+            .headers(Self::client_headers())
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        let index: SkillMarketplaceIndex = res.json().await.context("Invalid marketplace index")?;
+
+        // Entries without a download URL can't be installed, so don't list them
+        Ok(index
+            .skills
+            .into_iter()
+            .filter(|s| s.download_url.is_some())
+            .collect())
+    }
+
+    async fn download(url: &str) -> Result<String> {
+        let res = reqwest::Client::new()
+            .get(url)
+            .headers(Self::client_headers())
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        let bytes = res.bytes().await?;
+        if bytes.len() > MAX_SKILL_SIZE {
+            return Err(anyhow!("Skill file is too large ({} bytes)", bytes.len()));
+        }
+        String::from_utf8(bytes.to_vec()).context("Skill file is not valid UTF-8")
+    }
+
+    /// Parse downloaded markdown into a skill, taking identity fields from the marketplace entry
+    fn parse_skill(entry: &MarketplaceSkill, markdown: &str) -> Result<Skill> {
+        let temp_dir = tempfile::tempdir()?;
+        let temp_path = temp_dir.path().join(format!("{}.md", entry.id));
+        fs::write(&temp_path, markdown)?;
+
+        let mut skill = Skill::from_markdown_file(&temp_path)?;
+        let skills_dir = SettingsService::get_skills_path()?;
+
+        skill.id = entry.id.clone();
+        skill.name = entry.name.clone();
+        if !entry.description.is_empty() {
+            skill.description = entry.description.clone();
+        }
+        if let Some(version) = &entry.version {
+            skill.version = version.clone();
+        }
+        if !entry.category.trim().is_empty() {
+            skill.category = entry.category.trim().to_lowercase();
+        }
+        skill.capabilities.push("marketplace".to_string());
+        skill.file_path = skills_dir.join(format!("{}.md", entry.id));
+
+        skill
+            .validate()
+            .map_err(|errors| anyhow!("Invalid skill {}: {}", entry.id, errors.join("; ")))?;
+        Ok(skill)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, category: &str) -> MarketplaceSkill {
+        MarketplaceSkill {
+            id: id.to_string(),
+            name: id.to_string(),
+            category: category.to_string(),
+            description: String::new(),
+            author: None,
+            version: None,
+            download_url: Some(format!("https://example.com/{}.md", id)),
+            source: String::new(),
+            installed: false,
+        }
+    }
+
+    #[test]
+    fn test_bundled_skills_have_metadata() {
+        let bundled = SkillMarketplaceService::bundled_skills();
+        assert!(!bundled.is_empty());
+        let prd = bundled.iter().find(|s| s.id == "generate-prd-draft").unwrap();
+        assert_eq!(prd.name, "Generate PRD Draft");
+        assert!(prd.description.starts_with("Generates an initial"));
+        assert_eq!(prd.source, "bundled");
+    }
+
+    #[test]
+    fn test_remote_entries_override_bundled() {
+        let mut skills = SkillMarketplaceService::bundled_skills();
+        let count = skills.len();
+        SkillMarketplaceService::merge(
+            &mut skills,
+            vec![entry("generate-prd-draft", "Writing"), entry("new-skill", "Research")],
+        );
+        assert_eq!(skills.len(), count + 1);
+        let prd = skills.iter().find(|s| s.id == "generate-prd-draft").unwrap();
+        assert_eq!(prd.source, "remote");
+        assert_eq!(prd.category, "Writing");
+    }
+
+    #[test]
+    fn test_query_matches_category() {
+        let skill = entry("lit-review", "Research");
+        assert!(SkillMarketplaceService::matches_query(&skill, Some("research")));
+        assert!(SkillMarketplaceService::matches_query(&skill, Some("  ")));
+        assert!(!SkillMarketplaceService::matches_query(&skill, Some("coding")));
+    }
+
+    #[test]
+    fn test_parse_rejects_skill_without_prompt() {
+        let result = SkillMarketplaceService::parse_skill(
+            &entry("empty-skill", "Research"),
+            "# Empty Skill\n\n## Overview\nNothing here.\n",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_takes_category_from_entry() {
+        let skill = SkillMarketplaceService::parse_skill(
+            &entry("lit-review", "Research"),
+            "# Literature Review\n\n## Prompt Template\nReview {{topic}}\n",
+        )
+        .unwrap();
+        assert_eq!(skill.category, "research");
+        assert_eq!(skill.capabilities, vec!["marketplace".to_string()]);
+    }
+}
@@ -4,27 +4,39 @@
 //! - Discovering and loading skills from the skills directory
 //! - Creating new skills with templates
 //! - Saving and validating skills
-//! - Filtering skills by category
+//! - Filtering skills by category or capability
 //! - Managing skill lifecycle (create, read, update, delete)
 //!
 //! Task 3.6 Implementation: Skills Service
 //! All skills are stored as markdown files in {APP_DATA}/skills/
 
-use crate::models::skill::{Skill, SkillError};
+use crate::models::skill::{Skill, SkillError, DEFAULT_SKILL_CATEGORY};
 use crate::services::settings_service::SettingsService;
 use crate::services::pm_skills;
 use anyhow::Result;
 use std::fs;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// How many folders deep below the skills directory skills are found, so they can be
+/// grouped under e.g. `research/` or `coding/`
+pub const SKILL_DIR_MAX_DEPTH: usize = 4;
+
 pub struct SkillService;
 
 impl SkillService {
-    /// Scan skills directory and load all .md files (max depth 1)
-    /// Skip files starting with .
+    /// Scan skills directory and load all .md files, including those in subfolders
+    /// (up to `SKILL_DIR_MAX_DEPTH` deep)
+    /// Skip files and folders starting with .
     /// Parse each using Skill::from_markdown_file()
     /// Return list of all valid skills
     pub fn discover_skills() -> Result<Vec<Skill>, SkillError> {
+        Self::discover_skills_each(|_| {})
+    }
+
+    /// Same as `discover_skills`, calling `on_skill` as each skill is parsed so callers
+    /// can show results before the whole directory has been read
+    pub fn discover_skills_each(mut on_skill: impl FnMut(&Skill)) -> Result<Vec<Skill>, SkillError> {
         let skills_dir = SettingsService::get_skills_path().map_err(|e| {
             SkillError::ReadError(std::io::Error::other(format!(
                 "Failed to get skills directory: {}",
@@ -42,36 +54,59 @@ impl SkillService {
             log::error!("Failed to seed PM skills: {}", e);
         }
 
+        let mut skills = Self::scan_skills_dir(&skills_dir, &mut on_skill);
+
+        // SEED DEFAULT SKILL IF EMPTY
+        if skills.is_empty() {
+            let default_skill = Self::create_skill_template(
+                "research-specialist".to_string(),
+                "Research Specialist".to_string(),
+                "A versatile AI assistant capable of conducting research, analyzing topics, and synthesizing information.".to_string(),
+                vec!["research".to_string(), "analysis".to_string(), "synthesis".to_string()],
+            );
+
+            // Save it so it persists
+            if let Err(e) = Self::save_skill(&default_skill) {
+                eprintln!("Failed to seed default skill: {}", e);
+            } else {
+                on_skill(&default_skill);
+                skills.push(default_skill);
+            }
+        }
+
+        // Sort by name for consistent ordering
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(skills)
+    }
+
+    /// Every skill file below `skills_dir`, skipping dotfiles, dot-folders such as
+    /// `.metadata` and the top-level `template.md`
+    fn scan_skills_dir(skills_dir: &Path, on_skill: &mut impl FnMut(&Skill)) -> Vec<Skill> {
         let mut skills = Vec::new();
 
-        // Use WalkDir with max_depth(1) to scan only the immediate directory
-        for entry in WalkDir::new(&skills_dir)
-            .max_depth(1)
+        for entry in WalkDir::new(skills_dir)
+            .max_depth(SKILL_DIR_MAX_DEPTH)
             .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
 
-            // Skip if it's a directory
-            if !path.is_file() {
+            // Only process .md files
+            if !path.is_file() || path.extension().and_then(|s| s.to_str()) != Some("md") {
                 continue;
             }
-
-            // Skip files starting with .
-            if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                if file_name.starts_with('.') || file_name == "template.md" {
-                    continue;
-                }
-            }
-
-            // Only process .md files
-            if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            if entry.depth() == 1 && entry.file_name() == "template.md" {
                 continue;
             }
 
             // Try to parse the skill
-            match Skill::from_markdown_file(&path.to_path_buf()) {
-                Ok(skill) => skills.push(skill),
+            match Self::load_skill_file(skills_dir, path) {
+                Ok(skill) => {
+                    on_skill(&skill);
+                    skills.push(skill);
+                }
                 Err(e) => {
                     eprintln!("Warning: Failed to load skill at {:?}: {}", path, e);
                     continue;
@@ -79,27 +114,48 @@ impl SkillService {
             }
         }
 
-        // SEED DEFAULT SKILL IF EMPTY
-        if skills.is_empty() {
-            let default_skill = Self::create_skill_template(
-                "research-specialist".to_string(),
-                "Research Specialist".to_string(),
-                "A versatile AI assistant capable of conducting research, analyzing topics, and synthesizing information.".to_string(),
-                vec!["research".to_string(), "analysis".to_string(), "synthesis".to_string()],
-            );
+        skills
+    }
 
-            // Save it so it persists
-            if let Err(e) = Self::save_skill(&default_skill) {
-                eprintln!("Failed to seed default skill: {}", e);
-            } else {
-                skills.push(default_skill);
+    /// Load the skill file at `path`. Skills in a subfolder get the folder in their id
+    /// (`research/reviewer`) and, unless they declare a category, the top folder as category.
+    fn load_skill_file(skills_dir: &Path, path: &Path) -> Result<Skill, SkillError> {
+        let mut skill = Skill::from_markdown_file(&path.to_path_buf())?;
+
+        let folder = path
+            .parent()
+            .and_then(|parent| parent.strip_prefix(skills_dir).ok())
+            .map(|folder| {
+                folder
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if let (Some(top), Some(stem)) = (folder.first(), path.file_stem()) {
+            skill.id = format!("{}/{}", folder.join("/"), stem.to_string_lossy());
+            if skill.category == DEFAULT_SKILL_CATEGORY {
+                skill.category = top.to_lowercase();
             }
         }
+        Ok(skill)
+    }
 
-        // Sort by name for consistent ordering
-        skills.sort_by(|a, b| a.name.cmp(&b.name));
-
-        Ok(skills)
+    /// Ids may name a subfolder (`research/reviewer`) but never leave the skills directory
+    fn skill_path(skills_dir: &Path, skill_id: &str) -> Result<PathBuf, SkillError> {
+        let valid = skill_id.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        });
+        if !valid {
+            return Err(SkillError::InvalidStructure(format!(
+                "Invalid skill id: {}",
+                skill_id
+            )));
+        }
+        Ok(skills_dir.join(format!("{}.md", skill_id)))
     }
 
     /// Load a specific skill by ID
@@ -115,7 +171,7 @@ impl SkillService {
             )))
         })?;
 
-        let skill_path = skills_dir.join(format!("{}.md", skill_id));
+        let skill_path = Self::skill_path(&skills_dir, skill_id)?;
 
         if !skill_path.exists() {
             return Err(SkillError::InvalidStructure(format!(
@@ -124,7 +180,7 @@ impl SkillService {
             )));
         }
 
-        Skill::from_markdown_file(&skill_path)
+        Self::load_skill_file(&skills_dir, &skill_path)
     }
 
     /// Save a skill to disk
@@ -149,12 +205,54 @@ impl SkillService {
             fs::create_dir_all(&skills_dir)?;
         }
 
-        let skill_path = skills_dir.join(format!("{}.md", skill.id));
+        let skill_path = Self::skill_path(&skills_dir, &skill.id)?;
+        if let Some(folder) = skill_path.parent() {
+            fs::create_dir_all(folder)?;
+        }
         skill.save(&skill_path)?;
 
         Ok(())
     }
 
+    /// Export a skill as one shareable markdown file, metadata included
+    pub fn export_skill(skill_id: &str) -> Result<String, SkillError> {
+        Self::load_skill(skill_id)?.to_shareable_markdown()
+    }
+
+    /// Import a skill from markdown content (an exported skill or a plain skill file).
+    /// The id gets a `-1`, `-2`, ... suffix when it is already taken.
+    pub fn import_skill(content: &str) -> Result<Skill, SkillError> {
+        let skills_dir = SettingsService::get_skills_path().map_err(|e| {
+            SkillError::ReadError(std::io::Error::other(format!(
+                "Failed to get skills directory: {}",
+                e
+            )))
+        })?;
+
+        let skill = Self::prepare_import(content, |id| {
+            skills_dir.join(format!("{}.md", id)).exists()
+        })?;
+        Self::save_skill(&skill)?;
+        Ok(skill)
+    }
+
+    fn prepare_import(content: &str, exists: impl Fn(&str) -> bool) -> Result<Skill, SkillError> {
+        let mut skill =
+            Skill::from_markdown_str(content, "imported-skill", None, std::path::PathBuf::new())?;
+        skill.validate().map_err(SkillError::ValidationError)?;
+
+        if exists(&skill.id) {
+            let mut n = 1;
+            while exists(&format!("{}-{}", skill.id, n)) {
+                n += 1;
+            }
+            skill.id = format!("{}-{}", skill.id, n);
+        }
+        skill.file_path = std::path::PathBuf::from(format!("{}.md", skill.id));
+        skill.updated = chrono::Utc::now().to_rfc3339();
+        Ok(skill)
+    }
+
     /// Delete a skill by ID
     /// Check if file exists
     /// Delete the file
@@ -167,7 +265,7 @@ impl SkillService {
             )))
         })?;
 
-        let skill_path = skills_dir.join(format!("{}.md", skill_id));
+        let skill_path = Self::skill_path(&skills_dir, skill_id)?;
 
         if !skill_path.exists() {
             return Err(SkillError::InvalidStructure(format!(
@@ -178,10 +276,13 @@ impl SkillService {
 
         fs::remove_file(&skill_path)?;
 
-        // Also remove sidecar if it exists
-        let sidecar_path = skills_dir
+        // Also remove sidecar if it exists; it sits next to the file, named by its stem
+        let stem = skill_id.rsplit('/').next().unwrap_or(skill_id);
+        let sidecar_path = skill_path
+            .parent()
+            .unwrap_or(&skills_dir)
             .join(".metadata")
-            .join(format!("{}.json", skill_id));
+            .join(format!("{}.json", stem));
         if sidecar_path.exists() {
             fs::remove_file(sidecar_path).ok();
         }
@@ -189,25 +290,35 @@ impl SkillService {
         Ok(())
     }
 
-    /// Get skills filtered by category
-    /// Note: SkillCategory is not currently defined in the Skill model.
-    /// This method is provided for future compatibility when categories are added.
-    /// Currently, this filters by checking if the category string appears in capabilities.
+    /// Get skills whose category is `category` (exact, case-insensitive)
     pub fn get_skills_by_category(category: &str) -> Result<Vec<Skill>, SkillError> {
-        let all_skills = Self::discover_skills()?;
+        Ok(Self::filter_by_category(Self::discover_skills()?, category))
+    }
+
+    /// Get skills that list `capability` among their capabilities (exact, case-insensitive)
+    pub fn get_skills_by_capability(capability: &str) -> Result<Vec<Skill>, SkillError> {
+        Ok(Self::filter_by_capability(Self::discover_skills()?, capability))
+    }
+
+    fn filter_by_category(skills: Vec<Skill>, category: &str) -> Vec<Skill> {
+        let category = category.trim();
+        skills
+            .into_iter()
+            .filter(|skill| skill.category.eq_ignore_ascii_case(category))
+            .collect()
+    }
 
-        let filtered_skills: Vec<Skill> = all_skills
+    fn filter_by_capability(skills: Vec<Skill>, capability: &str) -> Vec<Skill> {
+        let capability = capability.trim();
+        skills
             .into_iter()
             .filter(|skill| {
-                // Check if the category appears in capabilities
                 skill
                     .capabilities
                     .iter()
-                    .any(|cap| cap.to_lowercase().contains(&category.to_lowercase()))
+                    .any(|cap| cap.trim().eq_ignore_ascii_case(capability))
             })
-            .collect();
-
-        Ok(filtered_skills)
+            .collect()
     }
 
     /// Create a skill template with default values
@@ -227,6 +338,7 @@ impl SkillService {
             id: id.clone(),
             name,
             description,
+            category: DEFAULT_SKILL_CATEGORY.to_string(),
             capabilities,
             prompt_template: format!(
                 "You are an AI assistant with the following skill: {}\n\nPlease help the user with their request.",
@@ -238,6 +350,10 @@ impl SkillService {
             created: now.clone(),
             updated: now,
             file_path: std::path::PathBuf::from(format!("{}.md", id)),
+            tool_filter: None,
+            required_tools: Vec::new(),
+            block_without_required_tools: false,
+            enabled: true,
         }
     }
 
@@ -260,18 +376,9 @@ impl SkillService {
         name: &str,
         description: &str,
         prompt_template: &str,
+        category: &str,
         capabilities: Vec<String>,
     ) -> Result<Skill, SkillError> {
-        // Generate skill ID from name
-        let skill_id = name
-            .to_lowercase()
-            .replace(' ', "-")
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
-            .collect::<String>();
-
-        // Check if skill already exists
-        // Check if skill already exists
         let skills_dir = SettingsService::get_skills_path().map_err(|e| {
             SkillError::ReadError(std::io::Error::other(format!(
                 "Failed to get skills directory: {}",
@@ -279,13 +386,10 @@ impl SkillService {
             )))
         })?;
 
-        let skill_path = skills_dir.join(format!("{}.md", skill_id));
-        if skill_path.exists() {
-            return Err(SkillError::InvalidStructure(format!(
-                "Skill already exists: {}",
-                skill_id
-            )));
-        }
+        // Generate skill ID from name, refusing ids that are already taken
+        let skill_id = crate::utils::slug::unique_slug(name, |id| {
+            skills_dir.join(format!("{}.md", id)).exists()
+        })?;
 
         // Try to load template from template.md or use default
         let template_path = skills_dir.join("template.md");
@@ -326,6 +430,9 @@ impl SkillService {
         if !prompt_template.is_empty() {
             skill.prompt_template = prompt_template.to_string();
         }
+        if !category.trim().is_empty() {
+            skill.category = category.trim().to_lowercase();
+        }
 
         // Save the skill
         Self::save_skill(&skill)?;
@@ -343,6 +450,15 @@ impl SkillService {
         Self::save_skill(&updated_skill)
     }
 
+    /// Enable or disable a skill. Disabled skills are still returned by `discover_skills`.
+    pub fn set_skill_enabled(skill_id: &str, enabled: bool) -> Result<Skill, SkillError> {
+        let mut skill = Self::load_skill(skill_id)?;
+        skill.enabled = enabled;
+        skill.updated = chrono::Utc::now().to_rfc3339();
+        Self::save_skill(&skill)?;
+        Ok(skill)
+    }
+
     /// Seed PM skills from hardcoded definitions
     pub fn seed_pm_skills() -> Result<(), SkillError> {
         let skills_dir = SettingsService::get_skills_path().map_err(|e| {
@@ -451,6 +567,65 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_export_and_import_round_trip() {
+        let mut skill = SkillService::create_skill_template(
+            "reviewer".to_string(),
+            "Reviewer".to_string(),
+            "Reviews papers".to_string(),
+            vec!["review".to_string()],
+        );
+        skill.category = "research".to_string();
+        skill.prompt_template = "Review {{paper}}".to_string();
+        skill.parameters = vec![crate::models::skill::SkillParameter {
+            name: "paper".to_string(),
+            param_type: "string".to_string(),
+            description: "Paper title".to_string(),
+            required: true,
+            default_value: None,
+        }];
+
+        let shared = skill.to_shareable_markdown().unwrap();
+        let taken = ["reviewer", "reviewer-1"];
+        let imported = SkillService::prepare_import(&shared, |id| taken.contains(&id)).unwrap();
+        assert_eq!(imported.id, "reviewer-2");
+        assert_eq!(imported.name, "Reviewer");
+        assert_eq!(imported.category, "research");
+        assert_eq!(imported.capabilities, vec!["review"]);
+        assert_eq!(imported.parameters.len(), 1);
+        assert_eq!(imported.file_path, std::path::PathBuf::from("reviewer-2.md"));
+
+        // A plain skill file without metadata still imports; invalid skills are refused
+        let plain = "# Summarizer\n\n## Overview\nSummarizes text\n\n## Prompt Template\nSummarize.\n";
+        let imported = SkillService::prepare_import(plain, |_| false).unwrap();
+        assert_eq!(imported.id, "imported-skill");
+        assert_eq!(imported.description, "Summarizes text");
+        assert!(SkillService::prepare_import("# Empty\n", |_| false).is_err());
+    }
+
+    #[test]
+    fn test_category_and_capability_filters_match_exactly() {
+        let skill = |id: &str, category: &str, capabilities: &[&str]| {
+            let mut skill = SkillService::create_skill_template(
+                id.to_string(),
+                id.to_string(),
+                "Test".to_string(),
+                capabilities.iter().map(|c| c.to_string()).collect(),
+            );
+            skill.category = category.to_string();
+            skill
+        };
+        let skills = vec![
+            skill("reviewer", "research", &["research"]),
+            skill("recruiter", "general", &["researcher"]),
+        ];
+
+        let ids = |skills: Vec<Skill>| skills.into_iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(SkillService::filter_by_category(skills.clone(), "Research")), vec!["reviewer"]);
+        assert_eq!(ids(SkillService::filter_by_capability(skills.clone(), "research")), vec!["reviewer"]);
+        assert!(SkillService::filter_by_category(skills, "writing").is_empty());
+    }
+
     #[test]
     fn test_skills_directory_auto_discovery() {
         // Create a temporary skills directory with multiple skill files
@@ -520,6 +695,49 @@ mod tests {
         fs::remove_dir_all(&temp_dir).ok();
     }
 
+    #[test]
+    fn test_nested_skill_folders() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let skills_dir = temp.path();
+        let skill = |id: &str| {
+            SkillService::create_skill_template(
+                id.to_string(),
+                id.to_string(),
+                "Test".to_string(),
+                vec![],
+            )
+        };
+
+        skill("top").save(skills_dir.join("top.md")).unwrap();
+        fs::create_dir_all(skills_dir.join("research/papers")).unwrap();
+        skill("reviewer").save(skills_dir.join("research/papers/reviewer.md")).unwrap();
+        let mut writer = skill("writer");
+        writer.category = "writing".to_string();
+        fs::create_dir_all(skills_dir.join("Coding")).unwrap();
+        writer.save(skills_dir.join("Coding/writer.md")).unwrap();
+        fs::create_dir_all(skills_dir.join(".archive")).unwrap();
+        skill("old").save(skills_dir.join(".archive/old.md")).unwrap();
+        fs::write(skills_dir.join("template.md"), "# Template").unwrap();
+
+        let mut skills = SkillService::scan_skills_dir(skills_dir, &mut |_: &Skill| {});
+        skills.sort_by(|a, b| a.id.cmp(&b.id));
+        let found: Vec<(&str, &str)> =
+            skills.iter().map(|s| (s.id.as_str(), s.category.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Coding/writer", "writing"),
+                ("research/papers/reviewer", "research"),
+                ("top", DEFAULT_SKILL_CATEGORY),
+            ]
+        );
+
+        let path = SkillService::skill_path(skills_dir, "research/papers/reviewer").unwrap();
+        assert_eq!(path, skills_dir.join("research/papers/reviewer.md"));
+        assert!(SkillService::skill_path(skills_dir, "../outside").is_err());
+        assert!(SkillService::skill_path(skills_dir, "research//x").is_err());
+    }
+
     #[test]
     fn test_skill_prompt_rendering_with_parameters() {
         use crate::models::skill::SkillParameter;
@@ -606,8 +824,13 @@ mod tests {
         skill.id = "invalid!exclaim".to_string();
         assert!(skill.validate().is_err());
 
-        skill.id = "invalid/slash".to_string();
-        assert!(skill.validate().is_err());
+        // Slashes only separate folder names
+        for id in ["/leading", "trailing/", "double//slash", "../escape"] {
+            skill.id = id.to_string();
+            assert!(skill.validate().is_err(), "{}", id);
+        }
+        skill.id = "research/web-search".to_string();
+        assert!(skill.validate().is_ok());
 
         // Valid characters should pass
         skill.id = "valid-id-123".to_string();
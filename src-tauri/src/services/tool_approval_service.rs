@@ -0,0 +1,96 @@
+//! Tool Approval Service - Tool calls waiting on the user's approve/deny decision
+//!
+//! When a project requires approval, the orchestrator registers each tool call here,
+//! emits `tool-approval-request` and waits. The frontend answers through the
+//! `respond_tool_approval` command. No answer within the timeout counts as a denial.
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+/// How long a tool call waits for the user before it is treated as denied
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+static PENDING_APPROVALS: Lazy<Mutex<HashMap<String, oneshot::Sender<bool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub struct ToolApprovalService;
+
+impl ToolApprovalService {
+    /// Start waiting for a decision on `request_id`
+    pub fn register(request_id: &str) -> oneshot::Receiver<bool> {
+        let (sender, receiver) = oneshot::channel();
+        PENDING_APPROVALS
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .insert(request_id.to_string(), sender);
+        receiver
+    }
+
+    /// Deliver the user's decision. Fails when the request already timed out.
+    pub fn respond(request_id: &str, approved: bool) -> Result<()> {
+        let sender = PENDING_APPROVALS
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .remove(request_id)
+            .ok_or_else(|| anyhow!("No pending tool approval '{}'; it may have timed out", request_id))?;
+        sender
+            .send(approved)
+            .map_err(|_| anyhow!("Tool approval '{}' is no longer awaited", request_id))
+    }
+
+    /// Wait for the decision. Timeouts and cancellation count as a denial.
+    pub async fn wait(
+        request_id: &str,
+        receiver: oneshot::Receiver<bool>,
+        timeout: Duration,
+        cancel: &CancellationToken,
+    ) -> bool {
+        let approved = tokio::select! {
+            decision = tokio::time::timeout(timeout, receiver) => matches!(decision, Ok(Ok(true))),
+            _ = cancel.cancelled() => false,
+        };
+        PENDING_APPROVALS
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .remove(request_id);
+        approved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_response_is_delivered() {
+        let receiver = ToolApprovalService::register("approval-test-1");
+        ToolApprovalService::respond("approval-test-1", true).unwrap();
+        let approved = ToolApprovalService::wait(
+            "approval-test-1",
+            receiver,
+            Duration::from_secs(5),
+            &CancellationToken::new(),
+        )
+        .await;
+        assert!(approved);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_denies_and_late_response_fails() {
+        let receiver = ToolApprovalService::register("approval-test-2");
+        let approved = ToolApprovalService::wait(
+            "approval-test-2",
+            receiver,
+            Duration::from_millis(10),
+            &CancellationToken::new(),
+        )
+        .await;
+        assert!(!approved);
+        assert!(ToolApprovalService::respond("approval-test-2", true).is_err());
+    }
+}
@@ -1,8 +1,16 @@
-use crate::models::ai::Message;
+use crate::models::ai::chat_models::ChatOptions;
+use crate::models::ai::{ChatResponse, Message};
+use crate::models::settings::LoggingPrivacyLevel;
+use crate::models::skill::Skill;
 use crate::models::workflow::*;
+use crate::services::agent_orchestrator::EventSink;
 use crate::services::ai_service::AIService;
+use crate::services::mcp_service::McpService;
 use crate::services::output_cleaner_service::OutputCleanerService;
+use crate::services::output_parser_service::OutputParserService;
 use crate::services::project_service::ProjectService;
+use crate::services::research_log_service::ResearchLogService;
+use crate::services::settings_service::SettingsService;
 use crate::services::skill_service::SkillService;
 use crate::services::chat_service::ChatService;
 use crate::services::artifact_service::ArtifactService;
@@ -11,14 +19,24 @@ use futures_util::stream::{FuturesUnordered, StreamExt};
 use glob::glob as glob_pattern;
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 pub struct WorkflowService;
 
 impl WorkflowService {
     /// Load all workflows for a project
-    /// Reads all .json files from {projects}/{project_id}/.workflows/
+    /// Reads all .json files from {projects}/{project_id}/.workflows/, skipping malformed ones
     pub fn load_project_workflows(project_id: &str) -> Result<Vec<Workflow>, WorkflowError> {
+        Ok(Self::scan_project_workflows(project_id)?.workflows)
+    }
+
+    /// Load every workflow file for a project, upgrading older schema versions on disk.
+    /// Files that still fail to parse are skipped and reported instead of failing the load.
+    pub fn scan_project_workflows(project_id: &str) -> Result<WorkflowLoadReport, WorkflowError> {
         let project_path = ProjectService::resolve_project_path(project_id).map_err(|e| {
             WorkflowError::ReadError(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
@@ -30,10 +48,10 @@ impl WorkflowService {
 
         // If directory doesn't exist, return empty list
         if !workflows_dir.exists() {
-            return Ok(Vec::new());
+            return Ok(WorkflowLoadReport::default());
         }
 
-        let mut workflows = Vec::new();
+        let mut report = WorkflowLoadReport::default();
 
         // Read all .json files
         let entries = fs::read_dir(&workflows_dir).map_err(WorkflowError::ReadError)?;
@@ -43,28 +61,23 @@ impl WorkflowService {
             let path = entry.path();
 
             // Only process .json files
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                // Read and deserialize each workflow, skipping invalid files
-                match fs::read_to_string(&path) {
-                    Ok(content) => {
-                        match serde_json::from_str::<Workflow>(&content) {
-                            Ok(workflow) => workflows.push(workflow),
-                            Err(e) => {
-                                log::warn!(
-                                    "Skipping workflow file {:?} due to parse error: {}",
-                                    path, e
-                                );
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::warn!("Skipping workflow file {:?} - could not read: {}", path, e);
-                    }
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+
+            match Self::read_workflow_file(&path, project_id) {
+                Ok(workflow) => report.workflows.push(workflow),
+                Err(e) => {
+                    log::warn!("Skipping workflow file {:?}: {}", path, e);
+                    report.errors.push(WorkflowLoadError {
+                        file_name: entry.file_name().to_string_lossy().to_string(),
+                        error: e.to_string(),
+                    });
                 }
             }
         }
 
-        Ok(workflows)
+        Ok(report)
     }
 
     /// Load a specific workflow by ID
@@ -89,10 +102,29 @@ impl WorkflowService {
             )));
         }
 
-        // Read and deserialize
-        let content = fs::read_to_string(&workflow_path)?;
-        let workflow: Workflow = serde_json::from_str(&content)
-            .map_err(|e| WorkflowError::ParseError(format!("Failed to parse workflow: {}", e)))?;
+        Self::read_workflow_file(&workflow_path, project_id)
+    }
+
+    /// Parse a workflow file, writing it back when it was upgraded from an older schema
+    fn read_workflow_file(path: &Path, project_id: &str) -> Result<Workflow, WorkflowError> {
+        let content = fs::read_to_string(path)?;
+        let (workflow, migrated) = Workflow::from_json(&content, project_id)?;
+
+        if migrated {
+            log::info!(
+                "Upgraded workflow {:?} to schema version {}",
+                path,
+                WORKFLOW_SCHEMA_VERSION
+            );
+            match serde_json::to_string_pretty(&workflow) {
+                Ok(json) => {
+                    if let Err(e) = fs::write(path, json) {
+                        log::warn!("Failed to save upgraded workflow {:?}: {}", path, e);
+                    }
+                }
+                Err(e) => log::warn!("Failed to serialize upgraded workflow {:?}: {}", path, e),
+            }
+        }
 
         Ok(workflow)
     }
@@ -119,7 +151,11 @@ impl WorkflowService {
             fs::create_dir_all(&workflows_dir)?;
         }
 
-        // Serialize to pretty JSON
+        // Serialize to pretty JSON, always in the current schema
+        let workflow = &Workflow {
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            ..workflow.clone()
+        };
         let json_content = serde_json::to_string_pretty(workflow).map_err(|e| {
             WorkflowError::ParseError(format!("Failed to serialize workflow: {}", e))
         })?;
@@ -156,18 +192,24 @@ impl WorkflowService {
     // ===== Execution Engine =====
 
     /// Execute a workflow by ID
-    /// Main entry point for workflow execution
-    pub async fn execute_workflow<F>(
+    /// Main entry point for workflow execution. Progress goes to `events` as
+    /// `workflow-progress`, `workflow-step-started`, `workflow-step-completed`,
+    /// `workflow-step-failed` and a final `workflow-completed`.
+    ///
+    /// Cancelling `cancel` stops the run between steps and aborts steps in flight;
+    /// completed steps keep their outputs and the rest are marked `Cancelled`.
+    pub async fn execute_workflow(
         project_id: &str,
         workflow_id: &str,
         parameters: Option<HashMap<String, String>>,
-        progress_callback: F,
-    ) -> Result<WorkflowExecution, WorkflowError>
-    where
-        F: Fn(WorkflowProgress) + Send + Sync,
-    {
+        events: &EventSink,
+        cancel: &CancellationToken,
+    ) -> Result<WorkflowExecution, WorkflowError> {
         // Load workflow
         let mut workflow = Self::load_workflow(project_id, workflow_id)?;
+        // Hand-edited files skip `save_workflow`; refuse cycles here rather than mid-run
+        workflow.validate().map_err(WorkflowError::ValidationError)?;
+        let run_started = Instant::now();
 
         // Create execution instance
         let mut execution = WorkflowExecution {
@@ -177,6 +219,8 @@ impl WorkflowService {
             status: ExecutionStatus::Running,
             error: None,
             step_results: HashMap::new(),
+            step_outputs: HashMap::new(),
+            total_usage: StepUsage::default(),
         };
 
         // Execute steps
@@ -185,14 +229,21 @@ impl WorkflowService {
             &mut execution,
             project_id,
             &parameters,
-            &progress_callback,
+            events,
+            cancel,
         )
         .await;
 
         // Update execution status
         execution.completed = Some(Utc::now().to_rfc3339());
+        execution.total_usage = StepUsage::total(execution.step_results.values().map(|r| &r.usage));
         execution.status = match &result {
             Ok(_) => ExecutionStatus::Completed,
+            Err(WorkflowError::Cancelled) => {
+                execution.error = Some(WorkflowError::Cancelled.to_string());
+                Self::log_cancelled_run(project_id, &workflow, &execution);
+                ExecutionStatus::Cancelled
+            }
             Err(e) => {
                 execution.error = Some(e.to_string());
                 // Check if any steps succeeded
@@ -208,6 +259,17 @@ impl WorkflowService {
             }
         };
 
+        Self::emit(
+            events,
+            "workflow-completed",
+            WorkflowCompletedEvent {
+                workflow_id: workflow.id.clone(),
+                status: execution.status.clone(),
+                duration_ms: run_started.elapsed().as_millis() as u64,
+                error: execution.error.clone(),
+            },
+        );
+
         // Update workflow metadata
         workflow.status = Some(format!("{:?}", execution.status));
         workflow.last_run = Some(execution.started.clone());
@@ -217,37 +279,31 @@ impl WorkflowService {
     }
 
     /// Execute workflow steps in dependency order
-    async fn execute_steps<F>(
+    async fn execute_steps(
         workflow: &Workflow,
         execution: &mut WorkflowExecution,
         project_id: &str,
         parameters: &Option<HashMap<String, String>>,
-        progress_callback: &F,
-    ) -> Result<(), WorkflowError>
-    where
-        F: Fn(WorkflowProgress) + Send + Sync,
-    {
+        events: &EventSink,
+        cancel: &CancellationToken,
+    ) -> Result<(), WorkflowError> {
         // Get execution layers
         let layers = Self::get_execution_layers(&workflow.steps)?;
         let total_steps = workflow.steps.len();
         let mut completed_count = 0;
+        let project_path = ProjectService::resolve_project_path(project_id).ok();
 
         // Execute layers in sequence
         for layer in layers {
+            if cancel.is_cancelled() {
+                Self::mark_cancelled(workflow, execution);
+                return Err(WorkflowError::Cancelled);
+            }
             let mut futures = FuturesUnordered::new();
 
             for step in layer {
-                // Check if all dependencies were successful
-                let deps_satisfied = step.depends_on.iter().all(|dep_id| {
-                    execution
-                        .step_results
-                        .get(dep_id)
-                        .map(|r| matches!(r.status, StepStatus::Completed))
-                        .unwrap_or(true) // No dependency means true
-                });
-
-                if !deps_satisfied {
-                    // Skip this step
+                // Skip the step when a dependency failed or a condition ruled it out
+                if let Some(reason) = Self::skip_reason(workflow, execution, &step) {
                     execution.step_results.insert(
                         step.id.clone(),
                         StepResult {
@@ -256,58 +312,82 @@ impl WorkflowService {
                             started: Utc::now().to_rfc3339(),
                             completed: Some(Utc::now().to_rfc3339()),
                             output_files: vec![],
-                            error: Some("Dependencies not satisfied".to_string()),
+                            error: Some(reason.clone()),
                             detailed_error: None,
                             logs: vec![],
                             next_step_id: None,
+                            condition_met: None,
+                            duration_ms: None,
+                            output: None,
+                            usage: StepUsage::default(),
                         },
                     );
                     completed_count += 1;
 
                     // Emit skip status
                     let progress_percent = ((completed_count as f32 / total_steps as f32) * 100.0) as u32;
-                    progress_callback(WorkflowProgress {
+                    Self::emit(events, "workflow-progress", WorkflowProgress {
                         workflow_id: workflow.id.clone(),
                         step_name: step.name.clone(),
                         status: "skipped".to_string(),
                         progress_percent,
+                        message: Some(reason),
                     });
                     continue;
                 }
 
                 // Emit running status
                 let progress_percent = ((completed_count as f32 / total_steps as f32) * 100.0) as u32;
-                progress_callback(WorkflowProgress {
+                Self::emit(events, "workflow-progress", WorkflowProgress {
                     workflow_id: workflow.id.clone(),
                     step_name: step.name.clone(),
                     status: "running".to_string(),
                     progress_percent,
+                    message: None,
                 });
+                Self::emit(events, "workflow-step-started", WorkflowStepEvent::new(&workflow.id, &step, 0));
 
                 // Prepare for parallel execution
                 let step_clone = step.clone();
                 let project_id_owned = project_id.to_string();
                 let execution_snapshot = execution.clone();
                 let parameters_owned = parameters.clone();
+                let cancel_owned = cancel.clone();
 
                 futures.push(async move {
+                    let step_started = Instant::now();
                     let result = Self::execute_step(
                         &step_clone,
                         &project_id_owned,
                         &execution_snapshot,
                         &parameters_owned,
+                        &cancel_owned,
                     ).await;
-                    (step_clone, result)
+                    (step_clone, result, step_started.elapsed())
                 });
             }
 
-            // Collect results from the current layer
-            while let Some((step, result)) = futures.next().await {
+            // Collect results from the current layer; cancelling drops the steps in flight
+            loop {
+                let next = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => None,
+                    next = futures.next() => next,
+                };
+                let Some((step, mut result, elapsed)) = next else { break };
+                result.duration_ms = Some(elapsed.as_millis() as u64);
                 execution
                     .step_results
                     .insert(step.id.clone(), result.clone());
                 completed_count += 1;
 
+                // Keep the output for `{{steps.<id>.output}}` references in later steps
+                if let Some(project_path) = project_path.as_deref() {
+                    if let Ok(output) = Self::load_step_output(&step.id, execution, project_path) {
+                        execution.step_outputs.insert(step.id.clone(), output);
+                    }
+                }
+
                 // Emit completed/failed status
                 let status_str = match result.status {
                     StepStatus::Completed => "completed",
@@ -316,12 +396,24 @@ impl WorkflowService {
                 };
 
                 let progress_percent = ((completed_count as f32 / total_steps as f32) * 100.0) as u32;
-                progress_callback(WorkflowProgress {
+                Self::emit(events, "workflow-progress", WorkflowProgress {
                     workflow_id: workflow.id.clone(),
                     step_name: step.name.clone(),
                     status: status_str.to_string(),
                     progress_percent,
+                    message: if matches!(result.status, StepStatus::Failed) {
+                        result.error.clone()
+                    } else {
+                        None
+                    },
                 });
+                let mut step_event = WorkflowStepEvent::new(&workflow.id, &step, elapsed.as_millis() as u64);
+                if matches!(result.status, StepStatus::Failed) {
+                    step_event.error = result.error.clone();
+                    Self::emit(events, "workflow-step-failed", step_event);
+                } else {
+                    Self::emit(events, "workflow-step-completed", step_event);
+                }
 
                 // Handle failure
                 if matches!(result.status, StepStatus::Failed) {
@@ -335,45 +427,180 @@ impl WorkflowService {
                     }
                 }
             }
+            if cancel.is_cancelled() {
+                Self::mark_cancelled(workflow, execution);
+                return Err(WorkflowError::Cancelled);
+            }
         }
 
         Ok(())
     }
 
+    /// Record every step that has no result yet, including those that were running, as cancelled
+    fn mark_cancelled(workflow: &Workflow, execution: &mut WorkflowExecution) {
+        let now = Utc::now().to_rfc3339();
+        for step in &workflow.steps {
+            execution.step_results.entry(step.id.clone()).or_insert_with(|| StepResult {
+                step_id: step.id.clone(),
+                status: StepStatus::Cancelled,
+                started: now.clone(),
+                completed: Some(now.clone()),
+                output_files: vec![],
+                error: Some("Workflow was cancelled".to_string()),
+                detailed_error: None,
+                logs: vec![],
+                next_step_id: None,
+                condition_met: None,
+                duration_ms: None,
+                output: None,
+                usage: StepUsage::default(),
+            });
+        }
+    }
+
+    /// Note a cancelled run in the research log with the steps that finished and their outputs
+    fn log_cancelled_run(project_id: &str, workflow: &Workflow, execution: &WorkflowExecution) {
+        let privacy = SettingsService::load_global_settings()
+            .map(|settings| settings.logging_privacy_level)
+            .unwrap_or_default();
+        if privacy == LoggingPrivacyLevel::Off {
+            return;
+        }
+        let finished: Vec<String> = workflow
+            .steps
+            .iter()
+            .filter_map(|step| {
+                let result = execution.step_results.get(&step.id)?;
+                if !matches!(result.status, StepStatus::Completed) {
+                    return None;
+                }
+                Some(if result.output_files.is_empty() {
+                    format!("- {}", step.name)
+                } else {
+                    format!("- {} -> {}", step.name, result.output_files.join(", "))
+                })
+            })
+            .collect();
+        let summary = format!(
+            "Workflow '{}' was cancelled after {} of {} steps.\n{}",
+            workflow.name,
+            finished.len(),
+            workflow.steps.len(),
+            finished.join("\n")
+        );
+        if let Err(e) = ResearchLogService::log_event(project_id, "Workflow", None, summary.trim_end()) {
+            log::warn!("Failed to record cancelled workflow run: {}", e);
+        }
+    }
+
+    fn emit<S: serde::Serialize>(events: &EventSink, event: &str, payload: S) {
+        if let Ok(value) = serde_json::to_value(payload) {
+            events(event, value);
+        }
+    }
+
+    /// Why `step` must not run, if any of its dependencies failed, were skipped, or are
+    /// conditional steps whose outcome rules it out
+    fn skip_reason(workflow: &Workflow, execution: &WorkflowExecution, step: &WorkflowStep) -> Option<String> {
+        step.depends_on.iter().find_map(|dep_id| {
+            let result = execution.step_results.get(dep_id)?;
+            let dep_step = workflow.steps.iter().find(|s| &s.id == dep_id)?;
+            if !matches!(result.status, StepStatus::Completed) {
+                return Some(format!("Dependency '{}' did not complete", dep_step.name));
+            }
+            if result.allows_dependent(dep_step, &step.id) {
+                return None;
+            }
+            let outcome = if result.condition_met == Some(true) { "true" } else { "false" };
+            Some(format!("Condition of step '{}' was {}", dep_step.name, outcome))
+        })
+    }
+
     /// Execute a single step with retry logic
     async fn execute_step(
         step: &WorkflowStep,
         project_id: &str,
         execution: &WorkflowExecution,
         parameters: &Option<HashMap<String, String>>,
+        cancel: &CancellationToken,
     ) -> StepResult {
+        let resolved_step;
+        let step = match Self::resolve_step_parameters(step, project_id, execution) {
+            Ok(Some(step)) => {
+                resolved_step = step;
+                &resolved_step
+            }
+            Ok(None) => step,
+            Err(e) => {
+                return StepResult {
+                    step_id: step.id.clone(),
+                    status: StepStatus::Failed,
+                    started: Utc::now().to_rfc3339(),
+                    completed: Some(Utc::now().to_rfc3339()),
+                    output_files: vec![],
+                    error: Some(e.clone()),
+                    detailed_error: Some(e),
+                    logs: vec![],
+                    next_step_id: None,
+                    condition_met: None,
+                    duration_ms: None,
+                    output: None,
+                    usage: StepUsage::default(),
+                };
+            }
+        };
+
         let max_retries = step.config.max_retries.unwrap_or(0);
         let mut last_error = None;
 
         for attempt in 0..=max_retries {
             if attempt > 0 {
-                // Wait before retry (exponential backoff)
-                tokio::time::sleep(tokio::time::Duration::from_secs(2_u64.pow(attempt))).await;
+                // Wait before retry (exponential backoff), unless the run is being cancelled
+                let backoff = tokio::time::sleep(tokio::time::Duration::from_secs(2_u64.pow(attempt)));
+                tokio::select! {
+                    _ = backoff => {}
+                    _ = cancel.cancelled() => {
+                        let now = Utc::now().to_rfc3339();
+                        return StepResult {
+                            step_id: step.id.clone(),
+                            status: StepStatus::Cancelled,
+                            started: now.clone(),
+                            completed: Some(now),
+                            output_files: vec![],
+                            error: Some("Workflow was cancelled".to_string()),
+                            detailed_error: last_error,
+                            logs: vec![],
+                            next_step_id: None,
+                            condition_met: None,
+                            duration_ms: None,
+                            output: None,
+                            usage: StepUsage::default(),
+                        };
+                    }
+                }
             }
 
             let result = match &step.step_type {
                 StepType::Input => Self::execute_input_step(step, project_id, parameters).await,
                 StepType::Agent | StepType::Skill => {
-                    Self::execute_agent_step(step, project_id, execution, parameters).await
+                    Self::execute_agent_step(step, project_id, execution, parameters, cancel).await
                 }
                 StepType::Iteration => {
-                    Self::execute_iteration_step(step, project_id, execution, parameters).await
+                    Self::execute_iteration_step(step, project_id, execution, parameters, cancel).await
                 }
                 StepType::Synthesis => {
-                    Self::execute_synthesis_step(step, project_id, execution, parameters).await
+                    Self::execute_synthesis_step(step, project_id, execution, parameters, cancel).await
+                }
+                StepType::Conditional => Self::execute_conditional_step(step, project_id, execution).await,
+                StepType::Loop => {
+                    Self::execute_loop_step(step, project_id, execution, parameters, cancel).await
                 }
-                StepType::Conditional => Self::execute_conditional_step(step, project_id).await,
                 StepType::SubAgent => {
-                    Self::execute_iteration_step(step, project_id, execution, parameters).await
+                    Self::execute_iteration_step(step, project_id, execution, parameters, cancel).await
                 }
                 _ => {
                     // Legacy step types
-                    Self::execute_agent_step(step, project_id, execution, parameters).await
+                    Self::execute_agent_step(step, project_id, execution, parameters, cancel).await
                 }
             };
 
@@ -401,9 +628,168 @@ impl WorkflowService {
             detailed_error: Some(err_msg),
             logs: vec![],
             next_step_id: None,
+            condition_met: None,
+            duration_ms: None,
+            output: None,
+            usage: StepUsage::default(),
         }
     }
 
+    /// Resolve `{{steps.<step_id>.output...}}` references in `step.config.parameters`.
+    /// Returns `None` when the step has no references and can run as-is.
+    fn resolve_step_parameters(
+        step: &WorkflowStep,
+        project_id: &str,
+        execution: &WorkflowExecution,
+    ) -> Result<Option<WorkflowStep>, String> {
+        if !step.config.parameters.to_string().contains(STEP_REFERENCE_PREFIX) {
+            return Ok(None);
+        }
+
+        let project_path = ProjectService::resolve_project_path(project_id)
+            .map_err(|e| format!("Failed to resolve project path: {}", e))?;
+
+        let mut resolved = step.clone();
+        resolved.config.parameters =
+            Self::resolve_step_references(&step.config.parameters, &|step_id: &str| {
+                Self::load_step_output(step_id, execution, &project_path)
+            })?;
+        Ok(Some(resolved))
+    }
+
+    /// Walk a parameters value and substitute step output references inside every string.
+    ///
+    /// Grammar: `{{steps.<step_id>.output}}` or `{{steps.<step_id>.output.<key>...}}`, where each
+    /// `<key>` is an object field or an array index into the step's output parsed as JSON.
+    /// A string that consists of a single reference takes the referenced JSON value as-is
+    /// (so numbers stay numbers); references embedded in text are replaced by their string form.
+    /// Any other text, including `{{param}}` placeholders, is left untouched.
+    fn resolve_step_references(
+        value: &serde_json::Value,
+        output_of: &dyn Fn(&str) -> Result<serde_json::Value, String>,
+    ) -> Result<serde_json::Value, String> {
+        use serde_json::Value;
+
+        match value {
+            Value::String(text) => {
+                let trimmed = text.trim();
+                if let Some(reference) = trimmed
+                    .strip_prefix("{{")
+                    .and_then(|t| t.strip_suffix("}}"))
+                    .filter(|r| r.starts_with("steps.") && !r.contains("{{"))
+                {
+                    return Self::resolve_step_reference(reference, output_of);
+                }
+
+                let mut result = String::new();
+                let mut rest = text.as_str();
+                while let Some(start) = rest.find(STEP_REFERENCE_PREFIX) {
+                    let Some(len) = rest[start..].find("}}") else {
+                        break;
+                    };
+                    let reference = &rest[start + 2..start + len];
+                    let resolved = Self::resolve_step_reference(reference, output_of)?;
+                    result.push_str(&rest[..start]);
+                    match resolved {
+                        Value::String(s) => result.push_str(&s),
+                        other => result.push_str(&other.to_string()),
+                    }
+                    rest = &rest[start + len + 2..];
+                }
+                result.push_str(rest);
+                Ok(Value::String(result))
+            }
+            Value::Array(items) => items
+                .iter()
+                .map(|item| Self::resolve_step_references(item, output_of))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            Value::Object(map) => {
+                let mut resolved = serde_json::Map::new();
+                for (key, item) in map {
+                    resolved.insert(key.clone(), Self::resolve_step_references(item, output_of)?);
+                }
+                Ok(Value::Object(resolved))
+            }
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// Resolve a single `steps.<step_id>.output[.<path>]` reference (without braces)
+    fn resolve_step_reference(
+        reference: &str,
+        output_of: &dyn Fn(&str) -> Result<serde_json::Value, String>,
+    ) -> Result<serde_json::Value, String> {
+        let mut segments = reference.split('.');
+        let (Some("steps"), Some(step_id), Some("output")) =
+            (segments.next(), segments.next(), segments.next())
+        else {
+            return Err(format!(
+                "Invalid step reference '{{{{{}}}}}' (expected steps.<step_id>.output[.<path>])",
+                reference
+            ));
+        };
+
+        let mut current = output_of(step_id)?;
+        for segment in segments {
+            let next = match &current {
+                serde_json::Value::Object(map) => map.get(segment).cloned(),
+                serde_json::Value::Array(items) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| items.get(i).cloned()),
+                _ => None,
+            };
+            current = next.ok_or_else(|| {
+                format!(
+                    "Reference '{{{{{}}}}}' not found: no '{}' in output of step '{}'",
+                    reference, segment, step_id
+                )
+            })?;
+        }
+        Ok(current)
+    }
+
+    /// Load the output of a completed step: the value recorded in `step_outputs`, else the
+    /// contents of its first output file, parsed as JSON when possible and otherwise a string
+    fn load_step_output(
+        step_id: &str,
+        execution: &WorkflowExecution,
+        project_path: &Path,
+    ) -> Result<serde_json::Value, String> {
+        if let Some(output) = execution.step_outputs.get(step_id) {
+            return Ok(output.clone());
+        }
+        let result = execution
+            .step_results
+            .get(step_id)
+            .ok_or_else(|| format!("Step '{}' has not run yet; add it to depends_on", step_id))?;
+        if !matches!(result.status, StepStatus::Completed) {
+            return Err(format!("Step '{}' did not complete successfully", step_id));
+        }
+        Self::read_result_output(step_id, result, project_path)
+    }
+
+    /// Output recorded on a step result, else its first output file as in `load_step_output`
+    fn read_result_output(
+        step_id: &str,
+        result: &StepResult,
+        project_path: &Path,
+    ) -> Result<serde_json::Value, String> {
+        if let Some(output) = &result.output {
+            return Ok(output.clone());
+        }
+        let output_file = result
+            .output_files
+            .first()
+            .ok_or_else(|| format!("Step '{}' produced no output", step_id))?;
+        let path = Self::safe_join_project(project_path, output_file)?;
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read output of step '{}': {}", step_id, e))?;
+
+        Ok(serde_json::from_str(&content).unwrap_or(serde_json::Value::String(content)))
+    }
+
     /// Helper to replace parameters in a string
     fn replace_parameters(text: &str, parameters: &Option<HashMap<String, String>>) -> String {
         let mut result = text.to_string();
@@ -540,6 +926,10 @@ impl WorkflowService {
             detailed_error: None,
             logs,
             next_step_id: None,
+            condition_met: None,
+            duration_ms: None,
+            output: None,
+            usage: StepUsage::default(),
         })
     }
 
@@ -547,8 +937,9 @@ impl WorkflowService {
     async fn execute_agent_step(
         step: &WorkflowStep,
         project_id: &str,
-        _execution: &WorkflowExecution,
+        execution: &WorkflowExecution,
         parameters: &Option<HashMap<String, String>>,
+        cancel: &CancellationToken,
     ) -> Result<StepResult, String> {
         let started = Utc::now().to_rfc3339();
         let mut logs = Vec::new();
@@ -628,13 +1019,19 @@ impl WorkflowService {
         }];
 
         let response_obj = ai_service
-            .chat(messages, None, Some(project_id.to_string()))
+            .chat_with_options(
+                messages,
+                None,
+                Some(project_id.to_string()),
+                Self::chat_options(project_id, execution, &skill, cancel),
+            )
             .await
             .map_err(|e| format!("AI Service error: {}", e))?;
 
         let response = OutputCleanerService::clean(&response_obj.content);
+        let usage = Self::response_usage(&response_obj);
 
-        logs.push(format!("Received response ({} chars)", response.len()));
+        logs.push(format!("Received response ({} chars, {})", response.len(), usage.summary()));
 
         // Create Artifact if specified
         if let Some(artifact_type) = &step.config.artifact_type {
@@ -686,6 +1083,10 @@ impl WorkflowService {
             detailed_error: None,
             logs,
             next_step_id: None,
+            condition_met: None,
+            duration_ms: None,
+            output: None,
+            usage,
         })
     }
 
@@ -695,6 +1096,7 @@ impl WorkflowService {
         project_id: &str,
         execution: &WorkflowExecution,
         parameters: &Option<HashMap<String, String>>,
+        cancel: &CancellationToken,
     ) -> Result<StepResult, String> {
         let started = Utc::now().to_rfc3339();
         let mut logs = Vec::new();
@@ -713,6 +1115,7 @@ impl WorkflowService {
 
         let parallel = step.config.parallel.unwrap_or(false);
         let mut output_files = Vec::new();
+        let mut usage = StepUsage::default();
 
         if parallel {
             logs.push("Running in parallel".to_string());
@@ -721,15 +1124,16 @@ impl WorkflowService {
             let mut futures = FuturesUnordered::new();
             for item in &items {
                 let future =
-                    Self::execute_iteration_item(step, item, project_id, execution, parameters);
+                    Self::execute_iteration_item(step, item, project_id, execution, parameters, cancel);
                 futures.push(future);
             }
 
             while let Some(result) = futures.next().await {
                 match result {
-                    Ok((file, item_logs)) => {
+                    Ok((file, item_logs, item_usage)) => {
                         logs.extend(item_logs);
                         logs.push(format!("Completed item, output: {}", file));
+                        usage.add(&item_usage);
                         output_files.push(file);
                     }
                     Err(e) => {
@@ -745,12 +1149,13 @@ impl WorkflowService {
 
             // Execute sequentially
             for item in &items {
-                match Self::execute_iteration_item(step, item, project_id, execution, parameters)
+                match Self::execute_iteration_item(step, item, project_id, execution, parameters, cancel)
                     .await
                 {
-                    Ok((file, item_logs)) => {
+                    Ok((file, item_logs, item_usage)) => {
                         logs.extend(item_logs);
                         output_files.push(file);
+                        usage.add(&item_usage);
                     }
                     Err(e) => {
                         logs.push(format!("Item '{}' failed: {}", item, e));
@@ -762,6 +1167,8 @@ impl WorkflowService {
             }
         }
 
+        logs.push(format!("Items used {}", usage.summary()));
+
         Ok(StepResult {
             step_id: step.id.clone(),
             status: StepStatus::Completed,
@@ -772,17 +1179,228 @@ impl WorkflowService {
             detailed_error: None,
             logs,
             next_step_id: None,
+            condition_met: None,
+            duration_ms: None,
+            output: None,
+            usage,
         })
     }
 
+    /// Run the loop's child step once per item, in order, collecting each child's output
+    /// into an array that later steps reference as `{{steps.<loop_id>.output}}`
+    fn execute_loop_step<'a>(
+        step: &'a WorkflowStep,
+        project_id: &'a str,
+        execution: &'a WorkflowExecution,
+        parameters: &'a Option<HashMap<String, String>>,
+        cancel: &'a CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<StepResult, String>> + Send + 'a>> {
+        // Boxed with an explicit Send bound since a child may itself be a loop
+        Box::pin(async move {
+            let started = Utc::now().to_rfc3339();
+            let child = step.config.child.as_deref().ok_or("child step not specified")?;
+            let project_path = ProjectService::resolve_project_path(project_id)
+                .map_err(|e| format!("Failed to resolve project path: {}", e))?;
+
+            let items = Self::resolve_loop_items(step, execution, &project_path)?;
+            let max_iterations = step.config.max_iterations.unwrap_or(DEFAULT_MAX_LOOP_ITERATIONS) as usize;
+            if items.len() > max_iterations {
+                return Err(format!(
+                    "Loop has {} items but max_iterations is {}",
+                    items.len(),
+                    max_iterations
+                ));
+            }
+
+            let continue_on_error = step.config.continue_on_error.unwrap_or(false);
+            let mut logs = vec![format!("Looping over {} items", items.len())];
+            let mut outputs = Vec::with_capacity(items.len());
+            let mut output_files = Vec::new();
+            let mut usage = StepUsage::default();
+
+            for (index, item) in items.iter().enumerate() {
+                if cancel.is_cancelled() {
+                    return Err(WorkflowError::Cancelled.to_string());
+                }
+                let iteration = Self::loop_iteration_step(step, child, item, index);
+
+                let result =
+                    Self::execute_step(&iteration, project_id, execution, parameters, cancel).await;
+
+                usage.add(&result.usage);
+                logs.extend(result.logs.iter().map(|line| format!("[{}] {}", index, line)));
+                if !matches!(result.status, StepStatus::Completed) {
+                    let error = format!("Item {} failed: {}", index, result.error.unwrap_or_default());
+                    if !continue_on_error {
+                        return Err(error);
+                    }
+                    logs.push(error);
+                    outputs.push(serde_json::Value::Null);
+                    continue;
+                }
+
+                outputs.push(
+                    Self::read_result_output(&iteration.id, &result, &project_path)
+                        .unwrap_or(serde_json::Value::Null),
+                );
+                output_files.extend(result.output_files);
+            }
+
+            let output = serde_json::Value::Array(outputs);
+            if let Some(output_file) = &step.config.output_file {
+                let output_file = Self::replace_parameters(output_file, parameters);
+                let output_path = Self::safe_join_project(&project_path, &output_file)?;
+                if let Some(parent) = output_path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+                }
+                let json = serde_json::to_string_pretty(&output)
+                    .map_err(|e| format!("Failed to serialize loop output: {}", e))?;
+                fs::write(&output_path, json).map_err(|e| format!("Failed to write output file: {}", e))?;
+                output_files.insert(0, output_file);
+            }
+            logs.push(format!("Loop used {}", usage.summary()));
+
+            Ok(StepResult {
+                step_id: step.id.clone(),
+                status: StepStatus::Completed,
+                started,
+                completed: Some(Utc::now().to_rfc3339()),
+                output_files,
+                error: None,
+                detailed_error: None,
+                logs,
+                next_step_id: None,
+                condition_met: None,
+                duration_ms: None,
+                output: Some(output),
+                usage,
+            })
+        })
+    }
+
+    /// The items of a loop step: its `items` array, or the list a step reference resolves to
+    fn resolve_loop_items(
+        step: &WorkflowStep,
+        execution: &WorkflowExecution,
+        project_path: &Path,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        use serde_json::Value;
+
+        let items = step.config.items.as_ref().ok_or("items not specified")?;
+        let resolved = Self::resolve_step_references(items, &|step_id: &str| {
+            Self::load_step_output(step_id, execution, project_path)
+        })?;
+        match resolved {
+            Value::Array(items) => Ok(items),
+            Value::String(text) => Ok(serde_json::from_str::<Vec<Value>>(&text).unwrap_or_else(|_| {
+                text.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(|line| Value::String(line.to_string()))
+                    .collect()
+            })),
+            other => Err(format!("Loop items must be a list, got {}", other)),
+        }
+    }
+
+    /// The child step for one loop item: `{{item}}` and `{{index}}` are substituted in its
+    /// config, and passed to its skill as the `item` and `index` parameters
+    fn loop_iteration_step(
+        step: &WorkflowStep,
+        child: &WorkflowStep,
+        item: &serde_json::Value,
+        index: usize,
+    ) -> WorkflowStep {
+        use serde_json::Value;
+
+        fn substitute(value: &Value, item: &str, index: &str) -> Value {
+            match value {
+                Value::String(text) => {
+                    Value::String(text.replace("{{item}}", item).replace("{{index}}", index))
+                }
+                Value::Array(values) => {
+                    Value::Array(values.iter().map(|v| substitute(v, item, index)).collect())
+                }
+                Value::Object(map) => Value::Object(
+                    map.iter()
+                        .map(|(key, v)| (key.clone(), substitute(v, item, index)))
+                        .collect(),
+                ),
+                other => other.clone(),
+            }
+        }
+
+        let item_text = match item {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        let index_text = index.to_string();
+
+        let mut iteration = child.clone();
+        iteration.id = format!("{}[{}]", step.id, index);
+        iteration.depends_on.clear();
+        if let Ok(config) = serde_json::to_value(&child.config) {
+            match serde_json::from_value(substitute(&config, &item_text, &index_text)) {
+                Ok(config) => iteration.config = config,
+                Err(e) => log::warn!("Failed to substitute loop item into step '{}': {}", child.id, e),
+            }
+        }
+        if iteration.config.parameters.is_null() {
+            iteration.config.parameters = Value::Object(Default::default());
+        }
+        if let Some(params) = iteration.config.parameters.as_object_mut() {
+            params.entry("item").or_insert_with(|| item.clone());
+            params.entry("index").or_insert_with(|| index.into());
+        }
+        iteration
+    }
+
+    /// Token usage of an AI step's response, from provider metadata or the CLI's metadata
+    /// block. Calls that report nothing still count as a call.
+    fn response_usage(response: &ChatResponse) -> StepUsage {
+        response
+            .metadata
+            .clone()
+            .or_else(|| OutputParserService::parse_generation_metadata(&response.content))
+            .map(|metadata| StepUsage::from_metadata(&metadata))
+            .unwrap_or(StepUsage {
+                ai_calls: 1,
+                ..Default::default()
+            })
+    }
+
+    /// Chat options for an AI step, restricting MCP tools to those permitted by the
+    /// project settings, the workflow and the step's skill
+    fn chat_options(
+        project_id: &str,
+        execution: &WorkflowExecution,
+        skill: &Skill,
+        cancel: &CancellationToken,
+    ) -> ChatOptions {
+        let workflow_filter = Self::load_workflow(project_id, &execution.workflow_id)
+            .ok()
+            .and_then(|workflow| workflow.tool_filter);
+
+        ChatOptions {
+            tool_filters: McpService::project_tool_filter(project_id)
+                .into_iter()
+                .chain(workflow_filter)
+                .chain(skill.tool_filter.clone())
+                .collect(),
+            cancel: Some(cancel.clone()),
+            ..Default::default()
+        }
+    }
+
     /// Execute single iteration item
     async fn execute_iteration_item(
         step: &WorkflowStep,
         item: &str,
         project_id: &str,
-        _execution: &WorkflowExecution,
+        execution: &WorkflowExecution,
         parameters: &Option<HashMap<String, String>>,
-    ) -> Result<(String, Vec<String>), String> {
+        cancel: &CancellationToken,
+    ) -> Result<(String, Vec<String>, StepUsage), String> {
         let mut logs = Vec::new();
         // Load skill
         let skill_id = step
@@ -841,12 +1459,17 @@ impl WorkflowService {
         }];
 
         let response_obj = ai_service
-            .chat(messages, None, Some(project_id.to_string()))
+            .chat_with_options(
+                messages,
+                None,
+                Some(project_id.to_string()),
+                Self::chat_options(project_id, execution, &skill, cancel),
+            )
             .await
             .map_err(|e| format!("AI Service error: {}", e))?;
 
         let response = OutputCleanerService::clean(&response_obj.content);
-
+        let usage = Self::response_usage(&response_obj);
 
         let output_pattern = step
             .config
@@ -876,15 +1499,16 @@ impl WorkflowService {
         fs::write(&output_path, &response)
             .map_err(|e| format!("Failed to write output file: {}", e))?;
 
-        Ok((output_file, logs))
+        Ok((output_file, logs, usage))
     }
 
     /// Execute synthesis step - combine multiple inputs
     async fn execute_synthesis_step(
         step: &WorkflowStep,
         project_id: &str,
-        _execution: &WorkflowExecution,
+        execution: &WorkflowExecution,
         parameters: &Option<HashMap<String, String>>,
+        cancel: &CancellationToken,
     ) -> Result<StepResult, String> {
         let started = Utc::now().to_rfc3339();
         let mut logs = Vec::new();
@@ -978,12 +1602,18 @@ impl WorkflowService {
         }];
 
         let response_obj = ai_service
-            .chat(messages, None, Some(project_id.to_string()))
+            .chat_with_options(
+                messages,
+                None,
+                Some(project_id.to_string()),
+                Self::chat_options(project_id, execution, &skill, cancel),
+            )
             .await
             .map_err(|e| format!("AI Service error: {}", e))?;
 
         let response = OutputCleanerService::clean(&response_obj.content);
-        logs.push(format!("Received synthesis ({} chars)", response.len()));
+        let usage = Self::response_usage(&response_obj);
+        logs.push(format!("Received synthesis ({} chars, {})", response.len(), usage.summary()));
 
         // Save to output file
         let raw_output_file = step
@@ -1014,6 +1644,10 @@ impl WorkflowService {
             detailed_error: None,
             logs,
             next_step_id: None,
+            condition_met: None,
+            duration_ms: None,
+            output: None,
+            usage,
         })
     }
 
@@ -1021,6 +1655,7 @@ impl WorkflowService {
     async fn execute_conditional_step(
         step: &WorkflowStep,
         project_id: &str,
+        execution: &WorkflowExecution,
     ) -> Result<StepResult, String> {
         let started = Utc::now().to_rfc3339();
         let mut logs = Vec::new();
@@ -1038,7 +1673,7 @@ impl WorkflowService {
             .map_err(|e| format!("Failed to load project: {}", e))?;
         let project_path = project.path;
 
-        let result = Self::evaluate_condition(condition, &project_path)?;
+        let result = Self::evaluate_condition(condition, &project_path, execution)?;
         logs.push(format!("Condition result: {}", result));
 
         // Determine next step
@@ -1062,6 +1697,10 @@ impl WorkflowService {
             detailed_error: None,
             logs,
             next_step_id,
+            condition_met: Some(result),
+            duration_ms: None,
+            output: None,
+            usage: StepUsage::default(),
         })
     }
 
@@ -1286,15 +1925,59 @@ impl WorkflowService {
         Ok(resolved)
     }
 
-    /// Evaluate a simple condition
-    fn evaluate_condition(condition: &str, project_path: &Path) -> Result<bool, String> {
-        // Simple condition parser
-        // Supports: file_exists:filename
+    /// Evaluate a conditional step's condition. Supported forms:
+    ///
+    /// - `file_exists:<path>` - the project file exists
+    /// - `{{steps.<id>.output...}} contains <text>` - the output includes `text`
+    /// - `{{steps.<id>.output...}} equals <text>` - the trimmed output is exactly `text`
+    /// - `{{steps.<id>.output...}} matches <regex>` - the output matches the regex
+    ///
+    /// `text` may be wrapped in double quotes to keep surrounding whitespace.
+    fn evaluate_condition(
+        condition: &str,
+        project_path: &Path,
+        execution: &WorkflowExecution,
+    ) -> Result<bool, String> {
+        let condition = condition.trim();
         if let Some(file_name) = condition.strip_prefix("file_exists:") {
             let file_path = Self::safe_join_project(project_path, file_name.trim())?;
-            Ok(file_path.exists())
-        } else {
-            Err(format!("Unknown condition format: {}", condition))
+            return Ok(file_path.exists());
+        }
+
+        let Some((reference, comparison)) = condition
+            .strip_prefix("{{")
+            .and_then(|rest| rest.split_once("}}"))
+        else {
+            return Err(format!("Unknown condition format: {}", condition));
+        };
+        let (operator, operand) = comparison
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("Condition '{}' is missing an operator and value", condition))?;
+        let operand = operand.trim();
+        let operand = operand
+            .strip_prefix('"')
+            .and_then(|o| o.strip_suffix('"'))
+            .unwrap_or(operand);
+
+        let output = Self::resolve_step_reference(reference.trim(), &|step_id: &str| {
+            Self::load_step_output(step_id, execution, project_path)
+        })?;
+        let output = match output {
+            serde_json::Value::String(text) => text,
+            other => other.to_string(),
+        };
+
+        match operator {
+            "contains" => Ok(output.contains(operand)),
+            "equals" => Ok(output.trim() == operand),
+            "matches" => regex::Regex::new(operand)
+                .map(|re| re.is_match(&output))
+                .map_err(|e| format!("Invalid regex in condition '{}': {}", condition, e)),
+            other => Err(format!(
+                "Unknown condition operator '{}' (expected contains, equals or matches)",
+                other
+            )),
         }
     }
 }
@@ -1366,6 +2049,8 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         }
     }
 
@@ -1413,6 +2098,38 @@ mod tests {
         assert_eq!(workflows.len(), 2);
     }
 
+    #[test]
+    fn test_scan_reports_malformed_files_and_upgrades_legacy_ones() {
+        let _lock = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        let (_temp_dir, project_id) = setup_test_env();
+
+        WorkflowService::save_workflow(&create_test_workflow(&project_id, "current")).unwrap();
+        let workflows_dir = ProjectService::resolve_project_path(&project_id)
+            .unwrap()
+            .join(".workflows");
+        fs::write(workflows_dir.join("broken.json"), "{ not json").unwrap();
+        fs::write(
+            workflows_dir.join("legacy.json"),
+            r#"{"id": "legacy", "steps": [{"id": "s1", "type": "Agent", "config": {"skill_id": "x"}}]}"#,
+        )
+        .unwrap();
+
+        let report = WorkflowService::scan_project_workflows(&project_id).unwrap();
+        assert_eq!(report.workflows.len(), 2);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].file_name, "broken.json");
+
+        let legacy = WorkflowService::load_workflow(&project_id, "legacy").unwrap();
+        assert_eq!(legacy.schema_version, WORKFLOW_SCHEMA_VERSION);
+        assert_eq!(legacy.project_id, project_id);
+        assert_eq!(legacy.steps[0].step_type, StepType::Agent);
+        assert!(legacy.steps[0].depends_on.is_empty());
+
+        // The upgrade was written back to disk
+        let saved = fs::read_to_string(workflows_dir.join("legacy.json")).unwrap();
+        assert!(saved.contains("\"schema_version\": 2"));
+    }
+
     #[test]
     fn test_delete_workflow() {
         let _lock = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
@@ -1513,6 +2230,8 @@ mod tests {
             last_run: None,
             active_execution_id: None,
             schedule: None,
+            schema_version: WORKFLOW_SCHEMA_VERSION,
+            tool_filter: None,
         };
         WorkflowService::save_workflow(&workflow).unwrap();
 
@@ -1520,9 +2239,14 @@ mod tests {
         let mut params = std::collections::HashMap::new();
         params.insert("input_file".to_string(), "input.txt".to_string());
 
-        // Execute workflow
+        // Execute workflow, capturing the events it emits
+        let events = std::sync::Arc::new(Mutex::new(Vec::<String>::new()));
+        let captured = events.clone();
+        let sink: EventSink = std::sync::Arc::new(move |event: &str, _: serde_json::Value| {
+            captured.lock().unwrap().push(event.to_string());
+        });
         let result =
-            WorkflowService::execute_workflow(&project_id, "workflow-param", Some(params), |_| {})
+            WorkflowService::execute_workflow(&project_id, "workflow-param", Some(params), &sink, &CancellationToken::new())
                 .await;
 
         assert!(
@@ -1532,6 +2256,11 @@ mod tests {
         );
         let execution = result.unwrap();
         assert_eq!(execution.status, ExecutionStatus::Completed);
+        let events = events.lock().unwrap();
+        assert_eq!(events.first().map(String::as_str), Some("workflow-progress"));
+        assert!(events.iter().any(|e| e == "workflow-step-started"));
+        assert!(events.iter().any(|e| e == "workflow-step-completed"));
+        assert_eq!(events.last().map(String::as_str), Some("workflow-completed"));
 
         // check output file content
         let output_path = temp_dir
@@ -1543,6 +2272,333 @@ mod tests {
         let content = fs::read_to_string(output_path).unwrap();
         assert_eq!(content, "Hello World");
     }
+
+    #[tokio::test]
+    async fn test_cancelled_workflow_marks_steps_and_logs_run() {
+        let _lock = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        let (temp_dir, project_id) = setup_test_env();
+        let workflow = create_test_workflow(&project_id, "workflow-cancel");
+        WorkflowService::save_workflow(&workflow).unwrap();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let sink: EventSink = std::sync::Arc::new(|_: &str, _: serde_json::Value| {});
+        let execution = WorkflowService::execute_workflow(&project_id, "workflow-cancel", None, &sink, &cancel)
+            .await
+            .unwrap();
+
+        assert_eq!(execution.status, ExecutionStatus::Cancelled);
+        assert_eq!(execution.step_results["step1"].status, StepStatus::Cancelled);
+        let log = fs::read_to_string(
+            temp_dir
+                .path()
+                .join("ai-researcher")
+                .join("projects")
+                .join(&project_id)
+                .join("research_log.md"),
+        )
+        .unwrap();
+        assert!(log.contains("Workflow 'Test Workflow' was cancelled after 0 of 1 steps."));
+    }
+
+    #[tokio::test]
+    async fn test_loop_step_runs_child_per_item_and_collects_outputs() {
+        let _lock = TEST_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+        let (temp_dir, project_id) = setup_test_env();
+
+        let text_input = |value: &str, output_file: &str| StepConfig {
+            source_type: Some("TextInput".to_string()),
+            source_value: Some(value.to_string()),
+            output_file: Some(output_file.to_string()),
+            ..Default::default()
+        };
+        let mut workflow = create_test_workflow(&project_id, "workflow-loop");
+        workflow.steps = vec![
+            WorkflowStep {
+                id: "papers".to_string(),
+                name: "Papers".to_string(),
+                step_type: StepType::Input,
+                config: text_input(r#"["alpha", "beta"]"#, "papers.json"),
+                depends_on: vec![],
+            },
+            WorkflowStep {
+                id: "summaries".to_string(),
+                name: "Summaries".to_string(),
+                step_type: StepType::Loop,
+                config: StepConfig {
+                    items: Some(serde_json::json!("{{steps.papers.output}}")),
+                    child: Some(Box::new(WorkflowStep {
+                        id: "summarize".to_string(),
+                        name: "Summarize".to_string(),
+                        step_type: StepType::Input,
+                        config: text_input("Summary of {{item}}", "summaries/{{index}}.md"),
+                        depends_on: vec![],
+                    })),
+                    ..Default::default()
+                },
+                depends_on: vec!["papers".to_string()],
+            },
+        ];
+        WorkflowService::save_workflow(&workflow).unwrap();
+
+        let sink: EventSink = std::sync::Arc::new(|_: &str, _: serde_json::Value| {});
+        let execution =
+            WorkflowService::execute_workflow(&project_id, "workflow-loop", None, &sink, &CancellationToken::new())
+                .await
+                .unwrap();
+
+        assert_eq!(execution.status, ExecutionStatus::Completed);
+        assert_eq!(
+            execution.step_outputs["summaries"],
+            serde_json::json!(["Summary of alpha", "Summary of beta"])
+        );
+        let project_dir = temp_dir.path().join("ai-researcher").join("projects").join(&project_id);
+        assert_eq!(
+            fs::read_to_string(project_dir.join("summaries").join("1.md")).unwrap(),
+            "Summary of beta"
+        );
+
+        // More items than the cap fails the loop instead of running them
+        workflow.steps[1].config.max_iterations = Some(1);
+        WorkflowService::save_workflow(&workflow).unwrap();
+        let execution =
+            WorkflowService::execute_workflow(&project_id, "workflow-loop", None, &sink, &CancellationToken::new())
+                .await
+                .unwrap();
+        let loop_result = &execution.step_results["summaries"];
+        assert_eq!(loop_result.status, StepStatus::Failed);
+        assert!(loop_result
+            .error
+            .as_deref()
+            .is_some_and(|e| e.contains("Loop has 2 items but max_iterations is 1")));
+    }
+
+    fn search_output(step_id: &str) -> Result<serde_json::Value, String> {
+        match step_id {
+            "search" => Ok(serde_json::json!({
+                "count": 3,
+                "results": [{"title": "First"}, {"title": "Second"}]
+            })),
+            "summary" => Ok(serde_json::Value::String("A short summary".to_string())),
+            other => Err(format!("Step '{}' has not run yet; add it to depends_on", other)),
+        }
+    }
+
+    #[test]
+    fn test_step_references_resolve_deep_paths() {
+        let params = serde_json::json!({
+            "count": "{{steps.search.output.count}}",
+            "second_title": "{{steps.search.output.results.1.title}}",
+            "sentence": "Found {{steps.search.output.count}} results: {{steps.summary.output}}",
+            "nested": ["{{steps.summary.output}}"]
+        });
+
+        let resolved = WorkflowService::resolve_step_references(&params, &search_output).unwrap();
+
+        assert_eq!(resolved["count"], serde_json::json!(3));
+        assert_eq!(resolved["second_title"], "Second");
+        assert_eq!(resolved["sentence"], "Found 3 results: A short summary");
+        assert_eq!(resolved["nested"][0], "A short summary");
+    }
+
+    #[test]
+    fn test_step_references_leave_literals_untouched() {
+        let params = serde_json::json!({
+            "topic": "{{topic}}",
+            "plain": "no templates here",
+            "braces": "{single} and {{steps.unterminated",
+            "number": 42
+        });
+
+        let resolved = WorkflowService::resolve_step_references(&params, &search_output).unwrap();
+        assert_eq!(resolved, params);
+    }
+
+    #[test]
+    fn test_step_references_report_missing_steps_and_paths() {
+        let missing_step = serde_json::json!({"x": "{{steps.unknown.output}}"});
+        let err = WorkflowService::resolve_step_references(&missing_step, &search_output).unwrap_err();
+        assert!(err.contains("'unknown' has not run yet"), "{}", err);
+
+        let missing_path = serde_json::json!({"x": "{{steps.search.output.results.5.title}}"});
+        let err = WorkflowService::resolve_step_references(&missing_path, &search_output).unwrap_err();
+        assert!(err.contains("no '5'"), "{}", err);
+
+        let malformed = serde_json::json!({"x": "{{steps.search.result}}"});
+        let err = WorkflowService::resolve_step_references(&malformed, &search_output).unwrap_err();
+        assert!(err.contains("Invalid step reference"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_step_output_reads_first_output_file() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("search.json"), r#"{"count": 2}"#).unwrap();
+        fs::write(temp_dir.path().join("notes.md"), "plain text").unwrap();
+
+        let completed = |id: &str, file: &str| StepResult {
+            step_id: id.to_string(),
+            status: StepStatus::Completed,
+            started: String::new(),
+            completed: None,
+            output_files: vec![file.to_string()],
+            error: None,
+            detailed_error: None,
+            logs: vec![],
+            next_step_id: None,
+            condition_met: None,
+            duration_ms: None,
+            output: None,
+            usage: StepUsage::default(),
+        };
+        let mut execution = WorkflowExecution {
+            workflow_id: "wf".to_string(),
+            started: String::new(),
+            completed: None,
+            status: ExecutionStatus::Running,
+            error: None,
+            step_results: HashMap::new(),
+            step_outputs: HashMap::new(),
+            total_usage: StepUsage::default(),
+        };
+        execution.step_results.insert("search".to_string(), completed("search", "search.json"));
+        execution.step_results.insert("notes".to_string(), completed("notes", "notes.md"));
+
+        let json = WorkflowService::load_step_output("search", &execution, temp_dir.path()).unwrap();
+        assert_eq!(json["count"], 2);
+        let text = WorkflowService::load_step_output("notes", &execution, temp_dir.path()).unwrap();
+        assert_eq!(text, "plain text");
+        assert!(WorkflowService::load_step_output("other", &execution, temp_dir.path()).is_err());
+
+        // Outputs recorded during the run win over the file, which a later step may rewrite
+        execution.step_outputs.insert("notes".to_string(), serde_json::json!("recorded"));
+        let text = WorkflowService::load_step_output("notes", &execution, temp_dir.path()).unwrap();
+        assert_eq!(text, "recorded");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_during_retry_backoff_marks_step_cancelled() {
+        // No output file, so every attempt fails before touching the project
+        let step = WorkflowStep {
+            id: "flaky".to_string(),
+            name: "Flaky".to_string(),
+            step_type: StepType::Input,
+            config: StepConfig {
+                source_type: Some("TextInput".to_string()),
+                max_retries: Some(3),
+                ..Default::default()
+            },
+            depends_on: vec![],
+        };
+        let execution = WorkflowExecution {
+            workflow_id: "wf".to_string(),
+            started: String::new(),
+            completed: None,
+            status: ExecutionStatus::Running,
+            error: None,
+            step_results: HashMap::new(),
+            step_outputs: HashMap::new(),
+            total_usage: StepUsage::default(),
+        };
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        let started = Instant::now();
+        let result = WorkflowService::execute_step(&step, "test-project", &execution, &None, &cancel).await;
+
+        assert_eq!(result.status, StepStatus::Cancelled);
+        assert!(result.detailed_error.unwrap().contains("output_file not specified"));
+        // The first backoff is two seconds; cancelling must not wait it out
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_conditions_over_step_outputs() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut execution = WorkflowExecution {
+            workflow_id: "wf".to_string(),
+            started: String::new(),
+            completed: None,
+            status: ExecutionStatus::Running,
+            error: None,
+            step_results: HashMap::new(),
+            step_outputs: HashMap::new(),
+            total_usage: StepUsage::default(),
+        };
+        execution.step_outputs.insert(
+            "scan".to_string(),
+            serde_json::Value::String("Found a Critical regression in the parser".to_string()),
+        );
+        execution
+            .step_outputs
+            .insert("score".to_string(), serde_json::json!({"grade": "B"}));
+
+        let eval = |condition: &str| WorkflowService::evaluate_condition(condition, temp_dir.path(), &execution);
+        assert!(eval("{{steps.scan.output}} contains Critical").unwrap());
+        assert!(!eval("{{steps.scan.output}} contains \"critical \"").unwrap());
+        assert!(eval("{{steps.score.output.grade}} equals B").unwrap());
+        assert!(eval("{{steps.scan.output}} matches (?i)critical\\s+regression").unwrap());
+        assert!(eval("{{steps.missing.output}} contains x").is_err());
+        assert!(eval("{{steps.scan.output}} starts_with Found").is_err());
+        assert!(!eval("file_exists:deep-dive.md").unwrap());
+    }
+
+    #[test]
+    fn test_false_condition_skips_dependents_except_else_branch() {
+        let step = |id: &str, step_type: StepType, depends_on: &[&str]| WorkflowStep {
+            id: id.to_string(),
+            name: id.to_string(),
+            step_type,
+            config: StepConfig::default(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        };
+        let mut workflow = create_test_workflow("test-project", "conditional");
+        let mut gate = step("gate", StepType::Conditional, &[]);
+        gate.config.else_step = Some("fallback".to_string());
+        workflow.steps = vec![
+            gate,
+            step("deep-dive", StepType::Agent, &["gate"]),
+            step("fallback", StepType::Agent, &["gate"]),
+        ];
+
+        let mut execution = WorkflowExecution {
+            workflow_id: "conditional".to_string(),
+            started: String::new(),
+            completed: None,
+            status: ExecutionStatus::Running,
+            error: None,
+            step_results: HashMap::new(),
+            step_outputs: HashMap::new(),
+            total_usage: StepUsage::default(),
+        };
+        let gate_result = |met: bool| StepResult {
+            step_id: "gate".to_string(),
+            status: StepStatus::Completed,
+            started: String::new(),
+            completed: None,
+            output_files: vec![],
+            error: None,
+            detailed_error: None,
+            logs: vec![],
+            next_step_id: None,
+            condition_met: Some(met),
+            duration_ms: None,
+            output: None,
+            usage: StepUsage::default(),
+        };
+
+        execution.step_results.insert("gate".to_string(), gate_result(false));
+        let reason = WorkflowService::skip_reason(&workflow, &execution, &workflow.steps[1]);
+        assert_eq!(reason.as_deref(), Some("Condition of step 'gate' was false"));
+        assert!(WorkflowService::skip_reason(&workflow, &execution, &workflow.steps[2]).is_none());
+
+        execution.step_results.insert("gate".to_string(), gate_result(true));
+        assert!(WorkflowService::skip_reason(&workflow, &execution, &workflow.steps[1]).is_none());
+        assert!(WorkflowService::skip_reason(&workflow, &execution, &workflow.steps[2]).is_some());
+    }
 }
 
 
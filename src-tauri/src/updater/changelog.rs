@@ -0,0 +1,262 @@
+//! Release notes for the "What's new" view shown after an update
+//!
+//! Notes come from the GitHub releases feed when it is reachable and are cached in the app
+//! data directory. The bundled CHANGELOG.md fills in anything the feed or cache lacks, so
+//! the view still works offline.
+
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/AIResearchFactory/ai-researcher/releases";
+const CACHE_FILE: &str = "changelog_cache.json";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const BUNDLED_CHANGELOG: &str = include_str!("../../../CHANGELOG.md");
+
+/// Release notes for a single version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub date: Option<String>,
+    pub notes: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    draft: bool,
+}
+
+/// Entries for versions newer than `from` up to and including `to`, newest first
+pub async fn entries_between(cache_dir: &Path, from: &str, to: &str) -> Vec<ChangelogEntry> {
+    let mut entries = parse_changelog(BUNDLED_CHANGELOG);
+
+    let remote = match fetch_releases().await {
+        Ok(releases) => {
+            if let Err(e) = save_cache(cache_dir, &releases) {
+                log::warn!("Failed to cache release notes: {}", e);
+            }
+            Some(releases)
+        }
+        Err(e) => {
+            log::warn!("Failed to fetch release notes, using cached copy: {}", e);
+            load_cache(cache_dir)
+        }
+    };
+    if let Some(remote) = remote {
+        merge(&mut entries, remote);
+    }
+
+    select_range(entries, from, to)
+}
+
+/// Parse `## [version] - date` sections from a Keep a Changelog style document.
+/// Sections whose heading isn't a version (e.g. "Unreleased") are skipped.
+pub fn parse_changelog(markdown: &str) -> Vec<ChangelogEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<ChangelogEntry> = None;
+
+    for line in markdown.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            entries.extend(current.take());
+
+            let (version, date) = match heading.split_once(" - ") {
+                Some((version, date)) => (version, Some(date.trim().to_string())),
+                None => (heading, None),
+            };
+            let version = normalize_version(version.trim().trim_matches(|c| c == '[' || c == ']'));
+            if version.starts_with(|c: char| c.is_ascii_digit()) {
+                current = Some(ChangelogEntry {
+                    version,
+                    date,
+                    notes: String::new(),
+                });
+            }
+        } else if let Some(entry) = current.as_mut() {
+            entry.notes.push_str(line);
+            entry.notes.push('\n');
+        }
+    }
+    entries.extend(current);
+
+    for entry in &mut entries {
+        entry.notes = entry.notes.trim().to_string();
+    }
+    entries
+}
+
+/// Compare two version strings such as `0.2.4` and `v0.2.4-beta.4`.
+/// Pre-releases sort before the release they precede.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_core, a_pre) = parse_version(a);
+    let (b_core, b_pre) = parse_version(b);
+
+    a_core.cmp(&b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a_pre), Some(b_pre)) => {
+            for (x, y) in a_pre.iter().zip(b_pre.iter()) {
+                let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a_pre.len().cmp(&b_pre.len())
+        }
+    })
+}
+
+fn parse_version(version: &str) -> ([u64; 3], Option<Vec<String>>) {
+    let version = normalize_version(version);
+    let version = version.split('+').next().unwrap_or_default();
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre.split('.').map(str::to_string).collect())),
+        None => (version, None),
+    };
+
+    let mut numbers = [0u64; 3];
+    for (slot, part) in numbers.iter_mut().zip(core.split('.')) {
+        *slot = part.parse().unwrap_or(0);
+    }
+    (numbers, pre)
+}
+
+fn normalize_version(version: &str) -> String {
+    version.trim().trim_start_matches('v').to_string()
+}
+
+fn select_range(entries: Vec<ChangelogEntry>, from: &str, to: &str) -> Vec<ChangelogEntry> {
+    let mut selected: Vec<ChangelogEntry> = entries
+        .into_iter()
+        .filter(|e| {
+            compare_versions(&e.version, from) == Ordering::Greater
+                && compare_versions(&e.version, to) != Ordering::Greater
+        })
+        .collect();
+    selected.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    selected
+}
+
+/// Remote notes replace bundled ones for the same version
+fn merge(entries: &mut Vec<ChangelogEntry>, remote: Vec<ChangelogEntry>) {
+    for entry in remote.into_iter().filter(|e| !e.notes.is_empty()) {
+        entries.retain(|e| compare_versions(&e.version, &entry.version) != Ordering::Equal);
+        entries.push(entry);
+    }
+}
+
+async fn fetch_releases() -> Result<Vec<ChangelogEntry>> {
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static("AI-Researcher-App/0.1"));
+    headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github+json"));
+
+    let releases: Vec<GithubRelease> = reqwest::Client::new()
+        .get(RELEASES_URL)
+        .headers(headers)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Invalid releases response")?;
+
+    Ok(releases
+        .into_iter()
+        .filter(|r| !r.draft)
+        .map(|r| ChangelogEntry {
+            version: normalize_version(&r.tag_name),
+            date: r
+                .published_at
+                .map(|d| d.split('T').next().unwrap_or_default().to_string()),
+            notes: r.body.unwrap_or_default().trim().to_string(),
+        })
+        .collect())
+}
+
+fn save_cache(cache_dir: &Path, entries: &[ChangelogEntry]) -> Result<()> {
+    fs::create_dir_all(cache_dir)?;
+    fs::write(cache_dir.join(CACHE_FILE), serde_json::to_string_pretty(entries)?)?;
+    Ok(())
+}
+
+fn load_cache(cache_dir: &Path) -> Option<Vec<ChangelogEntry>> {
+    let content = fs::read_to_string(cache_dir.join(CACHE_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(version: &str, notes: &str) -> ChangelogEntry {
+        ChangelogEntry {
+            version: version.to_string(),
+            date: None,
+            notes: notes.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.2.4", "v0.2.4"), Ordering::Equal);
+        assert_eq!(compare_versions("0.2.4-beta.4", "0.2.4"), Ordering::Less);
+        assert_eq!(compare_versions("0.2.4-beta.10", "0.2.4-beta.9"), Ordering::Greater);
+        assert_eq!(compare_versions("0.10.0", "0.9.9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_parse_changelog_sections() {
+        let markdown = "# Changelog\n\n## [Unreleased]\n- wip\n\n## [0.2.0] - 2025-02-01\n### Added\n- Workflows\n\n## 0.1.0\n- First release\n";
+        let entries = parse_changelog(markdown);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].version, "0.2.0");
+        assert_eq!(entries[0].date.as_deref(), Some("2025-02-01"));
+        assert_eq!(entries[0].notes, "### Added\n- Workflows");
+        assert_eq!(entries[1].notes, "- First release");
+    }
+
+    #[test]
+    fn test_bundled_changelog_covers_current_version() {
+        let entries = parse_changelog(BUNDLED_CHANGELOG);
+        assert!(entries
+            .iter()
+            .any(|e| compare_versions(&e.version, env!("CARGO_PKG_VERSION")) == Ordering::Equal));
+    }
+
+    #[test]
+    fn test_select_range_is_exclusive_of_previous_version() {
+        let entries = vec![entry("0.1.0", "a"), entry("0.2.0", "b"), entry("0.3.0", "c"), entry("0.4.0", "d")];
+        let selected = select_range(entries, "0.1.0", "0.3.0");
+        let versions: Vec<&str> = selected.iter().map(|e| e.version.as_str()).collect();
+        assert_eq!(versions, vec!["0.3.0", "0.2.0"]);
+    }
+
+    #[test]
+    fn test_remote_notes_override_bundled_and_cache_round_trips() {
+        let mut entries = vec![entry("0.2.0", "bundled")];
+        merge(&mut entries, vec![entry("v0.2.0", "remote"), entry("0.3.0", "")]);
+        assert_eq!(entries, vec![entry("v0.2.0", "remote")]);
+
+        let temp = TempDir::new().unwrap();
+        save_cache(temp.path(), &entries).unwrap();
+        assert_eq!(load_cache(temp.path()), Some(entries));
+    }
+}
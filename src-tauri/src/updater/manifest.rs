@@ -0,0 +1,144 @@
+//! Manifest written into every backup directory, listing each backed-up file
+//!
+//! Incremental backups copy only files whose size or modification time changed since the
+//! previous backup. Unchanged files are recorded with `stored_in` naming the older backup
+//! that holds their bytes, and restoring uses the manifest to reassemble the full tree.
+//! SHA-256 checksums let `verify` catch files that were corrupted after the backup.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::utils::fs::write_atomic;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub created_at: String,
+    /// Backup this one was compared against when it was taken incrementally
+    #[serde(default)]
+    pub base: Option<String>,
+    /// Backed-up files keyed by their `/`-separated path in the app data directory
+    #[serde(default)]
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub size: u64,
+    /// Modification time in milliseconds since the Unix epoch
+    pub modified_ms: u64,
+    /// Earlier backup directory holding this file; `None` when it is in this backup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored_in: Option<String>,
+    /// Hex SHA-256 of the contents; absent in manifests written before checksums
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// Outcome of checking a backup against its manifest
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupVerification {
+    /// True when nothing is missing or corrupted
+    pub intact: bool,
+    /// Files whose contents were read and checked
+    pub checked: usize,
+    /// Files in the manifest that are gone from the backup holding them
+    pub missing: Vec<String>,
+    /// Files whose contents no longer match their recorded checksum
+    pub corrupted: Vec<String>,
+}
+
+impl ManifestEntry {
+    /// Entry for the file at `path` as it is on disk now, stored in the new backup
+    pub fn for_file(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)?;
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_millis() as u64);
+        Ok(Self {
+            size: metadata.len(),
+            modified_ms,
+            stored_in: None,
+            sha256: None,
+        })
+    }
+
+    /// Whether the file still looks like it did when `self` was recorded
+    pub fn unchanged_since(&self, earlier: &ManifestEntry) -> bool {
+        self.size == earlier.size && self.modified_ms == earlier.modified_ms
+    }
+}
+
+impl BackupManifest {
+    /// The manifest of `backup_dir`, or `None` for backups made before manifests existed
+    pub fn load(backup_dir: &Path) -> Result<Option<Self>> {
+        let path = backup_dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        let manifest = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid backup manifest: {:?}", path))?;
+        Ok(Some(manifest))
+    }
+
+    pub fn save(&self, backup_dir: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        write_atomic(&backup_dir.join(MANIFEST_FILE), content)
+            .context("Failed to write backup manifest")?;
+        Ok(())
+    }
+
+    /// Recompute the checksum of every file and compare it with the manifest. Files held
+    /// by earlier backups are looked up in `backups_dir`. Entries without a checksum are
+    /// only checked for presence.
+    pub fn verify(&self, backup_dir: &Path, backups_dir: &Path) -> BackupVerification {
+        let mut result = BackupVerification::default();
+
+        for (key, entry) in &self.files {
+            let holder = match &entry.stored_in {
+                Some(name) => backups_dir.join(name),
+                None => backup_dir.to_path_buf(),
+            };
+            let path = holder.join(key);
+            if !path.is_file() {
+                result.missing.push(key.clone());
+                continue;
+            }
+
+            let Some(expected) = &entry.sha256 else {
+                continue;
+            };
+            result.checked += 1;
+            match sha256_file(&path) {
+                Ok(actual) if &actual == expected => {}
+                _ => result.corrupted.push(key.clone()),
+            }
+        }
+
+        result.intact = result.missing.is_empty() && result.corrupted.is_empty();
+        result
+    }
+
+    /// Whether restoring needs files from earlier backups
+    pub fn is_incremental(&self) -> bool {
+        self.files.values().any(|entry| entry.stored_in.is_some())
+    }
+}
+
+/// Hex SHA-256 of the file at `path`, read in a streaming fashion
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
@@ -1,10 +1,21 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use tempfile::TempDir;
+use walkdir::WalkDir;
 
+use crate::config::ConfigManager;
 use crate::directory;
 use crate::installer::InstallationConfig;
+use crate::utils::archive;
+
+pub mod changelog;
+pub mod manifest;
+
+use changelog::ChangelogEntry;
+use manifest::{BackupManifest, BackupVerification, ManifestEntry, MANIFEST_FILE};
 
 /// Update result information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +26,40 @@ pub struct UpdateResult {
     pub files_updated: Vec<String>,
     pub structure_verified: bool,
     pub message: String,
+    /// Version recorded in the app config before this update, if it differed
+    #[serde(default)]
+    pub previous_version: Option<String>,
+    /// Release notes for every version after `previous_version`, newest first
+    #[serde(default)]
+    pub changelog: Vec<ChangelogEntry>,
+}
+
+/// Critical user data captured by a backup, relative to the app data directory.
+/// Templates and other defaults are recreated on update, so they are left out.
+const BACKUP_ITEMS: &[&str] = &[
+    "projects",                 // User's research projects
+    "skills",                   // User's custom skills
+    "settings.json",            // User's settings
+    "secrets.encrypted.json",   // User's encrypted secrets
+    ".installation_state.json", // Installation state
+];
+
+/// A backup in the app's backups directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    /// A single zip archive rather than a directory tree
+    pub compressed: bool,
+    /// Size on disk: the archive size when compressed, otherwise the total of its files
+    pub size_bytes: u64,
+}
+
+/// A file inside a backup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// `/`-separated path relative to the app data directory
+    pub path: String,
+    pub size_bytes: u64,
 }
 
 /// Update Manager
@@ -38,6 +83,8 @@ impl UpdateManager {
             claude_code_detected: false,
             ollama_detected: false,
             gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
         };
         Ok(Self::new(config))
     }
@@ -59,10 +106,8 @@ impl UpdateManager {
         }
 
         // Create any missing subdirectories
-        let subdirs = vec!["projects", "skills", "templates", "backups", "logs"];
-
-        for subdir in subdirs {
-            let dir_path = base_path.join(subdir);
+        for subdir in directory::managed_subdirs(base_path) {
+            let dir_path = base_path.join(&subdir);
             if !dir_path.exists() {
                 fs::create_dir_all(&dir_path)
                     .context(format!("Failed to create directory: {:?}", dir_path))?;
@@ -89,6 +134,8 @@ impl UpdateManager {
             files_updated,
             structure_verified,
             message: "Directory structure updated successfully".to_string(),
+            previous_version: None,
+            changelog: Vec::new(),
         })
     }
 
@@ -96,52 +143,182 @@ impl UpdateManager {
     pub async fn backup_user_data(&self) -> Result<PathBuf> {
         log::info!("Creating backup of user data...");
 
+        let backup_dir = self.create_backup_dir()?;
+
+        // Backup critical user data (DO NOT backup templates or default files)
+        self.write_backup(&backup_dir, None)?;
+
+        log::info!("User data backup created at {:?}", backup_dir);
+        Ok(backup_dir)
+    }
+
+    /// Back up only the files whose size or modification time changed since the newest
+    /// backup directory. Unchanged files are referenced from the backup that already holds
+    /// them, which keeps frequent backups small. Without an earlier manifest this is a
+    /// full backup.
+    pub async fn backup_user_data_incremental(&self) -> Result<PathBuf> {
+        log::info!("Creating incremental backup of user data...");
+
+        let base = self.latest_manifest_backup()?;
+        let backup_dir = self.create_backup_dir()?;
+        let manifest = self.write_backup(
+            &backup_dir,
+            base.as_ref().map(|(name, manifest)| (name.as_str(), manifest)),
+        )?;
+
+        let reused = manifest
+            .files
+            .values()
+            .filter(|entry| entry.stored_in.is_some())
+            .count();
+        log::info!(
+            "Incremental backup created at {:?}: {} of {} files unchanged since {:?}",
+            backup_dir,
+            reused,
+            manifest.files.len(),
+            manifest.base
+        );
+        Ok(backup_dir)
+    }
+
+    /// Create an empty `backups/update_backup_{timestamp}` directory. Backups taken within
+    /// the same second get a numeric suffix.
+    fn create_backup_dir(&self) -> Result<PathBuf> {
         use chrono::Local;
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let backup_dir = self
-            .config
-            .app_data_path
-            .join("backups")
-            .join(format!("update_backup_{}", timestamp));
+        let backups_dir = self.config.app_data_path.join("backups");
+        let name = format!("update_backup_{}", Local::now().format("%Y%m%d_%H%M%S"));
+
+        let mut backup_dir = backups_dir.join(&name);
+        let mut suffix = 1;
+        while backup_dir.exists() {
+            backup_dir = backups_dir.join(format!("{}_{}", name, suffix));
+            suffix += 1;
+        }
 
-        // Create backup directory
         fs::create_dir_all(&backup_dir).context(format!(
             "Failed to create backup directory: {:?}",
             backup_dir
         ))?;
+        Ok(backup_dir)
+    }
 
-        // Backup critical user data (DO NOT backup templates or default files)
-        let items_to_backup = vec![
-            ("projects", true),                  // User's research projects
-            ("skills", true),                    // User's custom skills
-            ("settings.json", false),            // User's settings
-            ("secrets.encrypted.json", false),   // User's encrypted secrets
-            (".installation_state.json", false), // Installation state
-        ];
+    /// Copy `BACKUP_ITEMS` into `backup_dir` and write its manifest last, so a backup that
+    /// was cut short is never used as a base. Files unchanged since `base` (name and
+    /// manifest of an earlier backup) are referenced instead of copied.
+    fn write_backup(
+        &self,
+        backup_dir: &Path,
+        base: Option<(&str, &BackupManifest)>,
+    ) -> Result<BackupManifest> {
+        let base_path = &self.config.app_data_path;
+        let mut manifest = BackupManifest {
+            created_at: chrono::Utc::now().to_rfc3339(),
+            base: base.map(|(name, _)| name.to_string()),
+            files: BTreeMap::new(),
+        };
 
-        for (item, is_dir) in items_to_backup {
-            let source = self.config.app_data_path.join(item);
-            if source.exists() {
-                let dest = backup_dir.join(item);
+        for item in BACKUP_ITEMS {
+            let source = base_path.join(item);
+            if !source.exists() {
+                continue;
+            }
+
+            for entry in WalkDir::new(&source) {
+                let entry = entry?;
+                let relative = entry.path().strip_prefix(base_path)?;
+                let dest = backup_dir.join(relative);
+                if entry.file_type().is_dir() {
+                    fs::create_dir_all(&dest)?;
+                    continue;
+                }
+                if !entry.file_type().is_file() {
+                    continue;
+                }
 
-                if is_dir {
-                    Self::copy_dir_all(&source, &dest)?;
-                } else {
-                    if let Some(parent) = dest.parent() {
-                        fs::create_dir_all(parent)?;
+                let key = archive::entry_name(relative);
+                let mut file_entry = ManifestEntry::for_file(entry.path())?;
+                let earlier = base.and_then(|(name, base_manifest)| {
+                    let earlier = base_manifest.files.get(&key)?;
+                    file_entry.unchanged_since(earlier).then(|| {
+                        let stored_in =
+                            earlier.stored_in.clone().unwrap_or_else(|| name.to_string());
+                        (stored_in, earlier.sha256.clone())
+                    })
+                });
+
+                match earlier {
+                    Some((stored_in, sha256)) => {
+                        file_entry.stored_in = Some(stored_in);
+                        file_entry.sha256 = sha256;
+                    }
+                    None => {
+                        // Hash the source so the checksum reflects the data, not the copy
+                        file_entry.sha256 = Some(manifest::sha256_file(entry.path())?);
+                        if let Some(parent) = dest.parent() {
+                            fs::create_dir_all(parent)?;
+                        }
+                        fs::copy(entry.path(), &dest)
+                            .context(format!("Failed to backup {:?}", entry.path()))?;
                     }
-                    fs::copy(&source, &dest).context(format!("Failed to backup {:?}", source))?;
                 }
+                manifest.files.insert(key, file_entry);
+            }
+
+            log::info!("Backed up: {:?} -> {:?}", source, backup_dir.join(item));
+        }
+
+        manifest.save(backup_dir)?;
+        Ok(manifest)
+    }
 
-                log::info!("Backed up: {:?} -> {:?}", source, dest);
+    /// Newest `update_backup_*` directory with a readable manifest, as (name, manifest)
+    fn latest_manifest_backup(&self) -> Result<Option<(String, BackupManifest)>> {
+        let backups_dir = self.config.app_data_path.join("backups");
+        if !backups_dir.exists() {
+            return Ok(None);
+        }
+
+        let mut names: Vec<String> = fs::read_dir(&backups_dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("update_backup_"))
+            .collect();
+        names.sort();
+
+        for name in names.into_iter().rev() {
+            match BackupManifest::load(&backups_dir.join(&name)) {
+                Ok(Some(manifest)) => return Ok(Some((name, manifest))),
+                Ok(None) => {}
+                Err(e) => log::warn!("Skipping backup {} as an incremental base: {}", name, e),
             }
         }
+        Ok(None)
+    }
 
-        log::info!("User data backup created at {:?}", backup_dir);
-        Ok(backup_dir)
+    /// Like `backup_user_data`, but writes the same items into a single
+    /// `update_backup_{timestamp}.zip`, which is faster and smaller for large projects
+    pub async fn backup_user_data_compressed(&self) -> Result<PathBuf> {
+        log::info!("Creating compressed backup of user data...");
+
+        use chrono::Local;
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let archive_path = self
+            .config
+            .app_data_path
+            .join("backups")
+            .join(format!("update_backup_{}.zip", timestamp));
+
+        archive::write_archive(&self.config.app_data_path, BACKUP_ITEMS, &archive_path).context(
+            format!("Failed to create compressed backup: {:?}", archive_path),
+        )?;
+
+        log::info!("Compressed user data backup created at {:?}", archive_path);
+        Ok(archive_path)
     }
 
-    /// Restore data from a backup if needed (e.g., if update fails)
+    /// Restore data from a backup if needed (e.g., if update fails).
+    /// Accepts both backup directories and zip archives.
     pub async fn restore_if_needed(&self, backup_path: PathBuf) -> Result<()> {
         log::info!("Restoring data from backup: {:?}", backup_path);
 
@@ -152,10 +329,169 @@ impl UpdateManager {
             ));
         }
 
+        let (tree, _scratch) = self.materialize(&backup_path)?;
+        self.restore_tree(&tree)
+    }
+
+    /// Restore only `items` (files or directories, relative to the app data directory)
+    /// from a backup, leaving everything else alone. A restored directory replaces the
+    /// current one. Returns the restored items.
+    pub async fn restore_items(&self, backup_path: &Path, items: &[String]) -> Result<Vec<String>> {
+        log::info!("Restoring {:?} from backup: {:?}", items, backup_path);
+
+        let items: Vec<String> = items
+            .iter()
+            .map(|item| Self::normalize_item(item))
+            .collect::<Result<_>>()?;
+        if items.is_empty() {
+            return Err(anyhow::anyhow!("No items to restore"));
+        }
+
+        let (tree, _scratch) = self.materialize(backup_path)?;
+        let absent: Vec<&str> = items
+            .iter()
+            .filter(|item| !tree.join(item).exists())
+            .map(|item| item.as_str())
+            .collect();
+        if !absent.is_empty() {
+            return Err(anyhow::anyhow!("Not in this backup: {}", absent.join(", ")));
+        }
+
+        for item in &items {
+            let source = tree.join(item);
+            let dest = self.config.app_data_path.join(item);
+            if source.is_dir() {
+                if dest.exists() {
+                    fs::remove_dir_all(&dest)?;
+                }
+                Self::copy_dir_all(&source, &dest)?;
+            } else {
+                if let Some(parent) = dest.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&source, &dest).context(format!("Failed to restore {:?}", source))?;
+            }
+            log::info!("Restored: {:?} -> {:?}", source, dest);
+        }
+
+        Ok(items)
+    }
+
+    /// Files in a backup, sorted by path. Reads the archive index or the manifest when
+    /// there is one, so nothing is extracted or reassembled.
+    pub fn list_backup_contents(&self, backup_path: &Path) -> Result<Vec<BackupFile>> {
+        let mut files: Vec<BackupFile> = if archive::is_archive(backup_path) {
+            archive::list_files(backup_path)?
+                .into_iter()
+                .map(|(path, size_bytes)| BackupFile { path, size_bytes })
+                .collect()
+        } else if let Some(manifest) = BackupManifest::load(backup_path)? {
+            manifest
+                .files
+                .into_iter()
+                .map(|(path, entry)| BackupFile {
+                    path,
+                    size_bytes: entry.size,
+                })
+                .collect()
+        } else {
+            let mut files = Vec::new();
+            for entry in WalkDir::new(backup_path).min_depth(1) {
+                let entry = entry?;
+                if entry.file_type().is_file() {
+                    files.push(BackupFile {
+                        path: archive::entry_name(entry.path().strip_prefix(backup_path)?),
+                        size_bytes: entry.metadata()?.len(),
+                    });
+                }
+            }
+            files
+        };
+
+        files.retain(|file| file.path != MANIFEST_FILE);
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// A directory with the complete tree of `backup_path`. Zip archives are extracted and
+    /// incremental backups reassembled into a temp dir next to the backup, returned too so
+    /// it lives until the caller is done with the tree.
+    fn materialize(&self, backup_path: &Path) -> Result<(PathBuf, Option<TempDir>)> {
+        let scratch_root = backup_path.parent().unwrap_or(&self.config.app_data_path);
+
+        if archive::is_archive(backup_path) {
+            let extracted = tempfile::tempdir_in(scratch_root)?;
+            archive::extract_archive(backup_path, extracted.path())?;
+            return Ok((extracted.path().to_path_buf(), Some(extracted)));
+        }
+
+        let manifest = BackupManifest::load(backup_path)?;
+        if let Some(manifest) = manifest.filter(|m| m.is_incremental()) {
+            let assembled = tempfile::tempdir_in(scratch_root)?;
+            Self::assemble_incremental(backup_path, &manifest, scratch_root, assembled.path())?;
+            return Ok((assembled.path().to_path_buf(), Some(assembled)));
+        }
+
+        Ok((backup_path.to_path_buf(), None))
+    }
+
+    /// `item` as a clean `/`-separated relative path, rejecting anything that could point
+    /// outside the app data directory
+    fn normalize_item(item: &str) -> Result<String> {
+        let path = Path::new(item.trim());
+        let safe = path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        let normalized = archive::entry_name(path);
+        let normalized = normalized.trim_start_matches("./");
+        if !safe || normalized.is_empty() || normalized == "." || normalized == MANIFEST_FILE {
+            return Err(anyhow::anyhow!("Invalid item to restore: '{}'", item));
+        }
+        Ok(normalized.to_string())
+    }
+
+    /// Rebuild the full tree of an incremental backup in `target`: its own files plus the
+    /// unchanged ones it references in earlier backups
+    fn assemble_incremental(
+        backup_path: &Path,
+        manifest: &BackupManifest,
+        backups_dir: &Path,
+        target: &Path,
+    ) -> Result<()> {
+        Self::copy_dir_all(backup_path, target)?;
+
+        for (key, entry) in &manifest.files {
+            let Some(stored_in) = &entry.stored_in else {
+                continue;
+            };
+            let source = backups_dir.join(stored_in).join(key);
+            if !source.is_file() {
+                return Err(anyhow::anyhow!(
+                    "Cannot restore {}: earlier backup {} no longer has it",
+                    key,
+                    stored_in
+                ));
+            }
+
+            let dest = target.join(key);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&source, &dest).context(format!("Failed to restore {:?}", source))?;
+        }
+
+        Ok(())
+    }
+
+    /// Copy every top-level item of an uncompressed backup over the app data directory
+    fn restore_tree(&self, backup_path: &Path) -> Result<()> {
         // Restore backed up items
-        for entry in fs::read_dir(&backup_path)? {
+        for entry in fs::read_dir(backup_path)? {
             let entry = entry?;
             let item_name = entry.file_name();
+            if item_name == MANIFEST_FILE {
+                continue;
+            }
             let source = entry.path();
             let dest = self.config.app_data_path.join(&item_name);
 
@@ -176,6 +512,80 @@ impl UpdateManager {
         Ok(())
     }
 
+    /// Check that a backup's files are intact. Directories are compared with the SHA-256
+    /// checksums in their manifest; zip archives are checked against their CRC-32s.
+    pub fn verify_backup(&self, backup_path: &Path) -> Result<BackupVerification> {
+        if archive::is_archive(backup_path) {
+            let (checked, corrupted) = archive::verify_archive(backup_path)?;
+            return Ok(BackupVerification {
+                intact: corrupted.is_empty(),
+                checked,
+                missing: Vec::new(),
+                corrupted,
+            });
+        }
+
+        let manifest = BackupManifest::load(backup_path)?.ok_or_else(|| {
+            anyhow::anyhow!("Backup {:?} has no manifest to verify against", backup_path)
+        })?;
+        let backups_dir = backup_path.parent().unwrap_or(&self.config.app_data_path);
+        let result = manifest.verify(backup_path, backups_dir);
+        log::info!(
+            "Verified backup {:?}: {} checked, {} missing, {} corrupted",
+            backup_path,
+            result.checked,
+            result.missing.len(),
+            result.corrupted.len()
+        );
+        Ok(result)
+    }
+
+    /// Backups in the backups directory, newest first
+    pub fn list_backups(&self) -> Result<Vec<BackupInfo>> {
+        let backups_dir = self.config.app_data_path.join("backups");
+        if !backups_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for entry in fs::read_dir(&backups_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !(name.starts_with("backup_") || name.starts_with("update_backup_")) {
+                continue;
+            }
+
+            let path = entry.path();
+            let compressed = archive::is_archive(&path);
+            let size_bytes = if compressed {
+                entry.metadata()?.len()
+            } else if path.is_dir() {
+                Self::dir_size(&path)
+            } else {
+                continue;
+            };
+            backups.push(BackupInfo {
+                path,
+                compressed,
+                size_bytes,
+            });
+        }
+
+        // Names embed the timestamp, so reverse name order is newest first
+        backups.sort_by(|a, b| b.path.cmp(&a.path));
+        Ok(backups)
+    }
+
+    fn dir_size(path: &Path) -> u64 {
+        WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| e.metadata().ok())
+            .map(|m| m.len())
+            .sum()
+    }
+
     /// Verify the integrity of the installation after an update
     pub async fn verify_integrity(&self) -> Result<bool> {
         log::info!("Verifying installation integrity...");
@@ -189,9 +599,8 @@ impl UpdateManager {
         }
 
         // Check required directories
-        let required_dirs = vec!["projects", "skills", "templates", "backups", "logs"];
-        for dir in required_dirs {
-            let dir_path = base_path.join(dir);
+        for dir in directory::managed_subdirs(base_path) {
+            let dir_path = base_path.join(&dir);
             if !dir_path.exists() {
                 log::error!("Required directory missing: {:?}", dir_path);
                 return Ok(false);
@@ -273,10 +682,44 @@ impl UpdateManager {
         result.backup_path = backup_path;
         result.message = "Update completed successfully".to_string();
 
+        // Step 5: Collect release notes since the recorded version and record the new one
+        let current_version = env!("CARGO_PKG_VERSION");
+        if let Some(previous_version) = Self::recorded_version().filter(|v| v != current_version) {
+            result.changelog = changelog::entries_between(
+                &self.config.app_data_path,
+                &previous_version,
+                current_version,
+            )
+            .await;
+            if !result.changelog.is_empty() {
+                result.message = format!(
+                    "Updated from {} to {}. See what's new in {}.",
+                    previous_version, current_version, current_version
+                );
+            }
+            if let Err(e) = ConfigManager::update_config(|config| {
+                config.version = current_version.to_string();
+            }) {
+                log::warn!("Failed to record app version: {}", e);
+            }
+            result.previous_version = Some(previous_version);
+        }
+
         log::info!("Update process completed successfully");
         Ok(result)
     }
 
+    /// Version stored in config.json by the last install or update
+    fn recorded_version() -> Option<String> {
+        match ConfigManager::load_config() {
+            Ok(config) => config.map(|c| c.version),
+            Err(e) => {
+                log::warn!("Failed to load app config: {}", e);
+                None
+            }
+        }
+    }
+
     /// Update template files (only add new templates, don't overwrite)
     async fn update_templates(&self, files_updated: &mut Vec<String>) -> Result<()> {
         let templates_dir = self.config.app_data_path.join("templates");
@@ -589,6 +1032,8 @@ mod tests {
             claude_code_detected: false,
             ollama_detected: false,
             gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
         };
 
         let manager = UpdateManager::new(config);
@@ -617,6 +1062,8 @@ mod tests {
             claude_code_detected: false,
             ollama_detected: false,
             gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
         };
 
         let manager = UpdateManager::new(config);
@@ -627,6 +1074,197 @@ mod tests {
         assert!(backup_path.join("settings.json").exists());
     }
 
+    #[tokio::test]
+    async fn test_compressed_backup_restores_and_is_listed() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().to_path_buf();
+
+        fs::create_dir_all(base_path.join("projects/alpha")).unwrap();
+        fs::write(base_path.join("projects/alpha/notes.md"), "original").unwrap();
+        fs::write(base_path.join("settings.json"), "settings").unwrap();
+
+        let config = InstallationConfig {
+            app_data_path: base_path.clone(),
+            is_first_install: false,
+            claude_code_detected: false,
+            ollama_detected: false,
+            gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
+        };
+
+        let manager = UpdateManager::new(config);
+        let archive_path = manager.backup_user_data_compressed().await.unwrap();
+        assert!(archive_path.is_file());
+
+        let backups = manager.list_backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].compressed);
+        assert_eq!(
+            backups[0].size_bytes,
+            fs::metadata(&archive_path).unwrap().len()
+        );
+
+        fs::write(base_path.join("projects/alpha/notes.md"), "changed").unwrap();
+        manager.restore_if_needed(archive_path).await.unwrap();
+
+        assert_eq!(
+            fs::read_to_string(base_path.join("projects/alpha/notes.md")).unwrap(),
+            "original"
+        );
+        // The temporary extraction directory is cleaned up
+        assert_eq!(manager.list_backups().unwrap().len(), 1);
+        assert_eq!(fs::read_dir(base_path.join("backups")).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_incremental_backup_copies_only_changed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().to_path_buf();
+
+        fs::create_dir_all(base_path.join("projects/alpha")).unwrap();
+        fs::write(base_path.join("projects/alpha/kept.md"), "kept").unwrap();
+        fs::write(base_path.join("projects/alpha/edited.md"), "first").unwrap();
+        fs::write(base_path.join("settings.json"), "settings").unwrap();
+
+        let config = InstallationConfig {
+            app_data_path: base_path.clone(),
+            is_first_install: false,
+            claude_code_detected: false,
+            ollama_detected: false,
+            gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
+        };
+
+        let manager = UpdateManager::new(config);
+        let full = manager.backup_user_data().await.unwrap();
+        let full_name = full.file_name().unwrap().to_string_lossy().to_string();
+
+        fs::write(base_path.join("projects/alpha/edited.md"), "second edit").unwrap();
+        let incremental = manager.backup_user_data_incremental().await.unwrap();
+
+        assert!(incremental.join("projects/alpha/edited.md").exists());
+        assert!(!incremental.join("projects/alpha/kept.md").exists());
+        let manifest = BackupManifest::load(&incremental).unwrap().unwrap();
+        assert_eq!(manifest.base.as_deref(), Some(full_name.as_str()));
+        assert_eq!(
+            manifest.files["projects/alpha/kept.md"].stored_in.as_deref(),
+            Some(full_name.as_str())
+        );
+        assert!(manifest.files["projects/alpha/edited.md"].stored_in.is_none());
+
+        fs::remove_dir_all(base_path.join("projects")).unwrap();
+        manager.restore_if_needed(incremental).await.unwrap();
+
+        assert_eq!(
+            fs::read_to_string(base_path.join("projects/alpha/kept.md")).unwrap(),
+            "kept"
+        );
+        assert_eq!(
+            fs::read_to_string(base_path.join("projects/alpha/edited.md")).unwrap(),
+            "second edit"
+        );
+        assert!(!base_path.join(MANIFEST_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn test_verify_backup_reports_missing_and_corrupted_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().to_path_buf();
+
+        fs::create_dir_all(base_path.join("projects/alpha")).unwrap();
+        fs::write(base_path.join("projects/alpha/a.md"), "alpha").unwrap();
+        fs::write(base_path.join("projects/alpha/b.md"), "bravo").unwrap();
+        fs::write(base_path.join("settings.json"), "settings").unwrap();
+
+        let config = InstallationConfig {
+            app_data_path: base_path.clone(),
+            is_first_install: false,
+            claude_code_detected: false,
+            ollama_detected: false,
+            gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
+        };
+
+        let manager = UpdateManager::new(config);
+        let backup = manager.backup_user_data().await.unwrap();
+
+        let intact = manager.verify_backup(&backup).unwrap();
+        assert!(intact.intact);
+        assert_eq!(intact.checked, 3);
+
+        fs::write(backup.join("projects/alpha/a.md"), "alphX").unwrap();
+        fs::remove_file(backup.join("settings.json")).unwrap();
+
+        let damaged = manager.verify_backup(&backup).unwrap();
+        assert!(!damaged.intact);
+        assert_eq!(damaged.corrupted, vec!["projects/alpha/a.md".to_string()]);
+        assert_eq!(damaged.missing, vec!["settings.json".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_restore_items_leaves_other_data_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path().to_path_buf();
+
+        fs::create_dir_all(base_path.join("projects/alpha")).unwrap();
+        fs::create_dir_all(base_path.join("skills")).unwrap();
+        fs::write(base_path.join("projects/alpha/notes.md"), "backed up").unwrap();
+        fs::write(base_path.join("skills/summarize.md"), "skill").unwrap();
+        fs::write(base_path.join("settings.json"), "old settings").unwrap();
+
+        let config = InstallationConfig {
+            app_data_path: base_path.clone(),
+            is_first_install: false,
+            claude_code_detected: false,
+            ollama_detected: false,
+            gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
+        };
+
+        let manager = UpdateManager::new(config);
+        let backup = manager.backup_user_data_compressed().await.unwrap();
+
+        let contents = manager.list_backup_contents(&backup).unwrap();
+        let paths: Vec<&str> = contents.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["projects/alpha/notes.md", "settings.json", "skills/summarize.md"]
+        );
+
+        fs::remove_file(base_path.join("skills/summarize.md")).unwrap();
+        fs::write(base_path.join("projects/alpha/notes.md"), "current").unwrap();
+        fs::write(base_path.join("settings.json"), "new settings").unwrap();
+
+        let restored = manager
+            .restore_items(&backup, &["skills/summarize.md".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(restored, vec!["skills/summarize.md".to_string()]);
+
+        assert!(base_path.join("skills/summarize.md").exists());
+        assert_eq!(
+            fs::read_to_string(base_path.join("projects/alpha/notes.md")).unwrap(),
+            "current"
+        );
+        assert_eq!(
+            fs::read_to_string(base_path.join("settings.json")).unwrap(),
+            "new settings"
+        );
+
+        assert!(manager
+            .restore_items(&backup, &["../settings.json".to_string()])
+            .await
+            .is_err());
+        assert!(manager
+            .restore_items(&backup, &["projects/missing".to_string()])
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_verify_integrity() {
         let temp_dir = TempDir::new().unwrap();
@@ -643,6 +1281,8 @@ mod tests {
             claude_code_detected: false,
             ollama_detected: false,
             gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
         };
 
         let manager = UpdateManager::new(config);
@@ -668,6 +1308,8 @@ mod tests {
             claude_code_detected: false,
             ollama_detected: false,
             gemini_detected: false,
+            codex_detected: false,
+            node_toolchain: Default::default(),
         };
 
         let manager = UpdateManager::new(config);
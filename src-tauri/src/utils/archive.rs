@@ -0,0 +1,172 @@
+//! Zip archives for compressed backups of the app data directory and project exports
+
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use tempfile::NamedTempFile;
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Whether `path` is a zip backup rather than a backup directory
+pub fn is_archive(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .map_or(false, |ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Write `items` (files or directories relative to `base`) into a deflated zip at
+/// `archive_path`, skipping items that don't exist. The zip is built in a temp file and
+/// renamed into place, so an interrupted backup never looks like a finished one.
+pub fn write_archive(base: &Path, items: &[&str], archive_path: &Path) -> Result<()> {
+    write_archive_filtered(base, items, archive_path, |_| true)
+}
+
+/// Like `write_archive`, leaving out every path (relative to `base`) that `include`
+/// rejects. A rejected directory is left out along with its contents.
+pub fn write_archive_filtered(
+    base: &Path,
+    items: &[&str],
+    archive_path: &Path,
+    include: impl Fn(&Path) -> bool,
+) -> Result<()> {
+    let dir = archive_path
+        .parent()
+        .context("Archive path has no parent directory")?;
+    fs::create_dir_all(dir)?;
+
+    let mut zip = ZipWriter::new(NamedTempFile::new_in(dir)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for item in items {
+        let source = base.join(item);
+        if !source.exists() {
+            continue;
+        }
+        let entries = WalkDir::new(&source).into_iter().filter_entry(|e| {
+            e.path()
+                .strip_prefix(base)
+                .map_or(true, |relative| include(relative))
+        });
+        for entry in entries {
+            let entry = entry?;
+            let name = entry_name(entry.path().strip_prefix(base)?);
+            if entry.file_type().is_dir() {
+                zip.add_directory(name, options)?;
+            } else if entry.file_type().is_file() {
+                zip.start_file(name, options)?;
+                let mut file = File::open(entry.path())
+                    .with_context(|| format!("Failed to read {:?}", entry.path()))?;
+                io::copy(&mut file, &mut zip)?;
+            }
+        }
+    }
+
+    let temp = zip.finish()?;
+    temp.as_file().sync_all()?;
+    temp.persist(archive_path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Extract `archive` into `dest`. Entries whose names would escape `dest` are rejected.
+pub fn extract_archive(archive: &Path, dest: &Path) -> Result<()> {
+    let mut zip = open(archive)?;
+    zip.extract(dest).context("Failed to extract archive")?;
+    Ok(())
+}
+
+/// Read every file in `archive` so zip checks its CRC-32. Returns how many files were
+/// read and the names of those that failed.
+pub fn verify_archive(archive: &Path) -> Result<(usize, Vec<String>)> {
+    let mut zip = open(archive)?;
+
+    let mut checked = 0;
+    let mut corrupted = Vec::new();
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        checked += 1;
+        if io::copy(&mut entry, &mut io::sink()).is_err() {
+            corrupted.push(entry.name().to_string());
+        }
+    }
+    Ok((checked, corrupted))
+}
+
+/// Names and uncompressed sizes of the files in `archive`, read from its index
+pub fn list_files(archive: &Path) -> Result<Vec<(String, u64)>> {
+    let mut zip = open(archive)?;
+    let mut files = Vec::new();
+    for index in 0..zip.len() {
+        let entry = zip.by_index(index)?;
+        if !entry.is_dir() {
+            files.push((entry.name().to_string(), entry.size()));
+        }
+    }
+    Ok(files)
+}
+
+fn open(archive: &Path) -> Result<ZipArchive<File>> {
+    let file = File::open(archive).with_context(|| format!("Failed to open {:?}", archive))?;
+    ZipArchive::new(file).context("Not a valid zip archive")
+}
+
+/// `relative` with `/` separators, as used for zip entry names and backup manifests
+pub fn entry_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archive_round_trip_skips_missing_items() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().join("data");
+        fs::create_dir_all(base.join("projects/alpha")).unwrap();
+        fs::write(base.join("projects/alpha/.project.md"), "# Alpha").unwrap();
+        fs::write(base.join("settings.json"), "{}").unwrap();
+
+        let archive = temp.path().join("backups/update_backup_1.zip");
+        write_archive(&base, &["projects", "settings.json", "skills"], &archive).unwrap();
+        assert!(is_archive(&archive));
+
+        let restored = temp.path().join("restored");
+        extract_archive(&archive, &restored).unwrap();
+        assert_eq!(
+            fs::read_to_string(restored.join("projects/alpha/.project.md")).unwrap(),
+            "# Alpha"
+        );
+        assert!(restored.join("settings.json").exists());
+        assert!(!restored.join("skills").exists());
+    }
+
+    #[test]
+    fn test_filtered_archive_leaves_out_rejected_paths() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().join("projects");
+        fs::create_dir_all(base.join("alpha/cache")).unwrap();
+        fs::write(base.join("alpha/notes.md"), "notes").unwrap();
+        fs::write(base.join("alpha/.env"), "KEY=1").unwrap();
+        fs::write(base.join("alpha/cache/big.bin"), "data").unwrap();
+
+        let archive = temp.path().join("alpha.zip");
+        let include = |relative: &Path| {
+            relative != Path::new("alpha/.env") && relative != Path::new("alpha/cache")
+        };
+        write_archive_filtered(&base, &["alpha"], &archive, include).unwrap();
+
+        let names: Vec<String> = list_files(&archive).unwrap().into_iter().map(|f| f.0).collect();
+        assert_eq!(names, vec!["alpha/notes.md"]);
+    }
+}
@@ -0,0 +1,50 @@
+//! Crash-safe file writes for settings, secrets and other files that must never be
+//! left half-written
+
+use std::io::{self, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// Replace `path` with `content` atomically: write a temp file in the same directory,
+/// fsync it, then rename it over the target. Readers see either the old file or the
+/// new one, never a truncated mix, even if the process dies or the disk fills mid-write.
+pub fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut temp = NamedTempFile::new_in(dir)?;
+    temp.write_all(content.as_ref())?;
+    temp.as_file().sync_all()?;
+    temp.persist(path).map_err(|e| e.error)?;
+
+    // Make the rename itself durable; not supported for directories on Windows
+    #[cfg(unix)]
+    if let Ok(dir) = std::fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_replaces_and_leaves_no_temp_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.json");
+
+        write_atomic(&path, "{\"a\":1}").unwrap();
+        write_atomic(&path, "{\"a\":2}").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"a\":2}");
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_write_atomic_fails_without_touching_target_when_dir_is_missing() {
+        let temp = tempfile::tempdir().unwrap();
+        assert!(write_atomic(&temp.path().join("missing").join("secrets.json"), "x").is_err());
+    }
+}
@@ -1,3 +1,6 @@
+pub mod archive;
 pub mod env;
+pub mod fs;
 pub mod paths;
+pub mod slug;
 pub mod user;
@@ -175,6 +175,27 @@ pub fn get_secrets_path() -> Result<PathBuf> {
     Ok(app_data.join("secrets.encrypted.json"))
 }
 
+/// Get the master key file path (used when the key store is set to `File`)
+/// Returns: {APP_DATA}/master.key
+pub fn get_master_key_path() -> Result<PathBuf> {
+    let app_data = get_app_data_dir()?;
+    Ok(app_data.join("master.key"))
+}
+
+/// Get the directory for data that can be re-fetched at any time
+/// Returns: {APP_DATA}/cache
+pub fn get_cache_dir() -> Result<PathBuf> {
+    let app_data = get_app_data_dir()?;
+    Ok(app_data.join("cache"))
+}
+
+/// Get the directory holding automatic snapshots of one project
+/// Returns: {APP_DATA}/backups/projects/{project_id}
+pub fn get_project_backups_dir(project_id: &str) -> Result<PathBuf> {
+    let app_data = get_app_data_dir()?;
+    Ok(app_data.join("backups").join("projects").join(project_id))
+}
+
 /// Ensure the complete directory structure exists
 /// Creates:
 /// - {APP_DATA}/
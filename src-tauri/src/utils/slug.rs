@@ -0,0 +1,91 @@
+//! Filesystem-safe ids derived from display names, shared by projects and skills
+
+use serde::Serialize;
+use thiserror::Error;
+
+/// Returned when the id derived from a name is already in use
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[error("'{id}' is already in use; try '{suggestion}' instead")]
+pub struct SlugCollision {
+    pub id: String,
+    /// First free id of the form `<id>-2`, `<id>-3`, ...
+    pub suggestion: String,
+}
+
+/// Turn a display name into an id made of lowercase ASCII letters, digits, `_` and single
+/// hyphens. Whitespace becomes a hyphen, other characters are dropped, and leading,
+/// trailing and repeated hyphens are removed. Names with nothing usable left fall back
+/// to `untitled-<hash>`, which is stable for the same input.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.trim().chars() {
+        let c = if c.is_whitespace() { '-' } else { c.to_ascii_lowercase() };
+        if c == '-' {
+            if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            slug.push(c);
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    if slug.is_empty() {
+        format!("untitled-{:08x}", fnv1a(name.as_bytes()))
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Slugify `name` and make sure the id is free according to `exists`
+pub fn unique_slug(name: &str, exists: impl Fn(&str) -> bool) -> Result<String, SlugCollision> {
+    let id = slugify(name);
+    if !exists(&id) {
+        return Ok(id);
+    }
+
+    let suggestion = (2u32..)
+        .map(|n| format!("{}-{}", id, n))
+        .find(|candidate| !exists(candidate))
+        .unwrap_or_default();
+    Err(SlugCollision { id, suggestion })
+}
+
+/// 32-bit FNV-1a; unlike `DefaultHasher` its output never changes between Rust releases
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, b| {
+        (hash ^ u32::from(*b)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify_basic_names() {
+        assert_eq!(slugify("My Project_Name 123!"), "my-project_name-123");
+        assert_eq!(slugify("  Market -- Research  "), "market-research");
+        assert_eq!(slugify("-Lead-"), "lead");
+        assert_eq!(slugify("Café Notes"), "caf-notes");
+    }
+
+    #[test]
+    fn test_slugify_never_returns_empty() {
+        let id = slugify("!!! ???");
+        assert!(id.starts_with("untitled-"));
+        assert_eq!(id.len(), "untitled-".len() + 8);
+        assert_eq!(id, slugify("!!! ???"), "fallback is deterministic");
+        assert_ne!(id, slugify("***"));
+    }
+
+    #[test]
+    fn test_unique_slug_suggests_next_free_id() {
+        let taken = ["research", "research-2"];
+        let err = unique_slug("Research", |id| taken.contains(&id)).unwrap_err();
+        assert_eq!(err.id, "research");
+        assert_eq!(err.suggestion, "research-3");
+
+        assert_eq!(unique_slug("Other", |id| taken.contains(&id)).unwrap(), "other");
+    }
+}
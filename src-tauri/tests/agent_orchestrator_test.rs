@@ -0,0 +1,405 @@
+use app_lib::models::ai::chat_models::ChatRequest;
+use app_lib::models::ai::{ChatResponse, Message, ToolCall, ToolFunction};
+use app_lib::models::settings::LoggingPrivacyLevel;
+use app_lib::models::skill::SkillParameter;
+use app_lib::services::agent_orchestrator::{AgentOrchestrator, EventSink};
+use app_lib::services::ai_service::AIService;
+use app_lib::services::chat_service::ChatService;
+use app_lib::services::proposed_changes_service::ProposedChangesService;
+use app_lib::services::providers::mock::MockProvider;
+use app_lib::services::settings_service::SettingsService;
+use app_lib::services::skill_service::SkillService;
+use serde_json::Value;
+use std::fs;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+
+/// Tests below mutate process-wide env vars (HOME, PROJECTS_DIR, SKILLS_DIR),
+/// so they must not run concurrently.
+static ENV_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+type EventLog = Arc<Mutex<Vec<(String, Value)>>>;
+type RequestLog = Arc<Mutex<Vec<ChatRequest>>>;
+
+struct TestEnv {
+    _temp_dir: TempDir,
+    project_id: String,
+    project_path: PathBuf,
+    events: EventLog,
+}
+
+fn setup_env() -> TestEnv {
+    let temp_dir = TempDir::new().unwrap();
+    let projects_dir = temp_dir.path().join("projects");
+    let skills_dir = temp_dir.path().join("skills");
+    fs::create_dir_all(&projects_dir).unwrap();
+    fs::create_dir_all(&skills_dir).unwrap();
+
+    std::env::set_var("HOME", temp_dir.path());
+    std::env::set_var("PROJECTS_DIR", &projects_dir);
+    std::env::set_var("SKILLS_DIR", &skills_dir);
+
+    let project_id = "orchestrator-test".to_string();
+    let project_path = projects_dir.join(&project_id);
+    fs::create_dir_all(project_path.join(".metadata")).unwrap();
+    let project_meta = serde_json::json!({
+        "id": project_id,
+        "name": "Orchestrator Test",
+        "goal": "Exercise the agent loop offline",
+        "skills": [],
+        "created": "2025-01-01T00:00:00Z"
+    });
+    fs::write(
+        project_path.join(".metadata").join("project.json"),
+        serde_json::to_string(&project_meta).unwrap(),
+    )
+    .unwrap();
+
+    TestEnv {
+        _temp_dir: temp_dir,
+        project_id,
+        project_path,
+        events: Arc::new(Mutex::new(Vec::new())),
+    }
+}
+
+fn orchestrator(env: &TestEnv, responses: Vec<ChatResponse>) -> (AgentOrchestrator, RequestLog) {
+    let provider = MockProvider::with_responses(responses);
+    let requests = provider.request_log();
+    let ai_service = Arc::new(AIService::with_provider(Box::new(provider)));
+    let events = env.events.clone();
+    let sink: EventSink = Arc::new(move |event: &str, payload: Value| {
+        events.lock().unwrap().push((event.to_string(), payload));
+    });
+    (AgentOrchestrator::with_event_sink(ai_service, sink), requests)
+}
+
+fn text_response(content: &str) -> ChatResponse {
+    ChatResponse {
+        content: content.to_string(),
+        tool_calls: None,
+        metadata: None,
+    }
+}
+
+fn tool_call_response(call_id: &str, tool_name: &str) -> ChatResponse {
+    ChatResponse {
+        content: "Let me look that up.".to_string(),
+        tool_calls: Some(vec![ToolCall {
+            id: call_id.to_string(),
+            tool_type: "function".to_string(),
+            function: ToolFunction {
+                name: tool_name.to_string(),
+                arguments: r#"{"query":"rust"}"#.to_string(),
+            },
+        }]),
+        metadata: None,
+    }
+}
+
+fn set_auto_apply(enabled: bool) {
+    let mut settings = SettingsService::load_global_settings().unwrap();
+    settings.auto_apply_file_changes = enabled;
+    SettingsService::save_global_settings(&settings).unwrap();
+}
+
+fn set_privacy_level(level: LoggingPrivacyLevel) {
+    let mut settings = SettingsService::load_global_settings().unwrap();
+    settings.logging_privacy_level = level;
+    SettingsService::save_global_settings(&settings).unwrap();
+}
+
+fn set_max_tool_iterations(limit: usize) {
+    let mut settings = SettingsService::load_global_settings().unwrap();
+    settings.max_tool_iterations = limit;
+    SettingsService::save_global_settings(&settings).unwrap();
+}
+
+fn user_message(text: &str) -> Message {
+    Message {
+        role: "user".to_string(),
+        content: text.to_string(),
+        tool_calls: None,
+        tool_results: None,
+    }
+}
+
+#[tokio::test]
+async fn test_echo_turn_runs_offline_and_saves_history() {
+    let _guard = ENV_LOCK.lock().await;
+    let env = setup_env();
+    let (orchestrator, requests) = orchestrator(&env, Vec::new());
+
+    let response = orchestrator
+        .run_agent_loop(
+            vec![user_message("What is the goal?")],
+            None,
+            Some(env.project_id.clone()),
+            None,
+            None,
+        )
+        .await
+        .expect("mock agent loop should succeed");
+
+    assert_eq!(response.content, "[mock] You said: What is the goal?");
+
+    assert_eq!(requests.lock().unwrap().len(), 1, "no tool calls means a single provider round trip");
+
+    let chats = ChatService::get_chat_summaries(&env.project_id).await.unwrap();
+    assert_eq!(chats.len(), 1, "one chat file should be saved");
+    let metadata = chats[0].metadata.as_ref().expect("chat metadata should be recorded");
+    assert_eq!(metadata.provider.as_deref(), Some("Mock"));
+    assert_eq!(metadata.model, "mock-echo");
+
+    assert!(env.project_path.join("research_log.md").exists());
+
+    let events = env.events.lock().unwrap();
+    assert!(events.iter().any(|(name, _)| name == "trace-log"));
+}
+
+#[tokio::test]
+async fn test_tool_calls_loop_and_feed_results_back() {
+    let _guard = ENV_LOCK.lock().await;
+    let env = setup_env();
+    let (orchestrator, requests) = orchestrator(
+        &env,
+        vec![
+            tool_call_response("call-1", "search__web_search"),
+            text_response("Final answer after tools."),
+        ],
+    );
+
+    let response = orchestrator
+        .run_agent_loop(
+            vec![user_message("Find rust papers")],
+            None,
+            Some(env.project_id.clone()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.content, "Final answer after tools.");
+    assert_eq!(response.metadata.as_ref().unwrap().tool_iterations, 1);
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2, "tool call should trigger a second round trip");
+
+    let followup = &requests[1].messages;
+    let assistant_turn = &followup[followup.len() - 2];
+    assert_eq!(assistant_turn.role, "assistant");
+    assert_eq!(assistant_turn.tool_calls.as_ref().unwrap()[0].id, "call-1");
+
+    let results = followup.last().unwrap().tool_results.as_ref().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].tool_use_id, "call-1");
+
+    let chats = ChatService::get_chat_files(&env.project_id).await.unwrap();
+    assert_eq!(chats.len(), 1, "history is saved once per turn, not per round trip");
+}
+
+#[tokio::test]
+async fn test_tool_loop_limit_is_reported_in_chat() {
+    let _guard = ENV_LOCK.lock().await;
+    let env = setup_env();
+    set_max_tool_iterations(1);
+    let (orchestrator, requests) = orchestrator(
+        &env,
+        vec![
+            tool_call_response("call-1", "search__web_search"),
+            tool_call_response("call-2", "search__web_search"),
+            text_response("Never reached."),
+        ],
+    );
+
+    let response = orchestrator
+        .run_agent_loop(
+            vec![user_message("Keep searching")],
+            None,
+            Some(env.project_id.clone()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(requests.lock().unwrap().len(), 2, "the second tool request must not be run");
+    assert!(response.content.contains("Stopped after 1 tool round trips"));
+    assert_eq!(response.metadata.as_ref().unwrap().tool_iterations, 1);
+    assert!(env.events.lock().unwrap().iter().any(|(name, payload)| {
+        name == "trace-log" && payload.as_str().is_some_and(|log| log.contains("Tool loop limit reached"))
+    }));
+}
+
+#[tokio::test]
+async fn test_tool_errors_are_recorded_as_is_error() {
+    let _guard = ENV_LOCK.lock().await;
+    let env = setup_env();
+    let (orchestrator, requests) = orchestrator(
+        &env,
+        vec![
+            tool_call_response("call-bad", "not_a_namespaced_tool"),
+            text_response("Recovered."),
+        ],
+    );
+
+    orchestrator
+        .run_agent_loop(
+            vec![user_message("Use a tool")],
+            None,
+            Some(env.project_id.clone()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let requests = requests.lock().unwrap();
+    let result = &requests[1].messages.last().unwrap().tool_results.as_ref().unwrap()[0];
+    assert!(result.is_error, "failed tool calls must be flagged as errors");
+    assert!(result.content.contains("Invalid tool name format"));
+}
+
+#[tokio::test]
+async fn test_detected_file_changes_are_applied_within_project() {
+    let _guard = ENV_LOCK.lock().await;
+    let env = setup_env();
+    let output = "Here is the summary.\n\nFILE: notes/summary.md\n```markdown\n# Summary\nAll good.\n```\n";
+    set_auto_apply(true);
+    let (orchestrator, _requests) = orchestrator(&env, vec![text_response(output)]);
+
+    orchestrator
+        .run_agent_loop(
+            vec![user_message("Summarise")],
+            None,
+            Some(env.project_id.clone()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let written = env.project_path.join("notes").join("summary.md");
+    assert!(written.exists(), "file change should be written inside the project");
+    assert_eq!(fs::read_to_string(written).unwrap(), "# Summary\nAll good.");
+
+    let events = env.events.lock().unwrap();
+    assert!(events.iter().any(|(name, _)| name == "file-changed"));
+}
+
+#[tokio::test]
+async fn test_file_changes_are_proposed_when_auto_apply_disabled() {
+    let _guard = ENV_LOCK.lock().await;
+    let env = setup_env();
+    let output = "FILE: draft.md\n```markdown\nDraft body\n```\n";
+    set_auto_apply(false);
+    let (orchestrator, _requests) = orchestrator(&env, vec![text_response(output)]);
+
+    orchestrator
+        .run_agent_loop(
+            vec![user_message("Draft something")],
+            None,
+            Some(env.project_id.clone()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let target = env.project_path.join("draft.md");
+    assert!(!target.exists(), "nothing should be written before approval");
+    assert!(env
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(name, _)| name == "files-proposed"));
+
+    let pending = ProposedChangesService::list(&env.project_id).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].path, "draft.md");
+    assert!(!pending[0].overwrites);
+
+    let applied = ProposedChangesService::apply(&env.project_id, &[pending[0].id.clone()]).unwrap();
+    assert_eq!(applied, vec!["draft.md".to_string()]);
+    assert_eq!(fs::read_to_string(target).unwrap(), "Draft body");
+    assert!(ProposedChangesService::list(&env.project_id).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_active_skill_prompt_matches_preview() {
+    let _guard = ENV_LOCK.lock().await;
+    let env = setup_env();
+
+    let mut skill = SkillService::create_skill_template(
+        "topic-brief".to_string(),
+        "Topic Brief".to_string(),
+        "Writes a short brief".to_string(),
+        vec![],
+    );
+    skill.prompt_template = "Write a one-page brief about {{topic}}.".to_string();
+    skill.parameters = vec![SkillParameter {
+        name: "topic".to_string(),
+        param_type: "string".to_string(),
+        description: "Subject of the brief".to_string(),
+        required: true,
+        default_value: None,
+    }];
+    SkillService::save_skill(&skill).unwrap();
+    let skill = SkillService::get_skill("topic-brief").unwrap();
+
+    let params = HashMap::from([("topic".to_string(), "solid-state batteries".to_string())]);
+
+    // Preview before running: the turn itself adds history to the project context
+    let rendered = skill.render_prompt(params.clone()).unwrap();
+    let preview = AgentOrchestrator::assemble_system_prompt(
+        Some(&env.project_id),
+        Some("Be concise."),
+        Some((&skill, &rendered)),
+    );
+
+    let (orchestrator, requests) = orchestrator(&env, Vec::new());
+    orchestrator
+        .run_agent_loop(
+            vec![user_message("Go")],
+            Some("Be concise.".to_string()),
+            Some(env.project_id.clone()),
+            Some("topic-brief".to_string()),
+            Some(params),
+        )
+        .await
+        .unwrap();
+
+    let sent = requests.lock().unwrap()[0].system_prompt.clone().unwrap();
+    assert!(sent.contains("--- ACTIVE SKILL: Topic Brief ---\nWrite a one-page brief about solid-state batteries."));
+    assert_eq!(sent, preview);
+}
+
+#[tokio::test]
+async fn test_privacy_levels_limit_what_is_persisted() {
+    let _guard = ENV_LOCK.lock().await;
+    let env = setup_env();
+    let log_path = env.project_path.join("research_log.md");
+
+    set_privacy_level(LoggingPrivacyLevel::Off);
+    let (orchestrator, _requests) = orchestrator(&env, Vec::new());
+    orchestrator
+        .run_agent_loop(vec![user_message("secret plan")], None, Some(env.project_id.clone()), None, None)
+        .await
+        .unwrap();
+    assert!(!log_path.exists(), "nothing is written when logging is off");
+    assert!(ChatService::get_chat_files(&env.project_id).await.unwrap().is_empty());
+
+    set_privacy_level(LoggingPrivacyLevel::MetadataOnly);
+    orchestrator
+        .run_agent_loop(vec![user_message("secret plan")], None, Some(env.project_id.clone()), None, None)
+        .await
+        .unwrap();
+    let log = fs::read_to_string(&log_path).unwrap();
+    assert!(log.contains("**Status**: completed"));
+    assert!(!log.contains("secret plan"), "content must not be recorded");
+    assert!(ChatService::get_chat_files(&env.project_id).await.unwrap().is_empty());
+}
@@ -0,0 +1,72 @@
+use app_lib::services::context_service::ContextService;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+/// Tests below mutate process-wide env vars, so they must not run concurrently.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn setup_project() -> (TempDir, String, PathBuf) {
+    let temp_dir = TempDir::new().unwrap();
+    let projects_dir = temp_dir.path().join("projects");
+    std::env::set_var("HOME", temp_dir.path());
+    std::env::set_var("PROJECTS_DIR", &projects_dir);
+
+    let project_id = "context-test".to_string();
+    let project_path = projects_dir.join(&project_id);
+    fs::create_dir_all(project_path.join(".metadata")).unwrap();
+    let project_meta = serde_json::json!({
+        "id": project_id,
+        "name": "Context Test",
+        "goal": "Check pinned context",
+        "skills": [],
+        "created": "2025-01-01T00:00:00Z"
+    });
+    fs::write(
+        project_path.join(".metadata").join("project.json"),
+        serde_json::to_string(&project_meta).unwrap(),
+    )
+    .unwrap();
+
+    (temp_dir, project_id, project_path)
+}
+
+#[test]
+fn test_pinned_files_are_included_in_full() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (_temp_dir, project_id, project_path) = setup_project();
+
+    let long_body: String = (1..=20).map(|i| format!("line {}\n", i)).collect();
+    fs::write(project_path.join("spec.md"), &long_body).unwrap();
+    fs::write(project_path.join("other.md"), &long_body).unwrap();
+
+    let pinned = ContextService::pin_file(&project_id, "spec.md").unwrap();
+    assert_eq!(pinned, vec!["spec.md".to_string()]);
+    // Pinning twice is a no-op
+    assert_eq!(ContextService::pin_file(&project_id, "./spec.md").unwrap().len(), 1);
+
+    let context = ContextService::get_project_context(&project_id).unwrap();
+    let pinned_pos = context.find("## Pinned Files").expect("pinned section missing");
+    let overview_pos = context.find("## Project Files Overview").unwrap();
+    assert!(pinned_pos < overview_pos, "pinned files come before other files");
+    assert!(context.contains("line 20"), "pinned file is not truncated to a preview");
+    assert_eq!(context.matches("### File: spec.md").count(), 1);
+    assert!(context.contains("### File: other.md"));
+
+    let pinned = ContextService::unpin_file(&project_id, "spec.md").unwrap();
+    assert!(pinned.is_empty());
+    assert!(!ContextService::get_project_context(&project_id)
+        .unwrap()
+        .contains("## Pinned Files"));
+}
+
+#[test]
+fn test_pinning_missing_file_fails() {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (_temp_dir, project_id, _project_path) = setup_project();
+
+    assert!(ContextService::pin_file(&project_id, "missing.md").is_err());
+    assert!(ContextService::pin_file(&project_id, "../escape.md").is_err());
+    assert!(ContextService::get_pinned_files(&project_id).unwrap().is_empty());
+}
@@ -22,6 +22,11 @@ fn test_settings_service_workflow() {
         encryption_enabled: Some(true),
         personalization_rules: None,
         brand_settings: None,
+        pinned_context_files: Vec::new(),
+        context_include: Vec::new(),
+        context_exclude: Vec::new(),
+        tool_filter: None,
+        require_tool_approval: false,
     };
 
     let save_result = SettingsService::save_project_settings(&project_path, &settings);
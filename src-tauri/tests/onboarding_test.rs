@@ -63,6 +63,8 @@ async fn test_onboarding_update_flow_preserves_gemini() {
         claude_code_detected: true,
         ollama_detected: true,
         gemini_detected: true,
+        codex_detected: false,
+        node_toolchain: Default::default(),
     };
 
     let state_file = app_data_path.join(".installation_state.json");
@@ -0,0 +1,65 @@
+use app_lib::models::settings::ProjectSettings;
+use app_lib::services::project_validation_service::{IssueSeverity, ProjectValidationService};
+use app_lib::services::settings_service::SettingsService;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write_project(projects_dir: &Path, id: &str, skills: &[&str]) -> std::path::PathBuf {
+    let project_path = projects_dir.join(id);
+    fs::create_dir_all(project_path.join(".metadata")).unwrap();
+    let meta = serde_json::json!({
+        "id": id,
+        "name": id,
+        "goal": "Check project health",
+        "skills": skills,
+        "created": "2025-01-01T00:00:00Z"
+    });
+    fs::write(
+        project_path.join(".metadata").join("project.json"),
+        serde_json::to_string(&meta).unwrap(),
+    )
+    .unwrap();
+    project_path
+}
+
+#[test]
+fn test_validate_all_projects_reports_per_project_issues() {
+    let temp_dir = TempDir::new().unwrap();
+    let projects_dir = temp_dir.path().join("projects");
+    std::env::set_var("HOME", temp_dir.path());
+    std::env::set_var("PROJECTS_DIR", &projects_dir);
+    std::env::set_var("SKILLS_DIR", temp_dir.path().join("skills"));
+
+    let healthy = write_project(&projects_dir, "healthy", &[]);
+    SettingsService::save_project_settings(&healthy, &ProjectSettings::default()).unwrap();
+
+    let broken_refs = write_project(&projects_dir, "broken-refs", &["ghost-skill"]);
+    fs::create_dir_all(broken_refs.join(".workflows")).unwrap();
+    fs::write(broken_refs.join(".workflows").join("bad.json"), "{ nope").unwrap();
+
+    let corrupt = projects_dir.join("corrupt");
+    fs::create_dir_all(corrupt.join(".metadata")).unwrap();
+    fs::write(corrupt.join(".metadata").join("project.json"), "{}").unwrap();
+
+    let reports = ProjectValidationService::validate_all().unwrap();
+    let ids: Vec<&str> = reports.iter().map(|r| r.project_id.as_str()).collect();
+    assert_eq!(ids, vec!["broken-refs", "corrupt", "healthy"]);
+
+    let healthy = &reports[2];
+    assert!(healthy.valid);
+    assert!(healthy.issues.is_empty(), "unexpected issues: {:?}", healthy.issues);
+
+    let broken_refs = &reports[0];
+    assert!(!broken_refs.valid);
+    assert!(broken_refs.issues.iter().any(|i| {
+        i.severity == IssueSeverity::Warning && i.message.contains("ghost-skill")
+    }));
+    assert!(broken_refs.issues.iter().any(|i| {
+        i.severity == IssueSeverity::Error && i.message.contains("bad.json")
+    }));
+
+    let corrupt = &reports[1];
+    assert!(!corrupt.valid);
+    assert!(corrupt.name.is_none());
+}
@@ -0,0 +1,74 @@
+#![cfg(unix)]
+
+use app_lib::models::ai::{CustomCliConfig, Message, ProviderType};
+use app_lib::models::settings::{GlobalSettings, KeyStore};
+use app_lib::services::ai_service::AIService;
+use app_lib::services::secrets_service::{Secrets, SecretsService};
+use app_lib::services::settings_service::SettingsService;
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use tempfile::TempDir;
+
+fn save_key(value: &str) {
+    let secrets = Secrets {
+        custom_api_keys: HashMap::from([("echo_key".to_string(), value.to_string())]),
+        ..Default::default()
+    };
+    SecretsService::save_secrets(&secrets).unwrap();
+}
+
+fn hello() -> Vec<Message> {
+    vec![Message {
+        role: "user".to_string(),
+        content: "hello".to_string(),
+        tool_calls: None,
+        tool_results: None,
+    }]
+}
+
+#[tokio::test]
+async fn test_next_chat_uses_updated_secret() {
+    let temp_dir = TempDir::new().unwrap();
+    std::env::set_var("HOME", temp_dir.path());
+
+    // A "CLI" that answers with the key it was given
+    let script = temp_dir.path().join("echo-key.sh");
+    fs::write(&script, "#!/bin/sh\nprintf '%s' \"$ECHO_KEY\"\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let settings = GlobalSettings {
+        active_provider: ProviderType::Custom("custom-echo".to_string()),
+        custom_clis: vec![CustomCliConfig {
+            id: "echo".to_string(),
+            name: "Echo".to_string(),
+            command: script.to_string_lossy().to_string(),
+            api_key_secret_id: Some("echo_key".to_string()),
+            api_key_env_var: Some("ECHO_KEY".to_string()),
+            detected_path: None,
+            is_configured: true,
+            settings_file_path: None,
+            mcp_config_flag: None,
+        }],
+        // Keep the master key in the temp dir instead of the OS keychain
+        encryption_key_store: KeyStore::File,
+        ..GlobalSettings::default()
+    };
+    SettingsService::save_global_settings(&settings).unwrap();
+
+    save_key("old-key");
+    let service = AIService::new().await.unwrap();
+    let response = service.chat(hello(), None, None).await.unwrap();
+    assert_eq!(response.content, "old-key");
+
+    let generation = SecretsService::generation();
+    save_key("new-key");
+    assert!(SecretsService::generation() > generation);
+
+    let response = service.chat(hello(), None, None).await.unwrap();
+    assert_eq!(response.content, "new-key");
+    assert_eq!(
+        service.get_active_provider_type().await,
+        ProviderType::Custom("custom-echo".to_string())
+    );
+}
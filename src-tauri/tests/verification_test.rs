@@ -44,6 +44,8 @@ fn test_workflow_validation_valid() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     assert!(
@@ -73,6 +75,8 @@ fn test_workflow_validation_empty_name() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     let result = workflow.validate();
@@ -97,6 +101,8 @@ fn test_workflow_validation_empty_steps() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     let result = workflow.validate();
@@ -146,6 +152,8 @@ fn test_workflow_json_roundtrip() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     // Serialize to JSON
@@ -189,6 +197,8 @@ fn test_workflow_persistence_on_disk() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     // Save to disk
@@ -236,6 +246,8 @@ fn test_workflow_modification_add_step() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     assert_eq!(workflow.steps.len(), 1);
@@ -291,6 +303,8 @@ fn test_workflow_modification_remove_step() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     workflow.steps.retain(|s| s.id != "step_2");
@@ -323,6 +337,8 @@ fn test_workflow_modification_rename() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     workflow.name = "New Name".to_string();
@@ -363,6 +379,8 @@ fn test_workflow_circular_dependency_detection() {
         last_run: None,
         active_execution_id: None,
         schedule: None,
+        schema_version: WORKFLOW_SCHEMA_VERSION,
+        tool_filter: None,
     };
 
     let result = workflow.validate();
@@ -382,6 +400,7 @@ fn test_skill_validation_valid() {
         id: "test-runner".to_string(),
         name: "Test Runner".to_string(),
         description: "Runs tests".to_string(),
+        category: "general".to_string(),
         capabilities: vec!["test_execution".to_string()],
         prompt_template: "You are a test runner agent.".to_string(),
         examples: vec![],
@@ -396,6 +415,10 @@ fn test_skill_validation_valid() {
         created: "2026-02-19T00:00:00Z".to_string(),
         updated: "2026-02-19T00:00:00Z".to_string(),
         file_path: PathBuf::from("test-runner.md"),
+        tool_filter: None,
+        required_tools: Vec::new(),
+        block_without_required_tools: false,
+        enabled: true,
     };
 
     assert!(skill.validate().is_ok());
@@ -407,6 +430,7 @@ fn test_skill_render_prompt_with_required_param() {
         id: "test-skill".to_string(),
         name: "Test Skill".to_string(),
         description: "Test".to_string(),
+        category: "general".to_string(),
         capabilities: vec![],
         prompt_template: "Run tests for domain: {{test_domain}}".to_string(),
         examples: vec![],
@@ -421,6 +445,10 @@ fn test_skill_render_prompt_with_required_param() {
         created: "".to_string(),
         updated: "".to_string(),
         file_path: PathBuf::from("test.md"),
+        tool_filter: None,
+        required_tools: Vec::new(),
+        block_without_required_tools: false,
+        enabled: true,
     };
 
     let mut params = HashMap::new();
@@ -435,6 +463,7 @@ fn test_skill_render_prompt_missing_required() {
         id: "test-skill".to_string(),
         name: "Test Skill".to_string(),
         description: "Test".to_string(),
+        category: "general".to_string(),
         capabilities: vec![],
         prompt_template: "Run tests for domain: {{test_domain}}".to_string(),
         examples: vec![],
@@ -449,6 +478,10 @@ fn test_skill_render_prompt_missing_required() {
         created: "".to_string(),
         updated: "".to_string(),
         file_path: PathBuf::from("test.md"),
+        tool_filter: None,
+        required_tools: Vec::new(),
+        block_without_required_tools: false,
+        enabled: true,
     };
 
     let params = HashMap::new(); // No params provided
@@ -465,6 +498,7 @@ fn test_skill_markdown_roundtrip() {
         id: "test-skill".to_string(),
         name: "Test Skill".to_string(),
         description: "A test skill for roundtrip".to_string(),
+        category: "general".to_string(),
         capabilities: vec!["testing".to_string()],
         prompt_template: "You are a test agent for {{domain}}.".to_string(),
         examples: vec![SkillExample {
@@ -483,6 +517,10 @@ fn test_skill_markdown_roundtrip() {
         created: "2026-02-19T00:00:00Z".to_string(),
         updated: "2026-02-19T00:00:00Z".to_string(),
         file_path: skill_path.clone(),
+        tool_filter: None,
+        required_tools: Vec::new(),
+        block_without_required_tools: false,
+        enabled: true,
     };
 
     // Save
@@ -520,6 +558,11 @@ fn test_settings_round_trip_all_fields() {
         encryption_enabled: Some(false),
         personalization_rules: None,
         brand_settings: None,
+        pinned_context_files: Vec::new(),
+        context_include: Vec::new(),
+        context_exclude: Vec::new(),
+        tool_filter: None,
+        require_tool_approval: false,
     };
 
     // Save
@@ -559,6 +602,11 @@ fn test_settings_overwrite() {
         encryption_enabled: Some(true),
         personalization_rules: None,
         brand_settings: None,
+        pinned_context_files: Vec::new(),
+        context_include: Vec::new(),
+        context_exclude: Vec::new(),
+        tool_filter: None,
+        require_tool_approval: false,
     };
     SettingsService::save_project_settings(&project_path, &v1).unwrap();
 
@@ -572,6 +620,11 @@ fn test_settings_overwrite() {
         encryption_enabled: Some(true),
         personalization_rules: None,
         brand_settings: None,
+        pinned_context_files: Vec::new(),
+        context_include: Vec::new(),
+        context_exclude: Vec::new(),
+        tool_filter: None,
+        require_tool_approval: false,
     };
     SettingsService::save_project_settings(&project_path, &v2).unwrap();
 
@@ -657,6 +710,10 @@ fn test_workflow_execution_serialization() {
                 "AI response received".to_string(),
             ],
             next_step_id: None,
+            condition_met: None,
+            duration_ms: None,
+            output: None,
+            usage: StepUsage::default(),
         },
     );
 
@@ -667,6 +724,8 @@ fn test_workflow_execution_serialization() {
         status: ExecutionStatus::Completed,
         error: None,
         step_results,
+        step_outputs: HashMap::new(),
+        total_usage: StepUsage::default(),
     };
 
     // Serialize → deserialize roundtrip
@@ -698,6 +757,10 @@ fn test_step_result_with_error() {
             "Calling AI Service".to_string(),
         ],
         next_step_id: None,
+        condition_met: None,
+        duration_ms: None,
+        output: None,
+        usage: StepUsage::default(),
     };
 
     assert_eq!(result.status, StepStatus::Failed);
@@ -125,7 +125,8 @@ const invoke = async <T>(cmd: string, args?: any): Promise<T> => {
       is_first_install: true,
       claude_code_detected: true,
       ollama_detected: true,
-      gemini_detected: false
+      gemini_detected: false,
+      codex_detected: false
     } as any;
     if (cmd === 'get_openai_auth_status') return { connected: false, method: 'openai-oauth', details: 'Not authenticated' } as any;
     if (cmd === 'get_google_auth_status') return { connected: false, method: 'google-antigravity-login', details: 'Not authenticated' } as any;
@@ -219,6 +220,7 @@ export interface GlobalSettings {
   geminiCli: GeminiCliConfig;
   openAiCli: OpenAiCliConfig;
   liteLlm: LiteLlmConfig;
+  openaiCompatible?: OpenAiCompatibleConfig;
   customClis: CustomCliConfig[];
   mcpServers: McpServerConfig[];
   artifactTemplates?: Record<string, string>;
@@ -226,14 +228,95 @@ export interface GlobalSettings {
   autoEscalateThreshold: number;
   budgetWarningThreshold: number;
   selectedProviders: string[];
+  loggingPrivacyLevel?: LoggingPrivacyLevel;
+  autoBackupBeforeRiskyOps?: boolean;
+  projectBackupsToKeep?: number;
+  /** Tool round trips allowed per agent turn (default 10) */
+  maxToolIterations?: number;
+  /** Estimated tokens of project files and history added to the system prompt (default 16000) */
+  maxContextTokens?: number;
+  /** Extra folders under the app data directory to create and keep (relative paths) */
+  extraAppDataDirs?: string[];
+  /** When the master encryption key was last rotated */
+  encryptionKeyRotatedAt?: string;
+  /** Write file changes from model output immediately instead of proposing them for review */
+  autoApplyFileChanges?: boolean;
 }
 
-export type ProviderType = 'ollama' | 'claudeCode' | 'hostedApi' | 'geminiCli' | 'openAiCli' | 'liteLlm' | 'autoRouter' | string;
+/** Per-request generation settings; unset values use the provider defaults (4096 max tokens) */
+export interface ChatRequestOptions {
+  maxTokens?: number;
+  temperature?: number;
+  topP?: number;
+  stopSequences?: string[];
+  /** Force prompt caching of the system prompt on or off */
+  promptCaching?: boolean;
+}
+
+export interface TokenUsage {
+  input: number;
+  output: number;
+}
+
+export interface ChatUsageEvent {
+  projectId: string | null;
+  usage: TokenUsage;
+}
+
+/** A tool call waiting for the user; it is denied if not answered within `timeoutSecs` */
+export interface ToolApprovalRequest {
+  requestId: string;
+  projectId: string | null;
+  toolName: string;
+  arguments: any;
+  timeoutSecs: number;
+}
+
+/** A file change from model output waiting for the user to apply or discard it */
+export interface ProposedFileChange {
+  id: string;
+  path: string;
+  content: string;
+  /** Applying it would overwrite an existing file */
+  overwrites: boolean;
+  created: string;
+}
+
+/** Payload of the `files-proposed` event */
+export interface FilesProposedEvent {
+  projectId: string;
+  changes: ProposedFileChange[];
+}
+
+/** Final event of a streaming discovery; `error` is set when it stopped early */
+export interface DiscoveryDone {
+  count: number | null;
+  error: string | null;
+}
+
+export type LoggingPrivacyLevel = 'full' | 'metadataOnly' | 'off';
+
+export type ProviderType = 'ollama' | 'claudeCode' | 'hostedApi' | 'geminiCli' | 'openAiCli' | 'liteLlm' | 'openAiCompatible' | 'autoRouter' | 'mock' | string;
 
 export interface OllamaConfig {
   model: string;
   apiUrl: string;
   detectedPath?: string;
+  autoPullModels?: boolean;
+}
+
+export interface OllamaRunningModel {
+  name: string;
+  sizeBytes: number;
+  sizeVramBytes: number;
+  expiresAt?: string;
+}
+
+/** Payload of the `ollama-pull-progress` event */
+export interface OllamaPullProgress {
+  model: string;
+  status: string;
+  percent?: number;
 }
 
 export interface ClaudeConfig {
@@ -245,6 +328,24 @@ export interface HostedConfig {
   provider: string;
   model: string;
   apiKeySecretId: string;
+  /** Sent comma-joined in the `anthropic-beta` header */
+  betaFlags?: string[];
+  /** Extra request headers, passed through verbatim */
+  extraHeaders?: Record<string, string>;
+  /** How requests are spread across the stored Anthropic keys */
+  keyRotation?: 'onRateLimit' | 'roundRobin';
+  /** Retries after a 429 or 529 response (default 3) */
+  maxRetries?: number;
+  /** First backoff delay in ms, doubled per retry (default 1000) */
+  retryBaseDelayMs?: number;
+  /** System prompts of at least this many characters are sent with prompt caching */
+  promptCacheMinChars?: number;
+}
+
+export interface ApiKeySummary {
+  id: string;
+  redacted: string;
+  rateLimited: boolean;
 }
 
 export interface GeminiCliConfig {
@@ -290,6 +391,13 @@ export interface LiteLlmConfig {
   shadowMode: boolean;
 }
 
+/** Any server exposing OpenAI's `/v1/chat/completions` (vLLM, LM Studio, llama.cpp) */
+export interface OpenAiCompatibleConfig {
+  baseUrl: string;
+  model: string;
+  apiKeySecretId: string;
+}
+
 export interface CustomCliConfig {
   id: string;
   name: string;
@@ -316,6 +424,30 @@ export interface McpServerConfig {
   source?: string;
   categories?: string[];
   iconUrl?: string;
+  /** Seconds before a tool call is abandoned; 60 when unset */
+  toolTimeoutSecs?: number;
+  /** Unprefixed tool names offered to the model (empty offers none); a trailing `*` matches by prefix */
+  allowedTools?: string[];
+  /** Tools never offered or run, even when allowed */
+  blockedTools?: string[];
+}
+
+/** Tool names are `server__tool`; `server__*` matches all of a server's tools */
+export interface McpToolFilter {
+  allowedTools?: string[];
+  deniedTools?: string[];
+}
+
+export interface McpTool {
+  name: string;
+  description: string;
+  input_schema: Record<string, unknown>;
+}
+
+export interface McpServerTestResult {
+  ok: boolean;
+  tools: string[];
+  error?: string;
 }
 
 export interface ChatResponse {
@@ -336,21 +468,105 @@ export interface ProjectSettings {
   preferred_skills?: string[];
   personalization_rules?: string;
   brand_settings?: string;
+  pinned_context_files?: string[];
+  context_include?: string[];
+  context_exclude?: string[];
+  tool_filter?: McpToolFilter;
+  require_tool_approval?: boolean;
+}
+
+export interface ProjectCollaborator {
+  name: string;
+  email?: string;
+  role?: string;
+}
+
+export interface ProjectDetails {
+  status?: string;
+  collaborators?: ProjectCollaborator[];
+  links?: Record<string, string>;
+  /** YYYY-MM-DD */
+  dueDate?: string;
 }
 
-export interface Project {
+export interface Project extends ProjectDetails {
   id: string;
   name: string;
   goal: string;
   skills: string[];
+  tags: string[];
   created_at: string;
 }
 
+export interface ProjectIssue {
+  severity: 'warning' | 'error';
+  message: string;
+}
+
+export interface ProjectValidationReport {
+  projectId: string;
+  name?: string;
+  path: string;
+  /** False when any issue is an error */
+  valid: boolean;
+  issues: ProjectIssue[];
+}
+
+export type FileSort = 'name' | 'modified';
+
+export interface ProjectFileInfo {
+  path: string;
+  size: number;
+  modified?: string;
+}
+
+export interface ProjectFilePage {
+  files: ProjectFileInfo[];
+  /** Files in the whole listing, not just this page */
+  total: number;
+  offset: number;
+}
+
+export interface CompactionReport {
+  projectId: string;
+  chatsArchived: number;
+  workflowRunsPurged: number;
+  trashItemsPurged: number;
+  bytesReclaimed: number;
+}
+
+export type HealthStatus = 'unknown' | 'ok' | 'degraded' | 'down';
+
+export interface ComponentHealth {
+  name: string;
+  status: HealthStatus;
+  message?: string | null;
+}
+
+export interface HealthReport {
+  status: HealthStatus;
+  components: ComponentHealth[];
+  checkedAt: string;
+}
+
 export interface ChatMessage {
   role: string;
   content: string;
 }
 
+export interface ChatSearchSnippet {
+  /** Position of the message in the conversation (0-based) */
+  message_index: number;
+  role: string;
+  text: string;
+}
+
+export interface ChatSearchResult {
+  file_name: string;
+  match_count: number;
+  snippets: ChatSearchSnippet[];
+}
+
 export interface Secrets {
   claude_api_key?: string;
   gemini_api_key?: string;
@@ -358,6 +574,12 @@ export interface Secrets {
   custom_api_keys?: Record<string, string>;
 }
 
+/** Payload of the `secrets-changed` event emitted after `saveSecrets` */
+export interface SecretsChangedEvent {
+  changed_ids: string[];
+  generation: number;
+}
+
 export interface SkillParameter {
   name: string;
   type: 'string' | 'number' | 'boolean' | 'array';
@@ -376,6 +598,10 @@ export interface Skill {
   id: string;
   name: string;
   description: string;
+  /** Lowercase category, e.g. "research"; "general" when the skill doesn't declare one */
+  category: string;
+  /** Disabled skills are still listed but can't be used in chats */
+  enabled: boolean;
   prompt_template: string;
   capabilities: string[];
   parameters: SkillParameter[];
@@ -383,6 +609,36 @@ export interface Skill {
   version: string;
   created: string;
   updated: string;
+  tool_filter?: McpToolFilter;
+  /** MCP tools the skill depends on: `server__tool`, a bare tool name or `server__*` */
+  required_tools?: string[];
+  block_without_required_tools?: boolean;
+}
+
+/** A skill prompt rendered with sample params; unresolved placeholders stay in `rendered` */
+export interface SkillPromptPreview {
+  rendered: string;
+  unresolved: string[];
+  /** Parameter problems that would stop the skill from running in a chat */
+  problems: string[];
+}
+
+/** Payload of the `skill-tools-missing` event */
+export interface SkillToolsMissingEvent {
+  skillId: string;
+  missingTools: string[];
+}
+
+export interface MarketplaceSkill {
+  id: string;
+  name: string;
+  category: string;
+  description: string;
+  author?: string;
+  version?: string;
+  downloadUrl?: string;
+  source: 'bundled' | 'remote';
+  installed: boolean;
 }
 
 export interface WorkflowSchedule {
@@ -405,12 +661,24 @@ export interface Workflow {
   status?: string;
   last_run?: string;
   schedule?: WorkflowSchedule;
+  tool_filter?: McpToolFilter;
+  schema_version?: number;
+}
+
+export interface WorkflowLoadError {
+  file_name: string;
+  error: string;
+}
+
+export interface WorkflowLoadReport {
+  workflows: Workflow[];
+  errors: WorkflowLoadError[];
 }
 
 export interface WorkflowStep {
   id: string;
   name: string;
-  step_type: 'input' | 'agent' | 'iteration' | 'synthesis' | 'conditional' | 'skill' | 'api_call' | 'script' | 'condition' | 'subagent';
+  step_type: 'input' | 'agent' | 'iteration' | 'synthesis' | 'conditional' | 'loop' | 'skill' | 'api_call' | 'script' | 'condition' | 'subagent';
   config: StepConfig;
   depends_on: string[];
 }
@@ -431,6 +699,11 @@ export interface StepConfig {
   condition?: string;
   then_step?: string;
   else_step?: string;
+  /** Loop items: a JSON array or a `{{steps.<id>.output}}` reference */
+  items?: unknown[] | string;
+  /** Step run once per loop item, with `{{item}}` and `{{index}}` substituted */
+  child?: WorkflowStep;
+  max_iterations?: number;
   artifact_type?: ArtifactType;
   artifact_title?: string;
   context?: string;
@@ -440,14 +713,26 @@ export interface WorkflowExecution {
   workflow_id: string;
   started: string;
   completed?: string;
-  status: 'Running' | 'Completed' | 'Failed' | 'PartialSuccess';
+  status: 'Running' | 'Completed' | 'Failed' | 'PartialSuccess' | 'Cancelled';
   error?: string;
   step_results: Record<string, StepResult>;
+  /** Output of each completed step, as referenced by `{{steps.<id>.output}}` */
+  step_outputs?: Record<string, any>;
+  total_usage: StepUsage;
+}
+
+export interface StepUsage {
+  tokens_in: number;
+  tokens_out: number;
+  tokens_cache_read: number;
+  tokens_cache_write: number;
+  cost_usd: number;
+  ai_calls: number;
 }
 
 export interface StepResult {
   step_id: string;
-  status: 'Pending' | 'Running' | 'Completed' | 'Failed' | 'Skipped';
+  status: 'Pending' | 'Running' | 'Completed' | 'Failed' | 'Skipped' | 'Cancelled';
   started: string;
   completed?: string;
   output_files: string[];
@@ -455,9 +740,15 @@ export interface StepResult {
   detailed_error?: string;
   logs: string[];
   next_step_id?: string;
+  /** Outcome of a conditional step */
+  condition_met?: boolean;
+  duration_ms?: number;
+  /** Value output, such as the array a loop step collects */
+  output?: unknown;
+  usage: StepUsage;
 }
 
-export type ExecutionStatus = 'Running' | 'Completed' | 'Failed' | 'PartialSuccess';
+export type ExecutionStatus = 'Running' | 'Completed' | 'Failed' | 'PartialSuccess' | 'Cancelled';
 
 export interface WorkflowRunRecord {
   id: string;
@@ -467,8 +758,12 @@ export interface WorkflowRunRecord {
   started: string;
   completed?: string;
   status: ExecutionStatus;
+  error?: string;
   trigger: string;
   step_results: Record<string, StepResult>;
+  step_outputs?: Record<string, unknown>;
+  duration_ms?: number;
+  total_usage: StepUsage;
 }
 
 export interface WorkflowProgress {
@@ -476,6 +771,26 @@ export interface WorkflowProgress {
   step_name: string;
   status: string;
   progress_percent: number;
+  /** Why a step was skipped or failed */
+  message?: string;
+}
+
+export type WorkflowStepEventKind = 'started' | 'completed' | 'failed';
+
+export interface WorkflowStepEvent {
+  workflow_id: string;
+  step_id: string;
+  step_name: string;
+  /** 0 for `started` */
+  elapsed_ms: number;
+  error?: string;
+}
+
+export interface WorkflowCompletedEvent {
+  workflow_id: string;
+  status: ExecutionStatus;
+  duration_ms: number;
+  error?: string;
 }
 
 // Artifact types (PM ontology)
@@ -535,6 +850,31 @@ export interface InstallationConfig {
   claude_code_detected: boolean;
   ollama_detected: boolean;
   gemini_detected: boolean;
+  codex_detected: boolean;
+  node_toolchain?: NodeToolchainInfo;
+}
+
+/** Node.js commands needed to start MCP servers; versions are null when not found */
+export interface NodeToolchainInfo {
+  node_version: string | null;
+  npm_version: string | null;
+  npx_version: string | null;
+  missing: string[];
+}
+
+export interface OnboardingStep {
+  id: 'api_key' | 'ollama_running' | 'provider_ready' | 'mcp_servers';
+  title: string;
+  action: string;
+  complete: boolean;
+  required: boolean;
+}
+
+export interface OnboardingStatus {
+  ready: boolean;
+  activeProvider: ProviderType;
+  steps: OnboardingStep[];
+  nextSteps: OnboardingStep[];
 }
 
 export interface ClaudeCodeInfo {
@@ -543,6 +883,7 @@ export interface ClaudeCodeInfo {
   path?: string;
   in_path: boolean;
   authenticated?: boolean;
+  error?: string;
 }
 
 export interface OllamaInfo {
@@ -551,6 +892,7 @@ export interface OllamaInfo {
   path?: string;
   running: boolean;
   in_path: boolean;
+  models?: string[];
 }
 
 export interface GeminiInfo {
@@ -561,6 +903,17 @@ export interface GeminiInfo {
   authenticated?: boolean;
 }
 
+export interface CliToolInfo {
+  name: string;
+  installed: boolean;
+  version?: string;
+  path?: string;
+  in_path: boolean;
+  running?: boolean;
+  authenticated?: boolean;
+  error?: string;
+}
+
 export interface OpenAiCliInfo {
   installed: boolean;
   version?: string;
@@ -592,7 +945,66 @@ export interface SearchMatch {
   match_end: number;
 }
 
+export type GlobalSearchKind = 'project_file' | 'skill' | 'chat';
+
+export interface GlobalSearchHit {
+  kind: GlobalSearchKind;
+  project_id?: string;
+  skill_id?: string;
+  file_name: string;
+  /** 1-based line in project files and skills */
+  line_number?: number;
+  /** 0-based message position in chats */
+  message_index?: number;
+  snippet: string;
+}
+
+export interface GlobalSearchResults {
+  hits: GlobalSearchHit[];
+  /** More hits were found than the limit allowed */
+  truncated: boolean;
+}
+
+export interface ReplaceOptions {
+  caseSensitive?: boolean;
+  wholeWord?: boolean;
+  /** `find` is a regular expression; `replace` may use `$1`-style groups */
+  useRegex?: boolean;
+  /** Also search `.project.md`, which is skipped by default */
+  includeProjectFile?: boolean;
+}
+
+export interface ReplaceSample {
+  lineNumber: number;
+  before: string;
+  after: string;
+}
+
+export interface FileReplacePreview {
+  fileName: string;
+  matchCount: number;
+  samples: ReplaceSample[];
+}
+
+export interface ReplacePreview {
+  files: FileReplacePreview[];
+  totalMatches: number;
+}
+
+export interface ReplaceResult {
+  filesChanged: string[];
+  replacements: number;
+  /** Pass to `undoSearchAndReplace` to restore the previous contents */
+  snapshotId?: string;
+}
+
 // Update types
+export interface ChangelogEntry {
+  version: string;
+  date?: string;
+  notes: string;
+}
+
 export interface UpdateResult {
   success: boolean;
   backup_created: boolean;
@@ -600,6 +1012,33 @@ export interface UpdateResult {
   files_updated: string[];
   structure_verified: boolean;
   message: string;
+  /** Version recorded before this update; set only when it changed */
+  previous_version?: string;
+  /** Release notes since previous_version, newest first */
+  changelog: ChangelogEntry[];
+}
+
+export interface BackupInfo {
+  path: string;
+  /** A single zip archive rather than a directory tree */
+  compressed: boolean;
+  /** Archive size when compressed, otherwise the total size of its files */
+  size_bytes: number;
+}
+
+export interface BackupFile {
+  /** Path relative to the app data directory, `/`-separated */
+  path: string;
+  size_bytes: number;
+}
+
+export interface BackupVerification {
+  /** True when nothing is missing or corrupted */
+  intact: boolean;
+  /** Number of files whose contents were checked */
+  checked: number;
+  missing: string[];
+  corrupted: string[];
 }
 
 // Configuration types
@@ -645,6 +1084,11 @@ export const tauriApi = {
     return await invoke('get_all_projects');
   },
 
+  /** Loads projects one by one, emitting `project-discovered`; resolves with the count */
+  async discoverProjectsStream(): Promise<number> {
+    return await invoke('discover_projects_stream');
+  },
+
   async getProject(projectId: string): Promise<Project> {
     return await invoke('get_project', { projectId });
   },
@@ -658,18 +1102,78 @@ export const tauriApi = {
     return await invoke('get_project_files', { projectId });
   },
 
-  async deleteProject(projectId: string): Promise<void> {
+  async getProjectFilesPage(projectId: string, offset?: number, limit?: number, sort?: FileSort): Promise<ProjectFilePage> {
+    return await invoke('get_project_files_page', { projectId, offset, limit, sort });
+  },
+
+  /** Copies settings, workflows and markdown files (not chats) into a new project */
+  async duplicateProject(projectId: string, newName: string): Promise<Project> {
+    return await invoke('duplicate_project', { projectId, newName });
+  },
+
+  /** Backs the project up, deletes it and returns the backup path */
+  async deleteProject(projectId: string): Promise<string> {
     return await invoke('delete_project', { projectId });
   },
 
-  async renameProject(projectId: string, newName: string): Promise<void> {
-    return await invoke('rename_project', { projectId, newName });
+  /** With `renameDirectory` the folder and id follow the new name; returns the renamed project */
+  async renameProject(projectId: string, newName: string, renameDirectory?: boolean): Promise<Project> {
+    return await invoke('rename_project', { projectId, newName, renameDirectory });
+  },
+
+  async validateAllProjects(): Promise<ProjectValidationReport[]> {
+    return await invoke('validate_all_projects');
+  },
+
+  async compactProject(projectId: string, keepRecentChats?: number, retentionDays?: number): Promise<CompactionReport> {
+    return await invoke('compact_project', { projectId, keepRecentChats, retentionDays });
+  },
+
+  /** Returns the path of the written record, under the project's `.metadata/exports/` */
+  async exportResearchRecord(projectId: string, format: 'json' | 'markdown'): Promise<string> {
+    return await invoke('export_research_record', { projectId, format });
+  },
+
+  /** Returns the snapshot directory under `backups/projects/{projectId}/` */
+  async backupProject(projectId: string): Promise<string> {
+    return await invoke('backup_project', { projectId });
+  },
+
+  /** Zips the project (no secrets) into `.metadata/exports/` and returns the zip path */
+  async exportProject(projectId: string): Promise<string> {
+    return await invoke('export_project', { projectId });
+  },
+
+  /** Imports a zip from `exportProject`; a taken id gets a `-2`, `-3`, ... suffix */
+  async importProject(zipPath: string): Promise<Project> {
+    return await invoke('import_project', { zipPath });
+  },
+
+  /** Replaces the tags; blanks and case-insensitive duplicates are dropped */
+  async updateProjectTags(projectId: string, tags: string[]): Promise<Project> {
+    return await invoke('update_project_tags', { projectId, tags });
+  },
+
+  async getProjectsByTag(tag: string): Promise<Project[]> {
+    return await invoke('get_projects_by_tag', { tag });
+  },
+
+  async updateProjectDetails(projectId: string, details: ProjectDetails): Promise<Project> {
+    return await invoke('update_project_details', { projectId, details });
   },
 
   async getProjectCost(projectId: string): Promise<number> {
     return await invoke('get_project_cost', { projectId });
   },
 
+  async pinContextFile(projectId: string, filePath: string): Promise<string[]> {
+    return await invoke('pin_context_file', { projectId, filePath });
+  },
+
+  async unpinContextFile(projectId: string, filePath: string): Promise<string[]> {
+    return await invoke('unpin_context_file', { projectId, filePath });
+  },
+
   async getUsageStatistics(): Promise<UsageStatistics> {
     return await invoke('get_usage_statistics');
   },
@@ -703,23 +1207,62 @@ export const tauriApi = {
     return await invoke('rename_markdown_file', { projectId, oldName, newName });
   },
 
+  /** Markdown files in the project that link to this one, via [[Note]] or relative links */
+  async getBacklinks(projectId: string, fileName: string): Promise<string[]> {
+    return await invoke('get_backlinks', { projectId, fileName });
+  },
+
   async searchInFiles(projectId: string, searchText: string, caseSensitive: boolean, useRegex: boolean): Promise<SearchMatch[]> {
     return await invoke('search_in_files', { projectId, searchText, caseSensitive, useRegex });
   },
 
+  async globalSearch(query: string, kinds?: GlobalSearchKind[], limit?: number): Promise<GlobalSearchResults> {
+    return await invoke('global_search', { query, kinds, limit });
+  },
+
   async replaceInFiles(projectId: string, searchText: string, replaceText: string, caseSensitive: boolean, fileNames: string[]): Promise<number> {
     return await invoke('replace_in_files', { projectId, searchText, replaceText, caseSensitive, fileNames });
   },
 
+  async searchAndReplaceInProject(projectId: string, find: string, replace: string, options?: ReplaceOptions): Promise<ReplacePreview> {
+    return await invoke('search_and_replace_in_project', { projectId, find, replace, options });
+  },
+
+  async applySearchAndReplace(projectId: string, find: string, replace: string, options?: ReplaceOptions, fileNames?: string[]): Promise<ReplaceResult> {
+    return await invoke('apply_search_and_replace', { projectId, find, replace, options, fileNames });
+  },
+
+  async undoSearchAndReplace(projectId: string, snapshotId: string): Promise<string[]> {
+    return await invoke('undo_search_and_replace', { projectId, snapshotId });
+  },
+
   // Chat
-  async sendMessage(messages: ChatMessage[], projectId?: string, skillId?: string, skillParams?: Record<string, string>): Promise<ChatResponse> {
-    return await invoke('send_message', { messages, projectId, skillId, skillParams });
+  async sendMessage(messages: ChatMessage[], projectId?: string, skillId?: string, skillParams?: Record<string, string>, options?: ChatRequestOptions): Promise<ChatResponse> {
+    return await invoke('send_message', { messages, projectId, skillId, skillParams, options });
+  },
+
+  /** Scratch chat outside any project; nothing is saved */
+  async sendEphemeralMessage(messages: ChatMessage[], systemPrompt?: string, skillId?: string): Promise<ChatResponse> {
+    return await invoke('send_ephemeral_message', { messages, systemPrompt, skillId });
+  },
+
+  async previewAssembledPrompt(projectId: string | undefined, skillId: string, skillParams?: Record<string, string>): Promise<string> {
+    return await invoke('preview_assembled_prompt', { projectId, skillId, skillParams });
   },
 
   async stopAgentExecution(): Promise<void> {
     return await invoke('stop_agent_execution');
   },
 
+  /** Stop the streaming reply for a project (or scratch chat); the partial reply is kept */
+  async cancelChat(projectId?: string): Promise<void> {
+    return await invoke('cancel_chat', { projectId });
+  },
+
+  async respondToolApproval(requestId: string, approved: boolean): Promise<void> {
+    return await invoke('respond_tool_approval', { requestId, approved });
+  },
+
   async switchProvider(providerType: ProviderType): Promise<void> {
     return await invoke('switch_provider', { providerType });
   },
@@ -732,14 +1275,73 @@ export const tauriApi = {
     return await invoke('get_chat_files', { projectId });
   },
 
+  /** Case-insensitive search across the project's chats, most matches first */
+  async searchChats(projectId: string, query: string, wholeWord?: boolean): Promise<ChatSearchResult[]> {
+    return await invoke('search_chats', { projectId, query, wholeWord });
+  },
+
+  /** Writes the chat to the project's `.metadata/exports/` and returns the file path */
+  async exportChat(projectId: string, fileName: string, format: 'html' | 'markdown' | 'json'): Promise<string> {
+    return await invoke('export_chat', { projectId, fileName, format });
+  },
+
+  /** Rewrites the chat with the edited message as its last one; returns what's left */
+  async editChatMessage(projectId: string, chatFile: string, messageIndex: number, content: string): Promise<ChatMessage[]> {
+    return await invoke('edit_chat_message', { projectId, chatFile, messageIndex, content });
+  },
+
+  /** Re-runs the agent from a user message; the response is saved as a new chat */
+  async regenerateChatMessage(
+    projectId: string,
+    chatFile: string,
+    messageIndex: number,
+    content?: string,
+    skillId?: string,
+    skillParams?: Record<string, string>
+  ): Promise<ChatResponse> {
+    return await invoke('regenerate_chat_message', { projectId, chatFile, messageIndex, content, skillId, skillParams });
+  },
+
+  /** Copies the chat up to and including `upToMessageIndex` into a new chat; returns its file name */
+  async forkChat(projectId: string, fileName: string, upToMessageIndex: number): Promise<string> {
+    return await invoke('fork_chat', { projectId, fileName, upToMessageIndex });
+  },
+
+  /** Returns the new file name, with `.md` appended when missing */
+  async renameChat(projectId: string, oldName: string, newName: string): Promise<string> {
+    return await invoke('rename_chat', { projectId, oldName, newName });
+  },
+
+  async deleteChat(projectId: string, fileName: string): Promise<void> {
+    return await invoke('delete_chat', { projectId, fileName });
+  },
+
   async saveChat(projectId: string, messages: ChatMessage[], model: string): Promise<string> {
     return await invoke('save_chat', { projectId, messages, model });
   },
 
+  /** File changes held for review while `autoApplyFileChanges` is off */
+  async getProposedChanges(projectId: string): Promise<ProposedFileChange[]> {
+    return await invoke('get_proposed_changes', { projectId });
+  },
+
+  /** Backs up the project, writes the selected changes and returns their paths */
+  async applyProposedChanges(projectId: string, ids: string[]): Promise<string[]> {
+    return await invoke('apply_proposed_changes', { projectId, ids });
+  },
+
+  async discardProposedChanges(projectId: string, ids: string[]): Promise<void> {
+    return await invoke('discard_proposed_changes', { projectId, ids });
+  },
+
   async getOllamaModels(): Promise<string[]> {
     return await invoke('get_ollama_models');
   },
 
+  async getOllamaRunningModels(): Promise<OllamaRunningModel[]> {
+    return await invoke('get_ollama_running_models');
+  },
+
   // Secrets
   async saveSecret(key: string, value: string): Promise<void> {
     // Construct a Secrets object with just the key we want to update
@@ -769,6 +1371,14 @@ export const tauriApi = {
     return await invoke('save_secrets', { secrets });
   },
 
+  async listApiKeys(): Promise<ApiKeySummary[]> {
+    return await invoke('list_api_keys');
+  },
+
+  async addApiKey(apiKey: string): Promise<string> {
+    return await invoke('add_api_key', { apiKey });
+  },
+
   async hasClaudeApiKey(): Promise<boolean> {
     return await invoke('has_claude_api_key');
   },
@@ -785,6 +1395,21 @@ export const tauriApi = {
     return await invoke('list_saved_secret_ids');
   },
 
+  /** Re-encrypt all secrets under a new master key; resolves to the rotation time */
+  async rotateEncryptionKey(): Promise<string> {
+    return await invoke('rotate_encryption_key');
+  },
+
+  /** Portable, passphrase-encrypted copy of every stored secret */
+  async exportSecrets(passphrase: string): Promise<string> {
+    return await invoke('export_secrets', { passphrase });
+  },
+
+  /** Merge an `exportSecrets` blob into the local store; returns the imported ids */
+  async importSecrets(blob: string, passphrase: string): Promise<string[]> {
+    return await invoke('import_secrets', { blob, passphrase });
+  },
+
   // Event listeners
   async onProjectAdded(callback: (project: Project) => void): Promise<() => void> {
     return await listen('project-added', (event) => {
@@ -804,11 +1429,50 @@ export const tauriApi = {
     });
   },
 
+  /** A skill file was added, edited or removed in the skills directory (payload: skill id) */
+  async onSkillsChanged(callback: (skillId: string) => void): Promise<() => void> {
+    const unlisteners = await Promise.all(
+      ['skill-added', 'skill-changed', 'skill-removed'].map(name =>
+        listen(name, (event) => callback(event.payload as string))
+      )
+    );
+    return () => unlisteners.forEach(unlisten => unlisten());
+  },
+
+  async onProjectDiscovered(callback: (project: Project) => void): Promise<() => void> {
+    return await listen('project-discovered', (event) => {
+      callback(event.payload as Project);
+    });
+  },
+
+  async onProjectDiscoveryDone(callback: (result: DiscoveryDone) => void): Promise<() => void> {
+    return await listen('project-discovery-done', (event) => {
+      callback(event.payload as DiscoveryDone);
+    });
+  },
+
   // Skills
   async getAllSkills(): Promise<Skill[]> {
     return await invoke('get_all_skills');
   },
 
+  /** Parses skills one by one, emitting `skill-discovered`; resolves with the count */
+  async discoverSkillsStream(): Promise<number> {
+    return await invoke('discover_skills_stream');
+  },
+
+  async onSkillDiscovered(callback: (skill: Skill) => void): Promise<() => void> {
+    return await listen('skill-discovered', (event) => {
+      callback(event.payload as Skill);
+    });
+  },
+
+  async onSkillDiscoveryDone(callback: (result: DiscoveryDone) => void): Promise<() => void> {
+    return await listen('skill-discovery-done', (event) => {
+      callback(event.payload as DiscoveryDone);
+    });
+  },
+
   async getSkill(skillId: string): Promise<Skill> {
     return await invoke('get_skill', { skillId });
   },
@@ -818,6 +1482,7 @@ export const tauriApi = {
       name,
       description,
       promptTemplate: template,
+      category,
       capabilities: [category]
     });
   },
@@ -826,6 +1491,24 @@ export const tauriApi = {
     return await invoke('update_skill', { skill });
   },
 
+  async setSkillEnabled(skillId: string, enabled: boolean): Promise<Skill> {
+    return await invoke('set_skill_enabled', { skillId, enabled });
+  },
+
+  async previewSkillPrompt(skillId: string, params: Record<string, string>): Promise<SkillPromptPreview> {
+    return await invoke('preview_skill_prompt', { skillId, params });
+  },
+
+  /** The skill as a single markdown file, metadata in frontmatter */
+  async exportSkill(skillId: string): Promise<string> {
+    return await invoke('export_skill', { skillId });
+  },
+
+  /** Imports markdown from `exportSkill`; a taken id gets a `-1`, `-2`, ... suffix */
+  async importSkillContent(content: string): Promise<Skill> {
+    return await invoke('import_skill_content', { content });
+  },
+
   async importSkill(npxCommand: string): Promise<Skill> {
     return await invoke('import_skill', { skillCommand: npxCommand });
   },
@@ -834,11 +1517,24 @@ export const tauriApi = {
     return await invoke('delete_skill', { skillId });
   },
 
+  async fetchSkillMarketplace(query?: string): Promise<MarketplaceSkill[]> {
+    return await invoke('fetch_skill_marketplace', { query });
+  },
+
+  async installMarketplaceSkill(id: string): Promise<Skill> {
+    return await invoke('install_marketplace_skill', { id });
+  },
+
   // Workflows
   async getProjectWorkflows(projectId: string): Promise<Workflow[]> {
     return await invoke('get_project_workflows', { projectId });
   },
 
+  /** Like getProjectWorkflows, but also lists workflow files that failed to load */
+  async scanProjectWorkflows(projectId: string): Promise<WorkflowLoadReport> {
+    return await invoke('scan_project_workflows', { projectId });
+  },
+
   async getWorkflow(projectId: string, workflowId: string): Promise<Workflow> {
     return await invoke('get_workflow', { projectId, workflowId });
   },
@@ -859,6 +1555,11 @@ export const tauriApi = {
     return await invoke('execute_workflow', { projectId, workflowId, parameters });
   },
 
+  /** Stop a running workflow; finished steps keep their outputs */
+  async cancelWorkflow(projectId: string, workflowId: string): Promise<void> {
+    return await invoke('cancel_workflow', { projectId, workflowId });
+  },
+
   async setWorkflowSchedule(projectId: string, workflowId: string, schedule: WorkflowSchedule): Promise<Workflow> {
     return await invoke('set_workflow_schedule', { projectId, workflowId, schedule });
   },
@@ -871,8 +1572,9 @@ export const tauriApi = {
     return await invoke('validate_workflow', { workflow });
   },
 
-  async getWorkflowHistory(projectId: string, workflowId: string): Promise<WorkflowRunRecord[]> {
-    return await invoke('get_workflow_history', { projectId, workflowId });
+  /** Saved runs of a workflow, newest first */
+  async getWorkflowRuns(projectId: string, workflowId: string): Promise<WorkflowRunRecord[]> {
+    return await invoke('get_workflow_runs', { projectId, workflowId });
   },
 
   async get_active_runs(): Promise<Record<string, WorkflowExecution>> {
@@ -940,6 +1642,14 @@ export const tauriApi = {
     return await invoke('get_gemini_install_instructions');
   },
 
+  async getNodeInstallInstructions(): Promise<string> {
+    return await invoke('get_node_install_instructions');
+  },
+
+  async getCodexInstallInstructions(): Promise<string> {
+    return await invoke('get_codex_install_instructions');
+  },
+
   async runInstallation(
     appDataPath?: string,
     projectsPath?: string,
@@ -972,6 +1682,15 @@ export const tauriApi = {
     return await invoke('redetect_dependencies');
   },
 
+  async redetectDependenciesForce(): Promise<InstallationConfig> {
+    return await invoke('redetect_dependencies_force');
+  },
+
+  /** Clears all detection caches and re-runs every detector; custom CLIs are keyed `custom-<id>` */
+  async redetectAllDependencies(): Promise<Record<string, CliToolInfo>> {
+    return await invoke('redetect_all_dependencies');
+  },
+
   async backupInstallation(): Promise<string> {
     return await invoke('backup_installation');
   },
@@ -984,6 +1703,10 @@ export const tauriApi = {
     return await invoke('is_first_install');
   },
 
+  async getOnboardingStatus(): Promise<OnboardingStatus> {
+    return await invoke('onboarding_status');
+  },
+
   // Update operations
   async runUpdateProcess(): Promise<UpdateResult> {
     return await invoke('run_update_process');
@@ -997,15 +1720,43 @@ export const tauriApi = {
     return await invoke('backup_user_data');
   },
 
+  /** Back up user data into a single `update_backup_{timestamp}.zip` */
+  async backupUserDataCompressed(): Promise<string> {
+    return await invoke('backup_user_data_compressed');
+  },
+
+  /** Back up only files changed since the newest backup, referencing the rest */
+  async backupUserDataIncremental(): Promise<string> {
+    return await invoke('backup_user_data_incremental');
+  },
+
   async verifyInstallationIntegrity(): Promise<boolean> {
     return await invoke('verify_installation_integrity');
   },
 
+  async healthCheck(): Promise<HealthReport> {
+    return await invoke('health_check');
+  },
+
   async restoreFromBackup(backupPath: string): Promise<void> {
     return await invoke('restore_from_backup', { backupPath });
   },
 
-  async listBackups(): Promise<string[]> {
+  /** Restore only the given paths (e.g. `projects/my-project`) from a backup */
+  async restoreItems(backupPath: string, items: string[]): Promise<string[]> {
+    return await invoke('restore_items', { backupPath, items });
+  },
+
+  async listBackupContents(backupPath: string): Promise<BackupFile[]> {
+    return await invoke('list_backup_contents', { backupPath });
+  },
+
+  /** Check a backup's files against its checksums before trusting it for a restore */
+  async verifyBackup(backupPath: string): Promise<BackupVerification> {
+    return await invoke('verify_backup', { backupPath });
+  },
+
+  async listBackups(): Promise<BackupInfo[]> {
     return await invoke('list_backups');
   },
 
@@ -1102,8 +1853,17 @@ export const tauriApi = {
     return await invoke('update_mcp_server', { config });
   },
 
-  async fetchMcpMarketplace(query?: string): Promise<McpServerConfig[]> {
-    return await invoke('fetch_mcp_marketplace', { query });
+  async listMcpServerTools(id: string, refresh?: boolean): Promise<McpTool[]> {
+    return await invoke('list_mcp_server_tools', { id, refresh });
+  },
+
+  async testMcpServer(id: string): Promise<McpServerTestResult> {
+    return await invoke('test_mcp_server', { id });
+  },
+
+  /** The unfiltered listing is cached for 6 hours; `refresh` forces a re-fetch */
+  async fetchMcpMarketplace(query?: string, refresh?: boolean): Promise<McpServerConfig[]> {
+    return await invoke('fetch_mcp_marketplace', { query, refresh });
   },
 
   async syncMcpWithClis(): Promise<string[]> {
@@ -1120,12 +1880,61 @@ export const tauriApi = {
     });
   },
 
+  /** Tokens used by each chat turn; `projectId` is null for scratch chats */
+  async onChatUsage(callback: (event: ChatUsageEvent) => void): Promise<() => void> {
+    return await listen('chat-usage', (event) => {
+      callback(event.payload as ChatUsageEvent);
+    });
+  },
+
+  /** Assistant text from agent turns as it arrives; paused while tools run */
+  async onChatToken(callback: (text: string) => void): Promise<() => void> {
+    return await listen('chat-token', (event) => {
+      callback(event.payload as string);
+    });
+  },
+
+  /** Tool calls held until the user approves them, for projects with approval enabled */
+  async onToolApprovalRequest(callback: (request: ToolApprovalRequest) => void): Promise<() => void> {
+    return await listen('tool-approval-request', (event) => {
+      callback(event.payload as ToolApprovalRequest);
+    });
+  },
+
+  /** File changes from a reply that were held for review instead of written */
+  async onFilesProposed(callback: (event: FilesProposedEvent) => void): Promise<() => void> {
+    return await listen('files-proposed', (event) => {
+      callback(event.payload as FilesProposedEvent);
+    });
+  },
+
   async onWorkflowProgress(callback: (progress: WorkflowProgress) => void): Promise<() => void> {
     return await listen('workflow-progress', (event) => {
       callback(event.payload as WorkflowProgress);
     });
   },
 
+  /** `workflow-step-started`, `-completed` and `-failed`, with the time each step took */
+  async onWorkflowStepEvent(
+    callback: (kind: WorkflowStepEventKind, event: WorkflowStepEvent) => void
+  ): Promise<() => void> {
+    const kinds: WorkflowStepEventKind[] = ['started', 'completed', 'failed'];
+    const unlisteners = await Promise.all(
+      kinds.map((kind) =>
+        listen(`workflow-step-${kind}`, (event) => {
+          callback(kind, event.payload as WorkflowStepEvent);
+        })
+      )
+    );
+    return () => unlisteners.forEach((unlisten) => unlisten());
+  },
+
+  async onWorkflowCompleted(callback: (event: WorkflowCompletedEvent) => void): Promise<() => void> {
+    return await listen('workflow-completed', (event) => {
+      callback(event.payload as WorkflowCompletedEvent);
+    });
+  },
+
   // Version & Updater
   async getAppVersion(): Promise<string> {
     try {
@@ -22,6 +22,7 @@ interface BaseInfo {
 interface DependencyInfo extends BaseInfo {
   running?: boolean;
   authenticated?: boolean;
+  error?: string;
 }
 
 function DetectingState({ name }: { name: string }) {
@@ -131,10 +132,20 @@ function AuthenticationStatus({ authenticated, onAuthenticate, name }: { authent
   );
 }
 
+function UpgradeWarning({ error }: { error?: string }) {
+  if (!error) return null;
+  return (
+    <p className="text-sm text-amber-700 dark:text-amber-300">
+      {error}
+    </p>
+  );
+}
+
 function InstalledDetails({ info, onAuthenticate, name }: { info: DependencyInfo; onAuthenticate?: (provider: string) => void; name: string }) {
   return (
     <div className="mt-1 space-y-1">
       <VersionInfo version={info.version} />
+      <UpgradeWarning error={info.error} />
       <PathInfo path={info.path} />
       <InPathBadge inPath={info.in_path} />
       <RunningStatus running={info.running} />
@@ -203,6 +214,10 @@ function normalizeDependencyInfo(
     in_path: info.in_path
   };
 
+  if ('error' in info) {
+    normalized.error = info.error;
+  }
+
   if (showRunning && 'running' in info) {
     normalized.running = info.running;
   }
@@ -242,7 +242,7 @@ export default function InstallationInstructions({
                   Checking...
                 </>
               ) : (
-                'Re-detect Dependencies'
+                'Re-scan now'
               )}
             </Button>
           </div>
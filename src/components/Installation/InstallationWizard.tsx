@@ -1,6 +1,6 @@
 import { useState, useEffect } from 'react';
 import { Button } from '@/components/ui/button';
-import { tauriApi, ClaudeCodeInfo, OllamaInfo, GeminiInfo, InstallationProgress as TauriInstallationProgress, OpenAiAuthStatus } from '@/api/tauri';
+import { tauriApi, ClaudeCodeInfo, OllamaInfo, GeminiInfo, InstallationProgress as TauriInstallationProgress, NodeToolchainInfo, OpenAiAuthStatus } from '@/api/tauri';
 import ProgressDisplay, { ProgressStep } from './ProgressDisplay';
 import DirectorySelector from './DirectorySelector';
 import DependencyStatus from './DependencyStatus';
@@ -40,6 +40,7 @@ export default function InstallationWizard({ onComplete, onSkip }: InstallationW
   const [claudeCodeInstructions, setClaudeCodeInstructions] = useState('');
   const [ollamaInstructions, setOllamaInstructions] = useState('');
   const [geminiInstructions, setGeminiInstructions] = useState('');
+  const [nodeToolchain, setNodeToolchain] = useState<NodeToolchainInfo | null>(null);
   const [isDetecting, setIsDetecting] = useState(false);
   const [isInstalling, setIsInstalling] = useState(false);
   const [installationProgress, setInstallationProgress] = useState<TauriInstallationProgress | null>(null);
@@ -50,6 +51,7 @@ export default function InstallationWizard({ onComplete, onSkip }: InstallationW
     const loadDefaultPath = async () => {
       try {
         const config = await tauriApi.checkInstallationStatus();
+        setNodeToolchain(config.node_toolchain ?? null);
         setDefaultPath(config.app_data_path);
         setSelectedPath(config.app_data_path);
         
@@ -198,6 +200,12 @@ export default function InstallationWizard({ onComplete, onSkip }: InstallationW
   };
 
   const handleRedetect = async () => {
+    try {
+      // Bypass the detection cache so a tool installed moments ago is found
+      await tauriApi.redetectDependenciesForce();
+    } catch (error) {
+      console.error('Failed to re-scan dependencies:', error);
+    }
     await detectDependencies();
   };
 
@@ -458,6 +466,17 @@ export default function InstallationWizard({ onComplete, onSkip }: InstallationW
                 </div>
               )}
 
+              {nodeToolchain && nodeToolchain.missing.length > 0 && (
+                <div className="flex items-center gap-2 text-amber-500 bg-amber-500/10 p-4 rounded-lg text-sm">
+                  <AlertCircle className="w-5 h-5 flex-shrink-0" />
+                  <span>
+                    Node.js was not found ({nodeToolchain.missing.join(', ')} missing). Most MCP servers are started with npx
+                    and won't run until you install Node.js from{' '}
+                    <a href="https://nodejs.org" target="_blank" rel="noreferrer" className="underline">nodejs.org</a>.
+                  </span>
+                </div>
+              )}
+
               {selectedProviders.length === 0 && (
                 <div className="flex items-center gap-2 text-amber-500 bg-amber-500/10 p-4 rounded-lg text-sm">
                   <AlertCircle className="w-5 h-5" />
@@ -7,7 +7,7 @@ import { Label } from '@/components/ui/label';
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { Switch } from '@/components/ui/switch';
 import { Dialog, DialogContent, DialogHeader, DialogTitle, DialogTrigger, DialogFooter, DialogDescription } from "@/components/ui/dialog";
-import { Check, Download, Search, Trash2, Globe, Server, Database, Github, FolderOpen, Plus, FileJson, Star, User, ShieldCheck, Save, RotateCcw, Loader2 } from 'lucide-react';
+import { Check, Download, Search, Trash2, Globe, Server, Database, Github, FolderOpen, Plus, FileJson, Star, User, ShieldCheck, Save, RotateCcw, Loader2, PlugZap } from 'lucide-react';
 import { tauriApi, McpServerConfig } from '@/api/tauri';
 import { useToast } from '@/hooks/use-toast';
 
@@ -27,6 +27,7 @@ export default function McpMarketplace() {
     const [isSyncDialogOpen, setIsSyncDialogOpen] = useState(false);
     const [syncing, setSyncing] = useState(false);
     const [syncTargets, setSyncTargets] = useState<string[]>([]);
+    const [testingServerId, setTestingServerId] = useState<string | null>(null);
 
     // Sync config JSON when installed servers change
     useEffect(() => {
@@ -85,10 +86,10 @@ export default function McpMarketplace() {
         }
     };
 
-    const loadMarketplace = async (query?: string) => {
+    const loadMarketplace = async (query?: string, refresh?: boolean) => {
         setLoadingMarketplace(true);
         try {
-            const servers = await tauriApi.fetchMcpMarketplace(query);
+            const servers = await tauriApi.fetchMcpMarketplace(query, refresh);
             setMarketplaceServers(servers || []);
         } catch (error) {
             console.error('Failed to load MCP marketplace:', error);
@@ -346,6 +347,35 @@ export default function McpMarketplace() {
         }
     };
 
+    const handleTest = async (server: McpServerConfig) => {
+        setTestingServerId(server.id);
+        try {
+            const result = await tauriApi.testMcpServer(server.id);
+            if (result.ok) {
+                toast({
+                    title: 'Server Responded',
+                    description: result.tools.length > 0
+                        ? `${cleanName(server.name)} exposes ${result.tools.length} tools: ${result.tools.join(', ')}`
+                        : `${cleanName(server.name)} started but exposes no tools.`,
+                });
+            } else {
+                toast({
+                    title: 'Server Test Failed',
+                    description: result.error ?? 'The server did not respond.',
+                    variant: 'destructive',
+                });
+            }
+        } catch (error) {
+            toast({
+                title: 'Error',
+                description: String(error),
+                variant: 'destructive',
+            });
+        } finally {
+            setTestingServerId(null);
+        }
+    };
+
     const handleOpenSyncDialog = async () => {
         try {
             const settings = await tauriApi.getGlobalSettings();
@@ -505,6 +535,16 @@ export default function McpMarketplace() {
                         <TabsTrigger value="raw" className="px-4 py-2.5 rounded-xl text-sm font-semibold"><FileJson className="w-4 h-4" /></TabsTrigger>
                     </TabsList>
 
+                    <Button
+                        variant="outline"
+                        size="sm"
+                        className="gap-2 rounded-xl h-10"
+                        onClick={() => loadMarketplace(searchQuery || undefined, true)}
+                        disabled={loadingMarketplace}
+                    >
+                        <RotateCcw className="w-4 h-4" /> Refresh
+                    </Button>
+
                     <Dialog open={isDialogOpen} onOpenChange={setIsDialogOpen}>
                         <DialogTrigger asChild>
                             <Button variant="outline" size="sm" className="gap-2 rounded-xl h-10 border-dashed border-slate-300 dark:border-slate-700 hover:border-primary hover:text-primary hover:bg-primary/5">
@@ -729,6 +769,20 @@ export default function McpMarketplace() {
                                             checked={server.enabled}
                                             onCheckedChange={(checked) => handleToggle(server.id, checked)}
                                         />
+                                        <Button
+                                            variant="ghost"
+                                            size="icon"
+                                            title="Test connection"
+                                            className="w-8 h-8 -mr-1 text-slate-300 hover:text-primary hover:bg-primary/10 rounded-full transition-colors"
+                                            onClick={() => handleTest(server)}
+                                            disabled={testingServerId === server.id}
+                                        >
+                                            {testingServerId === server.id ? (
+                                                <Loader2 className="w-4 h-4 animate-spin" />
+                                            ) : (
+                                                <PlugZap className="w-4 h-4" />
+                                            )}
+                                        </Button>
                                         <Button
                                             variant="ghost"
                                             size="icon"
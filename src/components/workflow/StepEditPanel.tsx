@@ -2,7 +2,7 @@ import { useState, useEffect } from 'react';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
-import { X, Save, BrainCircuit, Type, FileText, ChevronDown, Activity, Zap, Plus } from 'lucide-react';
+import { X, Save, BrainCircuit, Type, FileText, ChevronDown, Activity, Zap, Plus, Repeat } from 'lucide-react';
 import { Skill, WorkflowStep, StepConfig } from '@/api/tauri';
 import {
     DropdownMenu,
@@ -26,6 +26,7 @@ const STEP_TYPES = [
     { id: 'iteration', name: 'Iteration', icon: Activity, description: 'Run a task for multiple items' },
     { id: 'synthesis', name: 'Synthesis', icon: BrainCircuit, description: 'Combine results from previous steps' },
     { id: 'conditional', name: 'Conditional', icon: Type, description: 'Branch logic based on conditions' },
+    { id: 'loop', name: 'Loop', icon: Repeat, description: 'Run a skill once per item and collect the outputs' },
     { id: 'subagent', name: 'Sub-Agent', icon: Activity, description: 'Parallel execution of sub-agents' },
 ];
 
@@ -77,6 +78,33 @@ export default function StepEditPanel({ step, skills, onSave, onClose, onNewSkil
         }));
     };
 
+    const updateLoopChild = (patch: Partial<StepConfig>) => {
+        setConfig(prev => {
+            const child: WorkflowStep = prev.child ?? {
+                id: `${step.id}-item`,
+                name: `${name} item`,
+                step_type: 'agent',
+                config: {},
+                depends_on: [],
+            };
+            return { ...prev, child: { ...child, config: { ...child.config, ...patch } } };
+        });
+    };
+
+    const loopItemsText = typeof config.items === 'string' ? config.items : config.items ? JSON.stringify(config.items) : '';
+
+    const handleLoopItemsChange = (text: string) => {
+        let items: unknown[] | string = text;
+        if (text.trim().startsWith('[')) {
+            try {
+                items = JSON.parse(text);
+            } catch {
+                // Keep the raw text while the array is being typed
+            }
+        }
+        setConfig(prev => ({ ...prev, items: text ? items : undefined }));
+    };
+
     const handleSave = () => {
         const finalConfig = {
             ...config,
@@ -253,6 +281,93 @@ export default function StepEditPanel({ step, skills, onSave, onClose, onNewSkil
                         </div>
                     )}
 
+                    {/* Conditional Config */}
+                    {stepType === 'conditional' && (
+                        <div className="space-y-4 pt-4 border-t border-gray-100 dark:border-gray-800">
+                            <div className="space-y-2">
+                                <Label htmlFor="condition" className="text-gray-700 dark:text-gray-300">Condition</Label>
+                                <Input
+                                    id="condition"
+                                    value={config.condition || ''}
+                                    onChange={(e) => setConfig(prev => ({ ...prev, condition: e.target.value }))}
+                                    placeholder="e.g. {{steps.step_id.output}} contains critical"
+                                    className="h-8 text-xs font-mono"
+                                />
+                                <p className="text-[10px] text-gray-400">
+                                    Use contains, equals or matches (regex), or file_exists:path. Dependent steps are skipped when it is false.
+                                </p>
+                            </div>
+                            <div className="space-y-2">
+                                <Label htmlFor="else-step" className="text-gray-700 dark:text-gray-300">Else Step (optional)</Label>
+                                <Input
+                                    id="else-step"
+                                    value={config.else_step || ''}
+                                    onChange={(e) => setConfig(prev => ({ ...prev, else_step: e.target.value || undefined }))}
+                                    placeholder="step_id that runs only when false"
+                                    className="h-8 text-xs font-mono"
+                                />
+                            </div>
+                        </div>
+                    )}
+
+                    {/* Loop Config */}
+                    {stepType === 'loop' && (
+                        <div className="space-y-4 pt-4 border-t border-gray-100 dark:border-gray-800">
+                            <div className="space-y-2">
+                                <Label htmlFor="loop-items" className="text-gray-700 dark:text-gray-300">Items</Label>
+                                <Input
+                                    id="loop-items"
+                                    value={loopItemsText}
+                                    onChange={(e) => handleLoopItemsChange(e.target.value)}
+                                    placeholder='e.g. ["paper-a", "paper-b"] or {{steps.step_id.output}}'
+                                    className="h-8 text-xs font-mono"
+                                />
+                                <p className="text-[10px] text-gray-400">JSON array or reference to a previous step's list output.</p>
+                            </div>
+                            <div className="space-y-2">
+                                <Label htmlFor="loop-skill" className="text-gray-700 dark:text-gray-300">Skill per Item</Label>
+                                <select
+                                    id="loop-skill"
+                                    value={config.child?.config.skill_id || ''}
+                                    onChange={(e) => updateLoopChild({ skill_id: e.target.value || undefined })}
+                                    className="w-full h-9 rounded-md border border-gray-200 dark:border-gray-800 bg-white dark:bg-gray-950 px-3 text-sm text-gray-900 dark:text-white"
+                                >
+                                    <option value="">Select a skill...</option>
+                                    {skills.map(skill => (
+                                        <option key={skill.id} value={skill.id}>{skill.name}</option>
+                                    ))}
+                                </select>
+                                <p className="text-[10px] text-gray-400">The skill receives the current item as {`{{item}}`} and its position as {`{{index}}`}.</p>
+                            </div>
+                            <div className="space-y-2">
+                                <Label htmlFor="loop-child-output" className="text-gray-700 dark:text-gray-300">Output File (per item)</Label>
+                                <Input
+                                    id="loop-child-output"
+                                    value={config.child?.config.output_file || ''}
+                                    onChange={(e) => updateLoopChild({ output_file: e.target.value || undefined })}
+                                    placeholder="e.g. summaries/{{index}}.md"
+                                    className="h-8 text-xs font-mono"
+                                />
+                            </div>
+                            <div className="space-y-2">
+                                <Label htmlFor="loop-max" className="text-gray-700 dark:text-gray-300">Max Iterations</Label>
+                                <Input
+                                    id="loop-max"
+                                    type="number"
+                                    min={1}
+                                    value={config.max_iterations ?? ''}
+                                    onChange={(e) => setConfig(prev => ({
+                                        ...prev,
+                                        max_iterations: e.target.value ? Math.max(1, parseInt(e.target.value, 10)) : undefined
+                                    }))}
+                                    placeholder="100"
+                                    className="h-8 text-xs"
+                                />
+                                <p className="text-[10px] text-gray-400">The loop fails instead of running when there are more items.</p>
+                            </div>
+                        </div>
+                    )}
+
                     {/* Iteration / Sub-Agent Config */}
                     {(stepType === 'iteration' || (stepType as string) === 'subagent') && (
                         <div className="space-y-4 pt-4 border-t border-gray-100 dark:border-gray-800">
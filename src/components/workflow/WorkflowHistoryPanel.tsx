@@ -31,6 +31,13 @@ const formatDate = (dateString: string, pattern: 'short' | 'time' = 'short') =>
     }
 };
 
+const formatDuration = (ms?: number) => {
+    if (ms === undefined) return null;
+    if (ms < 1000) return `${ms}ms`;
+    const seconds = Math.round(ms / 1000);
+    return seconds < 60 ? `${seconds}s` : `${Math.floor(seconds / 60)}m ${seconds % 60}s`;
+};
+
 export default function WorkflowHistoryPanel({ projectId, workflowId, onClose }: WorkflowHistoryPanelProps) {
     const [history, setHistory] = useState<WorkflowRunRecord[]>([]);
     const [isLoading, setIsLoading] = useState(true);
@@ -57,7 +64,7 @@ export default function WorkflowHistoryPanel({ projectId, workflowId, onClose }:
     const loadHistory = async () => {
         setIsLoading(true);
         try {
-            const result = await tauriApi.getWorkflowHistory(projectId, workflowId);
+            const result = await tauriApi.getWorkflowRuns(projectId, workflowId);
             setHistory(result);
         } catch (error) {
             console.error('Failed to load workflow history:', error);
@@ -114,6 +121,7 @@ export default function WorkflowHistoryPanel({ projectId, workflowId, onClose }:
                                         </div>
                                         <div className="text-[10px] text-gray-500 mt-0.5 truncate uppercase">
                                             {run.status} {run.completed && `• ${formatDate(run.completed, 'time')}`}
+                                            {run.duration_ms !== undefined && ` • ${formatDuration(run.duration_ms)}`}
                                         </div>
                                     </div>
                                     {expandedRunId === run.id ? (
@@ -131,7 +139,12 @@ export default function WorkflowHistoryPanel({ projectId, workflowId, onClose }:
                                             return (
                                                 <div key={stepId} className="pl-2 border-l-2 border-gray-200 dark:border-gray-700 py-1">
                                                     <div className="flex items-center justify-between">
-                                                        <span className="text-[11px] font-medium text-gray-700 dark:text-gray-300">{stepId}</span>
+                                                        <span className="text-[11px] font-medium text-gray-700 dark:text-gray-300">
+                                                            {stepId}
+                                                            {stepResult.duration_ms !== undefined && (
+                                                                <span className="ml-1 text-[9px] font-normal text-gray-400">{formatDuration(stepResult.duration_ms)}</span>
+                                                            )}
+                                                        </span>
                                                         <span className={`text-[9px] px-1 rounded ${
                                                             stepResult.status === 'Completed' ? 'bg-green-100 text-green-700 dark:bg-green-900/30 dark:text-green-400' :
                                                             stepResult.status === 'Failed' ? 'bg-red-100 text-red-700 dark:bg-red-900/30 dark:text-red-400' :
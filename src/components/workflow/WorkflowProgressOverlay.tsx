@@ -1,13 +1,15 @@
-import { Loader2 } from 'lucide-react';
+import { Loader2, Square } from 'lucide-react';
+import { Button } from '@/components/ui/button';
 import { motion, AnimatePresence } from 'framer-motion';
 import { WorkflowProgress } from '@/api/tauri';
 
 interface WorkflowProgressOverlayProps {
     isRunning: boolean;
     progress: WorkflowProgress | null;
+    onCancel?: () => void;
 }
 
-export default function WorkflowProgressOverlay({ isRunning, progress }: WorkflowProgressOverlayProps) {
+export default function WorkflowProgressOverlay({ isRunning, progress, onCancel }: WorkflowProgressOverlayProps) {
     return (
         <AnimatePresence>
             {isRunning && (
@@ -36,12 +38,26 @@ export default function WorkflowProgressOverlay({ isRunning, progress }: Workflo
                                                 ? 'Step completed'
                                                 : progress?.status === 'failed'
                                                     ? 'Step failed'
-                                                    : 'Preparing...'}
+                                                    : progress?.status === 'skipped'
+                                                        ? `Step skipped${progress.message ? `: ${progress.message}` : ''}`
+                                                        : 'Preparing...'}
                                     </div>
                                 </div>
                                 <span className="text-xs font-mono font-bold text-primary tabular-nums">
                                     {progress?.progress_percent ?? 0}%
                                 </span>
+                                {onCancel && (
+                                    <Button
+                                        variant="ghost"
+                                        size="sm"
+                                        onClick={onCancel}
+                                        className="h-7 px-2 text-xs text-muted-foreground hover:text-destructive"
+                                        title="Stop workflow"
+                                    >
+                                        <Square className="w-3 h-3 mr-1" />
+                                        Stop
+                                    </Button>
+                                )}
                             </div>
 
                             {/* Progress bar */}
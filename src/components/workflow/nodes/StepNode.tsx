@@ -1,6 +1,6 @@
 import { memo } from 'react';
 import { Handle, Position, NodeProps } from '@xyflow/react';
-import { Zap, Activity, FileText, Settings, Type } from 'lucide-react';
+import { Zap, Activity, FileText, Settings, Type, Repeat } from 'lucide-react';
 import { Button } from '@/components/ui/button';
 
 export interface StepNodeData {
@@ -30,6 +30,7 @@ const StepNode = memo(({ data, selected }: NodeProps<any>) => {
             case 'iteration': return Activity;
             case 'SubAgent': return Activity;
             case 'conditional': return Type;
+            case 'loop': return Repeat;
             default: return Zap;
         }
     };
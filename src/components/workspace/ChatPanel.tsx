@@ -18,6 +18,8 @@ import {
   ContextMenuTrigger,
 } from "@/components/ui/context-menu";
 import FileFormDialog from './FileFormDialog';
+import ToolApprovalDialog from './ToolApprovalDialog';
+import ProposedChangesDialog from './ProposedChangesDialog';
 import ThinkingBlock from './ThinkingBlock';
 import { useWorkflowGenerator } from '@/hooks/useWorkflowGenerator';
 import ApprovalCard, { ConfigAction } from './ApprovalCard';
@@ -179,7 +181,8 @@ export default function ChatPanel({ activeProject, skills = [], onToggleChat, wo
     'geminiCli': 'Google',
     'openAiCli': 'OpenAI',
     'liteLlm': 'LiteLLM Router',
-    'autoRouter': 'Auto-Router (Rules)'
+    'autoRouter': 'Auto-Router (Rules)',
+    'mock': 'Offline Echo (Mock)'
   };
 
   const [availableProviders, setAvailableProviders] = useState<ProviderType[]>([]);
@@ -1387,6 +1390,8 @@ export default function ChatPanel({ activeProject, skills = [], onToggleChat, wo
         onSubmit={handleFileCreate}
         projectName={activeProject?.name}
       />
+      <ToolApprovalDialog />
+      <ProposedChangesDialog projectId={activeProject?.id} />
 
       {/* Header */}
       <div className="h-12 border-b border-border/50 flex items-center justify-between px-4 glass-panel shrink-0 z-30">
@@ -1406,7 +1411,7 @@ export default function ChatPanel({ activeProject, skills = [], onToggleChat, wo
             </SelectTrigger>
             <SelectContent className="bg-background/80 backdrop-blur-xl border-white/10">
               <SelectItem value="no-skill" className="text-xs">No Skill</SelectItem>
-              {skills.map(skill => (
+              {skills.filter(skill => skill.enabled !== false).map(skill => (
                 <SelectItem key={skill.id} value={skill.id} className="text-xs">{skill.name}</SelectItem>
               ))}
             </SelectContent>
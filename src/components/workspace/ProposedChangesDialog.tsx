@@ -0,0 +1,126 @@
+import { useEffect, useState } from 'react';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import { FileDiff } from 'lucide-react';
+import { useToast } from '@/hooks/use-toast';
+import { tauriApi, ProposedFileChange } from '../../api/tauri';
+
+interface ProposedChangesDialogProps {
+  projectId?: string;
+}
+
+/** Lets the user apply or discard file changes the agent proposed while auto-apply is off */
+export default function ProposedChangesDialog({ projectId }: ProposedChangesDialogProps) {
+  const [changes, setChanges] = useState<ProposedFileChange[]>([]);
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [busy, setBusy] = useState(false);
+  const { toast } = useToast();
+
+  useEffect(() => {
+    setChanges([]);
+    if (!projectId) return;
+
+    // Every pending change is shown again, including ones left from an earlier review
+    const load = () =>
+      tauriApi
+        .getProposedChanges(projectId)
+        .then((pending) => {
+          setChanges(pending);
+          setSelected(new Set(pending.map((c) => c.id)));
+        })
+        .catch(console.error);
+    load();
+
+    let unlisten: (() => void) | undefined;
+    tauriApi
+      .onFilesProposed((event) => { if (event.projectId === projectId) load(); })
+      .then((fn) => { unlisten = fn; });
+    return () => { if (unlisten) unlisten(); };
+  }, [projectId]);
+
+  const toggle = (id: string) => {
+    setSelected((prev) => {
+      const next = new Set(prev);
+      if (next.has(id)) next.delete(id);
+      else next.add(id);
+      return next;
+    });
+  };
+
+  const resolve = async (apply: boolean) => {
+    if (!projectId) return;
+    const ids = apply ? changes.filter((c) => selected.has(c.id)).map((c) => c.id) : changes.map((c) => c.id);
+    setBusy(true);
+    try {
+      if (apply) {
+        const written = await tauriApi.applyProposedChanges(projectId, ids);
+        toast({ title: 'Changes applied', description: `${written.length} file(s) written` });
+      } else {
+        await tauriApi.discardProposedChanges(projectId, ids);
+      }
+      // Unselected changes stay pending for a later review
+      const remaining = changes.filter((c) => !ids.includes(c.id));
+      setChanges(remaining);
+      setSelected(new Set(remaining.map((c) => c.id)));
+    } catch (error) {
+      toast({ title: 'Could not update files', description: String(error), variant: 'destructive' });
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const open = changes.length > 0;
+
+  return (
+    <Dialog open={open} onOpenChange={(next) => { if (!next && !busy) setChanges([]); }}>
+      <DialogContent className="sm:max-w-[640px]">
+        <DialogHeader>
+          <div className="flex items-center gap-3 mb-2">
+            <div className="p-2 rounded-xl bg-primary/10 text-primary border border-primary/20">
+              <FileDiff className="w-5 h-5" />
+            </div>
+            <DialogTitle>Review file changes</DialogTitle>
+          </div>
+          <DialogDescription>
+            The agent proposed {changes.length} file change(s). Nothing is written until you apply them.
+            Closing this dialog keeps them pending until the next reply.
+          </DialogDescription>
+        </DialogHeader>
+        <div className="max-h-96 overflow-auto space-y-3">
+          {changes.map((change) => (
+            <div key={change.id} className="rounded-lg border border-border/50 p-3 space-y-2">
+              <label className="flex items-center gap-2 text-sm font-medium cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={selected.has(change.id)}
+                  onChange={() => toggle(change.id)}
+                />
+                <span className="font-mono truncate">{change.path}</span>
+                {change.overwrites && <Badge variant="destructive">overwrites</Badge>}
+              </label>
+              <pre className="max-h-40 overflow-auto rounded-lg bg-muted/50 p-3 text-xs font-mono whitespace-pre-wrap break-all">
+                {change.content}
+              </pre>
+            </div>
+          ))}
+        </div>
+        <DialogFooter>
+          <Button variant="outline" disabled={busy} onClick={() => resolve(false)}>
+            Discard all
+          </Button>
+          <Button disabled={busy || selected.size === 0} onClick={() => resolve(true)}>
+            Apply selected
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
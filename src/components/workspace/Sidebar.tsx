@@ -47,6 +47,7 @@ interface SidebarProps {
   onQuickScheduleWorkflow?: (workflow: any) => void;
 
   onDeleteProject?: (projectId: string) => void;
+  onDuplicateProject?: (projectId: string, newName: string) => void;
   onRenameProject?: (projectId: string, newName: string) => void;
   onAddFileToProject?: (projectId: string) => void;
   onDeleteFile?: (projectId: string, fileId: string) => void;
@@ -94,6 +95,7 @@ export default function Sidebar({
   onEditWorkflow,
   onQuickScheduleWorkflow,
   onDeleteProject,
+  onDuplicateProject,
   onAddFileToProject,
   onDeleteFile,
   onRenameFile,
@@ -276,6 +278,16 @@ export default function Sidebar({
                                   <ContextMenuItem onClick={() => onImportDocument && onImportDocument(project.id)}>
                                     <FolderPlus className="mr-2 h-4 w-4" /> Import Document
                                   </ContextMenuItem>
+                                  <ContextMenuItem
+                                    onClick={() => {
+                                      const newName = prompt('Name for the copy:', `${project.name} (copy)`);
+                                      if (newName && newName.trim() && onDuplicateProject) {
+                                        onDuplicateProject(project.id, newName.trim());
+                                      }
+                                    }}
+                                  >
+                                    <Folder className="mr-2 h-4 w-4" /> Duplicate as Template
+                                  </ContextMenuItem>
                                   <ContextMenuSeparator />
                                   <ContextMenuItem
                                     onClick={() => onDeleteProject && onDeleteProject(project.id)}
@@ -396,7 +408,7 @@ export default function Sidebar({
                             layout
                             initial={{ opacity: 0 }}
                             animate={{ opacity: 1 }}
-                            className="p-3 rounded-lg glass-card cursor-pointer transition-all group"
+                            className={`p-3 rounded-lg glass-card cursor-pointer transition-all group ${skill.enabled === false ? 'opacity-50' : ''}`}
                             onClick={() => onSkillSelect && onSkillSelect(skill)}
                           >
                             <div className="flex items-start gap-2.5">
@@ -404,7 +416,10 @@ export default function Sidebar({
                                 <Zap className="w-3.5 h-3.5" />
                               </div>
                               <div className="flex-1 min-w-0">
-                                <h4 className="text-xs font-semibold text-foreground truncate">{skill.name}</h4>
+                                <h4 className="text-xs font-semibold text-foreground truncate">
+                                  {skill.name}
+                                  {skill.enabled === false && <span className="ml-1.5 text-[10px] font-normal text-muted-foreground">(disabled)</span>}
+                                </h4>
                                 <p className="text-[10px] text-muted-foreground line-clamp-2 mt-0.5">{skill.description}</p>
                               </div>
                             </div>
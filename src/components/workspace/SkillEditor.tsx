@@ -3,8 +3,8 @@ import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
 import { Textarea } from '@/components/ui/textarea';
-import { Save, BrainCircuit, Workflow as WorkflowIcon, Wand2 } from 'lucide-react';
-import { Skill, Workflow, tauriApi } from '@/api/tauri';
+import { Save, BrainCircuit, Workflow as WorkflowIcon, Wand2, Eye } from 'lucide-react';
+import { Skill, SkillPromptPreview, Workflow, tauriApi } from '@/api/tauri';
 import { useToast } from '@/hooks/use-toast';
 
 interface SkillEditorProps {
@@ -21,11 +21,14 @@ export default function SkillEditor({ skill, workflows = [], onSave }: SkillEdit
     const [role, setRole] = useState('');
     const [tasks, setTasks] = useState('');
     const [output, setOutput] = useState('');
+    const [category, setCategory] = useState('');
     const [capabilities, setCapabilities] = useState('');
     const [additionalContent, setAdditionalContent] = useState('');
 
     const [isSaving, setIsSaving] = useState(false);
     const [isValidating, setIsValidating] = useState(false);
+    const [previewParams, setPreviewParams] = useState<Record<string, string>>({});
+    const [preview, setPreview] = useState<SkillPromptPreview | null>(null);
     const [usedInWorkflows, setUsedInWorkflows] = useState<Workflow[]>([]);
     const { toast } = useToast();
     const [hasChanges, setHasChanges] = useState(false);
@@ -42,8 +45,11 @@ export default function SkillEditor({ skill, workflows = [], onSave }: SkillEdit
         setRole(sections.role);
         setTasks(sections.tasks);
         setOutput(sections.output);
+        setCategory(skill.category || 'general');
         setCapabilities(skill.capabilities ? skill.capabilities.join(', ') : '');
         setAdditionalContent(sections.additional);
+        setPreviewParams({});
+        setPreview(null);
 
         setHasChanges(false);
 
@@ -172,7 +178,7 @@ export default function SkillEditor({ skill, workflows = [], onSave }: SkillEdit
         return () => {
             if (autoSaveTimerRef.current) clearTimeout(autoSaveTimerRef.current);
         };
-    }, [name, description, role, tasks, output, capabilities, additionalContent, hasChanges, isSaving]);
+    }, [name, description, role, tasks, output, category, capabilities, additionalContent, hasChanges, isSaving]);
 
     const handleSave = async (silent = false) => {
         if (!name.trim()) {
@@ -201,6 +207,7 @@ export default function SkillEditor({ skill, workflows = [], onSave }: SkillEdit
                 id: finalId,
                 name: name.trim(),
                 description: description.trim(),
+                category: category.trim().toLowerCase() || 'general',
                 prompt_template: recombineTemplate(),
                 capabilities: capabilities.split(',').map(c => c.trim()).filter(c => c)
             };
@@ -242,6 +249,18 @@ export default function SkillEditor({ skill, workflows = [], onSave }: SkillEdit
         }, 1500);
     };
 
+    const handlePreview = async () => {
+        try {
+            setPreview(await tauriApi.previewSkillPrompt(skill.id, previewParams));
+        } catch (error) {
+            toast({
+                title: 'Preview failed',
+                description: String(error),
+                variant: 'destructive'
+            });
+        }
+    };
+
     return (
         <div className="h-full flex flex-col bg-white dark:bg-gray-950 overflow-hidden">
             {/* Header */}
@@ -318,6 +337,16 @@ export default function SkillEditor({ skill, workflows = [], onSave }: SkillEdit
                         />
                     </div>
 
+                    <div className="grid gap-2">
+                        <Label htmlFor="skill-category">Category</Label>
+                        <Input
+                            id="skill-category"
+                            value={category}
+                            onChange={(e) => handleFieldChange(setCategory, e.target.value)}
+                            placeholder="e.g. research, development, writing, analysis, general"
+                        />
+                    </div>
+
                     <div className="grid gap-2">
                         <Label htmlFor="skill-capabilities">Capabilities</Label>
                         <Input
@@ -391,6 +420,56 @@ export default function SkillEditor({ skill, workflows = [], onSave }: SkillEdit
                         </p>
                     </div>
                 </div>
+
+                {/* Prompt Preview */}
+                <div className="grid gap-4 p-6 border border-border rounded-lg bg-muted/30">
+                    <div className="flex items-center justify-between">
+                        <div>
+                            <Label className="text-base font-medium">Prompt Preview</Label>
+                            <p className="text-xs text-muted-foreground mt-1">
+                                The prompt injected into chats, rendered from the saved skill{hasChanges ? ' (save to include your edits)' : ''}.
+                            </p>
+                        </div>
+                        <Button type="button" variant="outline" size="sm" className="gap-2" onClick={handlePreview}>
+                            <Eye className="w-4 h-4" />
+                            Preview
+                        </Button>
+                    </div>
+
+                    {skill.parameters.map(param => (
+                        <div key={param.name} className="grid gap-2">
+                            <Label htmlFor={`preview-${param.name}`} className="text-xs">
+                                {param.name}{param.required ? ' *' : ''}
+                            </Label>
+                            <Input
+                                id={`preview-${param.name}`}
+                                value={previewParams[param.name] ?? ''}
+                                onChange={(e) => setPreviewParams(prev => ({ ...prev, [param.name]: e.target.value }))}
+                                placeholder={param.default_value ?? param.description}
+                            />
+                        </div>
+                    ))}
+
+                    {preview && (
+                        <div className="grid gap-2">
+                            {preview.unresolved.length > 0 && (
+                                <p className="text-xs text-orange-600 dark:text-orange-400">
+                                    Unresolved placeholders: {preview.unresolved.map(name => `{{${name}}}`).join(', ')}
+                                </p>
+                            )}
+                            {preview.problems.map(problem => (
+                                <p key={problem} className="text-xs text-destructive">{problem}</p>
+                            ))}
+                            <pre className="whitespace-pre-wrap text-sm font-mono p-4 rounded-md bg-background border border-border max-h-[400px] overflow-y-auto">
+                                {preview.rendered.split(/(\{\{[^}]*\}\})/).map((part, i) =>
+                                    /^\{\{[^}]*\}\}$/.test(part)
+                                        ? <mark key={i} className="bg-orange-200 dark:bg-orange-900/60 text-foreground rounded px-0.5">{part}</mark>
+                                        : part
+                                )}
+                            </pre>
+                        </div>
+                    )}
+                </div>
             </div>
         </div>
     );
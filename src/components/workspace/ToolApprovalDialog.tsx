@@ -0,0 +1,65 @@
+import { useEffect, useState } from 'react';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { ShieldQuestion } from 'lucide-react';
+import { tauriApi, ToolApprovalRequest } from '../../api/tauri';
+
+/** Asks the user before the agent runs a tool in projects that require approval */
+export default function ToolApprovalDialog() {
+  const [queue, setQueue] = useState<ToolApprovalRequest[]>([]);
+  const current = queue[0];
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    tauriApi
+      .onToolApprovalRequest((request) => setQueue((prev) => [...prev, request]))
+      .then((fn) => { unlisten = fn; });
+    return () => { if (unlisten) unlisten(); };
+  }, []);
+
+  const respond = async (approved: boolean) => {
+    if (!current) return;
+    setQueue((prev) => prev.slice(1));
+    try {
+      await tauriApi.respondToolApproval(current.requestId, approved);
+    } catch (error) {
+      // The request already timed out and was denied
+      console.warn('Tool approval was not delivered:', error);
+    }
+  };
+
+  return (
+    <Dialog open={!!current} onOpenChange={(open) => { if (!open) respond(false); }}>
+      <DialogContent className="sm:max-w-[520px]">
+        <DialogHeader>
+          <div className="flex items-center gap-3 mb-2">
+            <div className="p-2 rounded-xl bg-amber-500/10 text-amber-500 border border-amber-500/20">
+              <ShieldQuestion className="w-5 h-5" />
+            </div>
+            <DialogTitle>Run {current?.toolName}?</DialogTitle>
+          </div>
+          <DialogDescription>
+            The agent wants to call this tool. It will be denied automatically after{' '}
+            {current?.timeoutSecs ?? 0} seconds.
+          </DialogDescription>
+        </DialogHeader>
+        <pre className="max-h-64 overflow-auto rounded-lg bg-muted/50 p-3 text-xs font-mono whitespace-pre-wrap break-all">
+          {JSON.stringify(current?.arguments ?? {}, null, 2)}
+        </pre>
+        <DialogFooter>
+          <Button variant="outline" onClick={() => respond(false)}>
+            Deny
+          </Button>
+          <Button onClick={() => respond(true)}>Approve</Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
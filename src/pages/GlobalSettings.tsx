@@ -584,18 +584,21 @@ export default function GlobalSettingsPage({ initialSection }: { initialSection?
   const handleRedetect = async () => {
     setLoading(true);
     try {
-      await tauriApi.clearAllCliDetectionCaches();
-      const [ollamaInfo, claudeInfo, geminiInfo] = await Promise.all([
-        tauriApi.detectOllama(),
-        tauriApi.detectClaudeCode(),
-        tauriApi.detectGemini()
-      ]);
+      const tools = await tauriApi.redetectAllDependencies();
+      const installed = (name: string) => (tools[name]?.installed ? tools[name] : null);
+      const ollama = installed('ollama');
+      const claudeCode = installed('claude-code');
+      const gemini = installed('gemini');
 
       setLocalModels({
-        ollama: ollamaInfo,
-        claudeCode: claudeInfo,
-        gemini: geminiInfo
+        ollama: ollama && { ...ollama, running: ollama.running ?? false },
+        claudeCode,
+        gemini
       });
+      setSettings(prev => ({
+        ...prev,
+        customClis: prev.customClis.map(cli => ({ ...cli, detectedPath: tools[`custom-${cli.id}`]?.path }))
+      }));
 
       toast({
         title: 'Environment Scanned',
@@ -963,6 +966,27 @@ export default function GlobalSettingsPage({ initialSection }: { initialSection?
                     />
                   </div>
                 </section>
+
+                {/* File Changes */}
+                <section className="space-y-6">
+                  <div>
+                    <h3 className="text-xl font-semibold text-gray-900 dark:text-gray-100">File Changes</h3>
+                    <p className="text-sm text-gray-500 dark:text-gray-400 mt-1">Decide how files written by the assistant reach your project</p>
+                  </div>
+
+                  <div className="flex items-center justify-between p-4 rounded-lg border border-gray-100 dark:border-gray-800 bg-gray-50/30 dark:bg-gray-900/20 max-w-md">
+                    <div className="space-y-0.5">
+                      <Label className="text-sm font-medium">Apply File Changes Automatically</Label>
+                      <p className="text-xs text-gray-500 dark:text-gray-400 mr-8">
+                        When off, proposed changes wait for your review in the chat
+                      </p>
+                    </div>
+                    <Switch
+                      checked={settings.autoApplyFileChanges ?? false}
+                      onCheckedChange={(checked) => setSettings(prev => ({ ...prev, autoApplyFileChanges: checked }))}
+                    />
+                  </div>
+                </section>
               </div>
             )}
 
@@ -28,6 +28,7 @@ export default function ProjectSettingsPage({ activeProject, onProjectCreated, o
     goal: activeProject?.description || '',
     autoSave: true,
     encryptData: true,
+    requireToolApproval: false,
     skills: [] as string[],
     personalizationRules: '',
     brandSettings: ''
@@ -55,6 +56,7 @@ export default function ProjectSettingsPage({ activeProject, onProjectCreated, o
           goal: settings.goal || activeProject.description || '',
           autoSave: settings.auto_save ?? true,
           encryptData: settings.encryption_enabled ?? true,
+          requireToolApproval: settings.require_tool_approval ?? false,
           skills: settings.preferred_skills || [],
           personalizationRules: settings.personalization_rules || '',
           brandSettings: settings.brand_settings || ''
@@ -119,6 +121,7 @@ export default function ProjectSettingsPage({ activeProject, onProjectCreated, o
           goal: projectSettings.goal,
           auto_save: projectSettings.autoSave,
           encryption_enabled: projectSettings.encryptData,
+          require_tool_approval: projectSettings.requireToolApproval,
           preferred_skills: projectSettings.skills,
           personalization_rules: projectSettings.personalizationRules,
           brand_settings: projectSettings.brandSettings || undefined
@@ -281,6 +284,19 @@ export default function ProjectSettingsPage({ activeProject, onProjectCreated, o
                       onCheckedChange={(checked) => setProjectSettings({ ...projectSettings, encryptData: checked })}
                     />
                   </div>
+
+                  <div className="flex items-center justify-between p-4 rounded-lg border border-gray-100 dark:border-gray-800 bg-gray-50/30 dark:bg-gray-900/20">
+                    <div className="space-y-0.5">
+                      <Label className="text-sm font-medium">Approve Tool Calls</Label>
+                      <p className="text-xs text-gray-500 dark:text-gray-400 mr-8">
+                        Ask before the agent runs any tool. Unanswered requests are denied after two minutes.
+                      </p>
+                    </div>
+                    <Switch
+                      checked={projectSettings.requireToolApproval}
+                      onCheckedChange={(checked) => setProjectSettings({ ...projectSettings, requireToolApproval: checked })}
+                    />
+                  </div>
                 </div>
               </section>
             )}
@@ -33,6 +33,60 @@ export default function SettingsPage({ activeProject }: SettingsPageProps) {
     dataDirectory: ''
   });
 
+  const [isRotatingKey, setIsRotatingKey] = useState(false);
+
+  const handleRotateKey = async () => {
+    setIsRotatingKey(true);
+    try {
+      const rotatedAt = await tauriApi.rotateEncryptionKey();
+      toast({
+        title: 'Encryption key rotated',
+        description: `All secrets were re-encrypted at ${new Date(rotatedAt).toLocaleString()}.`,
+      });
+    } catch (error) {
+      toast({
+        title: 'Key rotation failed',
+        description: `${error}. Your existing key and secrets were left unchanged.`,
+        variant: 'destructive',
+      });
+    } finally {
+      setIsRotatingKey(false);
+    }
+  };
+
+  const [transferPassphrase, setTransferPassphrase] = useState('');
+  const [importBlob, setImportBlob] = useState('');
+  const [isTransferring, setIsTransferring] = useState(false);
+
+  const handleExportSecrets = async () => {
+    setIsTransferring(true);
+    try {
+      const blob = await tauriApi.exportSecrets(transferPassphrase);
+      await navigator.clipboard.writeText(blob);
+      toast({
+        title: 'Secrets exported',
+        description: 'The encrypted export was copied to the clipboard. Import it on the new machine with the same passphrase.',
+      });
+    } catch (error) {
+      toast({ title: 'Export failed', description: String(error), variant: 'destructive' });
+    } finally {
+      setIsTransferring(false);
+    }
+  };
+
+  const handleImportSecrets = async () => {
+    setIsTransferring(true);
+    try {
+      const ids = await tauriApi.importSecrets(importBlob, transferPassphrase);
+      setImportBlob('');
+      toast({ title: 'Secrets imported', description: `Imported ${ids.length} secret(s).` });
+    } catch (error) {
+      toast({ title: 'Import failed', description: String(error), variant: 'destructive' });
+    } finally {
+      setIsTransferring(false);
+    }
+  };
+
   // Load the app data directory on mount
   useEffect(() => {
     const loadAppDataDirectory = async () => {
@@ -326,6 +380,45 @@ export default function SettingsPage({ activeProject }: SettingsPageProps) {
               </AlertDescription>
             </Alert>
 
+            <div className="flex items-center justify-between gap-4">
+              <div className="space-y-0.5">
+                <Label>Encryption Key</Label>
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                  Replace the master key and re-encrypt all saved API keys. Use this if you think the key leaked.
+                </p>
+              </div>
+              <Button variant="outline" size="sm" onClick={handleRotateKey} disabled={isRotatingKey}>
+                {isRotatingKey ? 'Rotating...' : 'Rotate Key'}
+              </Button>
+            </div>
+
+            <div className="space-y-2">
+              <Label htmlFor="transfer-passphrase">Move Secrets to Another Machine</Label>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                Export copies your API keys, encrypted with this passphrase, to the clipboard. Paste an export below to import it.
+              </p>
+              <Input
+                id="transfer-passphrase"
+                type="password"
+                placeholder="Passphrase (at least 8 characters)"
+                value={transferPassphrase}
+                onChange={(e) => setTransferPassphrase(e.target.value)}
+              />
+              <Input
+                placeholder="Paste an exported secrets blob to import"
+                value={importBlob}
+                onChange={(e) => setImportBlob(e.target.value)}
+              />
+              <div className="flex gap-2">
+                <Button variant="outline" size="sm" onClick={handleExportSecrets} disabled={isTransferring || !transferPassphrase}>
+                  Export Secrets
+                </Button>
+                <Button variant="outline" size="sm" onClick={handleImportSecrets} disabled={isTransferring || !transferPassphrase || !importBlob}>
+                  Import Secrets
+                </Button>
+              </div>
+            </div>
+
             <Button onClick={handleSaveGlobal} className="w-full">
               Save Global Settings
             </Button>
@@ -90,6 +90,7 @@ export default function Workspace() {
   const activeProjectRef = useRef(activeProject);
   const activeDocumentRef = useRef(activeDocument);
   const activeRunIdRef = useRef<string | null>(null);
+  const runningWorkflowRef = useRef<Workflow | null>(null);
   const artifactImportInputRef = useRef<HTMLInputElement | null>(null);
   const pendingArtifactImportTypeRef = useRef<ArtifactType>('insight');
 
@@ -411,6 +412,7 @@ export default function Workspace() {
     let unlistenModified: (() => void) | undefined;
     let unlistenFileChanged: (() => void) | undefined;
     let unlistenWorkflowChanged: (() => void) | undefined;
+    let unlistenSkills: (() => void) | undefined;
     let unlistenUpdate: (() => void) | undefined;
     let unlistenImport: (() => void) | undefined;
     let unlistenExport: (() => void) | undefined;
@@ -524,6 +526,14 @@ export default function Workspace() {
           }
         });
 
+        // Keep the skills list live when skill files are edited outside the app
+        unlistenSkills = await tauriApi.onSkillsChanged((skillId) => {
+          console.log('Skill changed on disk:', skillId);
+          tauriApi.getAllSkills().then(setSkills).catch(err => {
+            console.error("Failed to refresh skills:", err);
+          });
+        });
+
         // Listen for background update detection
         unlistenUpdate = await listen('update-available', (event: any) => {
           const version = event.payload;
@@ -555,6 +565,7 @@ export default function Workspace() {
       if (unlistenModified) unlistenModified();
       if (unlistenFileChanged) unlistenFileChanged();
       if (unlistenWorkflowChanged) unlistenWorkflowChanged();
+      if (unlistenSkills) unlistenSkills();
       if (unlistenUpdate) unlistenUpdate();
       if (unlistenImport) unlistenImport();
       if (unlistenExport) unlistenExport();
@@ -583,9 +594,10 @@ export default function Workspace() {
         setIsWorkflowRunning(false);
         setWorkflowProgress(null);
         activeRunIdRef.current = null;
+        runningWorkflowRef.current = null;
 
         // Fetch the full execution result from history
-        tauriApi.getWorkflowHistory(project_id, workflow_id).then(history => {
+        tauriApi.getWorkflowRuns(project_id, workflow_id).then(history => {
           const execution = history.find(h => h.id === run_id);
           if (execution) {
             setWorkflowResult(execution as any);
@@ -601,6 +613,11 @@ export default function Workspace() {
                   title: '✓ Workflow Completed',
                   description: `${completedSteps}/${stepEntries.length} steps completed${allOutputFiles.length > 0 ? `, ${allOutputFiles.length} file${allOutputFiles.length > 1 ? 's' : ''} created` : ''}`
                 });
+              } else if (status === 'Cancelled') {
+                toast({
+                  title: 'Workflow Cancelled',
+                  description: `Stopped after ${completedSteps}/${stepEntries.length} steps. Finished outputs were kept.`
+                });
               } else if (status === 'PartialSuccess') {
                 toast({
                   title: '⚠ Partially Completed',
@@ -1010,6 +1027,7 @@ export default function Workspace() {
       // Execute workflow in background - returns run_id immediately
       const runId = await tauriApi.executeWorkflow(workflow.project_id, workflow.id, parameters);
       activeRunIdRef.current = runId;
+      runningWorkflowRef.current = workflow;
       console.log("Workflow execution started with run_id:", runId);
 
       toast({
@@ -1030,6 +1048,21 @@ export default function Workspace() {
     }
   };
 
+  const handleCancelWorkflow = async () => {
+    const workflow = runningWorkflowRef.current;
+    if (!workflow) return;
+    try {
+      await tauriApi.cancelWorkflow(workflow.project_id, workflow.id);
+    } catch (error) {
+      console.error('Failed to cancel workflow:', error);
+      toast({
+        title: 'Error',
+        description: error instanceof Error ? error.message : String(error),
+        variant: 'destructive'
+      });
+    }
+  };
+
   const handleDeleteWorkflow = async (workflow: Workflow) => {
     try {
       await tauriApi.deleteWorkflow(workflow.project_id, workflow.id);
@@ -1074,7 +1107,7 @@ export default function Workspace() {
   };
 
   const handleNewProject = () => {
-    setActiveProject({ id: 'new-project', name: 'New Product', goal: '', description: '', created_at: '', skills: [], documents: [] });
+    setActiveProject({ id: 'new-project', name: 'New Product', goal: '', description: '', created_at: '', skills: [], tags: [], documents: [] });
     handleDocumentOpen(projectSettingsDocument);
   };
 
@@ -1112,6 +1145,8 @@ export default function Workspace() {
       id: 'draft-' + Date.now(),
       name: 'New Skill',
       description: '',
+      category: 'general',
+      enabled: true,
       prompt_template: '',
       capabilities: [],
       parameters: [],
@@ -1224,10 +1259,10 @@ export default function Workspace() {
   };
 
   const handleDeleteProject = async (projectId: string) => {
-    const confirmed = await ask('Are you sure you want to delete this project? This cannot be undone.', { title: 'Delete Project', kind: 'warning' });
+    const confirmed = await ask('Are you sure you want to delete this project? A backup is kept so it can be recovered.', { title: 'Delete Project', kind: 'warning' });
     if (confirmed) {
       try {
-        await tauriApi.deleteProject(projectId);
+        const backupPath = await tauriApi.deleteProject(projectId);
         // Optimistic update
         setProjects(prev => prev.filter(p => p.id !== projectId));
         if (activeProject?.id === projectId) {
@@ -1235,7 +1270,7 @@ export default function Workspace() {
           setOpenDocuments([]);
           setActiveDocument(null);
         }
-        toast({ title: 'Success', description: 'Project deleted' });
+        toast({ title: 'Success', description: `Project deleted. Backup saved to ${backupPath}` });
       } catch (error) {
         console.error('Failed to delete project:', error);
         toast({
@@ -1405,11 +1440,32 @@ export default function Workspace() {
     }
   };
 
+  const handleDuplicateProject = async (projectId: string, newName: string) => {
+    try {
+      const project = await tauriApi.duplicateProject(projectId, newName);
+      const adaptedProject: WorkspaceProject = {
+        ...project,
+        description: project.goal,
+        created: new Date().toISOString().split('T')[0],
+        documents: []
+      };
+      setProjects(prev => [...prev, adaptedProject]);
+      toast({ title: 'Success', description: `Created ${project.name} without the original's chats` });
+    } catch (error) {
+      console.error('Failed to duplicate project:', error);
+      toast({
+        title: 'Error',
+        description: error instanceof Error ? error.message : String(error),
+        variant: 'destructive'
+      });
+    }
+  };
+
   const handleRenameProject = async (projectId: string, newName: string) => {
     try {
-      await tauriApi.renameProject(projectId, newName);
+      const renamed = await tauriApi.renameProject(projectId, newName);
       // Optimistic update
-      setProjects(prev => prev.map(p => p.id === projectId ? { ...p, name: newName } : p));
+      setProjects(prev => prev.map(p => p.id === projectId ? { ...p, name: renamed.name } : p));
       toast({ title: 'Success', description: 'Project renamed' });
     } catch (error) {
       console.error('Failed to rename project:', error);
@@ -2555,6 +2611,7 @@ export default function Workspace() {
             onQuickScheduleWorkflow={handleQuickScheduleWorkflow}
 
             onDeleteProject={handleDeleteProject}
+            onDuplicateProject={handleDuplicateProject}
             onRenameProject={handleRenameProject}
             onAddFileToProject={handleAddFileToProject}
             onDeleteFile={handleDeleteFile}
@@ -2629,6 +2686,7 @@ export default function Workspace() {
           <WorkflowProgressOverlay
             isRunning={isWorkflowRunning}
             progress={workflowProgress}
+            onCancel={handleCancelWorkflow}
           />
 
           <MainPanel